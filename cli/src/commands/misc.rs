@@ -1,4 +1,24 @@
-use node::{account::AccountSecretKey, p2p::identity::SecretKey};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use ledger::scan_state::currency::{Amount, Fee, Nonce, Slot};
+use ledger::scan_state::transaction_logic::signed_command::{
+    Body, PaymentPayload, SignedCommandPayload,
+};
+use ledger::scan_state::transaction_logic::Memo;
+use ledger::vrf::{self, VrfEvaluationProof, VrfMessage};
+use ledger::{AccountIndex, BaseLedger, Database, Mask};
+use mina_p2p_messages::v2::{EpochSeed, MinaBaseLedgerHash0StableV1, MinaBaseUserCommandStableV2};
+use mina_signer::PubKey;
+use node::account::{AccountPublicKey, AccountSecretKey};
+use node::block_producer::{
+    BlockProducerDelegator, BlockProducerEpochData, BlockProducerVrfEvaluatorInput,
+};
+use node::daemon_json::{DaemonJson, DaemonJsonLedger};
+use node::ledger::LEDGER_DEPTH;
+use node::p2p::identity::SecretKey;
+use node::rpc::RpcTransactionSendResponse;
+use node::{NetworkConfig, NetworkId};
 
 use crate::CommandError;
 
@@ -13,6 +33,9 @@ impl Misc {
         match self.command {
             MiscCommand::P2PKeyPair(command) => command.run(),
             MiscCommand::MinaKeyPair(command) => command.run(),
+            MiscCommand::VrfWonSlots(command) => command.run(),
+            MiscCommand::VrfVerify(command) => command.run(),
+            MiscCommand::SendPayment(command) => command.run(),
         }
     }
 }
@@ -21,6 +44,9 @@ impl Misc {
 pub enum MiscCommand {
     P2PKeyPair(P2PKeyPair),
     MinaKeyPair(MinaKeyPair),
+    VrfWonSlots(VrfWonSlots),
+    VrfVerify(VrfVerify),
+    SendPayment(SendPayment),
 }
 
 #[derive(Debug, Clone, clap::Args)]
@@ -60,3 +86,320 @@ impl MinaKeyPair {
         Ok(())
     }
 }
+
+/// Lists slots of the epoch, won by the accounts delegating to the
+/// producer key in the staking ledger of the epoch.
+#[derive(Debug, Clone, clap::Args)]
+pub struct VrfWonSlots {
+    /// Network, genesis of which is used.
+    #[arg(long, short = 'n', env, default_value = "berkeley")]
+    network: NetworkId,
+
+    /// Mina-compatible runtime config (`daemon.json`) with the genesis ledger.
+    #[arg(long, short = 'c', env)]
+    config: Option<PathBuf>,
+
+    #[arg(long, short = 's', env = "OPENMINA_PRODUCER_KEY")]
+    producer_key: AccountSecretKey,
+
+    #[arg(long, short = 'e', default_value_t = 0)]
+    epoch: u32,
+
+    /// Seed of the epoch. Required for epochs other than the first one,
+    /// for which the seed of the genesis block is used.
+    #[arg(long)]
+    epoch_seed: Option<EpochSeed>,
+
+    /// Staking ledger of the epoch, as exported with
+    /// `mina ledger export staking-epoch-ledger`. Genesis ledger is the
+    /// staking ledger of the first two epochs, so it's only required
+    /// for the later ones.
+    #[arg(long)]
+    staking_ledger: Option<PathBuf>,
+
+    /// Print VRF evaluation proof of each won slot, which can be
+    /// checked with `vrf-verify` without the producer's secret key.
+    /// The proof format is OpenMina-only, Mina tooling can't verify it.
+    #[arg(long)]
+    proofs: bool,
+}
+
+impl VrfWonSlots {
+    pub fn run(self) -> Result<(), CommandError> {
+        let mut network = NetworkConfig::new(self.network);
+        if let Some(path) = &self.config {
+            network = network.apply_daemon_json(DaemonJson::load(path)?)?;
+        }
+        let accounts = match &self.staking_ledger {
+            Some(path) => DaemonJsonLedger::load_exported_accounts(path)?,
+            None if self.epoch <= 1 => network.genesis_ledger.clone().ok_or(
+                "genesis ledger of the network isn't known, set it with `--config` or `--staking-ledger`",
+            )?,
+            None => return Err("`--staking-ledger` must be set for this epoch".into()),
+        };
+        let genesis_epoch_data = network
+            .genesis_block
+            .as_ref()
+            .map(|block| &block.header().protocol_state.body.consensus_state)
+            .map(|consensus_state| &consensus_state.staking_epoch_data);
+        let seed = match (self.epoch_seed, genesis_epoch_data) {
+            (Some(seed), _) => seed,
+            (None, Some(data)) if self.epoch == 0 => data.seed.clone(),
+            (None, _) => return Err("`--epoch-seed` must be set for this epoch".into()),
+        };
+
+        let mut ledger = Mask::new_root(Database::create(LEDGER_DEPTH as u8));
+        for account in accounts {
+            ledger
+                .get_or_create_account(account.id(), account)
+                .map_err(|err| format!("failed to create staking ledger: {err:?}"))?;
+        }
+        let ledger_hash = MinaBaseLedgerHash0StableV1(ledger.merkle_root().into()).into();
+
+        let producer = self.producer_key.public_key();
+        let mut total_currency = 0;
+        let mut delegators = BTreeMap::new();
+        for index in 0..ledger.num_accounts() as u64 {
+            let Some(account) = ledger.get_at_index(AccountIndex(index)) else {
+                continue;
+            };
+            total_currency += account.balance.as_u64();
+            let delegate = account.delegate.clone().map(AccountPublicKey::from);
+            if delegate.as_ref() == Some(&producer) && account.token_id.is_default() {
+                let delegator = BlockProducerDelegator {
+                    pub_key: account.public_key.clone().into(),
+                    stake: account.balance.as_u64(),
+                };
+                delegators.insert(index, delegator);
+            }
+        }
+        let total_currency = match genesis_epoch_data {
+            Some(data) if self.staking_ledger.is_none() => data.ledger.total_currency.as_u64(),
+            _ => total_currency,
+        };
+        let delegated_stake: u64 = delegators.values().map(|d| d.stake).sum();
+
+        let slots_per_epoch = network.protocol_constants.slots_per_epoch.as_u32();
+        let epoch_start = self.epoch * slots_per_epoch;
+        let genesis_timestamp = network.protocol_constants.genesis_state_timestamp.as_u64();
+        let slot_duration_ms = network.constraint_constants.block_window_duration_ms;
+        let global_slot_since_genesis_offset = network
+            .constraint_constants
+            .fork
+            .as_ref()
+            .map_or(0, |fork| fork.previous_global_slot.as_u32());
+        let input = BlockProducerVrfEvaluatorInput {
+            epoch_data: BlockProducerEpochData {
+                epoch: self.epoch,
+                seed,
+                ledger_hash,
+                total_currency,
+            },
+            slots: epoch_start..(epoch_start + slots_per_epoch),
+            global_slot_since_genesis_offset,
+            genesis_timestamp,
+            slot_duration_ms,
+            delegators,
+        };
+
+        println!("producer:         {producer}");
+        println!("epoch:            {}", self.epoch);
+        println!("staking ledger:   {}", input.epoch_data.ledger_hash);
+        println!("delegated stake:  {delegated_stake} / {total_currency}");
+
+        let epoch_seed = input.epoch_data.seed.to_field();
+        let output = input.evaluate(self.producer_key.as_ref());
+        println!("won slots:        {}", output.won_slots.len());
+        if self.proofs {
+            println!("proofs:           OpenMina-only format, verifiable with `openmina misc vrf-verify`");
+        }
+        for won_slot in output.won_slots {
+            let slot_time_ms = genesis_timestamp + won_slot.global_slot as u64 * slot_duration_ms;
+            println!(
+                "slot: {}, slot since genesis: {}, time: {}, delegator: {} ({})",
+                won_slot.global_slot,
+                won_slot.global_slot_since_genesis,
+                slot_time_ms,
+                won_slot.delegator,
+                won_slot.delegator_index
+            );
+            if self.proofs {
+                let message = VrfMessage {
+                    global_slot: won_slot.global_slot,
+                    epoch_seed,
+                    delegator_index: AccountIndex(won_slot.delegator_index),
+                };
+                let (_, proof) = vrf::evaluate_with_proof(
+                    self.producer_key.as_ref(),
+                    &message,
+                    LEDGER_DEPTH as u64,
+                );
+                println!("  proof (OpenMina-only): {}", hex::encode(proof.to_bytes()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Verifies VRF evaluation proof of a block producer, printed by
+/// `vrf-won-slots --proofs`, and checks whether the output wins the slot.
+/// Only OpenMina's proof format is accepted, Mina has no standalone
+/// VRF proof.
+#[derive(Debug, Clone, clap::Args)]
+pub struct VrfVerify {
+    /// Public key of the block producer.
+    #[arg(long)]
+    producer: AccountPublicKey,
+
+    #[arg(long)]
+    epoch_seed: EpochSeed,
+
+    /// Global slot (since hard fork).
+    #[arg(long)]
+    slot: u32,
+
+    /// Index of the delegator's account in the staking ledger.
+    #[arg(long)]
+    delegator_index: u64,
+
+    /// Hex-encoded proof, in OpenMina-only format.
+    #[arg(long)]
+    proof: String,
+
+    /// Stake of the delegator, in nanomina. Threshold is checked if
+    /// it's set together with `--total-stake`.
+    #[arg(long, requires = "total_stake")]
+    stake: Option<u64>,
+
+    /// Total currency of the staking ledger, in nanomina.
+    #[arg(long, requires = "stake")]
+    total_stake: Option<u64>,
+}
+
+impl VrfVerify {
+    pub fn run(self) -> Result<(), CommandError> {
+        let public_key = PubKey::from_address(&self.producer.to_string())
+            .map_err(|err| format!("invalid producer key: {err}"))?;
+        let proof = VrfEvaluationProof::from_bytes(&hex::decode(&self.proof)?)
+            .ok_or("malformed VRF evaluation proof")?;
+        let message = VrfMessage {
+            global_slot: self.slot,
+            epoch_seed: self.epoch_seed.to_field(),
+            delegator_index: AccountIndex(self.delegator_index),
+        };
+        let output = vrf::verify(&public_key, &message, LEDGER_DEPTH as u64, &proof)
+            .ok_or("invalid VRF evaluation proof")?;
+
+        println!("truncated output: {}", hex::encode(output.truncated.0));
+        println!("fraction:         {}", output.fraction());
+        if let (Some(stake), Some(total_stake)) = (self.stake, self.total_stake) {
+            let won = output.satisfies_threshold(stake, total_stake);
+            println!("won slot:         {won}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Signs a payment and submits it to the transaction pool of the node.
+#[derive(Debug, Clone, clap::Args)]
+pub struct SendPayment {
+    /// Network, signature prefix of which is used.
+    #[arg(long, short = 'n', env, default_value = "berkeley")]
+    network: NetworkId,
+
+    /// Address of the node's http server.
+    #[arg(long, default_value = "http://127.0.0.1:3000")]
+    node: String,
+
+    #[arg(long, short = 's', env = "OPENMINA_SEC_KEY")]
+    sender_key: AccountSecretKey,
+
+    #[arg(long)]
+    receiver: AccountPublicKey,
+
+    /// Amount, in nanomina.
+    #[arg(long)]
+    amount: u64,
+
+    /// Fee, in nanomina.
+    #[arg(long, default_value_t = 10_000_000)]
+    fee: u64,
+
+    /// Nonce of the payment. Next nonce of the sender is requested from
+    /// the node, if not set.
+    #[arg(long)]
+    nonce: Option<u32>,
+
+    /// Global slot since genesis, after which the payment can no longer
+    /// be included in a block.
+    #[arg(long)]
+    valid_until: Option<u32>,
+
+    #[arg(long, default_value = "")]
+    memo: String,
+}
+
+impl SendPayment {
+    pub fn run(self) -> Result<(), CommandError> {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        rt.block_on(self.send())
+    }
+
+    async fn send(self) -> Result<(), CommandError> {
+        let client = hyper::Client::new();
+        let node = self.node.trim_end_matches('/');
+        let sender = self.sender_key.public_key();
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let uri = format!("{node}/transaction/nonce/{sender}").parse()?;
+                let resp = client.get(uri).await?;
+                let status = resp.status();
+                let body = hyper::body::to_bytes(resp.into_body()).await?;
+                if !status.is_success() {
+                    let error = String::from_utf8_lossy(&body);
+                    return Err(format!("failed to get nonce of the sender: {error}").into());
+                }
+                serde_json::from_slice(&body)?
+            }
+        };
+
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(self.fee),
+            sender.as_ref().into(),
+            Nonce::from_u32(nonce),
+            self.valid_until.map(Slot::from_u32),
+            Memo::create_from_string(&self.memo)?,
+            Body::Payment(PaymentPayload {
+                receiver_pk: self.receiver.as_ref().into(),
+                amount: Amount::from_u64(self.amount),
+            }),
+        );
+        let cmd = self
+            .sender_key
+            .sign_command(self.network.signature_network_id(), payload);
+        let transaction = MinaBaseUserCommandStableV2::SignedCommand((&cmd).into());
+
+        let req = hyper::Request::post(format!("{node}/transaction/send"))
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&transaction)?.into())?;
+        let resp = client.request(req).await?;
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        match serde_json::from_slice(&body)? {
+            RpcTransactionSendResponse::Ok { hash } => {
+                println!("sender:  {sender}");
+                println!("nonce:   {nonce}");
+                println!("hash:    {hash}");
+                Ok(())
+            }
+            RpcTransactionSendResponse::Rejected { error } => {
+                Err(format!("transaction rejected: {error}").into())
+            }
+        }
+    }
+}
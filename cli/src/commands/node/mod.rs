@@ -1,4 +1,5 @@
 use std::ffi::OsString;
+use std::net::{IpAddr, SocketAddr};
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -11,9 +12,10 @@ use rand::prelude::*;
 
 use tokio::select;
 
-use node::account::AccountPublicKey;
+use node::account::{AccountPublicKey, AccountSecretKey};
 use node::core::channels::mpsc;
 use node::core::log::inner::Level;
+use node::daemon_json::DaemonJson;
 use node::event_source::{
     EventSourceProcessEventsAction, EventSourceWaitForEventsAction, EventSourceWaitTimeoutAction,
 };
@@ -21,21 +23,24 @@ use node::ledger::LedgerCtx;
 use node::p2p::channels::ChannelId;
 use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
 use node::p2p::identity::SecretKey;
+use node::p2p::service_impl::libp2p::Libp2pService;
 use node::p2p::service_impl::webrtc::P2pServiceCtx;
 use node::p2p::service_impl::webrtc_with_libp2p::{self, P2pServiceWebrtcWithLibp2p};
-use node::p2p::{P2pConfig, P2pEvent};
+use node::p2p::{P2pConfig, P2pEvent, P2pGossipsubConfig, P2pPeerStore};
 use node::service::{Recorder, Service};
 use node::snark::{get_srs, get_verifier_index, VerifierKind};
 use node::stats::Stats;
 use node::{
-    BuildEnv, Config, GlobalConfig, LedgerConfig, SnarkConfig, SnarkerConfig, State,
-    TransitionFrontierConfig,
+    BlockProducerConfig, BuildEnv, Config, GlobalConfig, LedgerConfig, NetworkConfig, NetworkId,
+    SnarkConfig, SnarkerConfig, State, TransactionPoolConfig, TransitionFrontierConfig,
 };
 
+use openmina_node_native::http_server::{HttpServerConfig, HttpTlsConfig};
 use openmina_node_native::rpc::RpcService;
-use openmina_node_native::{http_server, tracing, NodeService, P2pTaskSpawner, RpcSender};
-
-const CHAIN_ID: &'static str = "3c41383994b87449625df91769dff7b507825c064287d30fada9286f3f1cb15e";
+use openmina_node_native::snark_worker_coordinator::SnarkWorkerCoordinatorConfig;
+use openmina_node_native::{
+    http_server, snark_worker_coordinator, tracing, NodeService, P2pTaskSpawner, RpcSender,
+};
 
 /// Openmina node
 #[derive(Debug, clap::Args)]
@@ -43,6 +48,15 @@ pub struct Node {
     #[arg(long, short = 'd', default_value = "~/.openmina")]
     pub work_dir: String,
 
+    /// Network to connect to.
+    #[arg(long, short = 'n', env, default_value = "berkeley")]
+    pub network: NetworkId,
+
+    /// Mina-compatible runtime config (`daemon.json`). Parameters set in
+    /// it override the ones of the selected `--network`.
+    #[arg(long, short = 'c', env)]
+    pub config: Option<PathBuf>,
+
     /// Peer secret key
     #[arg(long, short = 's', env = "OPENMINA_P2P_SEC_KEY")]
     pub p2p_secret_key: Option<SecretKey>,
@@ -51,11 +65,48 @@ pub struct Node {
     #[arg(long, short, env, default_value = "3000")]
     pub port: u16,
 
+    /// Address for the http server to listen to.
+    #[arg(long, env, default_value = "0.0.0.0")]
+    pub http_address: IpAddr,
+
+    /// Serve admin routes (state, stats, snarker, peers, transaction
+    /// submission, metrics, events and graphql) on this address instead of
+    /// `--port`, e.g. `127.0.0.1:3001`.
+    #[arg(long, env)]
+    pub http_admin_address: Option<SocketAddr>,
+
+    /// Require this bearer token for the admin routes. If neither this
+    /// nor `--http-admin-address` is set, admin routes are only
+    /// accessible from localhost.
+    #[arg(long, env = "OPENMINA_HTTP_ADMIN_TOKEN")]
+    pub http_admin_token: Option<String>,
+
+    /// Certificate (PEM) to serve https with.
+    #[arg(long, env, requires = "http_tls_key")]
+    pub http_tls_cert: Option<PathBuf>,
+
+    /// Private key (PEM) of the `--http-tls-cert` certificate.
+    #[arg(long, env, requires = "http_tls_cert")]
+    pub http_tls_key: Option<PathBuf>,
+
+    /// Origins allowed to make cross-origin requests to the http server.
+    /// If not set, any origin is allowed to access the public routes, but
+    /// none the admin ones.
+    #[arg(long, env, value_delimiter = ',')]
+    pub http_cors_origins: Vec<String>,
+
     /// Verbosity level
     #[arg(long, short, env, default_value = "info")]
     pub verbosity: Level,
 
-    #[arg(long, short = 'P', alias = "peer", num_args = 0.., default_values_t = default_peers(), env, value_delimiter = ' ')]
+    /// Gossipsub config (JSON) used with libp2p peers, e.g.
+    /// `{"mesh_n": 12, "peer_scoring": null}`. Missing fields get their
+    /// default values.
+    #[arg(long, env)]
+    pub p2p_gossipsub_config: Option<PathBuf>,
+
+    /// Peers to connect to. Seed peers of the network are used if not set.
+    #[arg(long, short = 'P', alias = "peer", num_args = 0.., env, value_delimiter = ' ')]
     pub peers: Vec<P2pConnectionOutgoingInitOpts>,
 
     /// Run Snark Worker.
@@ -72,29 +123,47 @@ pub struct Node {
     #[arg(long, env, default_value = "cli/bin/snark-worker")]
     pub snarker_exe_path: OsString,
 
-    #[arg(long, default_value = "none")]
-    pub record: String,
+    /// Number of snark workers working on jobs concurrently.
+    #[arg(long, env, default_value_t = 1)]
+    pub snarker_workers: usize,
 
-    #[arg(long, default_value = "none")]
-    pub additional_ledgers_path: Option<PathBuf>,
-}
+    /// Port to serve jobs to remote Mina snark workers on
+    /// (`mina internal snark-worker -daemon-address <host>:<port>`).
+    #[arg(long, env, requires = "run_snarker")]
+    pub snarker_coordinator_port: Option<u16>,
 
-fn default_peers() -> Vec<P2pConnectionOutgoingInitOpts> {
-    [
-        "/2ajh5CpZCHdv7tmMrotVnLjQXuhcuCzqKosdDmvN3tNTScw2fsd/http/65.109.110.75/10000",
+    /// Address for the snark worker coordinator to listen to. Workers
+    /// aren't authenticated, so only expose it to trusted networks.
+    #[arg(long, env, default_value = "127.0.0.1")]
+    pub snarker_coordinator_address: IpAddr,
 
-        "/dns4/seed-1.berkeley.o1test.net/tcp/10000/p2p/12D3KooWAdgYL6hv18M3iDBdaK1dRygPivSfAfBNDzie6YqydVbs",
-        "/dns4/seed-2.berkeley.o1test.net/tcp/10001/p2p/12D3KooWLjs54xHzVmMmGYb7W5RVibqbwD1co7M2ZMfPgPm7iAag",
-        "/dns4/seed-3.berkeley.o1test.net/tcp/10002/p2p/12D3KooWEiGVAFC7curXWXiGZyMWnZK9h8BKr88U8D5PKV3dXciv",
+    /// Addresses remote snark workers may connect from. Any address is
+    /// allowed if not set.
+    #[arg(long, env, value_delimiter = ',')]
+    pub snarker_coordinator_allowed_ips: Vec<IpAddr>,
 
-        "/dns4/webrtc2.webnode.openmina.com/tcp/443/p2p/12D3KooWFpqySZDHx7k5FMjdwmrU3TLhDbdADECCautBcEGtG4fr",
-        "/dns4/webrtc2.webnode.openmina.com/tcp/4431/p2p/12D3KooWJBeXosFxdBwe2mbKRjgRG69ERaUTpS9qo9NRkoE8kBpj",
+    /// Produce blocks with the given key (base58check encoded).
+    #[arg(long, env = "OPENMINA_PRODUCER_KEY")]
+    pub producer_key: Option<AccountSecretKey>,
+
+    /// Account receiving coinbase rewards of the produced blocks.
+    /// Defaults to the producer account.
+    #[arg(long, env, requires = "producer_key")]
+    pub coinbase_receiver: Option<AccountPublicKey>,
+
+    /// Maximum number of transactions in the transaction pool.
+    #[arg(long, env, default_value_t = TransactionPoolConfig::default().max_size)]
+    pub tx_pool_max_size: usize,
+
+    /// Minimum fee (in nanomina) of the transactions accepted to the pool.
+    #[arg(long, env, default_value_t = TransactionPoolConfig::default().min_fee)]
+    pub tx_pool_min_fee: u64,
+
+    #[arg(long, default_value = "none")]
+    pub record: String,
 
-        "/ip4/78.27.236.28/tcp/8302/p2p/12D3KooWDLNXPq28An4s2QaPZX5ftem1AfaCWuxHHJq97opeWxLy",
-    ]
-        .into_iter()
-        .map(|s| s.parse().unwrap())
-        .collect()
+    #[arg(long, default_value = "none")]
+    pub additional_ledgers_path: Option<PathBuf>,
 }
 
 impl Node {
@@ -125,9 +194,61 @@ impl Node {
         });
         let pub_key = secret_key.public_key();
 
+        let mut network = NetworkConfig::new(self.network);
+        if let Some(path) = &self.config {
+            network = network.apply_daemon_json(DaemonJson::load(path)?)?;
+        }
+        if self.producer_key.is_some() && network.proof_level.is_full() {
+            // TODO: native block prover.
+            let err = "block proofs can't be created yet, block producer requires \
+                `\"proof\": { \"level\": \"check\" }` in the `--config`";
+            return Err(err.into());
+        }
+
+        let gossipsub: P2pGossipsubConfig = match &self.p2p_gossipsub_config {
+            Some(path) => {
+                serde_json::from_reader(std::io::BufReader::new(std::fs::File::open(path)?))?
+            }
+            None => Default::default(),
+        };
+        Libp2pService::gossipsub_config_validate(&gossipsub)
+            .map_err(|err| format!("invalid `--p2p-gossipsub-config`: {err}"))?;
+
+        let http_tls = self
+            .http_tls_cert
+            .zip(self.http_tls_key)
+            .map(|(cert_path, key_path)| HttpTlsConfig {
+                cert_path,
+                key_path,
+            });
+        if let Some(tls) = &http_tls {
+            tls.validate()
+                .map_err(|err| format!("invalid `--http-tls-cert` or `--http-tls-key`: {err}"))?;
+        }
+
         let work_dir = shellexpand::full(&self.work_dir).unwrap().into_owned();
+        // Namespaced by chain id, so that switching networks in the same
+        // work dir doesn't restore frontier of another chain.
+        let frontier_dir = PathBuf::from(&work_dir)
+            .join("frontier")
+            .join(&network.chain_id);
+        let peer_store_path = PathBuf::from(&work_dir).join("peers.json");
+        let peer_store = match P2pPeerStore::open(&peer_store_path, redux::Timestamp::global_now())
+        {
+            Ok(v) => Some(v),
+            Err(err) => {
+                openmina_core::log::warn!(openmina_core::log::system_time();
+                    kind = "P2pPeerStoreLoad",
+                    summary = format!("failed to load peer store at {peer_store_path:?}, peers won't be persisted"),
+                    error = err.to_string());
+                None
+            }
+        };
         let rng_seed = rng.next_u64();
         let srs: Arc<_> = get_srs().into();
+        let mut transition_frontier =
+            TransitionFrontierConfig::new(network.protocol_constants.clone());
+        transition_frontier.genesis_block = network.genesis_block.clone();
         let config = Config {
             ledger: LedgerConfig {},
             snark: SnarkConfig {
@@ -146,15 +267,42 @@ impl Node {
                     )),
                     auto_commit: true,
                     path: self.snarker_exe_path,
+                    workers: self.snarker_workers,
                 }),
+                proof_level: network.proof_level,
             },
             p2p: P2pConfig {
                 identity_pub_key: pub_key,
-                initial_peers: self.peers,
+                // Peers from the previous runs are used along with the
+                // seeds, so that restarted nodes don't all hit the seeds.
+                initial_peers: if self.peers.is_empty() {
+                    let stored_peers = peer_store.iter().flat_map(|s| s.initial_peers());
+                    network
+                        .seed_peers
+                        .iter()
+                        .cloned()
+                        .chain(stored_peers)
+                        .collect()
+                } else {
+                    self.peers
+                },
                 max_peers: 100,
+                reputation: Default::default(),
+                kademlia: Default::default(),
+                gossipsub,
                 enabled_channels: ChannelId::iter_all().collect(),
             },
-            transition_frontier: TransitionFrontierConfig::default(),
+            transition_frontier,
+            block_producer: self.producer_key.as_ref().map(|key| BlockProducerConfig {
+                pub_key: key.public_key(),
+                custom_coinbase_receiver: self.coinbase_receiver,
+                slot_duration_ms: network.constraint_constants.block_window_duration_ms,
+                max_block_size: 1 << network.constraint_constants.transaction_capacity_log_2,
+            }),
+            transaction_pool: TransactionPoolConfig {
+                max_size: self.tx_pool_max_size,
+                min_fee: self.tx_pool_min_fee,
+            },
         };
         let (event_sender, event_receiver) = mpsc::unbounded_channel();
 
@@ -162,10 +310,16 @@ impl Node {
 
         let webrtc_with_libp2p::P2pServiceCtx {
             libp2p,
-            webrtc: P2pServiceCtx { cmd_sender, peers },
+            webrtc:
+                P2pServiceCtx {
+                    cmd_sender,
+                    peers,
+                    secret_key: p2p_secret_key,
+                },
         } = <NodeService as P2pServiceWebrtcWithLibp2p>::init(
             secret_key,
-            CHAIN_ID.to_owned(),
+            network.chain_id.clone(),
+            config.p2p.gossipsub.clone(),
             p2p_event_sender.clone(),
             P2pTaskSpawner {},
         );
@@ -181,7 +335,13 @@ impl Node {
 
         let mut rpc_service = RpcService::new();
 
-        let http_port = self.port;
+        let http_config = HttpServerConfig {
+            bind: SocketAddr::new(self.http_address, self.port),
+            admin_bind: self.http_admin_address,
+            admin_token: self.http_admin_token,
+            tls: http_tls,
+            cors_origins: self.http_cors_origins,
+        };
         let rpc_sender = RpcSender::new(rpc_service.req_sender().clone());
 
         // spawn http-server
@@ -193,10 +353,27 @@ impl Node {
             .name("openmina_http_server".to_owned())
             .spawn(move || {
                 let local_set = tokio::task::LocalSet::new();
-                local_set.block_on(&runtime, http_server::run(http_port, rpc_sender))
+                local_set.block_on(&runtime, http_server::run(http_config, rpc_sender))
             })
             .unwrap();
 
+        // spawn snark worker coordinator.
+        if let Some(port) = self.snarker_coordinator_port {
+            let config = SnarkWorkerCoordinatorConfig {
+                bind: SocketAddr::new(self.snarker_coordinator_address, port),
+                allowed_ips: self.snarker_coordinator_allowed_ips,
+            };
+            let rpc_sender = RpcSender::new(rpc_service.req_sender().clone());
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            std::thread::Builder::new()
+                .name("openmina_snark_worker_coordinator".to_owned())
+                .spawn(move || runtime.block_on(snark_worker_coordinator::run(config, rpc_sender)))
+                .unwrap();
+        }
+
         // spawn state machine thread.
         let runtime = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -213,6 +390,32 @@ impl Node {
                 } else {
                     LedgerCtx::default()
                 };
+                let ledger = ledger
+                    .with_constraint_constants(network.constraint_constants)
+                    .with_proof_level(network.proof_level);
+                let ledger = match network.genesis_ledger {
+                    Some(accounts) => ledger.with_genesis_ledger(accounts),
+                    None => ledger,
+                };
+                let ledger = match &network.genesis_block {
+                    Some(block) => ledger.with_genesis_block(block).unwrap_or_else(|err| {
+                        panic!("failed to initialize ledgers of the genesis block: {err}")
+                    }),
+                    None => ledger,
+                };
+                let mut ledger = ledger.with_persistence(&frontier_dir).unwrap_or_else(|err| {
+                    panic!("failed to open transition frontier storage at {frontier_dir:?}: {err}")
+                });
+                let restored_transition_frontier = match ledger.restore_transition_frontier() {
+                    Ok(v) => v,
+                    Err(err) => {
+                        openmina_core::log::warn!(openmina_core::log::system_time();
+                            kind = "TransitionFrontierRestore",
+                            summary = "failed to restore persisted transition frontier, syncing from scratch",
+                            error = err);
+                        None
+                    }
+                };
 
                 let local_set = tokio::task::LocalSet::new();
                 local_set.block_on(&runtime, async move {
@@ -224,10 +427,13 @@ impl Node {
                         cmd_sender,
                         ledger,
                         peers,
+                        p2p_secret_key,
                         libp2p,
+                        peer_store,
                         rpc: rpc_service,
-                        snark_worker_sender: None,
-                        stats: Stats::new(),
+                        snark_workers: Default::default(),
+                        block_producer_key: self.producer_key,
+                        stats: Stats::new(network.genesis_state_hash),
                         recorder: match record.trim() {
                             "none" => Recorder::None,
                             "state-with-input-actions" => Recorder::only_input_actions(work_dir),
@@ -235,7 +441,14 @@ impl Node {
                         },
                         replayer: None,
                     };
-                    let state = State::new(config);
+                    let mut state = State::new(config);
+                    if let Some(restored) = restored_transition_frontier {
+                        state.transition_frontier.restore(
+                            restored.best_chain,
+                            restored.needed_protocol_states,
+                            restored.epoch_ledgers,
+                        );
+                    }
                     let mut node = ::node::Node::new(state, service, None);
 
                     // record initial state.
@@ -247,6 +460,8 @@ impl Node {
                     node
                         .store_mut()
                         .dispatch(EventSourceProcessEventsAction {});
+                    let shutdown = tokio::signal::ctrl_c();
+                    tokio::pin!(shutdown);
                     loop {
                         node
                             .store_mut()
@@ -279,8 +494,19 @@ impl Node {
                             _ = timeout => {
                                 node.store_mut().dispatch(EventSourceWaitTimeoutAction {});
                             }
+                            _ = &mut shutdown => {
+                                openmina_core::log::info!(
+                                    openmina_core::log::system_time();
+                                    kind = "Shutdown",
+                                    summary = "received interrupt signal, shutting down"
+                                );
+                                break;
+                            }
                         }
                     }
+                    // Dropping the node flushes the peer store and
+                    // the recorder.
+                    drop(node);
                 });
                 let _ = redux_exited_tx.send(());
             })
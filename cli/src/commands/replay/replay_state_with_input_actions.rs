@@ -4,7 +4,8 @@ use node::core::channels::mpsc;
 use node::p2p::service_impl::libp2p::Libp2pService;
 use node::recorder::{Recorder, StateWithInputActionsReader};
 use node::snark::VerifierKind;
-use node::{ActionWithMeta, BuildEnv, Store};
+use node::stats::Stats;
+use node::{ActionWithMeta, BuildEnv, NetworkConfig, Store};
 use openmina_node_native::{rpc::RpcService, NodeService, ReplayerState};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
@@ -60,9 +61,11 @@ impl ReplayStateWithInputActions {
             ledger: Default::default(),
             peers: Default::default(),
             libp2p: Libp2pService::mocked().0,
+            peer_store: None,
             rpc: RpcService::new(),
-            snark_worker_sender: None,
-            stats: Default::default(),
+            snark_workers: Default::default(),
+            block_producer_key: None,
+            stats: Stats::new(NetworkConfig::berkeley().genesis_state_hash),
             recorder: Recorder::None,
             replayer: Some(ReplayerState {
                 initial_monotonic: redux::Instant::now(),
@@ -58,6 +58,7 @@ mod tree;
 mod tree_version;
 mod util;
 pub mod verifier;
+pub mod vrf;
 
 pub use account::*;
 pub use address::*;
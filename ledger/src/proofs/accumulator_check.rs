@@ -19,36 +19,49 @@ pub fn get_srs() -> super::VerifierSRS {
 pub fn accumulator_check(
     urs: &super::VerifierSRS,
     proof: &PicklesProofProofsVerified2ReprStableV2,
+) -> bool {
+    batch_accumulator_check(urs, [proof])
+}
+
+/// Checks accumulators of all the proofs at once, which is much cheaper
+/// than checking them one by one, as it needs a single multi-scalar
+/// multiplication over the URS.
+///
+/// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/pickles/verify.ml#L50
+pub fn batch_accumulator_check<'a>(
+    urs: &super::VerifierSRS,
+    proofs: impl IntoIterator<Item = &'a PicklesProofProofsVerified2ReprStableV2>,
 ) -> bool {
     // accumulator check
     // Note:
     // comms: statement.proof_state.messages_for_next_wrap_proof.challenge_polynomial_commitment
     // chals: statement.proof_state.deferred_values.bulletproof_challenges
 
-    let deferred_values = &proof.statement.proof_state.deferred_values;
-    let bulletproof_challenges = &deferred_values.bulletproof_challenges;
-    let bulletproof_challenges: Vec<Fp> = bulletproof_challenges
-        .iter()
-        .map(|chal| {
+    let of_coord = |x: &(BigInt, BigInt)| Vesta::of_coordinates(x.0.to_field(), x.1.to_field());
+    let endo = endo_fp();
+
+    let mut acc_comms = Vec::new();
+    let mut bulletproof_challenges = Vec::new();
+    for proof in proofs {
+        let deferred_values = &proof.statement.proof_state.deferred_values;
+        bulletproof_challenges.extend(deferred_values.bulletproof_challenges.iter().map(|chal| {
             let prechallenge = &chal.prechallenge.inner;
             let prechallenge: [u64; 2] = array::from_fn(|k| prechallenge[k].as_u64());
 
-            ScalarChallenge::from(prechallenge).to_field(&endo_fp())
-        })
-        .collect();
-
-    let of_coord = |x: &(BigInt, BigInt)| Vesta::of_coordinates(x.0.to_field(), x.1.to_field());
+            ScalarChallenge::from(prechallenge).to_field::<Fp>(&endo)
+        }));
 
-    // statement.proof_state.messages_for_next_wrap_proof.challenge_polynomial_commitment
-    let acc_comm = &proof
-        .statement
-        .proof_state
-        .messages_for_next_wrap_proof
-        .challenge_polynomial_commitment;
-    let acc_comm: Vesta = of_coord(acc_comm);
+        // statement.proof_state.messages_for_next_wrap_proof.challenge_polynomial_commitment
+        let acc_comm = &proof
+            .statement
+            .proof_state
+            .messages_for_next_wrap_proof
+            .challenge_polynomial_commitment;
+        acc_comms.push(of_coord(acc_comm));
+    }
 
     let acc_check =
-        urs_utils::batch_dlog_accumulator_check(urs, &[acc_comm], &bulletproof_challenges);
+        urs_utils::batch_dlog_accumulator_check(urs, &acc_comms, &bulletproof_challenges);
 
     if !acc_check {
         println!("accumulator_check failed");
@@ -2817,6 +2817,136 @@ impl From<&StagedLedgerDiffDiffStableV2> for crate::staged_ledger::diff::Diff {
     }
 }
 
+impl From<&super::scan_state::transaction_snark::work::Work> for TransactionSnarkWorkTStableV2 {
+    fn from(value: &super::scan_state::transaction_snark::work::Work) -> Self {
+        use super::scan_state::transaction_snark::OneOrTwo::{One, Two};
+        use mina_p2p_messages::v2::TransactionSnarkWorkTStableV2Proofs as B;
+
+        let super::scan_state::transaction_snark::work::Work {
+            fee,
+            proofs,
+            prover,
+        } = value;
+
+        Self {
+            fee: fee.into(),
+            proofs: match proofs {
+                One(proof) => B::One(proof.into()),
+                Two((p1, p2)) => B::Two((p1.into(), p2.into())),
+            },
+            prover: prover.into(),
+        }
+    }
+}
+
+impl From<&WithStatus<UserCommand>> for StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2B {
+    fn from(value: &WithStatus<UserCommand>) -> Self {
+        let WithStatus { data, status } = value;
+
+        Self {
+            data: data.into(),
+            status: status.into(),
+        }
+    }
+}
+
+impl From<&transaction_logic::CoinbaseFeeTransfer> for StagedLedgerDiffDiffFtStableV1 {
+    fn from(value: &transaction_logic::CoinbaseFeeTransfer) -> Self {
+        let transaction_logic::CoinbaseFeeTransfer { receiver_pk, fee } = value;
+
+        Self(MinaBaseCoinbaseFeeTransferStableV1 {
+            receiver_pk: receiver_pk.into(),
+            fee: fee.into(),
+        })
+    }
+}
+
+impl From<&crate::staged_ledger::diff::AtMostTwo<transaction_logic::CoinbaseFeeTransfer>>
+    for StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2Coinbase
+{
+    fn from(
+        value: &crate::staged_ledger::diff::AtMostTwo<transaction_logic::CoinbaseFeeTransfer>,
+    ) -> Self {
+        use crate::staged_ledger::diff::AtMostTwo as B;
+
+        match value {
+            B::Zero => Self::Zero,
+            B::One(one) => Self::One(one.as_ref().map(Into::into)),
+            B::Two(twos) => Self::Two(
+                twos.as_ref()
+                    .map(|(one, two)| (one.into(), two.as_ref().map(Into::into))),
+            ),
+        }
+    }
+}
+
+impl From<&crate::staged_ledger::diff::PreDiffWithAtMostTwoCoinbase>
+    for StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2
+{
+    fn from(value: &crate::staged_ledger::diff::PreDiffWithAtMostTwoCoinbase) -> Self {
+        let crate::staged_ledger::diff::PreDiffWithAtMostTwoCoinbase {
+            completed_works,
+            commands,
+            coinbase,
+            internal_command_statuses,
+        } = value;
+
+        Self {
+            completed_works: completed_works.iter().map(Into::into).collect(),
+            commands: commands.iter().map(Into::into).collect(),
+            coinbase: coinbase.into(),
+            internal_command_statuses: internal_command_statuses.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&crate::staged_ledger::diff::AtMostOne<transaction_logic::CoinbaseFeeTransfer>>
+    for StagedLedgerDiffDiffPreDiffWithAtMostOneCoinbaseStableV2Coinbase
+{
+    fn from(
+        value: &crate::staged_ledger::diff::AtMostOne<transaction_logic::CoinbaseFeeTransfer>,
+    ) -> Self {
+        use crate::staged_ledger::diff::AtMostOne as B;
+
+        match value {
+            B::Zero => Self::Zero,
+            B::One(one) => Self::One(one.as_ref().map(Into::into)),
+        }
+    }
+}
+
+impl From<&crate::staged_ledger::diff::PreDiffWithAtMostOneCoinbase>
+    for StagedLedgerDiffDiffPreDiffWithAtMostOneCoinbaseStableV2
+{
+    fn from(value: &crate::staged_ledger::diff::PreDiffWithAtMostOneCoinbase) -> Self {
+        let crate::staged_ledger::diff::PreDiffWithAtMostOneCoinbase {
+            completed_works,
+            commands,
+            coinbase,
+            internal_command_statuses,
+        } = value;
+
+        Self {
+            completed_works: completed_works.iter().map(Into::into).collect(),
+            commands: commands.iter().map(Into::into).collect(),
+            coinbase: coinbase.into(),
+            internal_command_statuses: internal_command_statuses.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&crate::staged_ledger::diff::Diff> for StagedLedgerDiffDiffStableV2 {
+    fn from(value: &crate::staged_ledger::diff::Diff) -> Self {
+        let crate::staged_ledger::diff::Diff {
+            diff: (first, second),
+        } = value;
+
+        Self {
+            diff: StagedLedgerDiffDiffDiffStableV2(first.into(), second.as_ref().map(Into::into)),
+        }
+    }
+}
+
 impl From<&MinaNumbersGlobalSlotSinceGenesisMStableV1> for Slot {
     fn from(value: &MinaNumbersGlobalSlotSinceGenesisMStableV1) -> Self {
         let MinaNumbersGlobalSlotSinceGenesisMStableV1::SinceGenesis(slot) = value;
@@ -555,6 +555,9 @@ impl Memo {
 
     const MAX_DIGESTIBLE_STRING_LENGTH: usize = 1000;
 
+    /// Version byte of the base58check encoding (`user_command_memo`).
+    const BASE58_CHECK_VERSION: u8 = 0x14;
+
     pub fn to_bits(&self) -> [bool; std::mem::size_of::<Self>() * 8] {
         use crate::proofs::witness::legacy_input::BitsIterator;
 
@@ -602,6 +605,13 @@ impl Memo {
         self.0.as_slice()
     }
 
+    /// Memo as shown by the Mina daemon, e.g. in graphql responses.
+    pub fn to_base58check(&self) -> String {
+        bs58::encode(&self.0)
+            .with_check_version(Self::BASE58_CHECK_VERSION)
+            .into_string()
+    }
+
     /// https://github.com/MinaProtocol/mina/blob/3a78f0e0c1343d14e2729c8b00205baa2ec70c93/src/lib/mina_base/signed_command_memo.ml#L151
     pub fn dummy() -> Self {
         // TODO
@@ -628,6 +638,22 @@ impl Memo {
         Self(s.into_bytes().try_into().unwrap())
     }
 
+    /// Memo containing the string itself, same as `create_from_string`
+    /// in `signed_command_memo.ml`.
+    pub fn create_from_string(s: &str) -> Result<Self, String> {
+        let bytes = s.as_bytes();
+        if bytes.len() > Self::MAX_INPUT_LENGTH {
+            return Err("Too_long_user_memo_input".to_string());
+        }
+
+        let mut memo = [0; Self::MEMO_LENGTH];
+        memo[Self::TAG_INDEX] = Self::BYTES_TAG;
+        memo[Self::LENGTH_INDEX] = bytes.len() as u8;
+        memo[2..2 + bytes.len()].copy_from_slice(bytes);
+
+        Ok(Self(memo))
+    }
+
     /// https://github.com/MinaProtocol/mina/blob/d7dad23d8ea2052f515f5d55d187788fe0701c7f/src/lib/mina_base/signed_command_memo.ml#L103
     fn create_by_digesting_string_exn(s: &str) -> Self {
         if s.len() > Self::MAX_DIGESTIBLE_STRING_LENGTH {
@@ -117,11 +117,11 @@ pub struct PreStatement<L: sparse_ledger::LedgerIntf + Clone> {
 #[derive(Debug)]
 pub struct DiffResult {
     pub hash_after_applying: StagedLedgerHash<Fp>,
-    ledger_proof: Option<(
+    pub ledger_proof: Option<(
         LedgerProof,
         Vec<TransactionsOrdered<(WithStatus<Transaction>, Fp, Slot)>>,
     )>,
-    pending_coinbase_update: (bool, Update),
+    pub pending_coinbase_update: (bool, Update),
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -186,7 +186,7 @@ impl StagedLedger {
         scan_state.check_invariants(
             constraint_constants,
             statement_check,
-            &Verifier,
+            &Verifier::digest_only(), // null
             "Error verifying the parallel scan state after applying the diff.",
             last_proof_statement,
             registers_end,
@@ -245,7 +245,7 @@ impl StagedLedger {
         scan_state.check_invariants(
             constraint_constants,
             StatementCheck::<fn(Fp) -> MinaStateProtocolStateValueStableV2>::Partial,
-            &Verifier, // null
+            &Verifier::digest_only(), // null
             "Staged_ledger.of_scan_state_and_ledger",
             last_proof_statement,
             Registers {
@@ -2082,7 +2082,7 @@ mod tests_ocaml {
 
     const LOGGER: () = ();
 
-    const VERIFIER: Verifier = Verifier;
+    const VERIFIER: Verifier = Verifier::digest_only();
 
     /// https://github.com/MinaProtocol/mina/blob/3753a8593cc1577bcf4da16620daf9946d88e8e5/src/lib/staged_ledger/staged_ledger.ml#L2092
     fn supercharge_coinbase(ledger: Mask, winner: CompressedPubKey, global_slot: Slot) -> bool {
@@ -2987,7 +2987,7 @@ mod tests_ocaml {
                     StagedLedger::of_scan_state_pending_coinbases_and_snarked_ledger(
                         (),
                         &CONSTRAINT_CONSTANTS,
-                        crate::verifier::Verifier,
+                        crate::verifier::Verifier::digest_only(),
                         sl.scan_state.clone(),
                         snarked_ledger.clone(),
                         {
@@ -4538,7 +4538,7 @@ mod tests_ocaml {
                             global_slot,
                             diff.clone(),
                             (),
-                            &Verifier,
+                            &VERIFIER,
                             &current_view,
                             state_hashes,
                             COINBASE_RECEIVER.clone(),
@@ -5748,7 +5748,7 @@ mod tests_ocaml {
                     global_slot,
                     diff.forget(),
                     (),
-                    &Verifier,
+                    &VERIFIER,
                     &current_state_view,
                     state_and_body_hash,
                     COINBASE_RECEIVER.clone(),
@@ -5937,7 +5937,7 @@ mod tests {
                 StagedLedger::of_scan_state_pending_coinbases_and_snarked_ledger(
                     (),
                     &CONSTRAINT_CONSTANTS,
-                    Verifier,
+                    Verifier::new(),
                     scan_state,
                     snarked_ledger,
                     LocalState::empty(),
@@ -6069,7 +6069,7 @@ mod tests {
                     Slot::from_u32(global_slot),
                     diff,
                     (),
-                    &Verifier,
+                    &Verifier::new(),
                     &prev_state_view,
                     prev_state_and_body_hash,
                     coinbase_receiver,
@@ -22,8 +22,6 @@ pub enum BlockBodyValidationError {
 }
 
 pub fn validate_block(block: &MinaBlockBlockStableV2) -> Result<(), BlockBodyValidationError> {
-    let bytes = serialize_with_len_and_tag(&block.body.staged_ledger_diff);
-
     let body_reference = &block
         .header
         .protocol_state
@@ -32,7 +30,7 @@ pub fn validate_block(block: &MinaBlockBlockStableV2) -> Result<(), BlockBodyVal
         .body_reference;
     let body_reference: &[u8] = body_reference;
 
-    let (_, hash) = blocks_of_data(MAX_BLOCK_SIZE, &bytes)?;
+    let hash = block_body_hash(&block.body.staged_ledger_diff)?;
 
     if body_reference == &hash[..] {
         Ok(())
@@ -46,6 +44,16 @@ pub fn validate_block(block: &MinaBlockBlockStableV2) -> Result<(), BlockBodyVal
     }
 }
 
+/// Computes the body reference (root of the bitswap blocks) of the block
+/// body, which is stored in the `blockchain_state` of the protocol state.
+pub fn block_body_hash(
+    body: &StagedLedgerDiffDiffStableV2,
+) -> Result<Link, BlockBodyValidationError> {
+    let bytes = serialize_with_len_and_tag(body);
+    let (_, hash) = blocks_of_data(MAX_BLOCK_SIZE, &bytes)?;
+    Ok(hash)
+}
+
 fn serialize_with_len_and_tag(block: &StagedLedgerDiffDiffStableV2) -> Vec<u8> {
     let mut bytes = Vec::with_capacity(32 * 1024);
     block.binprot_write(&mut bytes).unwrap();
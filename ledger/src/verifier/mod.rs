@@ -14,8 +14,10 @@ use crate::{
 
 use self::common::CheckResult;
 
-#[derive(Debug, Clone)]
-pub struct Verifier;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Verifier {
+    digest_only: bool,
+}
 
 use mina_p2p_messages::v2::{
     PicklesProofProofsVerified2ReprStableV2, PicklesProofProofsVerifiedMaxStableV2,
@@ -34,7 +36,7 @@ pub static SRS: Lazy<Arc<VerifierSRS>> =
     Lazy::new(|| std::sync::Arc::new(crate::proofs::accumulator_check::get_srs()));
 
 /// https://github.com/MinaProtocol/mina/blob/bfd1009abdbee78979ff0343cc73a3480e862f58/src/lib/transaction_snark/transaction_snark.ml#L3492
-fn verify(ts: Vec<(LedgerProof, SokMessage)>) -> Result<(), String> {
+fn verify(ts: Vec<(&LedgerProof, &SokMessage)>) -> Result<(), String> {
     if ts.iter().all(|(proof, msg)| {
         let LedgerProof(TransactionSnark { statement, .. }) = proof;
         statement.sok_digest == msg.digest()
@@ -76,8 +78,7 @@ fn verify(ts: Vec<(LedgerProof, SokMessage)>) -> Result<(), String> {
 }
 
 /// https://github.com/MinaProtocol/mina/blob/bfd1009abdbee78979ff0343cc73a3480e862f58/src/lib/verifier/dummy.ml#L59C1-L75C81
-#[cfg(test)]
-fn verify_digest_only(ts: Vec<(LedgerProof, SokMessage)>) -> Result<(), String> {
+fn verify_digest_only(ts: Vec<(&LedgerProof, &SokMessage)>) -> Result<(), String> {
     use crate::scan_state::scan_state::transaction_snark::SokDigest;
 
     if ts.iter().all(|(proof, msg)| {
@@ -110,24 +111,51 @@ pub enum VerifyCommandsResult {
 }
 
 impl Verifier {
-    pub fn verify(&self, _proofs: &[LedgerProofWithSokMessage]) -> Result<Result<(), ()>, String> {
-        // Implement verification later
-        //
-        // https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/pickles/pickles.ml#L1122
-        // https://viable-systems.slack.com/archives/D01SVA87PQC/p1671715846448749
-        Ok(Ok(()))
+    /// Verifier which fully verifies ledger proofs.
+    pub const fn new() -> Self {
+        Self { digest_only: false }
+    }
+
+    /// Verifier which only checks that ledger proofs were made for their
+    /// sok messages and assumes zkapp proofs are valid, like the dummy
+    /// verifier OCaml node uses with `check` and `none` proof levels.
+    pub const fn digest_only() -> Self {
+        Self { digest_only: true }
+    }
+
+    pub fn is_digest_only(&self) -> bool {
+        self.digest_only
+    }
+
+    /// Verifies ledger proofs of the scan state, all of them in a single
+    /// batch. `Ok(Err(()))` if any of the proofs is invalid.
+    ///
+    /// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/pickles/pickles.ml#L1122
+    pub fn verify(&self, proofs: &[LedgerProofWithSokMessage]) -> Result<Result<(), ()>, String> {
+        let ts = proofs
+            .iter()
+            .map(|LedgerProofWithSokMessage { proof, sok_message }| (proof, sok_message))
+            .collect();
+
+        Ok(self.verify_ledger_proofs(ts).map_err(|_| ()))
     }
 
     /// https://github.com/MinaProtocol/mina/blob/bfd1009abdbee78979ff0343cc73a3480e862f58/src/lib/verifier/prod.ml#L138
-    #[allow(unreachable_code)]
     pub fn verify_transaction_snarks(
         &self,
         ts: Vec<(LedgerProof, SokMessage)>,
     ) -> Result<(), String> {
-        #[cfg(test)]
-        return verify_digest_only(ts);
+        let ts = ts.iter().map(|(proof, msg)| (proof, msg)).collect();
 
-        verify(ts)
+        self.verify_ledger_proofs(ts)
+    }
+
+    fn verify_ledger_proofs(&self, ts: Vec<(&LedgerProof, &SokMessage)>) -> Result<(), String> {
+        if self.digest_only {
+            verify_digest_only(ts)
+        } else {
+            verify(ts)
+        }
     }
 
     pub fn verify_commands(
@@ -146,7 +174,7 @@ impl Verifier {
             })
             .flatten();
 
-        let all_verified = if skip_verification.is_some() {
+        let all_verified = if skip_verification.is_some() || self.digest_only {
             true
         } else {
             let srs = SRS.as_ref();
@@ -367,3 +395,111 @@ pub mod common {
         rv.y.into_repr().is_even() && rv.x == *rx
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use mina_p2p_messages::{
+        binprot::BinProtRead,
+        gossip::GossipNetMessageV2,
+        v2::{NetworkPoolSnarkPoolDiffVersionedStableV2, TransactionSnarkWorkTStableV2Proofs},
+    };
+
+    use super::*;
+
+    /// Ledger proofs of the work from a snark pool diff gossiped by OCaml
+    /// nodes, along with the sok message of the work.
+    fn gossiped_ledger_proofs() -> Vec<(LedgerProof, SokMessage)> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../mina-p2p-messages/tests/files/v2/gossip/snark_pool_diff.bin");
+        let bytes = std::fs::read(path).unwrap();
+
+        let GossipNetMessageV2::SnarkPoolDiff { message, .. } =
+            GossipNetMessageV2::binprot_read(&mut bytes.as_slice()).unwrap()
+        else {
+            panic!("not a snark pool diff");
+        };
+        let NetworkPoolSnarkPoolDiffVersionedStableV2::AddSolvedWork(work) = message else {
+            panic!("empty snark pool diff");
+        };
+        let (_, work) = *work;
+
+        let sok_message = SokMessage::create((&work.fee.fee).into(), (&work.fee.prover).into());
+        let proofs = match &work.proof {
+            TransactionSnarkWorkTStableV2Proofs::One(proof) => vec![proof],
+            TransactionSnarkWorkTStableV2Proofs::Two((proof1, proof2)) => vec![proof1, proof2],
+        };
+        proofs
+            .into_iter()
+            .map(|proof| (proof.into(), sok_message.clone()))
+            .collect()
+    }
+
+    fn as_refs(ts: &[(LedgerProof, SokMessage)]) -> Vec<(&LedgerProof, &SokMessage)> {
+        ts.iter().map(|(proof, msg)| (proof, msg)).collect()
+    }
+
+    #[test]
+    fn verify_gossiped_ledger_proofs() {
+        let ts = gossiped_ledger_proofs();
+        assert_eq!(verify(as_refs(&ts)), Ok(()));
+    }
+
+    #[test]
+    fn verify_ledger_proof_with_tampered_statement() {
+        let mut ts = gossiped_ledger_proofs();
+        let statement = &mut ts[0].0 .0.statement;
+        std::mem::swap(&mut statement.source, &mut statement.target);
+
+        assert!(verify(as_refs(&ts)).is_err());
+        // Digest doesn't cover the statement, so the dummy verifier accepts it.
+        assert_eq!(verify_digest_only(as_refs(&ts)), Ok(()));
+    }
+
+    #[test]
+    fn verify_ledger_proof_with_mismatched_sok_message() {
+        let mut ts = gossiped_ledger_proofs();
+        ts[0].1.prover = CompressedPubKey::empty();
+
+        assert!(verify(as_refs(&ts)).is_err());
+        assert!(verify_digest_only(as_refs(&ts)).is_err());
+    }
+
+    #[test]
+    fn digest_only_verifier() {
+        let ts = gossiped_ledger_proofs();
+        let proofs = ts
+            .into_iter()
+            .map(|(proof, sok_message)| LedgerProofWithSokMessage { proof, sok_message })
+            .collect::<Vec<_>>();
+
+        assert!(Verifier::digest_only().is_digest_only());
+        assert!(!Verifier::new().is_digest_only());
+        assert_eq!(Verifier::digest_only().verify(&proofs), Ok(Ok(())));
+
+        let mut mismatched = proofs;
+        mismatched[0].sok_message.prover = CompressedPubKey::empty();
+        assert_eq!(Verifier::digest_only().verify(&mismatched), Ok(Err(())));
+    }
+
+    #[test]
+    fn full_verifier() {
+        let ts = gossiped_ledger_proofs();
+        assert_eq!(
+            Verifier::new().verify_transaction_snarks(ts.clone()),
+            Ok(())
+        );
+
+        let mut proofs = ts
+            .into_iter()
+            .map(|(proof, sok_message)| LedgerProofWithSokMessage { proof, sok_message })
+            .collect::<Vec<_>>();
+        assert_eq!(Verifier::new().verify(&proofs), Ok(Ok(())));
+
+        let statement = &mut proofs[0].proof.0.statement;
+        std::mem::swap(&mut statement.source, &mut statement.target);
+        assert_eq!(Verifier::new().verify(&proofs), Ok(Err(())));
+        assert_eq!(Verifier::digest_only().verify(&proofs), Ok(Ok(())));
+    }
+}
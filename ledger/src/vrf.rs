@@ -0,0 +1,446 @@
+//! Verifiable random function used to determine slot winners.
+//!
+//! https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/consensus/vrf/consensus_vrf.ml
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, UniformRand, Zero};
+use kimchi::groupmap::{BWParameters, GroupMap};
+use mina_curves::pasta::{Fq, PallasParameters};
+use mina_hasher::Fp;
+use mina_signer::{CurvePoint, Keypair, PubKey};
+use num_bigint::{BigInt, Sign};
+use o1_utils::FieldHelpers;
+use once_cell::sync::Lazy;
+
+use crate::{hash_with_kimchi, AccountIndex, Inputs};
+
+/// Amount of bits of the VRF output, which are used for the threshold check.
+pub const VRF_OUTPUT_TRUNCATED_BITS: u32 = 253;
+
+/// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/consensus/vrf/consensus_vrf.ml#L99
+#[derive(Debug, Clone)]
+pub struct VrfMessage {
+    pub global_slot: u32,
+    pub epoch_seed: Fp,
+    pub delegator_index: AccountIndex,
+}
+
+/// Result of the VRF evaluation.
+#[derive(Debug, Clone)]
+pub struct VrfOutput {
+    /// Full VRF output, used to update epoch seed.
+    pub output: Fp,
+    pub truncated: VrfOutputTruncated,
+}
+
+/// Little-endian bytes of the VRF output truncated to
+/// [`VRF_OUTPUT_TRUNCATED_BITS`], as it's stored in the consensus state
+/// (`last_vrf_output`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VrfOutputTruncated(pub [u8; 32]);
+
+/// Proof that the VRF output was computed with the secret key matching
+/// the producer's public key, so that outputs of other producers can be
+/// verified without their secret key.
+///
+/// Discrete log equality proof (Chaum-Pedersen) of
+/// `log_G(public_key) == log_H(scaled_message_hash)`, where `H` is the
+/// message hashed onto the curve.
+///
+/// The proof format is OpenMina's own. Mina has no standalone VRF proof,
+/// evaluation is proven inside the block SNARK, so these proofs can only
+/// be checked by OpenMina (`openmina misc vrf-verify`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct VrfEvaluationProof {
+    /// Message hash scaled by the secret key of the producer.
+    pub scaled_message_hash: CurvePoint,
+    pub c: Fq,
+    pub s: Fq,
+}
+
+impl VrfMessage {
+    fn inputs(&self, ledger_depth: u64) -> Inputs {
+        let mut inputs = Inputs::new();
+        inputs.append_field(self.epoch_seed);
+        inputs.append_u32(self.global_slot);
+        for i in 0..ledger_depth {
+            inputs.append_bool((self.delegator_index.0 >> i) & 1 == 1);
+        }
+        inputs
+    }
+
+    /// Hashes the message onto the curve.
+    fn to_group(&self, ledger_depth: u64) -> CurvePoint {
+        let hash = hash_with_kimchi("MinaVrfMessage", &self.inputs(ledger_depth).to_fields());
+        let group_map = <BWParameters<PallasParameters> as GroupMap<Fp>>::setup();
+        let (x, y) = group_map.to_group(hash);
+        CurvePoint::new(x, y, false)
+    }
+
+    /// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/consensus/vrf/consensus_vrf.ml#L192
+    fn output_hash(&self, ledger_depth: u64, point: &CurvePoint) -> Fp {
+        let mut inputs = self.inputs(ledger_depth);
+        inputs.append_field(point.x);
+        inputs.append_field(point.y);
+        hash_with_kimchi("MinaVrfOutput", &inputs.to_fields())
+    }
+}
+
+impl VrfEvaluationProof {
+    /// Encoded proof length: `x` and `y` of the scaled message hash,
+    /// `c` and `s`, 32 bytes each.
+    pub const BYTES_LEN: usize = 4 * 32;
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            self.scaled_message_hash.x.to_bytes(),
+            self.scaled_message_hash.y.to_bytes(),
+            self.c.to_bytes(),
+            self.s.to_bytes(),
+        ]
+        .concat()
+    }
+
+    /// Decodes the proof. `None` if the length is wrong or some of the
+    /// field elements aren't canonical. Whether the point is on the
+    /// curve is checked by [`verify`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTES_LEN {
+            return None;
+        }
+        let mut chunks = bytes.chunks(32);
+        let mut next = || chunks.next().unwrap();
+        let x = Fp::from_bytes(next()).ok()?;
+        let y = Fp::from_bytes(next()).ok()?;
+        Some(Self {
+            scaled_message_hash: CurvePoint::new(x, y, false),
+            c: Fq::from_bytes(next()).ok()?,
+            s: Fq::from_bytes(next()).ok()?,
+        })
+    }
+}
+
+impl VrfOutput {
+    pub fn from_output(output: Fp) -> Self {
+        let mut truncated = [0u8; 32];
+        truncated.copy_from_slice(&output.into_repr().to_bytes_le());
+        truncated[31] &= (1u8 << (VRF_OUTPUT_TRUNCATED_BITS - 248)) - 1;
+        Self {
+            output,
+            truncated: VrfOutputTruncated(truncated),
+        }
+    }
+
+    pub fn fraction(&self) -> f64 {
+        self.truncated.fraction()
+    }
+
+    pub fn satisfies_threshold(&self, stake: u64, total_stake: u64) -> bool {
+        self.truncated.satisfies_threshold(stake, total_stake)
+    }
+}
+
+impl VrfOutputTruncated {
+    /// Parses truncated output from the consensus state. `None` if it's
+    /// longer than 32 bytes or has bits set above the truncation limit.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() > 32 {
+            return None;
+        }
+        let mut truncated = [0u8; 32];
+        truncated[..bytes.len()].copy_from_slice(bytes);
+        if truncated[31] >> (VRF_OUTPUT_TRUNCATED_BITS - 248) != 0 {
+            return None;
+        }
+        Some(Self(truncated))
+    }
+
+    /// Truncated output as a fraction in range `[0, 1)`.
+    pub fn fraction(&self) -> f64 {
+        self.0
+            .iter()
+            .rev()
+            .fold(0f64, |acc, byte| acc * 256f64 + *byte as f64)
+            / 2f64.powi(VRF_OUTPUT_TRUNCATED_BITS as i32)
+    }
+
+    /// Whether the delegator with the given stake wins the slot.
+    ///
+    /// Checks that `output / 2^253 <= 1 - (1/4)^(stake / total_stake)`,
+    /// where the right side is approximated with a Taylor series the
+    /// same way as in Mina, and compared exactly.
+    ///
+    /// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/consensus/vrf/consensus_vrf.ml#L266
+    pub fn satisfies_threshold(&self, stake: u64, total_stake: u64) -> bool {
+        if stake == 0 || total_stake == 0 {
+            return false;
+        }
+        let params = &*THRESHOLD_PARAMS;
+        let precision = params.per_term_precision;
+        let terms = params.coefficients.len();
+
+        // `stake / total_stake` truncated to `precision` bits.
+        let input = (BigInt::from(stake) << precision) / BigInt::from(total_stake);
+
+        // Series multiplied by `2^(precision * (terms + 1))`, so that
+        // all of its terms are integers.
+        let mut rhs = (&params.linear_term_integer_part * &input) << (precision * terms);
+        let mut input_pow = BigInt::from(1u8);
+        for (i, coefficient) in params.coefficients.iter().enumerate() {
+            input_pow *= &input;
+            rhs += (coefficient * &input_pow) << (precision * (terms - i - 1));
+        }
+
+        let lhs = BigInt::from_bytes_le(Sign::Plus, &self.0) << (precision * (terms + 1));
+        lhs <= rhs << VRF_OUTPUT_TRUNCATED_BITS
+    }
+}
+
+static THRESHOLD_PARAMS: Lazy<ThresholdParams> = Lazy::new(ThresholdParams::new);
+
+/// Parameters of the Taylor series for `1 - (1/4)^x`, same as the ones
+/// computed by `Snarky_taylor.Exp.params ~field_size_in_bits:255`.
+///
+/// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/snarky_taylor/snarky_taylor.ml
+struct ThresholdParams {
+    per_term_precision: usize,
+    /// Signed coefficients of `x^1..x^n`, as fixed point numbers with
+    /// `per_term_precision` bits of the fractional part. Integer part
+    /// of the first one is in `linear_term_integer_part`.
+    coefficients: Vec<BigInt>,
+    linear_term_integer_part: BigInt,
+}
+
+impl ThresholdParams {
+    const FIELD_SIZE_IN_BITS: usize = 255;
+    /// Terms of the series used to compute `log(1/4)`.
+    const LOG_TERMS: usize = 100;
+
+    fn new() -> Self {
+        // `|log(1/4)| = sum_{i=1}^{n} (3/4)^i / i` as `num / den`.
+        let den = BigInt::from(4u8).pow(Self::LOG_TERMS as u32) * factorial(Self::LOG_TERMS);
+        let num = (1..=Self::LOG_TERMS)
+            .map(|i| {
+                BigInt::from(3u8).pow(i as u32)
+                    * BigInt::from(4u8).pow((Self::LOG_TERMS - i) as u32)
+                    * (factorial(Self::LOG_TERMS) / BigInt::from(i))
+            })
+            .sum::<BigInt>();
+
+        // Least amount of terms, with which the error is below `2^-k`.
+        let terms_needed = |k: usize| {
+            (1usize..)
+                .find(|&n| factorial(n) * den.pow(n as u32) >= (num.pow(n as u32) << k))
+                .unwrap()
+        };
+        let ceil_log2 = |n: usize| (n - 1).checked_ilog2().map_or(0, |v| v as usize + 1);
+        let bit_params = |k: usize| {
+            let terms = terms_needed(k);
+            (terms, ceil_log2(terms) + k)
+        };
+        let fits_into_field = |(terms, per_term_precision): (usize, usize)| {
+            (terms + 1) * per_term_precision < Self::FIELD_SIZE_IN_BITS
+        };
+
+        // Highest precision with which the computation fits into the field.
+        let mut k = 1;
+        while fits_into_field(bit_params(k + 1)) {
+            k += 1;
+        }
+        let (terms, per_term_precision) = bit_params(k);
+
+        let linear_term_integer_part = &num / &den;
+        let coefficients = (1..=terms)
+            .map(|i| {
+                let (num, den) = (num.pow(i as u32), den.pow(i as u32) * factorial(i));
+                let mut c = (num << per_term_precision) / den;
+                if i == 1 {
+                    c -= &linear_term_integer_part << per_term_precision;
+                }
+                match i % 2 {
+                    0 => -c,
+                    _ => c,
+                }
+            })
+            .collect();
+
+        Self {
+            per_term_precision,
+            coefficients,
+            linear_term_integer_part,
+        }
+    }
+}
+
+fn factorial(n: usize) -> BigInt {
+    (1..=n).map(BigInt::from).product()
+}
+
+/// Evaluates VRF for the given message with the secret key of the block
+/// producer.
+pub fn evaluate(keypair: &Keypair, message: &VrfMessage, ledger_depth: u64) -> VrfOutput {
+    let point = message
+        .to_group(ledger_depth)
+        .mul(*keypair.secret.scalar())
+        .into_affine();
+    VrfOutput::from_output(message.output_hash(ledger_depth, &point))
+}
+
+/// Same as [`evaluate`], but also creates the proof of the evaluation,
+/// which can be checked with [`verify`].
+pub fn evaluate_with_proof(
+    keypair: &Keypair,
+    message: &VrfMessage,
+    ledger_depth: u64,
+) -> (VrfOutput, VrfEvaluationProof) {
+    let secret = *keypair.secret.scalar();
+    let generator = CurvePoint::prime_subgroup_generator();
+    let message_hash = message.to_group(ledger_depth);
+    let scaled_message_hash = message_hash.mul(secret).into_affine();
+
+    let nonce = Fq::rand(&mut rand::thread_rng());
+    let challenge = dleq_challenge(
+        keypair.public.point(),
+        &message_hash,
+        &scaled_message_hash,
+        &generator.mul(nonce).into_affine(),
+        &message_hash.mul(nonce).into_affine(),
+    );
+    let proof = VrfEvaluationProof {
+        scaled_message_hash,
+        c: challenge,
+        s: nonce + challenge * secret,
+    };
+    let output = VrfOutput::from_output(message.output_hash(ledger_depth, &scaled_message_hash));
+    (output, proof)
+}
+
+/// Verifies VRF evaluation of another block producer and returns its
+/// output, or `None` if the proof is invalid.
+pub fn verify(
+    public_key: &PubKey,
+    message: &VrfMessage,
+    ledger_depth: u64,
+    proof: &VrfEvaluationProof,
+) -> Option<VrfOutput> {
+    let public_key = public_key.point();
+    let generator = CurvePoint::prime_subgroup_generator();
+    let message_hash = message.to_group(ledger_depth);
+    let scaled_message_hash = &proof.scaled_message_hash;
+    if !scaled_message_hash.is_on_curve() || scaled_message_hash.is_zero() {
+        return None;
+    }
+
+    // g^s / pk^c == g^r and h^s / (h^sk)^c == h^r, if the proof is valid.
+    let generator_nonce = generator.mul(proof.s) - public_key.mul(proof.c);
+    let message_hash_nonce = message_hash.mul(proof.s) - scaled_message_hash.mul(proof.c);
+    let challenge = dleq_challenge(
+        public_key,
+        &message_hash,
+        scaled_message_hash,
+        &generator_nonce.into_affine(),
+        &message_hash_nonce.into_affine(),
+    );
+    if challenge != proof.c {
+        return None;
+    }
+    Some(VrfOutput::from_output(
+        message.output_hash(ledger_depth, scaled_message_hash),
+    ))
+}
+
+fn dleq_challenge(
+    public_key: &CurvePoint,
+    message_hash: &CurvePoint,
+    scaled_message_hash: &CurvePoint,
+    generator_nonce: &CurvePoint,
+    message_hash_nonce: &CurvePoint,
+) -> Fq {
+    let fields = [
+        public_key,
+        message_hash,
+        scaled_message_hash,
+        generator_nonce,
+        message_hash_nonce,
+    ]
+    .into_iter()
+    .flat_map(|point| [point.x, point.y])
+    .collect::<Vec<_>>();
+    let hash = hash_with_kimchi("MinaVrfEvaluation", &fields);
+    // Base field modulus is smaller than the scalar field one.
+    Fq::from_repr(hash.into_repr()).unwrap()
+}
+
+/// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/consensus/proof_of_stake.ml#L562
+pub fn epoch_seed_update(seed: Fp, vrf_output: Fp) -> Fp {
+    hash_with_kimchi("MinaEpochSeed", &[seed, vrf_output])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncated_output() {
+        let output = VrfOutput::from_output(-Fp::from(1u64));
+        assert_eq!(output.truncated.0[31] >> 5, 0);
+        assert!(output.fraction() < 1f64);
+
+        let output = VrfOutput::from_output(Fp::from(0u64));
+        assert_eq!(output.fraction(), 0f64);
+        assert!(output.satisfies_threshold(1, 100));
+        assert!(!output.satisfies_threshold(0, 100));
+        assert!(!output.satisfies_threshold(1, 0));
+
+        let parsed = VrfOutputTruncated::from_bytes(&output.truncated.0).unwrap();
+        assert_eq!(parsed, output.truncated);
+        assert!(VrfOutputTruncated::from_bytes(&[0xff; 32]).is_none());
+    }
+
+    #[test]
+    fn test_threshold() {
+        let params = &*THRESHOLD_PARAMS;
+        assert_eq!(params.linear_term_integer_part, BigInt::from(1u8));
+
+        // Output which is `fraction` of the maximum one.
+        let output = |fraction: f64| {
+            let n = BigInt::from((fraction * 2f64.powi(53)) as u64) << (253 - 53);
+            let (_, bytes) = n.to_bytes_le();
+            VrfOutputTruncated::from_bytes(&bytes).unwrap()
+        };
+        for (stake, total_stake) in [(1, 100), (1, 3), (1, 2), (99, 100), (1, 1)] {
+            let threshold = 1f64 - 0.25f64.powf(stake as f64 / total_stake as f64);
+            assert!(output(threshold - 1e-6).satisfies_threshold(stake, total_stake));
+            assert!(!output(threshold + 1e-6).satisfies_threshold(stake, total_stake));
+        }
+        assert!(!output(0.9).satisfies_threshold(1, 1));
+    }
+
+    #[test]
+    fn test_evaluation_proof() {
+        let keypair = Keypair::rand(&mut rand::thread_rng());
+        let message = VrfMessage {
+            global_slot: 42,
+            epoch_seed: Fp::from(7u64),
+            delegator_index: AccountIndex(3),
+        };
+        let (output, proof) = evaluate_with_proof(&keypair, &message, 35);
+        assert_eq!(output.output, evaluate(&keypair, &message, 35).output);
+
+        let verified = verify(&keypair.public, &message, 35, &proof).unwrap();
+        assert_eq!(verified.output, output.output);
+
+        let decoded = VrfEvaluationProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(VrfEvaluationProof::from_bytes(&proof.to_bytes()[1..]).is_none());
+
+        let other = Keypair::rand(&mut rand::thread_rng());
+        assert!(verify(&other.public, &message, 35, &proof).is_none());
+
+        let message = VrfMessage {
+            global_slot: 43,
+            ..message
+        };
+        assert!(verify(&keypair.public, &message, 35, &proof).is_none());
+    }
+}
@@ -250,20 +250,20 @@ mina_rpc!(GetNodeStatusV1, "get_node_status", 1, (), RpcResult<NodeStatusV1, cor
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, BinProtRead, BinProtWrite)]
 pub struct NodeStatusV2 {
-    node_ip_addr: InetAddrV1Versioned,
-    node_peer_id: v1::NetworkPeerPeerIdStableV1Versioned,
-    sync_status: v1::SyncStatusTStableV1Versioned,
-    peers: Vec<v1::NetworkPeerPeerIdStableV1Versioned>,
-    block_producers: Vec<v1::PublicKeyCompressedStableV1Versioned>,
-    protocol_state_hash: StateHashV1Versioned,
-    ban_statuses: Vec<(
+    pub node_ip_addr: InetAddrV1Versioned,
+    pub node_peer_id: v1::NetworkPeerPeerIdStableV1Versioned,
+    pub sync_status: v1::SyncStatusTStableV1Versioned,
+    pub peers: Vec<v1::NetworkPeerPeerIdStableV1Versioned>,
+    pub block_producers: Vec<v1::PublicKeyCompressedStableV1Versioned>,
+    pub protocol_state_hash: StateHashV1Versioned,
+    pub ban_statuses: Vec<(
         v1::NetworkPeerPeerIdStableV1Versioned,
         v1::TrustSystemPeerStatusStableV1Versioned,
     )>,
-    k_block_hashes_and_timestamps: Vec<(StateHashV1Versioned, CharString)>,
-    git_commit: CharString,
-    uptime_minutes: i32,
-    block_height_opt: Option<i32>,
+    pub k_block_hashes_and_timestamps: Vec<(StateHashV1Versioned, CharString)>,
+    pub git_commit: CharString,
+    pub uptime_minutes: i32,
+    pub block_height_opt: Option<i32>,
 }
 mina_rpc!(GetNodeStatusV2, "get_node_status", 2, (), RpcResult<NodeStatusV2, core::Error>);
 
@@ -271,6 +271,24 @@ mina_rpc!(GetEpochLedgerV1, "get_epoch_ledger", 1, LedgerHashV1Versioned, RpcRes
 
 mina_rpc!(GetEpochLedgerV2, "get_epoch_ledger", 2, LedgerHashV1, RpcResult<MinaBaseSparseLedgerBaseStableV2, CharString>);
 
+// Snark worker requesting a job from the coordinator.
+mina_rpc!(
+    SnarkWorkerGetWorkV2,
+    "get_work",
+    2,
+    (),
+    v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse
+);
+
+// Snark worker submitting the produced work to the coordinator.
+mina_rpc!(
+    SnarkWorkerSubmitWorkV2,
+    "submit_work",
+    2,
+    v2::SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery,
+    ()
+);
+
 /// Registry for uniformly JSONifying RPC payload data.
 ///
 /// ```
@@ -12,8 +12,8 @@ pub type PublicKeyCompressedStableV1Versioned = NonzeroCurvePointV1Versioned;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, BinProtRead, BinProtWrite)]
 pub struct NonzeroCurvePointV1 {
-    x: crate::bigint::BigInt,
-    is_odd: bool,
+    pub x: crate::bigint::BigInt,
+    pub is_odd: bool,
 }
 
 /// Location: [src/lib/parallel_scan/parallel_scan.ml:226:6](https://github.com/openmina/mina/blob/da4c511501876adff40f3e1281392fedd121d607/src/lib/parallel_scan/parallel_scan.ml#L226)
@@ -17,8 +17,8 @@ use mina_p2p_messages::v2::{
 use node::core::channels::{mpsc, oneshot};
 use node::event_source::Event;
 use node::external_snark_worker::{
-    ExternalSnarkWorkerError, ExternalSnarkWorkerEvent, ExternalSnarkWorkerService,
-    ExternalSnarkWorkerWorkError, SnarkWorkSpec,
+    ExternalSnarkWorkerError, ExternalSnarkWorkerEvent, ExternalSnarkWorkerId,
+    ExternalSnarkWorkerService, ExternalSnarkWorkerWorkError, SnarkWorkSpec,
 };
 
 use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
@@ -190,9 +190,28 @@ async fn stderr_reader<R: AsyncRead + Unpin>(r: R) -> Result<(), SnarkerError> {
     Ok(())
 }
 
+/// Sends events of the snark worker, tagged with its id.
+#[derive(Clone)]
+pub struct SnarkWorkerEventSender {
+    worker_id: ExternalSnarkWorkerId,
+    sender: mpsc::UnboundedSender<Event>,
+}
+
+impl SnarkWorkerEventSender {
+    pub fn new(worker_id: ExternalSnarkWorkerId, sender: mpsc::UnboundedSender<Event>) -> Self {
+        Self { worker_id, sender }
+    }
+
+    pub fn send(&self, event: ExternalSnarkWorkerEvent) {
+        _ = self
+            .sender
+            .send(Event::ExternalSnarkWorker(self.worker_id, event));
+    }
+}
+
 macro_rules! send_event {
     ($channel:expr, $event:expr) => {
-        _ = $channel.send(node::event_source::Event::ExternalSnarkWorker($event));
+        $channel.send($event);
     };
 }
 
@@ -201,7 +220,7 @@ impl ExternalSnarkWorkerFacade {
         path: P,
         public_key: NonZeroCurvePoint,
         fee: CurrencyFeeStableV1,
-        event_sender: mpsc::UnboundedSender<Event>,
+        event_sender: SnarkWorkerEventSender,
     ) -> Result<Self, SnarkerError> {
         let (data_chan, mut data_rx) = mpsc::channel(1);
         let (cancel_chan, mut cancel_rx) = mpsc::channel(1);
@@ -382,6 +401,7 @@ impl ExternalSnarkWorkerFacade {
 impl ExternalSnarkWorkerService for NodeService {
     fn start<P: AsRef<OsStr>>(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
         path: P,
         public_key: NonZeroCurvePoint,
         fee: CurrencyFeeStableV1,
@@ -389,43 +409,47 @@ impl ExternalSnarkWorkerService for NodeService {
         if self.replayer.is_some() {
             return Ok(());
         }
-        let cmd_sender =
-            ExternalSnarkWorkerFacade::start(path, public_key, fee, self.event_sender.clone())?;
-        self.snark_worker_sender = Some(cmd_sender);
+        let event_sender = SnarkWorkerEventSender::new(worker_id, self.event_sender.clone());
+        let cmd_sender = ExternalSnarkWorkerFacade::start(path, public_key, fee, event_sender)?;
+        self.snark_workers.insert(worker_id, cmd_sender);
         Ok(())
     }
 
     fn submit(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
         spec: SnarkWorkSpec,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        self.snark_worker_sender
-            .as_mut()
+        self.snark_workers
+            .get_mut(&worker_id)
             .ok_or(SnarkerError::NotRunning)
             .and_then(|sender| sender.submit(spec))?;
         Ok(())
     }
 
-    fn cancel(&mut self) -> Result<(), ExternalSnarkWorkerError> {
+    fn cancel(&mut self, worker_id: ExternalSnarkWorkerId) -> Result<(), ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        self.snark_worker_sender
-            .as_mut()
+        self.snark_workers
+            .get_mut(&worker_id)
             .ok_or(SnarkerError::NotRunning)
             .and_then(|sender| sender.cancel())?;
         Ok(())
     }
 
-    fn kill(&mut self) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
+    fn kill(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+    ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        self.snark_worker_sender
-            .take()
+        self.snark_workers
+            .remove(&worker_id)
             .ok_or(SnarkerError::NotRunning)
             .and_then(|sender| sender.kill())?;
         Ok(())
@@ -449,12 +473,12 @@ mod tests {
     };
 
     use super::super::tracing;
-    use super::ExternalSnarkWorkerFacade;
+    use super::{ExternalSnarkWorkerFacade, SnarkWorkerEventSender};
 
     macro_rules! expect_event {
         ($source:expr, $event:pat) => {
             let result = $source.recv().await.expect("failed to receive an event");
-            let Event::ExternalSnarkWorker(result) = result else {
+            let Event::ExternalSnarkWorker(_, result) = result else {
                 panic!("unexpected event kind");
             };
             let $event = result else {
@@ -483,7 +507,7 @@ mod tests {
                     10_u64.into(),
                 ),
             ),
-            event_tx,
+            SnarkWorkerEventSender::new(0, event_tx),
         )
         .unwrap();
 
@@ -516,8 +540,13 @@ mod tests {
         let (public_key, fee, instances) = read_input(&mut r);
 
         let (event_tx, mut event_rx) = mpsc::unbounded_channel();
-        let mut cmd_sender =
-            ExternalSnarkWorkerFacade::start(mina_exe_path(), public_key, fee, event_tx).unwrap();
+        let mut cmd_sender = ExternalSnarkWorkerFacade::start(
+            mina_exe_path(),
+            public_key,
+            fee,
+            SnarkWorkerEventSender::new(0, event_tx),
+        )
+        .unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
 
@@ -535,8 +564,13 @@ mod tests {
         let (public_key, fee, instances) = read_input(&mut r);
 
         let (event_tx, mut event_rx) = mpsc::unbounded_channel();
-        let mut cmd_sender =
-            ExternalSnarkWorkerFacade::start(mina_exe_path(), public_key, fee, event_tx).unwrap();
+        let mut cmd_sender = ExternalSnarkWorkerFacade::start(
+            mina_exe_path(),
+            public_key,
+            fee,
+            SnarkWorkerEventSender::new(0, event_tx),
+        )
+        .unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
 
@@ -566,8 +600,13 @@ mod tests {
         let (public_key, fee, instances) = read_input(&mut r);
 
         let (event_tx, mut event_rx) = mpsc::unbounded_channel();
-        let mut cmd_sender =
-            ExternalSnarkWorkerFacade::start(mina_exe_path(), public_key, fee, event_tx).unwrap();
+        let mut cmd_sender = ExternalSnarkWorkerFacade::start(
+            mina_exe_path(),
+            public_key,
+            fee,
+            SnarkWorkerEventSender::new(0, event_tx),
+        )
+        .unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
 
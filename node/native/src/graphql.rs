@@ -1,14 +1,131 @@
-use juniper::{EmptyMutation, EmptySubscription, GraphQLEnum, RootNode};
+use std::collections::BTreeSet;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures::Stream;
+use juniper::{FieldResult, GraphQLEnum, GraphQLInputObject, GraphQLObject, RootNode};
+use juniper_graphql_ws::ConnectionConfig;
+use juniper_warp::subscriptions::serve_graphql_ws;
+use ledger::scan_state::currency::{Amount, Fee, Nonce, Slot};
+use ledger::scan_state::transaction_logic::signed_command::{
+    Body, PaymentPayload, SignedCommand, SignedCommandPayload, StakeDelegationPayload,
+};
+use ledger::scan_state::transaction_logic::Memo;
+use ledger::TokenId;
+use mina_p2p_messages::v2::{
+    ConsensusProofOfStakeDataConsensusStateValueStableV2, MinaBaseAccountTimingStableV2,
+    MinaBaseEpochLedgerValueStableV1, MinaBaseSignedCommandPayloadBodyStableV2,
+    MinaBaseSignedCommandStableV2, MinaBaseStakeDelegationStableV2,
+    MinaBaseTransactionStatusFailureStableV2, MinaBaseTransactionStatusStableV2,
+    MinaBaseUserCommandStableV2, StateHash, TokenIdKeyHash, TransactionSnarkWorkTStableV2,
+};
 use node::{
-    rpc::{RpcRequest, RpcSyncStatsGetResponse, SyncStatsQuery},
-    stats::sync::SyncKind,
+    account::AccountPublicKey,
+    p2p::channels::rpc::P2pNodeSyncStatus,
+    rpc::{
+        RpcAccount, RpcAccountGetQuery, RpcAccountGetResponse, RpcBestChainGetResponse,
+        RpcBlockGetQuery, RpcBlockGetResponse, RpcEvent, RpcEventKind, RpcNodeStatusGetResponse,
+        RpcRequest, RpcSnarkPoolGetResponse, RpcSubscribeQuery, RpcTransactionPoolGetResponse,
+        RpcTransactionSendResponse,
+    },
 };
-use warp::{Filter, Rejection, Reply};
+use openmina_core::block::ArcBlockWithHash;
+use openmina_core::snark::SnarkJobId;
+use serde::Serialize;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
-struct Context(super::RpcSender);
+#[derive(Clone)]
+struct Context {
+    rpc_sender: super::RpcSender,
+    start_time: Instant,
+}
 
 impl juniper::Context for Context {}
 
+impl Context {
+    async fn request<T>(&self, req: RpcRequest) -> FieldResult<T>
+    where
+        T: 'static + Send + Serialize,
+    {
+        self.rpc_sender
+            .oneshot_request(req)
+            .await
+            .ok_or_else(|| "node didn't respond to the request".into())
+    }
+
+    async fn account_get(&self, query: RpcAccountGetQuery) -> FieldResult<Option<RpcAccount>> {
+        let account: RpcAccountGetResponse = self.request(RpcRequest::AccountGet(query)).await?;
+        Ok(account)
+    }
+
+    /// Signs the command with the signature provided by the client and
+    /// adds it to the transaction pool.
+    async fn signed_command_send(
+        &self,
+        common: UserCommandCommonInput<'_>,
+        body: Body,
+        signature: Option<SignatureInput>,
+    ) -> FieldResult<UserCommand> {
+        let signature = signature
+            .ok_or("signature is required, the node doesn't hold any private keys")?
+            .signature()?;
+        let from: AccountPublicKey = parse(common.from, "public key")?;
+        let nonce = match common.nonce {
+            Some(nonce) => parse(nonce, "nonce")?,
+            None => {
+                let query = RpcAccountGetQuery {
+                    public_key: from.clone(),
+                    token_id: None,
+                };
+                self.account_get(query)
+                    .await?
+                    .ok_or("sender account not found")?
+                    .inferred_nonce
+            }
+        };
+        let valid_until = common
+            .valid_until
+            .map(|slot| parse(slot, "global slot"))
+            .transpose()?;
+
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(parse(common.fee, "fee")?),
+            from.as_ref().into(),
+            Nonce::from_u32(nonce),
+            valid_until.map(Slot::from_u32),
+            Memo::create_from_string(common.memo.unwrap_or_default())?,
+            body,
+        );
+        let cmd = SignedCommand {
+            payload,
+            signer: from.as_ref().into(),
+            signature,
+        };
+        let transaction = MinaBaseUserCommandStableV2::SignedCommand((&cmd).into());
+
+        let req = RpcRequest::TransactionSend(Box::new(transaction.clone()));
+        match self.request(req).await? {
+            RpcTransactionSendResponse::Ok { .. } => UserCommand::new(&transaction, None)
+                .ok_or_else(|| "failed to hash the transaction".into()),
+            RpcTransactionSendResponse::Rejected { error } => {
+                Err(format!("transaction rejected: {error}").into())
+            }
+        }
+    }
+}
+
+fn parse<T: FromStr>(value: &str, what: &str) -> FieldResult<T> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {what}: {value}").into())
+}
+
+fn default_token_id() -> String {
+    TokenIdKeyHash::from(TokenId::default()).to_string()
+}
+
 #[derive(Clone, Copy, Debug, GraphQLEnum)]
 enum SyncStatus {
     CONNECTING,
@@ -19,61 +136,517 @@ enum SyncStatus {
     CATCHUP,
 }
 
+impl From<P2pNodeSyncStatus> for SyncStatus {
+    fn from(value: P2pNodeSyncStatus) -> Self {
+        match value {
+            P2pNodeSyncStatus::Connecting => Self::CONNECTING,
+            P2pNodeSyncStatus::Listening => Self::LISTENING,
+            P2pNodeSyncStatus::Offline => Self::OFFLINE,
+            P2pNodeSyncStatus::Bootstrap => Self::BOOTSTRAP,
+            P2pNodeSyncStatus::Synced => Self::SYNCED,
+            P2pNodeSyncStatus::Catchup => Self::CATCHUP,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
-struct ProtocolState {
-    consensus_state: ConsensusState,
-    blockchain_state: BlockchainState,
+struct Block(ArcBlockWithHash);
+
+impl Block {
+    fn consensus_state(&self) -> &ConsensusProofOfStakeDataConsensusStateValueStableV2 {
+        &self.0.header().protocol_state.body.consensus_state
+    }
+}
+
+#[juniper::graphql_object(context = Context)]
+impl Block {
+    fn state_hash(&self) -> String {
+        self.0.hash().to_string()
+    }
+
+    fn creator(&self) -> String {
+        self.consensus_state().block_creator.to_string()
+    }
+
+    fn protocol_state(&self) -> ProtocolState {
+        ProtocolState(self.0.clone())
+    }
+
+    fn transactions(&self) -> Transactions {
+        Transactions(self.0.clone())
+    }
+
+    fn command_transaction_count(&self) -> i32 {
+        self.0.commands_iter().count() as i32
+    }
+
+    fn snark_jobs(&self) -> Vec<CompletedWork> {
+        self.0
+            .completed_works_iter()
+            .map(CompletedWork::from)
+            .collect()
+    }
 }
 
+#[derive(Clone, Debug)]
+struct ProtocolState(ArcBlockWithHash);
+
 #[juniper::graphql_object(context = Context)]
 impl ProtocolState {
-    fn consensus_state(&self) -> &ConsensusState {
-        &self.consensus_state
+    fn previous_state_hash(&self) -> String {
+        self.0.pred_hash().to_string()
     }
 
-    fn blockchain_state(&self) -> &BlockchainState {
-        &self.blockchain_state
+    fn blockchain_state(&self) -> BlockchainState {
+        BlockchainState(self.0.clone())
+    }
+
+    fn consensus_state(&self) -> ConsensusState {
+        ConsensusState(self.0.clone())
     }
 }
 
 #[derive(Clone, Debug)]
-struct ConsensusState {
-    block_height: i32,
+struct BlockchainState(ArcBlockWithHash);
+
+#[juniper::graphql_object(context = Context)]
+impl BlockchainState {
+    /// Block timestamp in milliseconds.
+    fn date(&self) -> String {
+        let state = &self.0.header().protocol_state.body.blockchain_state;
+        state.timestamp.as_u64().to_string()
+    }
+
+    fn utc_date(&self) -> String {
+        self.date()
+    }
+
+    fn snarked_ledger_hash(&self) -> String {
+        self.0.snarked_ledger_hash().to_string()
+    }
+
+    fn staged_ledger_hash(&self) -> String {
+        self.0.staged_ledger_hash().to_string()
+    }
+
+    fn genesis_ledger_hash(&self) -> String {
+        let state = &self.0.header().protocol_state.body.blockchain_state;
+        state.genesis_ledger_hash.to_string()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ConsensusState(ArcBlockWithHash);
+
+impl ConsensusState {
+    fn state(&self) -> &ConsensusProofOfStakeDataConsensusStateValueStableV2 {
+        &self.0.header().protocol_state.body.consensus_state
+    }
 }
 
 #[juniper::graphql_object(context = Context)]
 impl ConsensusState {
-    fn block_height(&self) -> i32 {
-        self.block_height
+    fn block_height(&self) -> String {
+        self.0.height().to_string()
+    }
+
+    fn blockchain_length(&self) -> String {
+        self.0.height().to_string()
+    }
+
+    fn epoch_count(&self) -> String {
+        self.state().epoch_count.as_u32().to_string()
+    }
+
+    fn min_window_density(&self) -> String {
+        self.state().min_window_density.as_u32().to_string()
+    }
+
+    fn total_currency(&self) -> String {
+        self.state().total_currency.as_u64().to_string()
+    }
+
+    fn epoch(&self) -> String {
+        let slot = &self.state().curr_global_slot;
+        (slot.slot_number.as_u32() / slot.slots_per_epoch.as_u32()).to_string()
+    }
+
+    /// Slot within the epoch.
+    fn slot(&self) -> String {
+        let slot = &self.state().curr_global_slot;
+        (slot.slot_number.as_u32() % slot.slots_per_epoch.as_u32()).to_string()
+    }
+
+    fn slot_since_genesis(&self) -> String {
+        self.0.global_slot().to_string()
+    }
+
+    fn staking_epoch_data(&self) -> EpochData {
+        let data = &self.state().staking_epoch_data;
+        EpochData::new(
+            &data.ledger,
+            data.seed.to_string(),
+            &data.start_checkpoint,
+            &data.lock_checkpoint,
+            data.epoch_length.as_u32(),
+        )
+    }
+
+    fn next_epoch_data(&self) -> EpochData {
+        let data = &self.state().next_epoch_data;
+        EpochData::new(
+            &data.ledger,
+            data.seed.to_string(),
+            &data.start_checkpoint,
+            &data.lock_checkpoint,
+            data.epoch_length.as_u32(),
+        )
+    }
+
+    fn has_ancestor_in_same_checkpoint_window(&self) -> bool {
+        self.state().has_ancestor_in_same_checkpoint_window
+    }
+
+    fn block_creator(&self) -> String {
+        self.state().block_creator.to_string()
+    }
+
+    fn block_stake_winner(&self) -> String {
+        self.state().block_stake_winner.to_string()
+    }
+
+    /// Spelled the same way as in the Mina daemon's schema.
+    fn coinbase_receiever(&self) -> String {
+        self.state().coinbase_receiver.to_string()
+    }
+
+    fn supercharged_coinbase(&self) -> bool {
+        self.state().supercharge_coinbase
     }
 }
 
-#[derive(Clone, Debug)]
-struct BlockchainState {
-    snarked_ledger_hash: String,
+#[derive(Clone, Debug, GraphQLObject)]
+#[graphql(context = Context)]
+struct EpochData {
+    ledger: EpochLedger,
+    seed: String,
+    start_checkpoint: String,
+    lock_checkpoint: String,
+    epoch_length: String,
+}
+
+impl EpochData {
+    fn new(
+        ledger: &MinaBaseEpochLedgerValueStableV1,
+        seed: String,
+        start_checkpoint: &StateHash,
+        lock_checkpoint: &StateHash,
+        epoch_length: u32,
+    ) -> Self {
+        Self {
+            ledger: EpochLedger {
+                hash: ledger.hash.to_string(),
+                total_currency: ledger.total_currency.as_u64().to_string(),
+            },
+            seed,
+            start_checkpoint: start_checkpoint.to_string(),
+            lock_checkpoint: lock_checkpoint.to_string(),
+            epoch_length: epoch_length.to_string(),
+        }
+    }
 }
 
+#[derive(Clone, Debug, GraphQLObject)]
+#[graphql(context = Context)]
+struct EpochLedger {
+    hash: String,
+    total_currency: String,
+}
+
+#[derive(Clone, Debug)]
+struct Transactions(ArcBlockWithHash);
+
 #[juniper::graphql_object(context = Context)]
-impl BlockchainState {
-    fn snarked_ledger_hash(&self) -> &str {
-        &self.snarked_ledger_hash
+impl Transactions {
+    /// Signed commands of the block. Zkapp commands aren't included.
+    fn user_commands(&self) -> Vec<UserCommand> {
+        self.0
+            .commands_iter()
+            .filter_map(|cmd| UserCommand::new(&cmd.data, Some(&cmd.status)))
+            .collect()
     }
 }
 
 #[derive(Clone, Debug)]
-struct BestChain {
-    state_hash: String,
-    protocol_state: ProtocolState,
+struct UserCommand {
+    hash: String,
+    command: MinaBaseSignedCommandStableV2,
+    failure_reason: Option<String>,
+}
+
+impl UserCommand {
+    /// `None` for zkapp commands.
+    fn new(
+        command: &MinaBaseUserCommandStableV2,
+        status: Option<&MinaBaseTransactionStatusStableV2>,
+    ) -> Option<Self> {
+        let MinaBaseUserCommandStableV2::SignedCommand(signed) = command else {
+            return None;
+        };
+        let failure_reason = match status {
+            Some(MinaBaseTransactionStatusStableV2::Failed(failures)) => {
+                failures.0.iter().flatten().next().map(failure_to_string)
+            }
+            _ => None,
+        };
+        Some(Self {
+            hash: command.hash().ok()?.to_string(),
+            command: signed.clone(),
+            failure_reason,
+        })
+    }
+}
+
+/// Failure in the format of the Mina daemon, e.g. `Receiver_not_present`.
+fn failure_to_string(failure: &MinaBaseTransactionStatusFailureStableV2) -> String {
+    let debug = format!("{failure:?}");
+    let name = debug.split('(').next().unwrap_or_default();
+    let mut result = String::with_capacity(name.len() + 8);
+    for (i, c) in name.chars().enumerate() {
+        if i == 0 {
+            result.push(c);
+        } else if c.is_ascii_uppercase() {
+            result.push('_');
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
 }
 
 #[juniper::graphql_object(context = Context)]
-impl BestChain {
-    fn state_hash(&self) -> &str {
-        &self.state_hash
+impl UserCommand {
+    fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    fn kind(&self) -> &str {
+        match &self.command.payload.body {
+            MinaBaseSignedCommandPayloadBodyStableV2::Payment(_) => "PAYMENT",
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(_) => "STAKE_DELEGATION",
+        }
+    }
+
+    fn nonce(&self) -> i32 {
+        self.command.payload.common.nonce.as_u32() as i32
     }
 
-    fn protocol_state(&self) -> &ProtocolState {
-        &self.protocol_state
+    fn from(&self) -> String {
+        self.command.payload.common.fee_payer_pk.to_string()
+    }
+
+    /// Receiver of the payment, or the new delegate.
+    fn to(&self) -> String {
+        match &self.command.payload.body {
+            MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => {
+                payment.receiver_pk.to_string()
+            }
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+            ) => new_delegate.to_string(),
+        }
+    }
+
+    fn amount(&self) -> String {
+        match &self.command.payload.body {
+            MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => {
+                payment.amount.as_u64().to_string()
+            }
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(_) => "0".to_owned(),
+        }
+    }
+
+    fn fee(&self) -> String {
+        self.command.payload.common.fee.as_u64().to_string()
+    }
+
+    fn token(&self) -> String {
+        default_token_id()
+    }
+
+    fn fee_token(&self) -> String {
+        default_token_id()
+    }
+
+    fn valid_until(&self) -> String {
+        self.command.payload.common.valid_until.as_u32().to_string()
+    }
+
+    fn memo(&self) -> String {
+        Memo::from(&self.command.payload.common.memo).to_base58check()
+    }
+
+    fn is_delegation(&self) -> bool {
+        matches!(
+            self.command.payload.body,
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(_)
+        )
+    }
+
+    fn failure_reason(&self) -> Option<&str> {
+        self.failure_reason.as_deref()
+    }
+}
+
+#[derive(Clone, Debug, GraphQLObject)]
+#[graphql(context = Context)]
+struct CompletedWork {
+    prover: String,
+    fee: String,
+    /// Ids of the snark jobs, in the same format as in the node's http
+    /// api, instead of the integer ids used by the Mina daemon.
+    work_ids: Vec<String>,
+}
+
+impl From<&TransactionSnarkWorkTStableV2> for CompletedWork {
+    fn from(work: &TransactionSnarkWorkTStableV2) -> Self {
+        Self {
+            prover: work.prover.to_string(),
+            fee: work.fee.as_u64().to_string(),
+            work_ids: vec![SnarkJobId::from(&work.proofs).to_string()],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Account(RpcAccount);
+
+#[juniper::graphql_object(context = Context)]
+impl Account {
+    fn public_key(&self) -> String {
+        self.0.account.public_key.to_string()
+    }
+
+    fn token_id(&self) -> String {
+        self.0.account.token_id.to_string()
+    }
+
+    fn balance(&self) -> AnnotatedBalance {
+        AnnotatedBalance {
+            total: self.0.account.balance.as_u64().to_string(),
+            block_height: self.0.block_height.to_string(),
+            state_hash: Some(self.0.state_hash.to_string()),
+        }
+    }
+
+    fn nonce(&self) -> Option<String> {
+        Some(self.0.account.nonce.as_u32().to_string())
+    }
+
+    fn inferred_nonce(&self) -> Option<String> {
+        Some(self.0.inferred_nonce.to_string())
+    }
+
+    fn delegate(&self) -> Option<String> {
+        self.0.account.delegate.as_ref().map(ToString::to_string)
+    }
+
+    fn voting_for(&self) -> Option<String> {
+        Some(self.0.account.voting_for.to_string())
+    }
+
+    fn token_symbol(&self) -> Option<String> {
+        String::try_from(&self.0.account.token_symbol.0).ok()
+    }
+
+    fn zkapp_uri(&self) -> Option<String> {
+        let zkapp = self.0.account.zkapp.as_ref()?;
+        String::try_from(&zkapp.zkapp_uri.0).ok()
+    }
+
+    fn timing(&self) -> AccountTiming {
+        match &self.0.account.timing {
+            MinaBaseAccountTimingStableV2::Untimed => AccountTiming::default(),
+            MinaBaseAccountTimingStableV2::Timed {
+                initial_minimum_balance,
+                cliff_time,
+                cliff_amount,
+                vesting_period,
+                vesting_increment,
+            } => AccountTiming {
+                initial_minimum_balance: Some(initial_minimum_balance.as_u64().to_string()),
+                cliff_time: Some(cliff_time.as_u32().to_string()),
+                cliff_amount: Some(cliff_amount.as_u64().to_string()),
+                vesting_period: Some(vesting_period.as_u32().to_string()),
+                vesting_increment: Some(vesting_increment.as_u64().to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, GraphQLObject)]
+#[graphql(context = Context)]
+struct AnnotatedBalance {
+    total: String,
+    block_height: String,
+    state_hash: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, GraphQLObject)]
+#[graphql(context = Context)]
+struct AccountTiming {
+    initial_minimum_balance: Option<String>,
+    cliff_time: Option<String>,
+    cliff_amount: Option<String>,
+    vesting_period: Option<String>,
+    vesting_increment: Option<String>,
+}
+
+#[derive(Clone, Debug, GraphQLObject)]
+#[graphql(context = Context)]
+struct DaemonStatus {
+    sync_status: SyncStatus,
+    blockchain_length: Option<i32>,
+    highest_block_length_received: Option<i32>,
+    uptime_secs: i32,
+    state_hash: Option<String>,
+    ledger_merkle_root: Option<String>,
+    commit_id: String,
+    peers: Vec<Peer>,
+    block_production_keys: Vec<String>,
+    snark_worker: Option<String>,
+    snark_work_fee: Option<String>,
+}
+
+#[derive(Clone, Debug, GraphQLObject)]
+#[graphql(context = Context)]
+struct Peer {
+    peer_id: String,
+    host: Option<String>,
+    libp2p_port: Option<i32>,
+}
+
+impl Peer {
+    /// Host and port are taken from the peer's address, which is either
+    /// a multiaddr (`/ip4/<host>/tcp/<port>`) or a signaling url
+    /// (`/<peer id>/http/<host>/<port>`).
+    fn new(peer_id: String, address: Option<&str>) -> Self {
+        let mut parts = address
+            .unwrap_or_default()
+            .split('/')
+            .skip_while(|part| {
+                !matches!(
+                    *part,
+                    "ip4" | "ip6" | "dns" | "dns4" | "dns6" | "http" | "https"
+                )
+            })
+            .skip(1);
+        Self {
+            peer_id,
+            host: parts.next().map(ToOwned::to_owned),
+            libp2p_port: parts.find_map(|part| part.parse().ok()),
+        }
     }
 }
 
@@ -82,70 +655,581 @@ struct Query;
 
 #[juniper::graphql_object(context = Context)]
 impl Query {
-    async fn sync_status(context: &Context) -> SyncStatus {
-        let state: RpcSyncStatsGetResponse = context
-            .0
-            .oneshot_request(RpcRequest::SyncStatsGet(SyncStatsQuery { limit: Some(1) }))
-            .await
-            .unwrap();
+    async fn sync_status(context: &Context) -> FieldResult<SyncStatus> {
+        let status: RpcNodeStatusGetResponse = context.request(RpcRequest::NodeStatusGet).await?;
+        Ok(status.sync_status.into())
+    }
 
-        if let Some(state) = state.as_ref().and_then(|s| s.first()) {
-            if state.synced.is_some() {
-                SyncStatus::SYNCED
-            } else {
-                match &state.kind {
-                    SyncKind::Bootstrap => SyncStatus::BOOTSTRAP,
-                    SyncKind::Catchup => SyncStatus::CATCHUP,
-                }
-            }
-        } else {
-            SyncStatus::LISTENING
-        }
+    async fn daemon_status(context: &Context) -> FieldResult<DaemonStatus> {
+        let status: RpcNodeStatusGetResponse = context.request(RpcRequest::NodeStatusGet).await?;
+        let best_tip = status.best_tip.as_ref();
+        Ok(DaemonStatus {
+            sync_status: status.sync_status.into(),
+            blockchain_length: best_tip.map(|best_tip| best_tip.height as i32),
+            highest_block_length_received: status.highest_block_length_received.map(|h| h as i32),
+            uptime_secs: context.start_time.elapsed().as_secs() as i32,
+            state_hash: best_tip.map(|best_tip| best_tip.hash.to_string()),
+            // Mina returns `non_snark.ledger_hash`, not the whole staged ledger hash.
+            ledger_merkle_root: best_tip.map(|best_tip| best_tip.staged_ledger_hash.to_string()),
+            commit_id: status.commit_id,
+            peers: status
+                .peers
+                .iter()
+                .map(|peer| Peer::new(peer.peer_id.to_string(), peer.address.as_deref()))
+                .collect(),
+            block_production_keys: status
+                .block_producer
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            snark_worker: status.snarker.as_ref().map(ToString::to_string),
+            snark_work_fee: status.snark_work_fee.map(|fee| fee.to_string()),
+        })
     }
 
-    async fn best_chain(max_length: i32, context: &Context) -> Vec<BestChain> {
-        let state: RpcSyncStatsGetResponse = context
-            .0
-            .oneshot_request(RpcRequest::SyncStatsGet(SyncStatsQuery {
-                limit: Some(max_length as _),
-            }))
-            .await
-            .unwrap();
-        state
-            .unwrap_or_default()
+    /// Blocks of the best chain, ending with the best tip.
+    async fn best_chain(max_length: Option<i32>, context: &Context) -> FieldResult<Vec<Block>> {
+        let max_length = max_length.map_or(u32::MAX, |v| v.max(0) as u32);
+        let blocks: RpcBestChainGetResponse = context
+            .request(RpcRequest::BestChainGet { max_length })
+            .await?;
+        Ok(blocks.into_iter().map(Block).collect())
+    }
+
+    /// Block of the best chain with the given state hash or height.
+    async fn block(
+        state_hash: Option<String>,
+        height: Option<i32>,
+        context: &Context,
+    ) -> FieldResult<Block> {
+        let query = match (state_hash, height) {
+            (Some(hash), None) => RpcBlockGetQuery::WithHash(parse(&hash, "state hash")?),
+            (None, Some(height)) => RpcBlockGetQuery::WithHeight(height.max(0) as u32),
+            _ => return Err("either stateHash or height must be provided".into()),
+        };
+        let block: RpcBlockGetResponse = context.request(RpcRequest::BlockGet(query)).await?;
+        block.map(Block).ok_or_else(|| "block not found".into())
+    }
+
+    /// Account in the staged ledger of the best tip.
+    async fn account(
+        public_key: String,
+        token: Option<String>,
+        context: &Context,
+    ) -> FieldResult<Option<Account>> {
+        let query = RpcAccountGetQuery {
+            public_key: parse(&public_key, "public key")?,
+            token_id: token.map(|token| parse(&token, "token id")).transpose()?,
+        };
+        Ok(context.account_get(query).await?.map(Account))
+    }
+
+    /// Commands in the transaction pool, optionally only those sent from
+    /// the given account or with the given hashes.
+    async fn pooled_user_commands(
+        public_key: Option<String>,
+        hashes: Option<Vec<String>>,
+        context: &Context,
+    ) -> FieldResult<Vec<UserCommand>> {
+        let public_key = public_key
+            .map(|pk| parse::<AccountPublicKey>(&pk, "public key"))
+            .transpose()?;
+        let transactions: RpcTransactionPoolGetResponse =
+            context.request(RpcRequest::TransactionPoolGet).await?;
+        Ok(transactions
             .into_iter()
-            .filter_map(|x| {
-                let head = x.blocks.first()?;
-                let snarked_ledger_hash = x.ledgers.root?.snarked.hash?;
-                Some(BestChain {
-                    state_hash: head.hash.to_string(),
-                    protocol_state: ProtocolState {
-                        consensus_state: ConsensusState {
-                            block_height: head.height as _,
-                        },
-                        blockchain_state: BlockchainState {
-                            snarked_ledger_hash: snarked_ledger_hash.to_string(),
-                        },
-                    },
+            .filter(|tx| public_key.as_ref().map_or(true, |pk| &tx.fee_payer == pk))
+            .filter(|tx| {
+                hashes
+                    .as_ref()
+                    .map_or(true, |hashes| hashes.contains(&tx.hash.to_string()))
+            })
+            .filter_map(|tx| UserCommand::new(&tx.transaction, None))
+            .collect())
+    }
+
+    /// Completed snark work in the snark pool.
+    async fn snark_pool(context: &Context) -> FieldResult<Vec<CompletedWork>> {
+        let jobs: RpcSnarkPoolGetResponse = context.request(RpcRequest::SnarkPoolGet).await?;
+        Ok(jobs
+            .into_iter()
+            .filter_map(|job| {
+                let snark = job.snark?;
+                Some(CompletedWork {
+                    prover: snark.snarker.to_string(),
+                    fee: snark.fee.as_u64().to_string(),
+                    work_ids: vec![job.id.to_string()],
                 })
             })
-            .collect()
+            .collect())
     }
 }
 
+/// Fields shared by the payment and delegation inputs.
+struct UserCommandCommonInput<'a> {
+    from: &'a str,
+    fee: &'a str,
+    nonce: Option<&'a str>,
+    valid_until: Option<&'a str>,
+    memo: Option<&'a str>,
+}
+
+#[derive(Clone, Debug, GraphQLInputObject)]
+struct SendPaymentInput {
+    from: String,
+    to: String,
+    amount: String,
+    fee: String,
+    valid_until: Option<String>,
+    memo: Option<String>,
+    nonce: Option<String>,
+}
+
+#[derive(Clone, Debug, GraphQLInputObject)]
+struct SendDelegationInput {
+    from: String,
+    to: String,
+    fee: String,
+    valid_until: Option<String>,
+    memo: Option<String>,
+    nonce: Option<String>,
+}
+
+#[derive(Clone, Debug, GraphQLInputObject)]
+struct SignatureInput {
+    field: Option<String>,
+    scalar: Option<String>,
+    raw_signature: Option<String>,
+}
+
+impl SignatureInput {
+    fn signature(&self) -> FieldResult<mina_signer::Signature> {
+        if self.raw_signature.is_some() {
+            return Err("rawSignature isn't supported, use field and scalar".into());
+        }
+        let (Some(field), Some(scalar)) = (&self.field, &self.scalar) else {
+            return Err("both field and scalar of the signature are required".into());
+        };
+        let rx = mina_signer::BaseField::from_str(field)
+            .map_err(|_| format!("invalid signature field: {field}"))?;
+        let s = mina_signer::ScalarField::from_str(scalar)
+            .map_err(|_| format!("invalid signature scalar: {scalar}"))?;
+        Ok(mina_signer::Signature::new(rx, s))
+    }
+}
+
+#[derive(Clone, Debug, GraphQLObject)]
+#[graphql(context = Context)]
+struct SendPaymentPayload {
+    payment: UserCommand,
+}
+
+#[derive(Clone, Debug, GraphQLObject)]
+#[graphql(context = Context)]
+struct SendDelegationPayload {
+    delegation: UserCommand,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Mutation;
+
+#[juniper::graphql_object(context = Context)]
+impl Mutation {
+    async fn send_payment(
+        input: SendPaymentInput,
+        signature: Option<SignatureInput>,
+        context: &Context,
+    ) -> FieldResult<SendPaymentPayload> {
+        let common = UserCommandCommonInput {
+            from: &input.from,
+            fee: &input.fee,
+            nonce: input.nonce.as_deref(),
+            valid_until: input.valid_until.as_deref(),
+            memo: input.memo.as_deref(),
+        };
+        let to: AccountPublicKey = parse(&input.to, "public key")?;
+        let body = Body::Payment(PaymentPayload {
+            receiver_pk: to.as_ref().into(),
+            amount: Amount::from_u64(parse(&input.amount, "amount")?),
+        });
+        let payment = context.signed_command_send(common, body, signature).await?;
+        Ok(SendPaymentPayload { payment })
+    }
+
+    async fn send_delegation(
+        input: SendDelegationInput,
+        signature: Option<SignatureInput>,
+        context: &Context,
+    ) -> FieldResult<SendDelegationPayload> {
+        let common = UserCommandCommonInput {
+            from: &input.from,
+            fee: &input.fee,
+            nonce: input.nonce.as_deref(),
+            valid_until: input.valid_until.as_deref(),
+            memo: input.memo.as_deref(),
+        };
+        let to: AccountPublicKey = parse(&input.to, "public key")?;
+        let body = Body::StakeDelegation(StakeDelegationPayload::SetDelegate {
+            new_delegate: to.as_ref().into(),
+        });
+        let delegation = context.signed_command_send(common, body, signature).await?;
+        Ok(SendDelegationPayload { delegation })
+    }
+}
+
+type BlockStream = Pin<Box<dyn Stream<Item = FieldResult<Block>> + Send>>;
+
+#[derive(Clone, Copy, Debug)]
+struct Subscription;
+
+#[juniper::graphql_subscription(context = Context)]
+impl Subscription {
+    /// New best tips, optionally only those created by the given
+    /// public key.
+    async fn new_block(public_key: Option<String>, context: &Context) -> FieldResult<BlockStream> {
+        let creator = public_key
+            .map(|pk| parse::<AccountPublicKey>(&pk, "public key"))
+            .transpose()?
+            .map(|pk| pk.to_string());
+        let rpc_sender = context.rpc_sender.clone();
+        let query = RpcSubscribeQuery {
+            kinds: BTreeSet::from([RpcEventKind::BestTip]),
+            accounts: vec![],
+        };
+        let events = rpc_sender.subscribe(query).await;
+
+        let stream =
+            futures::stream::unfold((rpc_sender, events), move |(rpc_sender, mut events)| {
+                let creator = creator.clone();
+                async move {
+                    loop {
+                        // Node has shut down.
+                        let RpcEvent::BestTip { hash, .. } = events.recv().await? else {
+                            continue;
+                        };
+                        let query = RpcBlockGetQuery::WithHash(hash);
+                        let block: RpcBlockGetResponse = rpc_sender
+                            .oneshot_request(RpcRequest::BlockGet(query))
+                            .await?;
+                        // Best tip might have already changed.
+                        let Some(block) = block else { continue };
+
+                        let block = Block(block);
+                        let block_creator = block.consensus_state().block_creator.to_string();
+                        if creator.as_ref().map_or(true, |pk| pk == &block_creator) {
+                            return Some((Ok(block), (rpc_sender, events)));
+                        }
+                    }
+                }
+            });
+        Ok(Box::pin(stream))
+    }
+}
+
+type Schema = RootNode<'static, Query, Mutation, Subscription>;
+
+fn schema() -> Schema {
+    RootNode::new(Query, Mutation, Subscription)
+}
+
+/// `auth` guards queries and mutations, `ws_auth` the subscriptions, as
+/// browsers can't set headers for websockets.
 pub fn routes(
-    rpc_sernder: super::RpcSender,
+    rpc_sender: super::RpcSender,
+    auth: BoxedFilter<()>,
+    ws_auth: BoxedFilter<()>,
 ) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
-    let state = warp::any().map(move || Context(rpc_sernder.clone()));
-    let schema = RootNode::new(
-        Query,
-        EmptyMutation::<Context>::new(),
-        EmptySubscription::<Context>::new(),
-    );
-    let graphql_filter = juniper_warp::make_graphql_filter(schema, state.boxed());
+    let context = Context {
+        rpc_sender,
+        start_time: Instant::now(),
+    };
+
+    let ws_context = context.clone();
+    let ws_schema = Arc::new(schema());
+    let graphql_ws =
+        warp::path("graphql")
+            .and(ws_auth)
+            .and(warp::ws())
+            .map(move |ws: warp::ws::Ws| {
+                let context = ws_context.clone();
+                let schema = ws_schema.clone();
+                let reply = ws.on_upgrade(move |websocket| async move {
+                    let config = ConnectionConfig::new(context);
+                    if let Err(err) = serve_graphql_ws(websocket, schema, config).await {
+                        openmina_core::log::warn!(openmina_core::log::system_time();
+                        kind = "GraphQL",
+                        summary = "subscription connection failed",
+                        error = err.to_string());
+                    }
+                });
+                warp::reply::with_header(reply, "Sec-WebSocket-Protocol", "graphql-ws")
+            });
+
+    let state = warp::any().map(move || context.clone());
+    let graphql_filter = juniper_warp::make_graphql_filter(schema(), state.boxed());
 
     warp::get()
         .and(warp::path("graphiql"))
-        .and(juniper_warp::graphiql_filter("/graphql", None))
-        .or(warp::path("graphql").and(graphql_filter))
+        .and(auth.clone())
+        .and(juniper_warp::graphiql_filter("/graphql", Some("/graphql")))
+        .or(graphql_ws)
+        .or(warp::path("graphql").and(auth).and(graphql_filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use juniper::Variables;
+    use ledger::scan_state::currency::Balance;
+    use ledger::AccountId;
+    use node::core::channels::oneshot;
+    use node::transition_frontier::GENESIS_WINNER_PUBLIC_KEY;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::NodeRpcRequest;
+
+    const FROM: &str = "B62qjVQLxt9nYMWGn45mkgwYfcz8e8jvjNCBo11VKJb7vxDNwv5QLPS";
+
+    /// Context with the state machine replaced by a task, which knows
+    /// the `FROM` account with the given inferred nonce and forwards
+    /// sent transactions to the returned channel.
+    fn test_context(
+        inferred_nonce: u32,
+        reject: bool,
+    ) -> (
+        Context,
+        mpsc::UnboundedReceiver<MinaBaseUserCommandStableV2>,
+    ) {
+        let (req_tx, mut req_rx) = mpsc::channel::<NodeRpcRequest>(8);
+        let (sent_tx, sent_rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Some(NodeRpcRequest { req, responder }) = req_rx.recv().await {
+                match req {
+                    RpcRequest::AccountGet(query) => {
+                        let responder = responder
+                            .downcast::<oneshot::Sender<RpcAccountGetResponse>>()
+                            .unwrap();
+                        let account_id =
+                            AccountId::new(query.public_key.as_ref().into(), TokenId::default());
+                        let account = ledger::Account::create_with(
+                            account_id,
+                            Balance::from_u64(1_000_000_000_000),
+                        );
+                        let _ = responder.send(Some(RpcAccount {
+                            account: Box::new((&account).into()),
+                            inferred_nonce,
+                            block_height: 1,
+                            state_hash: "3NLESd9gzU52bDWSXL5uUAYbCojHXSVdeBX4sCMF3V8Ns9D1Sriy"
+                                .parse()
+                                .unwrap(),
+                        }));
+                    }
+                    RpcRequest::TransactionSend(transaction) => {
+                        let responder = responder
+                            .downcast::<oneshot::Sender<RpcTransactionSendResponse>>()
+                            .unwrap();
+                        let response = if reject {
+                            RpcTransactionSendResponse::Rejected {
+                                error: "invalid signature".to_owned(),
+                            }
+                        } else {
+                            RpcTransactionSendResponse::Ok {
+                                hash: transaction.hash().unwrap(),
+                            }
+                        };
+                        let _ = sent_tx.send(*transaction);
+                        let _ = responder.send(response);
+                    }
+                    req => panic!("unexpected request: {req:?}"),
+                }
+            }
+        });
+
+        let context = Context {
+            rpc_sender: crate::RpcSender::new(req_tx),
+            start_time: Instant::now(),
+        };
+        (context, sent_rx)
+    }
+
+    async fn execute(query: &str, context: &Context) -> (serde_json::Value, Vec<String>) {
+        let (value, errors) = juniper::execute(query, None, &schema(), &Variables::new(), context)
+            .await
+            .unwrap();
+        let errors = errors
+            .iter()
+            .map(|err| err.error().message().to_owned())
+            .collect();
+        (serde_json::to_value(&value).unwrap(), errors)
+    }
+
+    /// Names of the fields (or input fields) of the type, via introspection.
+    async fn field_names(type_name: &str) -> Vec<String> {
+        let (context, _) = test_context(0, false);
+        let query = format!(
+            r#"{{ __type(name: "{type_name}") {{ fields {{ name }} inputFields {{ name }} }} }}"#
+        );
+        let (value, errors) = execute(&query, &context).await;
+        assert_eq!(errors, Vec::<String>::new());
+        let ty = &value["__type"];
+        let fields = ty["fields"].as_array().or(ty["inputFields"].as_array());
+        fields
+            .unwrap_or_else(|| panic!("no type {type_name}"))
+            .iter()
+            .map(|field| field["name"].as_str().unwrap().to_owned())
+            .collect()
+    }
+
+    /// Clients written for the Mina daemon rely on these names.
+    #[tokio::test]
+    async fn schema_field_names() {
+        let expected: &[(&str, &[&str])] = &[
+            (
+                "Query",
+                &[
+                    "syncStatus",
+                    "daemonStatus",
+                    "bestChain",
+                    "block",
+                    "account",
+                    "pooledUserCommands",
+                    "snarkPool",
+                ],
+            ),
+            ("Mutation", &["sendPayment", "sendDelegation"]),
+            ("Subscription", &["newBlock"]),
+            (
+                "Block",
+                &[
+                    "stateHash",
+                    "creator",
+                    "protocolState",
+                    "transactions",
+                    "commandTransactionCount",
+                    "snarkJobs",
+                ],
+            ),
+            (
+                "ConsensusState",
+                &[
+                    "blockHeight",
+                    "blockchainLength",
+                    "epochCount",
+                    "epoch",
+                    "slot",
+                    "slotSinceGenesis",
+                    "stakingEpochData",
+                    "nextEpochData",
+                    "blockCreator",
+                    "blockStakeWinner",
+                    "coinbaseReceiever",
+                ],
+            ),
+            (
+                "UserCommand",
+                &[
+                    "hash",
+                    "kind",
+                    "nonce",
+                    "from",
+                    "to",
+                    "amount",
+                    "fee",
+                    "token",
+                    "feeToken",
+                    "validUntil",
+                    "memo",
+                    "isDelegation",
+                    "failureReason",
+                ],
+            ),
+            (
+                "SendPaymentInput",
+                &["from", "to", "amount", "fee", "validUntil", "memo", "nonce"],
+            ),
+            ("SignatureInput", &["field", "scalar", "rawSignature"]),
+        ];
+
+        for (type_name, names) in expected {
+            let fields = field_names(type_name).await;
+            for name in *names {
+                assert!(
+                    fields.iter().any(|field| field == name),
+                    "{type_name} lacks `{name}`, has: {fields:?}"
+                );
+            }
+        }
+    }
+
+    fn send_payment(signature: &str, nonce: Option<u32>) -> String {
+        let nonce = nonce.map_or(String::new(), |nonce| format!(r#", nonce: "{nonce}""#));
+        format!(
+            r#"mutation {{
+                sendPayment(
+                    input: {{ from: "{FROM}", to: "{GENESIS_WINNER_PUBLIC_KEY}", amount: "1000000000", fee: "10000000"{nonce} }}
+                    {signature}
+                ) {{
+                    payment {{ hash kind nonce from to amount fee }}
+                }}
+            }}"#
+        )
+    }
+
+    const SIGNATURE: &str = r#"signature: { field: "1", scalar: "1" }"#;
+
+    #[tokio::test]
+    async fn signed_command_send_uses_inferred_nonce() {
+        let (context, mut sent) = test_context(7, false);
+        let (value, errors) = execute(&send_payment(SIGNATURE, None), &context).await;
+        assert_eq!(errors, Vec::<String>::new());
+
+        let transaction = sent.try_recv().unwrap();
+        let payment = &value["sendPayment"]["payment"];
+        assert_eq!(
+            payment["hash"],
+            transaction.hash().unwrap().to_string().as_str()
+        );
+        assert_eq!(payment["kind"], "PAYMENT");
+        assert_eq!(payment["nonce"], 7);
+        assert_eq!(payment["from"], FROM);
+        assert_eq!(payment["to"], GENESIS_WINNER_PUBLIC_KEY);
+        assert_eq!(payment["amount"], "1000000000");
+        assert_eq!(payment["fee"], "10000000");
+
+        let MinaBaseUserCommandStableV2::SignedCommand(command) = transaction else {
+            panic!("not a signed command");
+        };
+        assert_eq!(command.payload.common.nonce.as_u32(), 7);
+        assert_eq!(command.signer.to_string(), FROM);
+    }
+
+    #[tokio::test]
+    async fn signed_command_send_with_explicit_nonce() {
+        let (context, mut sent) = test_context(7, false);
+        let (value, errors) = execute(&send_payment(SIGNATURE, Some(3)), &context).await;
+        assert_eq!(errors, Vec::<String>::new());
+        assert_eq!(value["sendPayment"]["payment"]["nonce"], 3);
+        assert!(sent.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn signed_command_send_errors() {
+        let (context, mut sent) = test_context(7, false);
+        let (_, errors) = execute(&send_payment("", None), &context).await;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("signature is required"), "{errors:?}");
+
+        let raw = r#"signature: { rawSignature: "00" }"#;
+        let (_, errors) = execute(&send_payment(raw, None), &context).await;
+        assert!(errors[0].contains("rawSignature"), "{errors:?}");
+        assert!(sent.try_recv().is_err());
+
+        let (context, _) = test_context(0, true);
+        let (_, errors) = execute(&send_payment(SIGNATURE, None), &context).await;
+        assert!(
+            errors[0].contains("transaction rejected: invalid signature"),
+            "{errors:?}"
+        );
+    }
 }
@@ -1,7 +1,9 @@
 pub mod ext_snark_worker;
 pub mod graphql;
 pub mod http_server;
+pub mod metrics;
 pub mod rpc;
+pub mod snark_worker_coordinator;
 pub mod tracing;
 
 mod service;
@@ -0,0 +1,436 @@
+//! Rendering of the node's metrics in the Prometheus text format.
+
+use std::fmt::{Display, Write};
+
+use node::rpc::{RpcMetrics, RpcPeerStatus, RpcPeerTransport};
+use node::stats::actions::ActionStatsForRanges;
+use node::stats::durations::{DurationHistogram, DURATION_BUCKETS};
+use node::stats::sync::{SyncKind, SyncStatsSnapshot};
+use redux::Timestamp;
+
+/// Upper bounds of `ActionStatsForRanges` buckets in nanoseconds.
+const ACTION_DURATION_BUCKETS: [u64; 8] = [
+    1_000, 10_000, 50_000, 100_000, 500_000, 1_000_000, 5_000_000, 50_000_000,
+];
+
+pub fn render(metrics: &RpcMetrics) -> String {
+    let mut out = Output::default();
+
+    if let Some(actions) = &metrics.actions {
+        out.header(
+            "openmina_action_duration_seconds",
+            "histogram",
+            "Time from the action till the next one, by action kind.",
+        );
+        for (kind, stats) in actions.iter() {
+            let kind = kind.to_string();
+            let (buckets, sum, count) = action_histogram(stats);
+            if count > 0 {
+                out.histogram(
+                    "openmina_action_duration_seconds",
+                    &[("kind", kind.as_str())],
+                    &buckets,
+                    sum,
+                    count,
+                );
+            }
+        }
+    }
+
+    out.header(
+        "openmina_peers",
+        "gauge",
+        "Number of peers by transport and connection status.",
+    );
+    for peers in &metrics.peers {
+        let transport = match peers.transport {
+            RpcPeerTransport::WebRTC => "webrtc",
+            RpcPeerTransport::LibP2P => "libp2p",
+            RpcPeerTransport::Unknown => "unknown",
+        };
+        let status = match peers.status {
+            RpcPeerStatus::Connecting => "connecting",
+            RpcPeerStatus::Ready => "ready",
+            RpcPeerStatus::Disconnected => "disconnected",
+        };
+        out.sample(
+            "openmina_peers",
+            &[("transport", transport), ("status", status)],
+            peers.count,
+        );
+    }
+
+    out.header(
+        "openmina_p2p_rpc_latency_seconds",
+        "histogram",
+        "Time from sending the rpc request to a peer till receiving the response.",
+    );
+    for (kind, stats) in &metrics.p2p_rpc {
+        let kind = format!("{kind:?}");
+        out.duration_histogram(
+            "openmina_p2p_rpc_latency_seconds",
+            &[("kind", kind.as_str())],
+            &stats.latency,
+        );
+    }
+    out.header(
+        "openmina_p2p_rpc_timeouts_total",
+        "counter",
+        "Number of rpc requests peers didn't respond to in time.",
+    );
+    for (kind, stats) in &metrics.p2p_rpc {
+        let kind = format!("{kind:?}");
+        out.sample(
+            "openmina_p2p_rpc_timeouts_total",
+            &[("kind", kind.as_str())],
+            stats.timeouts,
+        );
+    }
+
+    let snark_pool = &metrics.snark_pool;
+    out.gauge(
+        "openmina_snark_pool_jobs",
+        "Number of jobs in the snark pool.",
+        snark_pool.jobs,
+    );
+    out.gauge(
+        "openmina_snark_pool_committed_jobs",
+        "Number of jobs in the snark pool that a snarker committed to do.",
+        snark_pool.committed_jobs,
+    );
+    out.gauge(
+        "openmina_snark_pool_snarks",
+        "Number of jobs in the snark pool for which we have a snark.",
+        snark_pool.snarks,
+    );
+    out.gauge(
+        "openmina_transaction_pool_size",
+        "Number of transactions in the transaction pool.",
+        metrics.transaction_pool_size,
+    );
+
+    if let Some(sync) = &metrics.last_sync {
+        out.header(
+            "openmina_sync_phase_duration_seconds",
+            "gauge",
+            "Durations of the phases of the latest sync.",
+        );
+        let kind = match sync.kind {
+            SyncKind::Bootstrap => "bootstrap",
+            SyncKind::Catchup => "catchup",
+        };
+        for (phase, duration) in sync_phases(sync) {
+            out.sample(
+                "openmina_sync_phase_duration_seconds",
+                &[("kind", kind), ("phase", phase)],
+                duration,
+            );
+        }
+    }
+
+    if let Some(height) = metrics.best_tip_height {
+        out.gauge(
+            "openmina_best_tip_height",
+            "Height of the best tip.",
+            height,
+        );
+    }
+
+    if let Some(block_apply) = &metrics.block_apply {
+        out.header(
+            "openmina_block_apply_duration_seconds",
+            "histogram",
+            "Time it takes to apply a block to the transition frontier.",
+        );
+        out.duration_histogram("openmina_block_apply_duration_seconds", &[], block_apply);
+    }
+
+    out.0
+}
+
+fn action_histogram(stats: &ActionStatsForRanges) -> (Vec<(u64, u64)>, u64, u64) {
+    let ranges = [
+        &stats.under_1_us,
+        &stats.under_10_us,
+        &stats.under_50_us,
+        &stats.under_100_us,
+        &stats.under_500_us,
+        &stats.under_1_ms,
+        &stats.under_5_ms,
+        &stats.under_50_ms,
+    ];
+    let buckets = ACTION_DURATION_BUCKETS
+        .into_iter()
+        .zip(ranges)
+        .map(|(bound, range)| (bound, range.total_calls))
+        .collect();
+    let all = ranges.into_iter().chain([&stats.above_50_ms]);
+    let (sum, count) = all.fold((0, 0), |(sum, count), range| {
+        (sum + range.total_duration, count + range.total_calls)
+    });
+    (buckets, sum, count)
+}
+
+/// Phases of the sync with their durations in seconds. Phases which
+/// haven't finished are skipped.
+fn sync_phases(sync: &SyncStatsSnapshot) -> Vec<(&'static str, f64)> {
+    fn duration(start: Option<Timestamp>, end: Option<Timestamp>) -> Option<f64> {
+        let duration = end?.checked_sub(start?)?;
+        Some(duration.as_secs_f64())
+    }
+
+    let mut phases = vec![];
+    if let Some(ledger) = &sync.ledgers.root {
+        let snarked = &ledger.snarked;
+        let staged = &ledger.staged;
+        phases.extend([
+            (
+                "snarked_ledger_fetch_hashes",
+                duration(snarked.fetch_hashes_start, snarked.fetch_hashes_end),
+            ),
+            (
+                "snarked_ledger_fetch_accounts",
+                duration(snarked.fetch_accounts_start, snarked.fetch_accounts_end),
+            ),
+            (
+                "staged_ledger_fetch_parts",
+                duration(staged.fetch_parts_start, staged.fetch_parts_end),
+            ),
+            (
+                "staged_ledger_reconstruct",
+                duration(staged.reconstruct_start, staged.reconstruct_end),
+            ),
+        ]);
+    }
+    let blocks = &sync.blocks;
+    phases.extend([
+        (
+            "blocks_fetch",
+            duration(
+                blocks.iter().filter_map(|b| b.fetch_start).min(),
+                blocks.iter().filter_map(|b| b.fetch_end).max(),
+            ),
+        ),
+        (
+            "blocks_apply",
+            duration(
+                blocks.iter().filter_map(|b| b.apply_start).min(),
+                blocks.iter().filter_map(|b| b.apply_end).max(),
+            ),
+        ),
+        ("total", duration(Some(sync.best_tip_received), sync.synced)),
+    ]);
+    phases
+        .into_iter()
+        .filter_map(|(phase, duration)| Some((phase, duration?)))
+        .collect()
+}
+
+#[derive(Default)]
+struct Output(String);
+
+impl Output {
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.0, "# HELP {name} {help}");
+        let _ = writeln!(self.0, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.0.push_str(name);
+        if !labels.is_empty() {
+            let labels = labels
+                .iter()
+                .map(|(k, v)| format!("{k}=\"{}\"", escape(v)))
+                .collect::<Vec<_>>()
+                .join(",");
+            let _ = write!(self.0, "{{{labels}}}");
+        }
+        let _ = writeln!(self.0, " {value}");
+    }
+
+    fn gauge(&mut self, name: &str, help: &str, value: impl Display) {
+        self.header(name, "gauge", help);
+        self.sample(name, &[], value);
+    }
+
+    /// `buckets` are pairs of the upper bound and the number of values in
+    /// the bucket, both non-cumulative and in nanoseconds, as is `sum`.
+    fn histogram(
+        &mut self,
+        name: &str,
+        labels: &[(&str, &str)],
+        buckets: &[(u64, u64)],
+        sum: u64,
+        count: u64,
+    ) {
+        let bucket_name = format!("{name}_bucket");
+        let mut cumulative = 0;
+        for (bound, n) in buckets {
+            cumulative += n;
+            let le = nanos_to_secs(*bound).to_string();
+            let labels = [labels, &[("le", le.as_str())]].concat();
+            self.sample(&bucket_name, &labels, cumulative);
+        }
+        let labels_inf = [labels, &[("le", "+Inf")]].concat();
+        self.sample(&bucket_name, &labels_inf, count);
+        self.sample(&format!("{name}_sum"), labels, nanos_to_secs(sum));
+        self.sample(&format!("{name}_count"), labels, count);
+    }
+
+    fn duration_histogram(
+        &mut self,
+        name: &str,
+        labels: &[(&str, &str)],
+        histogram: &DurationHistogram,
+    ) {
+        let buckets = DURATION_BUCKETS
+            .into_iter()
+            .zip(histogram.buckets)
+            .collect::<Vec<_>>();
+        self.histogram(name, labels, &buckets, histogram.sum, histogram.count);
+    }
+}
+
+fn nanos_to_secs(nanos: u64) -> f64 {
+    nanos as f64 / 1_000_000_000.0
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use node::p2p::channels::rpc::P2pRpcKind;
+    use node::rpc::{RpcMetricsPeers, RpcMetricsSnarkPool};
+    use node::stats::durations::P2pRpcKindStats;
+
+    use super::*;
+
+    const SEC: u64 = 1_000_000_000;
+
+    fn metrics() -> RpcMetrics {
+        let mut latency = DurationHistogram::default();
+        for duration in [SEC, SEC, 3 * SEC, 200 * SEC] {
+            latency.add(duration);
+        }
+        RpcMetrics {
+            actions: None,
+            p2p_rpc: BTreeMap::from([(
+                P2pRpcKind::BestTipWithProof,
+                P2pRpcKindStats {
+                    latency,
+                    timeouts: 2,
+                },
+            )]),
+            block_apply: None,
+            last_sync: None,
+            peers: vec![RpcMetricsPeers {
+                transport: RpcPeerTransport::LibP2P,
+                status: RpcPeerStatus::Ready,
+                count: 3,
+            }],
+            snark_pool: RpcMetricsSnarkPool {
+                jobs: 4,
+                committed_jobs: 1,
+                snarks: 2,
+            },
+            transaction_pool_size: 5,
+            best_tip_height: Some(10),
+        }
+    }
+
+    #[test]
+    fn render_exposition_format() {
+        let out = render(&metrics());
+        let lines = out.lines().collect::<Vec<_>>();
+
+        for line in &lines {
+            if let Some(comment) = line.strip_prefix("# ") {
+                assert!(
+                    comment.starts_with("HELP openmina_") || comment.starts_with("TYPE openmina_"),
+                    "{line}"
+                );
+                continue;
+            }
+            // Every sample is `name{labels} value` with a numeric value.
+            let (name, value) = line.rsplit_once(' ').unwrap();
+            assert!(value.parse::<f64>().is_ok(), "{line}");
+            let metric = name.split('{').next().unwrap();
+            assert!(metric.starts_with("openmina_"), "{line}");
+            assert!(
+                !name.contains('{') || name.ends_with('}'),
+                "unterminated labels: {line}"
+            );
+        }
+
+        // Each metric is described once, before its samples.
+        for name in ["openmina_peers", "openmina_p2p_rpc_latency_seconds"] {
+            let help = format!("# HELP {name} ");
+            let ty = format!("# TYPE {name} ");
+            assert_eq!(lines.iter().filter(|l| l.starts_with(&help)).count(), 1);
+            let help_pos = lines.iter().position(|l| l.starts_with(&help)).unwrap();
+            assert!(lines[help_pos + 1].starts_with(&ty));
+            let sample_pos = lines
+                .iter()
+                .position(|l| l.starts_with(name) && !l.starts_with('#'))
+                .unwrap();
+            assert!(help_pos < sample_pos);
+        }
+
+        assert!(lines.contains(&"# TYPE openmina_p2p_rpc_latency_seconds histogram"));
+        assert!(lines.contains(&"# TYPE openmina_p2p_rpc_timeouts_total counter"));
+        assert!(lines.contains(&"openmina_peers{transport=\"libp2p\",status=\"ready\"} 3"));
+        assert!(lines.contains(&"openmina_p2p_rpc_timeouts_total{kind=\"BestTipWithProof\"} 2"));
+        assert!(lines.contains(&"openmina_snark_pool_jobs 4"));
+        assert!(lines.contains(&"openmina_transaction_pool_size 5"));
+        assert!(lines.contains(&"openmina_best_tip_height 10"));
+        assert!(out.ends_with('\n'));
+    }
+
+    #[test]
+    fn render_cumulative_buckets() {
+        let out = render(&metrics());
+        let buckets = out
+            .lines()
+            .filter_map(|l| l.strip_prefix("openmina_p2p_rpc_latency_seconds_bucket"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(buckets.len(), DURATION_BUCKETS.len() + 1);
+        assert_eq!(buckets[0], "{kind=\"BestTipWithProof\",le=\"0.001\"} 0");
+        assert_eq!(buckets[6], "{kind=\"BestTipWithProof\",le=\"1\"} 2");
+        assert_eq!(buckets[7], "{kind=\"BestTipWithProof\",le=\"5\"} 3");
+        assert_eq!(buckets[11], "{kind=\"BestTipWithProof\",le=\"120\"} 3");
+        // Duration above the last bound is only counted by `+Inf`.
+        assert_eq!(buckets[12], "{kind=\"BestTipWithProof\",le=\"+Inf\"} 4");
+
+        let counts = buckets
+            .iter()
+            .map(|b| b.rsplit_once(' ').unwrap().1.parse::<u64>().unwrap())
+            .collect::<Vec<_>>();
+        assert!(counts.windows(2).all(|w| w[0] <= w[1]), "{counts:?}");
+
+        let lines = out.lines().collect::<Vec<_>>();
+        assert!(
+            lines.contains(&"openmina_p2p_rpc_latency_seconds_sum{kind=\"BestTipWithProof\"} 205")
+        );
+        assert!(
+            lines.contains(&"openmina_p2p_rpc_latency_seconds_count{kind=\"BestTipWithProof\"} 4")
+        );
+    }
+
+    #[test]
+    fn label_values_escaped() {
+        let mut out = Output::default();
+        out.sample("m", &[("a", "back\\slash"), ("b", "\"quoted\"\nnext")], 1);
+        assert_eq!(
+            out.0,
+            "m{a=\"back\\\\slash\",b=\"\\\"quoted\\\"\\nnext\"} 1\n"
+        );
+    }
+}
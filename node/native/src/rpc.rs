@@ -1,4 +1,4 @@
-use node::rpc::{RpcHealthCheckResponse, RpcReadinessCheckResponse};
+use node::rpc::{RpcEvent, RpcHealthCheckResponse, RpcReadinessCheckResponse};
 use serde::{Deserialize, Serialize};
 
 use node::core::channels::{mpsc, oneshot};
@@ -10,7 +10,6 @@ pub use node::rpc::{
     RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcStateGetResponse,
     RpcSyncStatsGetResponse,
 };
-use node::State;
 use node::{event_source::Event, rpc::RpcSnarkPoolJobGetResponse};
 
 use super::{NodeRpcRequest, NodeService};
@@ -47,6 +46,19 @@ impl RpcService {
     pub fn req_receiver(&mut self) -> &mut mpsc::Receiver<NodeRpcRequest> {
         &mut self.req_receiver
     }
+
+    /// Whether the receiver of the events subscription was dropped. Unknown
+    /// subscriptions are considered closed.
+    pub fn event_subscriber_is_closed(&self, rpc_id: RpcId) -> bool {
+        self.pending
+            .get(rpc_id)
+            .and_then(|chan| chan.downcast_ref::<mpsc::Sender<RpcEvent>>())
+            .map_or(true, |chan| chan.is_closed())
+    }
+
+    pub fn event_subscriber_remove(&mut self, rpc_id: RpcId) {
+        self.pending.remove(rpc_id);
+    }
 }
 
 impl NodeService {
@@ -81,17 +93,7 @@ macro_rules! rpc_service_impl {
 }
 
 impl node::rpc::RpcService for NodeService {
-    fn respond_state_get(&mut self, rpc_id: RpcId, response: &State) -> Result<(), RespondError> {
-        let entry = self.rpc.pending.remove(rpc_id);
-        let chan = entry.ok_or(RespondError::UnknownRpcId)?;
-        let chan = chan
-            .downcast::<oneshot::Sender<RpcStateGetResponse>>()
-            .or(Err(RespondError::UnexpectedResponseType))?;
-        chan.send(Box::new(response.clone()))
-            .or(Err(RespondError::RespondingFailed))?;
-        Ok(())
-    }
-
+    rpc_service_impl!(respond_state_get, RpcStateGetResponse);
     rpc_service_impl!(respond_sync_stats_get, RpcSyncStatsGetResponse);
     rpc_service_impl!(respond_action_stats_get, RpcActionStatsGetResponse);
     rpc_service_impl!(
@@ -145,10 +147,97 @@ impl node::rpc::RpcService for NodeService {
         respond_snarker_workers,
         node::rpc::RpcSnarkerWorkersResponse
     );
+    rpc_service_impl!(
+        respond_snarker_work_get,
+        node::rpc::RpcSnarkerWorkGetResponse
+    );
+    rpc_service_impl!(
+        respond_snarker_work_submit,
+        node::rpc::RpcSnarkerWorkSubmitResponse
+    );
+    rpc_service_impl!(
+        respond_transaction_nonce_get,
+        node::rpc::RpcTransactionNonceGetResponse
+    );
+    rpc_service_impl!(
+        respond_transaction_send,
+        node::rpc::RpcTransactionSendResponse
+    );
+    rpc_service_impl!(respond_stake_get, node::rpc::RpcStakeGetResponse);
+    rpc_service_impl!(respond_best_chain_get, node::rpc::RpcBestChainGetResponse);
+    rpc_service_impl!(respond_block_get, node::rpc::RpcBlockGetResponse);
+    rpc_service_impl!(respond_account_get, node::rpc::RpcAccountGetResponse);
+    rpc_service_impl!(
+        respond_transaction_pool_get,
+        node::rpc::RpcTransactionPoolGetResponse
+    );
+    rpc_service_impl!(respond_node_status_get, node::rpc::RpcNodeStatusGetResponse);
+    rpc_service_impl!(respond_metrics_get, node::rpc::RpcMetricsGetResponse);
+
+    fn respond_event(&mut self, rpc_id: RpcId, event: RpcEvent) -> Result<(), RespondError> {
+        let entry = self.rpc.pending.get(rpc_id);
+        let chan = entry.ok_or(RespondError::UnknownRpcId)?;
+        let chan = chan
+            .downcast_ref::<mpsc::Sender<RpcEvent>>()
+            .ok_or(RespondError::UnexpectedResponseType)?
+            .clone();
+        // Subscriber went away or can't keep up with the events.
+        if chan.try_send(event).is_err() {
+            self.rpc.event_subscriber_remove(rpc_id);
+            return Err(RespondError::RespondingFailed);
+        }
+        Ok(())
+    }
+
+    fn event_subscriber_is_closed(&self, rpc_id: RpcId) -> bool {
+        self.rpc.event_subscriber_is_closed(rpc_id)
+    }
+
+    fn event_subscriber_remove(&mut self, rpc_id: RpcId) {
+        self.rpc.event_subscriber_remove(rpc_id);
+    }
     rpc_service_impl!(
         respond_snarker_config_get,
         node::rpc::RpcSnarkerConfigGetResponse
     );
+    rpc_service_impl!(
+        respond_peers_reputation_get,
+        node::rpc::RpcPeersReputationGetResponse
+    );
+    rpc_service_impl!(respond_peer_ban, node::rpc::RpcPeerBanResponse);
+    rpc_service_impl!(respond_peer_unban, node::rpc::RpcPeerUnbanResponse);
     rpc_service_impl!(respond_health_check, RpcHealthCheckResponse);
     rpc_service_impl!(respond_readiness_check, RpcReadinessCheckResponse);
 }
+
+#[cfg(test)]
+mod tests {
+    use node::core::channels::mpsc::error::TryRecvError;
+
+    use super::*;
+
+    #[test]
+    fn closed_event_subscriber() {
+        let mut rpc = RpcService::new();
+        let (tx, rx) = mpsc::channel::<RpcEvent>(1);
+        let rpc_id = rpc.pending.add(Box::new(tx));
+        assert!(!rpc.event_subscriber_is_closed(rpc_id));
+
+        drop(rx);
+        assert!(rpc.event_subscriber_is_closed(rpc_id));
+        rpc.event_subscriber_remove(rpc_id);
+        assert!(rpc.pending.get(rpc_id).is_none());
+        assert!(rpc.event_subscriber_is_closed(rpc_id));
+    }
+
+    #[test]
+    fn removed_event_subscriber_ends_subscription() {
+        let mut rpc = RpcService::new();
+        let (tx, mut rx) = mpsc::channel::<RpcEvent>(1);
+        let rpc_id = rpc.pending.add(Box::new(tx));
+
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+        rpc.event_subscriber_remove(rpc_id);
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Disconnected)));
+    }
+}
@@ -3,22 +3,30 @@ use std::collections::{BTreeMap, VecDeque};
 use std::sync::Arc;
 
 use ledger::scan_state::scan_state::transaction_snark::{SokDigest, Statement};
-use mina_p2p_messages::v2::{LedgerProofProdStableV2, TransactionSnarkWorkTStableV2Proofs};
+use mina_p2p_messages::v2::{
+    LedgerProofProdStableV2, StateHash, TransactionSnarkWorkTStableV2Proofs,
+};
 use rand::prelude::*;
 use redux::ActionMeta;
 use serde::Serialize;
 
+use node::account::AccountSecretKey;
+use node::block_producer::{
+    BlockProducerEvent, BlockProducerService, BlockProducerVrfEvaluatorInput, BlockProverInput,
+};
 use node::core::channels::{mpsc, oneshot};
 use node::core::snark::Snark;
 use node::event_source::Event;
+use node::external_snark_worker::ExternalSnarkWorkerId;
 use node::ledger::LedgerCtx;
 use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use node::p2p::identity::SecretKey;
 use node::p2p::service_impl::libp2p::Libp2pService;
 use node::p2p::service_impl::webrtc::{Cmd, P2pServiceWebrtc, PeerState};
 use node::p2p::service_impl::webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p;
 use node::p2p::service_impl::TaskSpawner;
-use node::p2p::{P2pEvent, PeerId};
-use node::rpc::{RpcP2pConnectionOutgoingResponse, RpcRequest};
+use node::p2p::{P2pEvent, P2pPeerStore, P2pPeerStoreService, PeerId};
+use node::rpc::{RpcEvent, RpcP2pConnectionOutgoingResponse, RpcRequest, RpcSubscribeQuery};
 use node::service::{EventSourceService, Recorder};
 use node::snark::block_verify::{
     SnarkBlockVerifyError, SnarkBlockVerifyId, SnarkBlockVerifyService, VerifiableBlockWithHash,
@@ -40,9 +48,12 @@ pub struct NodeService {
     pub cmd_sender: mpsc::UnboundedSender<Cmd>,
     pub ledger: LedgerCtx,
     pub peers: BTreeMap<PeerId, PeerState>,
+    pub p2p_secret_key: SecretKey,
     pub libp2p: Libp2pService,
+    pub peer_store: Option<P2pPeerStore>,
     pub rpc: RpcService,
-    pub snark_worker_sender: Option<ext_snark_worker::ExternalSnarkWorkerFacade>,
+    pub snark_workers: BTreeMap<ExternalSnarkWorkerId, ext_snark_worker::ExternalSnarkWorkerFacade>,
+    pub block_producer_key: Option<AccountSecretKey>,
     pub stats: Stats,
     pub recorder: Recorder,
     pub replayer: Option<ReplayerState>,
@@ -124,6 +135,10 @@ impl P2pServiceWebrtc for NodeService {
     fn peers(&mut self) -> &mut BTreeMap<PeerId, PeerState> {
         &mut self.peers
     }
+
+    fn secret_key(&mut self) -> &SecretKey {
+        &self.p2p_secret_key
+    }
 }
 
 impl P2pServiceWebrtcWithLibp2p for NodeService {
@@ -132,6 +147,12 @@ impl P2pServiceWebrtcWithLibp2p for NodeService {
     }
 }
 
+impl P2pPeerStoreService for NodeService {
+    fn peer_store(&mut self) -> Option<&mut P2pPeerStore> {
+        self.peer_store.as_mut()
+    }
+}
+
 impl SnarkBlockVerifyService for NodeService {
     fn verify_init(
         &mut self,
@@ -209,6 +230,35 @@ impl SnarkWorkVerifyService for NodeService {
     }
 }
 
+impl BlockProducerService for NodeService {
+    fn vrf_evaluate(&mut self, input: BlockProducerVrfEvaluatorInput) {
+        if self.replayer.is_some() {
+            return;
+        }
+        let tx = self.event_sender.clone();
+        let Some(key) = self.block_producer_key.clone() else {
+            let error = "block producer key not set".to_owned();
+            let _ = tx.send(BlockProducerEvent::VrfEvaluator(Err(error)).into());
+            return;
+        };
+        rayon::spawn_fifo(move || {
+            let output = input.evaluate(key.as_ref());
+            let _ = tx.send(BlockProducerEvent::VrfEvaluator(Ok(output)).into());
+        });
+    }
+
+    fn prove(&mut self, block_hash: StateHash, _input: Box<BlockProverInput>) {
+        if self.replayer.is_some() {
+            return;
+        }
+        // TODO: native block prover.
+        let error = "block prover not available, use proof level other than `full`".to_owned();
+        let _ = self
+            .event_sender
+            .send(BlockProducerEvent::BlockProve(block_hash, Err(error)).into());
+    }
+}
+
 pub struct EventReceiver {
     rx: mpsc::UnboundedReceiver<Event>,
     queue: Vec<Event>,
@@ -258,6 +308,10 @@ pub struct NodeRpcRequest {
     pub responder: Box<dyn Send + std::any::Any>,
 }
 
+/// Number of events buffered for a subscriber, before it's dropped for
+/// not keeping up with them.
+const EVENTS_BUFFER_SIZE: usize = 1024;
+
 #[derive(Clone)]
 pub struct RpcSender {
     tx: mpsc::Sender<NodeRpcRequest>,
@@ -296,6 +350,13 @@ impl RpcSender {
         rx
     }
 
+    /// Subscribes to the node's events. The subscription ends when the
+    /// receiver is dropped.
+    pub async fn subscribe(&self, query: RpcSubscribeQuery) -> mpsc::Receiver<RpcEvent> {
+        self.multishot_request(EVENTS_BUFFER_SIZE, RpcRequest::Subscribe(query))
+            .await
+    }
+
     pub async fn peer_connect(
         &self,
         opts: P2pConnectionOutgoingInitOpts,
@@ -0,0 +1,448 @@
+//! Coordinator for remote Mina snark workers.
+//!
+//! Serves `get_work` and `submit_work` rpcs over TCP, same as the Mina
+//! daemon does, so that `mina internal snark-worker` can be run with
+//! `-daemon-address` pointing to this node.
+//!
+//! The protocol can't carry a token, so the listener should only be
+//! reachable by the workers, which is what `SnarkWorkerCoordinatorConfig`
+//! is for. Submitted work is verified by the node and credited to its
+//! snarker.
+
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use mina_p2p_messages::binprot::{BinProtRead, BinProtWrite};
+use mina_p2p_messages::rpc::{SnarkWorkerGetWorkV2, SnarkWorkerSubmitWorkV2, VersionedRpcMenuV1};
+use mina_p2p_messages::rpc_kernel::{
+    Error, Message, MessageHeader, NeedsLength, PayloadBinprotReader, QueryHeader, QueryID,
+    Response, RpcMethod, RpcResult,
+};
+use node::rpc::{RpcRequest, RpcSnarkerWorkGetResponse, RpcSnarkerWorkSubmitResponse};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+use crate::RpcSender;
+
+const HANDSHAKE_MSG: &[u8] = b"\x07\x00\x00\x00\x00\x00\x00\x00\x02\xfdRPC\x00\x01";
+const HEARTBEAT_MSG: &[u8] = b"\x01\x00\x00\x00\x00\x00\x00\x00\x00";
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+/// Submitted work with two proofs is well below that.
+const MAX_MESSAGE_LEN: u64 = 64 * 1024 * 1024;
+
+/// Sent by the worker if it fails to produce the work. Its query isn't
+/// decoded, it is only acknowledged.
+const FAILED_TO_GENERATE_SNARK: (&str, i32) = ("failed_to_generate_snark", 2);
+
+#[derive(Debug, Clone)]
+pub struct SnarkWorkerCoordinatorConfig {
+    /// Address to listen to for workers.
+    pub bind: SocketAddr,
+    /// Addresses workers may connect from, any if empty.
+    pub allowed_ips: Vec<IpAddr>,
+}
+
+impl SnarkWorkerCoordinatorConfig {
+    fn is_allowed(&self, ip: IpAddr) -> bool {
+        // Connections from ipv4 peers to an ipv6 listener have ipv4-mapped
+        // addresses.
+        let ip = match ip {
+            IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or(IpAddr::V6(ip), IpAddr::V4),
+            ip => ip,
+        };
+        self.allowed_ips.is_empty() || self.allowed_ips.contains(&ip)
+    }
+}
+
+pub async fn run(config: SnarkWorkerCoordinatorConfig, rpc_sender: RpcSender) {
+    let addr = config.bind;
+    let listener = match TcpListener::bind(addr).await {
+        Ok(v) => v,
+        Err(err) => {
+            openmina_core::log::error!(openmina_core::log::system_time();
+                kind = "SnarkWorkerCoordinator",
+                summary = format!("failed to listen on {addr}"),
+                error = err.to_string());
+            return;
+        }
+    };
+
+    serve(listener, config, rpc_sender).await
+}
+
+async fn serve(listener: TcpListener, config: SnarkWorkerCoordinatorConfig, rpc_sender: RpcSender) {
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(v) => v,
+            Err(err) => {
+                openmina_core::log::warn!(openmina_core::log::system_time();
+                    kind = "SnarkWorkerCoordinator",
+                    summary = "failed to accept connection",
+                    error = err.to_string());
+                continue;
+            }
+        };
+        if !config.is_allowed(addr.ip()) {
+            openmina_core::log::warn!(openmina_core::log::system_time();
+                kind = "SnarkWorkerCoordinator",
+                summary = "connection from not allowed address rejected",
+                addr = addr.to_string());
+            continue;
+        }
+        openmina_core::log::info!(openmina_core::log::system_time();
+            kind = "SnarkWorkerCoordinator",
+            summary = "snark worker connected",
+            addr = addr.to_string());
+        let rpc_sender = rpc_sender.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(stream, rpc_sender).await {
+                openmina_core::log::info!(openmina_core::log::system_time();
+                    kind = "SnarkWorkerCoordinator",
+                    summary = "snark worker disconnected",
+                    addr = addr.to_string(),
+                    error = err.to_string());
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, rpc_sender: RpcSender) -> io::Result<()> {
+    let (reader, writer) = stream.into_split();
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::try_join!(write_loop(writer, rx), read_loop(reader, tx, rpc_sender)).map(|_| ())
+}
+
+async fn write_loop(
+    mut writer: OwnedWriteHalf,
+    mut rx: mpsc::UnboundedReceiver<Vec<u8>>,
+) -> io::Result<()> {
+    writer.write_all(HANDSHAKE_MSG).await?;
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    loop {
+        let bytes = tokio::select! {
+            _ = heartbeat.tick() => HEARTBEAT_MSG.to_vec(),
+            bytes = rx.recv() => match bytes {
+                Some(bytes) => bytes,
+                None => return Ok(()),
+            },
+        };
+        writer.write_all(&bytes).await?;
+    }
+}
+
+async fn read_loop(
+    mut reader: OwnedReadHalf,
+    tx: mpsc::UnboundedSender<Vec<u8>>,
+    rpc_sender: RpcSender,
+) -> io::Result<()> {
+    loop {
+        let message = read_message(&mut reader).await?;
+        let mut payload = message.as_slice();
+        match MessageHeader::binprot_read(&mut payload).map_err(invalid_data)? {
+            // handshake is received as a response.
+            MessageHeader::Heartbeat | MessageHeader::Response(_) => {}
+            MessageHeader::Query(header) => {
+                let response = handle_query(header, payload, &rpc_sender).await?;
+                if tx.send(response).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+async fn handle_query(
+    header: QueryHeader,
+    mut payload: &[u8],
+    rpc_sender: &RpcSender,
+) -> io::Result<Vec<u8>> {
+    let QueryHeader { tag, version, id } = header;
+    let name = tag.to_string_lossy();
+
+    match (name.as_str(), version) {
+        (VersionedRpcMenuV1::NAME, VersionedRpcMenuV1::VERSION) => {
+            let menu = [
+                (SnarkWorkerGetWorkV2::NAME, SnarkWorkerGetWorkV2::VERSION),
+                (
+                    SnarkWorkerSubmitWorkV2::NAME,
+                    SnarkWorkerSubmitWorkV2::VERSION,
+                ),
+                FAILED_TO_GENERATE_SNARK,
+            ]
+            .into_iter()
+            .map(|(name, version)| (name.into(), version))
+            .collect::<<VersionedRpcMenuV1 as RpcMethod>::Response>();
+            encode_response(id, Ok(menu))
+        }
+        (SnarkWorkerGetWorkV2::NAME, SnarkWorkerGetWorkV2::VERSION) => {
+            let work: RpcSnarkerWorkGetResponse = rpc_sender
+                .oneshot_request(RpcRequest::SnarkerWorkGet)
+                .await
+                .ok_or_else(node_shutdown)?;
+            encode_response(id, Ok(work))
+        }
+        (SnarkWorkerSubmitWorkV2::NAME, SnarkWorkerSubmitWorkV2::VERSION) => {
+            let work =
+                SnarkWorkerSubmitWorkV2::query_payload(&mut payload).map_err(invalid_data)?;
+            let resp: RpcSnarkerWorkSubmitResponse = rpc_sender
+                .oneshot_request(RpcRequest::SnarkerWorkSubmit(Box::new(work)))
+                .await
+                .ok_or_else(node_shutdown)?;
+            if let RpcSnarkerWorkSubmitResponse::JobNotFound = resp {
+                openmina_core::log::info!(openmina_core::log::system_time();
+                    kind = "SnarkWorkerCoordinator",
+                    summary = "submitted work is no longer needed");
+            }
+            encode_response(id, Ok(()))
+        }
+        FAILED_TO_GENERATE_SNARK => {
+            openmina_core::log::warn!(openmina_core::log::system_time();
+                kind = "SnarkWorkerCoordinator",
+                summary = "snark worker failed to generate snark");
+            encode_response(id, Ok(()))
+        }
+        _ => encode_response::<()>(id, Err(Error::Unimplemented_rpc(tag, version))),
+    }
+}
+
+async fn read_message<R: AsyncRead + Unpin>(r: &mut R) -> io::Result<Vec<u8>> {
+    let len = r.read_u64_le().await?;
+    if len > MAX_MESSAGE_LEN {
+        return Err(invalid_data(format!("message too long: {len}")));
+    }
+    let mut buf = vec![0; len as usize];
+    r.read_exact(&mut buf).await?;
+    Ok(buf)
+}
+
+fn encode_response<T: BinProtWrite>(id: QueryID, data: Result<T, Error>) -> io::Result<Vec<u8>> {
+    let msg = Message::Response(Response {
+        id,
+        data: RpcResult(data.map(NeedsLength)),
+    });
+    let mut bytes = vec![0; 8];
+    msg.binprot_write(&mut bytes)?;
+    let len = (bytes.len() - 8) as u64;
+    bytes[..8].copy_from_slice(&len.to_le_bytes());
+    Ok(bytes)
+}
+
+fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+fn node_shutdown() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "node is shutting down")
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::gossip::GossipNetMessageV2;
+    use mina_p2p_messages::number::Number;
+    use mina_p2p_messages::rpc_kernel::Query;
+    use mina_p2p_messages::v2::{
+        NetworkPoolSnarkPoolDiffVersionedStableV2,
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+        SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery,
+        SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetrics,
+        SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetricsA1,
+    };
+    use node::core::channels::oneshot;
+
+    use super::*;
+    use crate::NodeRpcRequest;
+
+    fn get_work_response() -> SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse {
+        const DATA: &[u8] = include_bytes!("../../../tests/files/snark_spec/spec1.bin");
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse::binprot_read(&mut &DATA[..]).unwrap()
+    }
+
+    fn submit_work_query() -> SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery {
+        const DATA: &[u8] =
+            include_bytes!("../../../mina-p2p-messages/tests/files/v2/gossip/snark_pool_diff.bin");
+        let GossipNetMessageV2::SnarkPoolDiff {
+            message: NetworkPoolSnarkPoolDiffVersionedStableV2::AddSolvedWork(work),
+            ..
+        } = GossipNetMessageV2::binprot_read(&mut &DATA[..]).unwrap()
+        else {
+            unreachable!("not a snark pool diff with work");
+        };
+        let (_, work) = *work;
+        let SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((spec, _))) = get_work_response()
+        else {
+            unreachable!("no job in the work spec");
+        };
+
+        SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery {
+            proofs: work.proof,
+            metrics: SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetrics::One((
+                Number(1.5),
+                SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetricsA1::Transition,
+            )),
+            spec,
+            prover: work.fee.prover,
+        }
+    }
+
+    /// Starts the coordinator with the state machine replaced by a task,
+    /// which responds with `get_work` and forwards submitted work to the
+    /// returned channel.
+    async fn start_coordinator() -> (
+        SocketAddr,
+        mpsc::UnboundedReceiver<SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery>,
+    ) {
+        start_coordinator_allowing(vec![]).await
+    }
+
+    async fn start_coordinator_allowing(
+        allowed_ips: Vec<IpAddr>,
+    ) -> (
+        SocketAddr,
+        mpsc::UnboundedReceiver<SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let config = SnarkWorkerCoordinatorConfig {
+            bind: addr,
+            allowed_ips,
+        };
+        let (req_tx, mut req_rx) = mpsc::channel::<NodeRpcRequest>(8);
+        let (submitted_tx, submitted_rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(serve(listener, config, RpcSender::new(req_tx)));
+        tokio::spawn(async move {
+            while let Some(NodeRpcRequest { req, responder }) = req_rx.recv().await {
+                match req {
+                    RpcRequest::SnarkerWorkGet => {
+                        let responder = responder
+                            .downcast::<oneshot::Sender<RpcSnarkerWorkGetResponse>>()
+                            .unwrap();
+                        let _ = responder.send(get_work_response());
+                    }
+                    RpcRequest::SnarkerWorkSubmit(work) => {
+                        let responder = responder
+                            .downcast::<oneshot::Sender<RpcSnarkerWorkSubmitResponse>>()
+                            .unwrap();
+                        let _ = submitted_tx.send(*work);
+                        let _ = responder.send(RpcSnarkerWorkSubmitResponse::Ok);
+                    }
+                    req => panic!("unexpected request: {req:?}"),
+                }
+            }
+        });
+
+        (addr, submitted_rx)
+    }
+
+    async fn query<M>(
+        stream: &mut TcpStream,
+        id: QueryID,
+        query: M::Query,
+    ) -> Result<M::Response, Error>
+    where
+        M: RpcMethod,
+    {
+        let msg = Message::Query(Query {
+            tag: M::NAME.into(),
+            version: M::VERSION,
+            id,
+            data: NeedsLength(query),
+        });
+        let mut bytes = vec![0; 8];
+        msg.binprot_write(&mut bytes).unwrap();
+        let len = (bytes.len() - 8) as u64;
+        bytes[..8].copy_from_slice(&len.to_le_bytes());
+        stream.write_all(&bytes).await.unwrap();
+
+        // skip handshake and heartbeats.
+        loop {
+            let message = read_message(stream).await.unwrap();
+            let mut payload = message.as_slice();
+            match MessageHeader::binprot_read(&mut payload).unwrap() {
+                MessageHeader::Response(header) if header.id == id => {
+                    return M::response_payload(&mut payload).unwrap();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn get_work() {
+        let (addr, _) = start_coordinator().await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+
+        let work = query::<SnarkWorkerGetWorkV2>(&mut stream, 1, ()).await;
+        assert_eq!(work, Ok(get_work_response()));
+    }
+
+    #[tokio::test]
+    async fn submit_work() {
+        let (addr, mut submitted) = start_coordinator().await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+
+        let work = submit_work_query();
+        let resp = query::<SnarkWorkerSubmitWorkV2>(&mut stream, 1, work.clone()).await;
+        assert_eq!(resp, Ok(()));
+        assert_eq!(submitted.recv().await, Some(work));
+    }
+
+    #[tokio::test]
+    async fn menu_and_unknown_rpc() {
+        let (addr, _) = start_coordinator().await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+
+        let menu = query::<VersionedRpcMenuV1>(&mut stream, 1, ())
+            .await
+            .unwrap();
+        let menu = menu
+            .into_iter()
+            .map(|(name, version)| (name.to_string_lossy(), version))
+            .collect::<Vec<_>>();
+        assert!(menu.contains(&(SnarkWorkerGetWorkV2::NAME.to_owned(), 2)));
+        assert!(menu.contains(&(SnarkWorkerSubmitWorkV2::NAME.to_owned(), 2)));
+
+        struct GetWorkV1;
+        impl RpcMethod for GetWorkV1 {
+            const NAME: &'static str = SnarkWorkerGetWorkV2::NAME;
+            const VERSION: i32 = 1;
+            type Query = ();
+            type Response = ();
+        }
+        let resp = query::<GetWorkV1>(&mut stream, 2, ()).await;
+        assert!(matches!(resp, Err(Error::Unimplemented_rpc(_, 1))));
+    }
+
+    #[tokio::test]
+    async fn allowed_ips() {
+        let (addr, _) = start_coordinator_allowing(vec![[127, 0, 0, 1].into()]).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let work = query::<SnarkWorkerGetWorkV2>(&mut stream, 1, ()).await;
+        assert_eq!(work, Ok(get_work_response()));
+
+        // Connection is closed before the handshake.
+        let (addr, _) = start_coordinator_allowing(vec![[10, 0, 0, 1].into()]).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let mut buf = [0; 1];
+        assert_eq!(stream.read(&mut buf).await.unwrap(), 0);
+    }
+
+    #[test]
+    fn ipv4_mapped_address_allowed() {
+        let config = SnarkWorkerCoordinatorConfig {
+            bind: ([0, 0, 0, 0], 0).into(),
+            allowed_ips: vec![[192, 168, 0, 2].into()],
+        };
+        let mapped = std::net::Ipv4Addr::new(192, 168, 0, 2).to_ipv6_mapped();
+        assert!(config.is_allowed(mapped.into()));
+        assert!(config.is_allowed([192, 168, 0, 2].into()));
+        assert!(!config.is_allowed([192, 168, 0, 3].into()));
+    }
+}
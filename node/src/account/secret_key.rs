@@ -1,6 +1,8 @@
 use std::{fmt, str::FromStr};
 
-use mina_signer::{keypair::KeypairError, Keypair};
+use ledger::scan_state::transaction_logic::signed_command::{SignedCommand, SignedCommandPayload};
+use ledger::scan_state::transaction_logic::transaction_union_payload::TransactionUnionPayload;
+use mina_signer::{keypair::KeypairError, Keypair, NetworkId, Signer};
 
 use super::AccountPublicKey;
 
@@ -28,6 +30,33 @@ impl AccountSecretKey {
     pub fn public_key(&self) -> AccountPublicKey {
         self.0.public.clone().into()
     }
+
+    /// Signs the payment or stake delegation payload. Fee payer of the
+    /// payload must be this key's account.
+    ///
+    /// `network_id` determines the signature prefix, see
+    /// [`crate::NetworkId::signature_network_id`].
+    pub fn sign_command(
+        &self,
+        network_id: NetworkId,
+        payload: SignedCommandPayload,
+    ) -> SignedCommand {
+        let payload_to_sign = TransactionUnionPayload::of_user_command_payload(&payload);
+        let mut signer = mina_signer::create_legacy(network_id);
+        let signature = signer.sign(&self.0, &payload_to_sign);
+
+        SignedCommand {
+            payload,
+            signer: self.0.public.into_compressed(),
+            signature,
+        }
+    }
+}
+
+impl AsRef<Keypair> for AccountSecretKey {
+    fn as_ref(&self) -> &Keypair {
+        &self.0
+    }
 }
 
 impl FromStr for AccountSecretKey {
@@ -76,6 +105,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_account_secret_key_sign_command() {
+        use ledger::scan_state::currency::{Amount, Fee, Nonce};
+        use ledger::scan_state::transaction_logic::signed_command::{Body, PaymentPayload};
+        use ledger::scan_state::transaction_logic::{verifiable, Memo};
+
+        let sender = AccountSecretKey::rand();
+        let receiver = AccountSecretKey::rand().public_key();
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(10_000_000),
+            sender.public_key().as_ref().into(),
+            Nonce::from_u32(0),
+            None,
+            Memo::create_from_string("hello").unwrap(),
+            Body::Payment(PaymentPayload {
+                receiver_pk: receiver.as_ref().into(),
+                amount: Amount::from_u64(1_000_000_000),
+            }),
+        );
+        let cmd = sender.sign_command(NetworkId::TESTNET, payload);
+        assert!(verifiable::check_only_for_signature(Box::new(cmd)).is_ok());
+    }
+
     #[test]
     fn test_account_secret_key_display() {
         let parsed: AccountSecretKey = "EKFWgzXsoMYcP1Hnj7dBhsefxNucZ6wyz676Qg5uMFNzytXAi2Ww"
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 pub type ActionWithMeta = redux::ActionWithMeta<Action>;
 pub type ActionWithMetaRef<'a> = redux::ActionWithMeta<&'a Action>;
 
+pub use crate::block_producer::BlockProducerAction;
 pub use crate::consensus::ConsensusAction;
 pub use crate::event_source::EventSourceAction;
 use crate::external_snark_worker::ExternalSnarkWorkerAction;
@@ -10,6 +11,7 @@ pub use crate::p2p::P2pAction;
 pub use crate::rpc::RpcAction;
 pub use crate::snark::SnarkAction;
 pub use crate::snark_pool::SnarkPoolAction;
+pub use crate::transaction_pool::TransactionPoolAction;
 pub use crate::transition_frontier::TransitionFrontierAction;
 pub use crate::watched_accounts::WatchedAccountsAction;
 
@@ -27,6 +29,8 @@ pub enum Action {
     Consensus(ConsensusAction),
     TransitionFrontier(TransitionFrontierAction),
     SnarkPool(SnarkPoolAction),
+    TransactionPool(TransactionPoolAction),
+    BlockProducer(BlockProducerAction),
     Rpc(RpcAction),
     ExternalSnarkWorker(ExternalSnarkWorkerAction),
 
@@ -14,6 +14,18 @@
 use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
 
+use crate::block_producer::{
+    BlockProducerAction, BlockProducerBlockInjectAction, BlockProducerBlockProducedAction,
+    BlockProducerBlockProveInitAction, BlockProducerBlockProvePendingAction,
+    BlockProducerBlockProveSuccessAction, BlockProducerBlockUnprovenBuildAction,
+    BlockProducerStagedLedgerDiffCreateInitAction,
+    BlockProducerStagedLedgerDiffCreatePendingAction,
+    BlockProducerStagedLedgerDiffCreateSuccessAction, BlockProducerVrfEvaluationErrorAction,
+    BlockProducerVrfEvaluationInitAction, BlockProducerVrfEvaluationPendingAction,
+    BlockProducerVrfEvaluationSuccessAction, BlockProducerWonSlotAction,
+    BlockProducerWonSlotDiscardAction, BlockProducerWonSlotProduceInitAction,
+    BlockProducerWonSlotSearchAction,
+};
 use crate::consensus::{
     ConsensusAction, ConsensusBestTipUpdateAction, ConsensusBlockChainProofUpdateAction,
     ConsensusBlockReceivedAction, ConsensusBlockSnarkVerifyPendingAction,
@@ -60,6 +72,14 @@ use crate::p2p::channels::snark_job_commitment::{
     P2pChannelsSnarkJobCommitmentRequestSendAction,
     P2pChannelsSnarkJobCommitmentResponseSendAction,
 };
+use crate::p2p::channels::transaction::{
+    P2pChannelsTransactionAction, P2pChannelsTransactionInitAction,
+    P2pChannelsTransactionLibp2pBroadcastAction, P2pChannelsTransactionLibp2pReceivedAction,
+    P2pChannelsTransactionPendingAction, P2pChannelsTransactionPromiseReceivedAction,
+    P2pChannelsTransactionReadyAction, P2pChannelsTransactionReceivedAction,
+    P2pChannelsTransactionRequestReceivedAction, P2pChannelsTransactionRequestSendAction,
+    P2pChannelsTransactionResponseSendAction,
+};
 use crate::p2p::channels::{P2pChannelsAction, P2pChannelsMessageReceivedAction};
 use crate::p2p::connection::incoming::{
     P2pConnectionIncomingAction, P2pConnectionIncomingAnswerReadyAction,
@@ -88,21 +108,30 @@ use crate::p2p::disconnection::{
     P2pDisconnectionAction, P2pDisconnectionFinishAction, P2pDisconnectionInitAction,
 };
 use crate::p2p::discovery::{
-    P2pDiscoveryAction, P2pDiscoveryInitAction, P2pDiscoverySuccessAction,
-    P2pDiscoveryTimeoutAction,
+    P2pDiscoveryAction, P2pDiscoveryInitAction, P2pDiscoveryKademliaAddRouteAction,
+    P2pDiscoveryKademliaFailureAction, P2pDiscoveryKademliaInitAction,
+    P2pDiscoveryKademliaSuccessAction, P2pDiscoverySuccessAction, P2pDiscoveryTimeoutAction,
+};
+use crate::p2p::peer::{
+    P2pPeerAction, P2pPeerBanAction, P2pPeerBestTipUpdateAction, P2pPeerForgiveAction,
+    P2pPeerPenalizeAction, P2pPeerReadyAction, P2pPeerUnbanAction,
 };
-use crate::p2p::peer::{P2pPeerAction, P2pPeerBestTipUpdateAction, P2pPeerReadyAction};
 use crate::p2p::P2pAction;
 use crate::rpc::{
-    RpcAction, RpcActionStatsGetAction, RpcFinishAction, RpcGlobalStateGetAction,
-    RpcHealthCheckAction, RpcP2pConnectionIncomingErrorAction, RpcP2pConnectionIncomingInitAction,
+    RpcAccountGetAction, RpcAction, RpcActionStatsGetAction, RpcBestChainGetAction,
+    RpcBlockGetAction, RpcEventNotifyAction, RpcFinishAction, RpcGlobalStateGetAction,
+    RpcHealthCheckAction, RpcMetricsGetAction, RpcNodeStatusGetAction,
+    RpcP2pConnectionIncomingErrorAction, RpcP2pConnectionIncomingInitAction,
     RpcP2pConnectionIncomingPendingAction, RpcP2pConnectionIncomingRespondAction,
     RpcP2pConnectionIncomingSuccessAction, RpcP2pConnectionOutgoingErrorAction,
     RpcP2pConnectionOutgoingInitAction, RpcP2pConnectionOutgoingPendingAction,
-    RpcP2pConnectionOutgoingSuccessAction, RpcReadinessCheckAction, RpcScanStateSummaryGetAction,
+    RpcP2pConnectionOutgoingSuccessAction, RpcPeerBanAction, RpcPeerUnbanAction,
+    RpcPeersReputationGetAction, RpcReadinessCheckAction, RpcScanStateSummaryGetAction,
     RpcSnarkPoolAvailableJobsGetAction, RpcSnarkPoolJobGetAction, RpcSnarkerConfigGetAction,
-    RpcSnarkerJobCommitAction, RpcSnarkerJobSpecAction, RpcSnarkersWorkersGetAction,
-    RpcSyncStatsGetAction,
+    RpcSnarkerJobCommitAction, RpcSnarkerJobSpecAction, RpcSnarkerWorkGetAction,
+    RpcSnarkerWorkSubmitAction, RpcSnarkersWorkersGetAction, RpcStakeGetAction, RpcSubscribeAction,
+    RpcSyncStatsGetAction, RpcTransactionNonceGetAction, RpcTransactionPoolGetAction,
+    RpcTransactionSendAction, RpcUnsubscribeAction,
 };
 use crate::snark::block_verify::{
     SnarkBlockVerifyAction, SnarkBlockVerifyErrorAction, SnarkBlockVerifyFinishAction,
@@ -127,6 +156,11 @@ use crate::snark_pool::{
     SnarkPoolJobCommitmentTimeoutAction, SnarkPoolJobsUpdateAction, SnarkPoolP2pSendAction,
     SnarkPoolP2pSendAllAction, SnarkPoolWorkAddAction,
 };
+use crate::transaction_pool::{
+    TransactionPoolAction, TransactionPoolAddAction, TransactionPoolBestTipUpdateAction,
+    TransactionPoolP2pSendAction, TransactionPoolP2pSendAllAction, TransactionPoolReceivedAction,
+    TransactionPoolRejectAction, TransactionPoolRemoveAction,
+};
 use crate::transition_frontier::sync::ledger::snarked::{
     TransitionFrontierSyncLedgerSnarkedAction,
     TransitionFrontierSyncLedgerSnarkedChildAccountsReceivedAction,
@@ -174,7 +208,11 @@ use crate::transition_frontier::sync::{
     TransitionFrontierSyncBlocksPeerQuerySuccessAction,
     TransitionFrontierSyncBlocksPeersQueryAction, TransitionFrontierSyncBlocksPendingAction,
     TransitionFrontierSyncBlocksSuccessAction, TransitionFrontierSyncInitAction,
+    TransitionFrontierSyncLedgerNextEpochPendingAction,
+    TransitionFrontierSyncLedgerNextEpochSuccessAction,
     TransitionFrontierSyncLedgerRootPendingAction, TransitionFrontierSyncLedgerRootSuccessAction,
+    TransitionFrontierSyncLedgerStakingPendingAction,
+    TransitionFrontierSyncLedgerStakingSuccessAction,
 };
 use crate::transition_frontier::{TransitionFrontierAction, TransitionFrontierSyncedAction};
 use crate::watched_accounts::{
@@ -196,6 +234,23 @@ use crate::{Action, ActionKindGet, CheckTimeoutsAction};
 #[repr(u16)]
 pub enum ActionKind {
     None,
+    BlockProducerBlockInject,
+    BlockProducerBlockProduced,
+    BlockProducerBlockProveInit,
+    BlockProducerBlockProvePending,
+    BlockProducerBlockProveSuccess,
+    BlockProducerBlockUnprovenBuild,
+    BlockProducerStagedLedgerDiffCreateInit,
+    BlockProducerStagedLedgerDiffCreatePending,
+    BlockProducerStagedLedgerDiffCreateSuccess,
+    BlockProducerVrfEvaluationError,
+    BlockProducerVrfEvaluationInit,
+    BlockProducerVrfEvaluationPending,
+    BlockProducerVrfEvaluationSuccess,
+    BlockProducerWonSlot,
+    BlockProducerWonSlotDiscard,
+    BlockProducerWonSlotProduceInit,
+    BlockProducerWonSlotSearch,
     CheckTimeouts,
     ConsensusBestTipUpdate,
     ConsensusBlockChainProofUpdate,
@@ -256,6 +311,16 @@ pub enum ActionKind {
     P2pChannelsSnarkRequestReceived,
     P2pChannelsSnarkRequestSend,
     P2pChannelsSnarkResponseSend,
+    P2pChannelsTransactionInit,
+    P2pChannelsTransactionLibp2pBroadcast,
+    P2pChannelsTransactionLibp2pReceived,
+    P2pChannelsTransactionPending,
+    P2pChannelsTransactionPromiseReceived,
+    P2pChannelsTransactionReady,
+    P2pChannelsTransactionReceived,
+    P2pChannelsTransactionRequestReceived,
+    P2pChannelsTransactionRequestSend,
+    P2pChannelsTransactionResponseSend,
     P2pConnectionIncomingAnswerReady,
     P2pConnectionIncomingAnswerSdpCreateError,
     P2pConnectionIncomingAnswerSdpCreatePending,
@@ -288,14 +353,28 @@ pub enum ActionKind {
     P2pDisconnectionFinish,
     P2pDisconnectionInit,
     P2pDiscoveryInit,
+    P2pDiscoveryKademliaAddRoute,
+    P2pDiscoveryKademliaFailure,
+    P2pDiscoveryKademliaInit,
+    P2pDiscoveryKademliaSuccess,
     P2pDiscoverySuccess,
     P2pDiscoveryTimeout,
+    P2pPeerBan,
     P2pPeerBestTipUpdate,
+    P2pPeerForgive,
+    P2pPeerPenalize,
     P2pPeerReady,
+    P2pPeerUnban,
+    RpcAccountGet,
     RpcActionStatsGet,
+    RpcBestChainGet,
+    RpcBlockGet,
+    RpcEventNotify,
     RpcFinish,
     RpcGlobalStateGet,
     RpcHealthCheck,
+    RpcMetricsGet,
+    RpcNodeStatusGet,
     RpcP2pConnectionIncomingError,
     RpcP2pConnectionIncomingInit,
     RpcP2pConnectionIncomingPending,
@@ -305,6 +384,9 @@ pub enum ActionKind {
     RpcP2pConnectionOutgoingInit,
     RpcP2pConnectionOutgoingPending,
     RpcP2pConnectionOutgoingSuccess,
+    RpcPeerBan,
+    RpcPeerUnban,
+    RpcPeersReputationGet,
     RpcReadinessCheck,
     RpcScanStateSummaryGet,
     RpcSnarkPoolAvailableJobsGet,
@@ -312,8 +394,16 @@ pub enum ActionKind {
     RpcSnarkerConfigGet,
     RpcSnarkerJobCommit,
     RpcSnarkerJobSpec,
+    RpcSnarkerWorkGet,
+    RpcSnarkerWorkSubmit,
     RpcSnarkersWorkersGet,
+    RpcStakeGet,
+    RpcSubscribe,
     RpcSyncStatsGet,
+    RpcTransactionNonceGet,
+    RpcTransactionPoolGet,
+    RpcTransactionSend,
+    RpcUnsubscribe,
     SnarkBlockVerifyError,
     SnarkBlockVerifyFinish,
     SnarkBlockVerifyInit,
@@ -343,6 +433,13 @@ pub enum ActionKind {
     SnarkWorkVerifyInit,
     SnarkWorkVerifyPending,
     SnarkWorkVerifySuccess,
+    TransactionPoolAdd,
+    TransactionPoolBestTipUpdate,
+    TransactionPoolP2pSend,
+    TransactionPoolP2pSendAll,
+    TransactionPoolReceived,
+    TransactionPoolReject,
+    TransactionPoolRemove,
     TransitionFrontierSyncBestTipUpdate,
     TransitionFrontierSyncBlocksFetchSuccess,
     TransitionFrontierSyncBlocksNextApplyInit,
@@ -358,6 +455,8 @@ pub enum ActionKind {
     TransitionFrontierSyncBlocksSuccess,
     TransitionFrontierSyncInit,
     TransitionFrontierSyncLedgerInit,
+    TransitionFrontierSyncLedgerNextEpochPending,
+    TransitionFrontierSyncLedgerNextEpochSuccess,
     TransitionFrontierSyncLedgerRootPending,
     TransitionFrontierSyncLedgerRootSuccess,
     TransitionFrontierSyncLedgerSnarkedChildAccountsReceived,
@@ -384,6 +483,8 @@ pub enum ActionKind {
     TransitionFrontierSyncLedgerStagedReconstructPending,
     TransitionFrontierSyncLedgerStagedReconstructSuccess,
     TransitionFrontierSyncLedgerStagedSuccess,
+    TransitionFrontierSyncLedgerStakingPending,
+    TransitionFrontierSyncLedgerStakingSuccess,
     TransitionFrontierSyncLedgerSuccess,
     TransitionFrontierSynced,
     WatchedAccountsAdd,
@@ -399,7 +500,7 @@ pub enum ActionKind {
 }
 
 impl ActionKind {
-    pub const COUNT: u16 = 201;
+    pub const COUNT: u16 = 264;
 }
 
 impl std::fmt::Display for ActionKind {
@@ -418,6 +519,8 @@ impl ActionKindGet for Action {
             Self::Consensus(a) => a.kind(),
             Self::TransitionFrontier(a) => a.kind(),
             Self::SnarkPool(a) => a.kind(),
+            Self::TransactionPool(a) => a.kind(),
+            Self::BlockProducer(a) => a.kind(),
             Self::Rpc(a) => a.kind(),
             Self::ExternalSnarkWorker(a) => a.kind(),
             Self::WatchedAccounts(a) => a.kind(),
@@ -504,6 +607,44 @@ impl ActionKindGet for SnarkPoolAction {
     }
 }
 
+impl ActionKindGet for TransactionPoolAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Received(a) => a.kind(),
+            Self::Add(a) => a.kind(),
+            Self::Reject(a) => a.kind(),
+            Self::Remove(a) => a.kind(),
+            Self::BestTipUpdate(a) => a.kind(),
+            Self::P2pSendAll(a) => a.kind(),
+            Self::P2pSend(a) => a.kind(),
+        }
+    }
+}
+
+impl ActionKindGet for BlockProducerAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::VrfEvaluationInit(a) => a.kind(),
+            Self::VrfEvaluationPending(a) => a.kind(),
+            Self::VrfEvaluationSuccess(a) => a.kind(),
+            Self::VrfEvaluationError(a) => a.kind(),
+            Self::WonSlotSearch(a) => a.kind(),
+            Self::WonSlot(a) => a.kind(),
+            Self::WonSlotDiscard(a) => a.kind(),
+            Self::WonSlotProduceInit(a) => a.kind(),
+            Self::StagedLedgerDiffCreateInit(a) => a.kind(),
+            Self::StagedLedgerDiffCreatePending(a) => a.kind(),
+            Self::StagedLedgerDiffCreateSuccess(a) => a.kind(),
+            Self::BlockUnprovenBuild(a) => a.kind(),
+            Self::BlockProveInit(a) => a.kind(),
+            Self::BlockProvePending(a) => a.kind(),
+            Self::BlockProveSuccess(a) => a.kind(),
+            Self::BlockProduced(a) => a.kind(),
+            Self::BlockInject(a) => a.kind(),
+        }
+    }
+}
+
 impl ActionKindGet for RpcAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -526,6 +667,23 @@ impl ActionKindGet for RpcAction {
             Self::SnarkerJobCommit(a) => a.kind(),
             Self::SnarkerJobSpec(a) => a.kind(),
             Self::SnarkerWorkersGet(a) => a.kind(),
+            Self::SnarkerWorkGet(a) => a.kind(),
+            Self::SnarkerWorkSubmit(a) => a.kind(),
+            Self::TransactionNonceGet(a) => a.kind(),
+            Self::TransactionSend(a) => a.kind(),
+            Self::StakeGet(a) => a.kind(),
+            Self::BestChainGet(a) => a.kind(),
+            Self::BlockGet(a) => a.kind(),
+            Self::AccountGet(a) => a.kind(),
+            Self::TransactionPoolGet(a) => a.kind(),
+            Self::NodeStatusGet(a) => a.kind(),
+            Self::MetricsGet(a) => a.kind(),
+            Self::Subscribe(a) => a.kind(),
+            Self::EventNotify(a) => a.kind(),
+            Self::Unsubscribe(a) => a.kind(),
+            Self::PeersReputationGet(a) => a.kind(),
+            Self::PeerBan(a) => a.kind(),
+            Self::PeerUnban(a) => a.kind(),
             Self::HealthCheck(a) => a.kind(),
             Self::ReadinessCheck(a) => a.kind(),
             Self::Finish(a) => a.kind(),
@@ -618,6 +776,10 @@ impl ActionKindGet for P2pDiscoveryAction {
             Self::Init(a) => a.kind(),
             Self::Success(a) => a.kind(),
             Self::Timeout(a) => a.kind(),
+            Self::KademliaInit(a) => a.kind(),
+            Self::KademliaAddRoute(a) => a.kind(),
+            Self::KademliaSuccess(a) => a.kind(),
+            Self::KademliaFailure(a) => a.kind(),
         }
     }
 }
@@ -627,6 +789,7 @@ impl ActionKindGet for P2pChannelsAction {
         match self {
             Self::MessageReceived(a) => a.kind(),
             Self::BestTip(a) => a.kind(),
+            Self::Transaction(a) => a.kind(),
             Self::Snark(a) => a.kind(),
             Self::SnarkJobCommitment(a) => a.kind(),
             Self::Rpc(a) => a.kind(),
@@ -639,6 +802,10 @@ impl ActionKindGet for P2pPeerAction {
         match self {
             Self::Ready(a) => a.kind(),
             Self::BestTipUpdate(a) => a.kind(),
+            Self::Penalize(a) => a.kind(),
+            Self::Ban(a) => a.kind(),
+            Self::Unban(a) => a.kind(),
+            Self::Forgive(a) => a.kind(),
         }
     }
 }
@@ -720,6 +887,10 @@ impl ActionKindGet for TransitionFrontierSyncAction {
         match self {
             Self::Init(a) => a.kind(),
             Self::BestTipUpdate(a) => a.kind(),
+            Self::LedgerStakingPending(a) => a.kind(),
+            Self::LedgerStakingSuccess(a) => a.kind(),
+            Self::LedgerNextEpochPending(a) => a.kind(),
+            Self::LedgerNextEpochSuccess(a) => a.kind(),
             Self::LedgerRootPending(a) => a.kind(),
             Self::LedgerRootSuccess(a) => a.kind(),
             Self::BlocksPending(a) => a.kind(),
@@ -816,6 +987,150 @@ impl ActionKindGet for SnarkPoolJobCommitmentTimeoutAction {
     }
 }
 
+impl ActionKindGet for TransactionPoolReceivedAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransactionPoolReceived
+    }
+}
+
+impl ActionKindGet for TransactionPoolAddAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransactionPoolAdd
+    }
+}
+
+impl ActionKindGet for TransactionPoolRejectAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransactionPoolReject
+    }
+}
+
+impl ActionKindGet for TransactionPoolRemoveAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransactionPoolRemove
+    }
+}
+
+impl ActionKindGet for TransactionPoolBestTipUpdateAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransactionPoolBestTipUpdate
+    }
+}
+
+impl ActionKindGet for TransactionPoolP2pSendAllAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransactionPoolP2pSendAll
+    }
+}
+
+impl ActionKindGet for TransactionPoolP2pSendAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransactionPoolP2pSend
+    }
+}
+
+impl ActionKindGet for BlockProducerVrfEvaluationInitAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerVrfEvaluationInit
+    }
+}
+
+impl ActionKindGet for BlockProducerVrfEvaluationPendingAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerVrfEvaluationPending
+    }
+}
+
+impl ActionKindGet for BlockProducerVrfEvaluationSuccessAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerVrfEvaluationSuccess
+    }
+}
+
+impl ActionKindGet for BlockProducerVrfEvaluationErrorAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerVrfEvaluationError
+    }
+}
+
+impl ActionKindGet for BlockProducerWonSlotSearchAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerWonSlotSearch
+    }
+}
+
+impl ActionKindGet for BlockProducerWonSlotAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerWonSlot
+    }
+}
+
+impl ActionKindGet for BlockProducerWonSlotDiscardAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerWonSlotDiscard
+    }
+}
+
+impl ActionKindGet for BlockProducerWonSlotProduceInitAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerWonSlotProduceInit
+    }
+}
+
+impl ActionKindGet for BlockProducerStagedLedgerDiffCreateInitAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerStagedLedgerDiffCreateInit
+    }
+}
+
+impl ActionKindGet for BlockProducerStagedLedgerDiffCreatePendingAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerStagedLedgerDiffCreatePending
+    }
+}
+
+impl ActionKindGet for BlockProducerStagedLedgerDiffCreateSuccessAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerStagedLedgerDiffCreateSuccess
+    }
+}
+
+impl ActionKindGet for BlockProducerBlockUnprovenBuildAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerBlockUnprovenBuild
+    }
+}
+
+impl ActionKindGet for BlockProducerBlockProveInitAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerBlockProveInit
+    }
+}
+
+impl ActionKindGet for BlockProducerBlockProvePendingAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerBlockProvePending
+    }
+}
+
+impl ActionKindGet for BlockProducerBlockProveSuccessAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerBlockProveSuccess
+    }
+}
+
+impl ActionKindGet for BlockProducerBlockProducedAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerBlockProduced
+    }
+}
+
+impl ActionKindGet for BlockProducerBlockInjectAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::BlockProducerBlockInject
+    }
+}
+
 impl ActionKindGet for RpcGlobalStateGetAction {
     fn kind(&self) -> ActionKind {
         ActionKind::RpcGlobalStateGet
@@ -930,6 +1245,108 @@ impl ActionKindGet for RpcSnarkersWorkersGetAction {
     }
 }
 
+impl ActionKindGet for RpcSnarkerWorkGetAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcSnarkerWorkGet
+    }
+}
+
+impl ActionKindGet for RpcSnarkerWorkSubmitAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcSnarkerWorkSubmit
+    }
+}
+
+impl ActionKindGet for RpcTransactionNonceGetAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcTransactionNonceGet
+    }
+}
+
+impl ActionKindGet for RpcTransactionSendAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcTransactionSend
+    }
+}
+
+impl ActionKindGet for RpcStakeGetAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcStakeGet
+    }
+}
+
+impl ActionKindGet for RpcBestChainGetAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcBestChainGet
+    }
+}
+
+impl ActionKindGet for RpcBlockGetAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcBlockGet
+    }
+}
+
+impl ActionKindGet for RpcAccountGetAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcAccountGet
+    }
+}
+
+impl ActionKindGet for RpcTransactionPoolGetAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcTransactionPoolGet
+    }
+}
+
+impl ActionKindGet for RpcNodeStatusGetAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcNodeStatusGet
+    }
+}
+
+impl ActionKindGet for RpcMetricsGetAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcMetricsGet
+    }
+}
+
+impl ActionKindGet for RpcSubscribeAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcSubscribe
+    }
+}
+
+impl ActionKindGet for RpcEventNotifyAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcEventNotify
+    }
+}
+
+impl ActionKindGet for RpcUnsubscribeAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcUnsubscribe
+    }
+}
+
+impl ActionKindGet for RpcPeersReputationGetAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcPeersReputationGet
+    }
+}
+
+impl ActionKindGet for RpcPeerBanAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcPeerBan
+    }
+}
+
+impl ActionKindGet for RpcPeerUnbanAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::RpcPeerUnban
+    }
+}
+
 impl ActionKindGet for RpcHealthCheckAction {
     fn kind(&self) -> ActionKind {
         ActionKind::RpcHealthCheck
@@ -1159,6 +1576,30 @@ impl ActionKindGet for P2pDiscoveryTimeoutAction {
     }
 }
 
+impl ActionKindGet for P2pDiscoveryKademliaInitAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pDiscoveryKademliaInit
+    }
+}
+
+impl ActionKindGet for P2pDiscoveryKademliaAddRouteAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pDiscoveryKademliaAddRoute
+    }
+}
+
+impl ActionKindGet for P2pDiscoveryKademliaSuccessAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pDiscoveryKademliaSuccess
+    }
+}
+
+impl ActionKindGet for P2pDiscoveryKademliaFailureAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pDiscoveryKademliaFailure
+    }
+}
+
 impl ActionKindGet for P2pChannelsMessageReceivedAction {
     fn kind(&self) -> ActionKind {
         ActionKind::P2pChannelsMessageReceived
@@ -1179,6 +1620,23 @@ impl ActionKindGet for P2pChannelsBestTipAction {
     }
 }
 
+impl ActionKindGet for P2pChannelsTransactionAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Init(a) => a.kind(),
+            Self::Pending(a) => a.kind(),
+            Self::Ready(a) => a.kind(),
+            Self::RequestSend(a) => a.kind(),
+            Self::PromiseReceived(a) => a.kind(),
+            Self::Received(a) => a.kind(),
+            Self::RequestReceived(a) => a.kind(),
+            Self::ResponseSend(a) => a.kind(),
+            Self::Libp2pReceived(a) => a.kind(),
+            Self::Libp2pBroadcast(a) => a.kind(),
+        }
+    }
+}
+
 impl ActionKindGet for P2pChannelsSnarkAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1238,6 +1696,30 @@ impl ActionKindGet for P2pPeerBestTipUpdateAction {
     }
 }
 
+impl ActionKindGet for P2pPeerPenalizeAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pPeerPenalize
+    }
+}
+
+impl ActionKindGet for P2pPeerBanAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pPeerBan
+    }
+}
+
+impl ActionKindGet for P2pPeerUnbanAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pPeerUnban
+    }
+}
+
+impl ActionKindGet for P2pPeerForgiveAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pPeerForgive
+    }
+}
+
 impl ActionKindGet for SnarkBlockVerifyInitAction {
     fn kind(&self) -> ActionKind {
         ActionKind::SnarkBlockVerifyInit
@@ -1310,6 +1792,30 @@ impl ActionKindGet for TransitionFrontierSyncBestTipUpdateAction {
     }
 }
 
+impl ActionKindGet for TransitionFrontierSyncLedgerStakingPendingAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransitionFrontierSyncLedgerStakingPending
+    }
+}
+
+impl ActionKindGet for TransitionFrontierSyncLedgerStakingSuccessAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransitionFrontierSyncLedgerStakingSuccess
+    }
+}
+
+impl ActionKindGet for TransitionFrontierSyncLedgerNextEpochPendingAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransitionFrontierSyncLedgerNextEpochPending
+    }
+}
+
+impl ActionKindGet for TransitionFrontierSyncLedgerNextEpochSuccessAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::TransitionFrontierSyncLedgerNextEpochSuccess
+    }
+}
+
 impl ActionKindGet for TransitionFrontierSyncLedgerRootPendingAction {
     fn kind(&self) -> ActionKind {
         ActionKind::TransitionFrontierSyncLedgerRootPending
@@ -1681,6 +2187,66 @@ impl ActionKindGet for P2pChannelsBestTipResponseSendAction {
     }
 }
 
+impl ActionKindGet for P2pChannelsTransactionInitAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsTransactionInit
+    }
+}
+
+impl ActionKindGet for P2pChannelsTransactionPendingAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsTransactionPending
+    }
+}
+
+impl ActionKindGet for P2pChannelsTransactionReadyAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsTransactionReady
+    }
+}
+
+impl ActionKindGet for P2pChannelsTransactionRequestSendAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsTransactionRequestSend
+    }
+}
+
+impl ActionKindGet for P2pChannelsTransactionPromiseReceivedAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsTransactionPromiseReceived
+    }
+}
+
+impl ActionKindGet for P2pChannelsTransactionReceivedAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsTransactionReceived
+    }
+}
+
+impl ActionKindGet for P2pChannelsTransactionRequestReceivedAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsTransactionRequestReceived
+    }
+}
+
+impl ActionKindGet for P2pChannelsTransactionResponseSendAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsTransactionResponseSend
+    }
+}
+
+impl ActionKindGet for P2pChannelsTransactionLibp2pReceivedAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsTransactionLibp2pReceived
+    }
+}
+
+impl ActionKindGet for P2pChannelsTransactionLibp2pBroadcastAction {
+    fn kind(&self) -> ActionKind {
+        ActionKind::P2pChannelsTransactionLibp2pBroadcast
+    }
+}
+
 impl ActionKindGet for P2pChannelsSnarkInitAction {
     fn kind(&self) -> ActionKind {
         ActionKind::P2pChannelsSnarkInit
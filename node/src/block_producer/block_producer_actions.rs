@@ -0,0 +1,332 @@
+use std::sync::Arc;
+
+use mina_p2p_messages::v2::{MinaBaseProofStableV2, StateHash};
+use openmina_core::block::ArcBlockWithHash;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    BlockProducerCurrentState, BlockProducerEpochData, BlockProducerStagedLedgerDiffCreateOutput,
+    BlockProducerVrfEvaluatorOutput, BlockProducerVrfEvaluatorStatus, BlockProducerWonSlot,
+    BlockProducerWonSlotDiscardReason,
+};
+
+pub type BlockProducerActionWithMeta = redux::ActionWithMeta<BlockProducerAction>;
+pub type BlockProducerActionWithMetaRef<'a> = redux::ActionWithMeta<&'a BlockProducerAction>;
+
+#[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
+pub enum BlockProducerAction {
+    VrfEvaluationInit(BlockProducerVrfEvaluationInitAction),
+    VrfEvaluationPending(BlockProducerVrfEvaluationPendingAction),
+    VrfEvaluationSuccess(BlockProducerVrfEvaluationSuccessAction),
+    VrfEvaluationError(BlockProducerVrfEvaluationErrorAction),
+    WonSlotSearch(BlockProducerWonSlotSearchAction),
+    WonSlot(BlockProducerWonSlotAction),
+    WonSlotDiscard(BlockProducerWonSlotDiscardAction),
+    WonSlotProduceInit(BlockProducerWonSlotProduceInitAction),
+    StagedLedgerDiffCreateInit(BlockProducerStagedLedgerDiffCreateInitAction),
+    StagedLedgerDiffCreatePending(BlockProducerStagedLedgerDiffCreatePendingAction),
+    StagedLedgerDiffCreateSuccess(BlockProducerStagedLedgerDiffCreateSuccessAction),
+    BlockUnprovenBuild(BlockProducerBlockUnprovenBuildAction),
+    BlockProveInit(BlockProducerBlockProveInitAction),
+    BlockProvePending(BlockProducerBlockProvePendingAction),
+    BlockProveSuccess(BlockProducerBlockProveSuccessAction),
+    BlockProduced(BlockProducerBlockProducedAction),
+    BlockInject(BlockProducerBlockInjectAction),
+}
+
+/// Start evaluating won slots for the staking epoch of the best tip,
+/// or for the next epoch, if it's data is already final.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerVrfEvaluationInitAction {}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerVrfEvaluationInitAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.transition_frontier.sync.is_synced()
+            && state
+                .transition_frontier
+                .best_tip()
+                .map_or(false, |best_tip| {
+                    state
+                        .block_producer
+                        .vrf_evaluation_next(best_tip, state.time())
+                        .is_some()
+                })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerVrfEvaluationPendingAction {
+    pub epoch_data: BlockProducerEpochData,
+}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerVrfEvaluationPendingAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.with(false, |this| {
+            !matches!(
+                this.vrf_evaluator.status,
+                BlockProducerVrfEvaluatorStatus::Pending { .. }
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerVrfEvaluationSuccessAction {
+    pub output: BlockProducerVrfEvaluatorOutput,
+}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerVrfEvaluationSuccessAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.with(false, |this| {
+            matches!(
+                &this.vrf_evaluator.status,
+                BlockProducerVrfEvaluatorStatus::Pending { epoch_data, .. }
+                    if epoch_data == &self.output.epoch_data
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerVrfEvaluationErrorAction {
+    pub error: String,
+}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerVrfEvaluationErrorAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.with(false, |this| {
+            matches!(
+                this.vrf_evaluator.status,
+                BlockProducerVrfEvaluatorStatus::Pending { .. }
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerWonSlotSearchAction {}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerWonSlotSearchAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state
+            .transition_frontier
+            .best_tip()
+            .map_or(false, |best_tip| {
+                state
+                    .block_producer
+                    .next_won_slot(best_tip, state.time())
+                    .is_some()
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerWonSlotAction {
+    pub won_slot: BlockProducerWonSlot,
+}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerWonSlotAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state
+            .block_producer
+            .with(false, |this| this.current.is_idle())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerWonSlotDiscardAction {
+    pub reason: BlockProducerWonSlotDiscardReason,
+}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerWonSlotDiscardAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.with(false, |this| {
+            !this.current.is_idle()
+                && !matches!(this.current, BlockProducerCurrentState::Produced { .. })
+        })
+    }
+}
+
+/// Start producing the block once the won slot begins.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerWonSlotProduceInitAction {}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerWonSlotProduceInitAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.transition_frontier.sync.is_synced()
+            && state.block_producer.with(false, |this| {
+                let BlockProducerCurrentState::WonSlot { won_slot, .. } = &this.current else {
+                    return false;
+                };
+                won_slot.is_started(state.time())
+                    && state
+                        .transition_frontier
+                        .best_tip()
+                        .and_then(|best_tip| state.block_producer.won_slot_discard_reason(best_tip))
+                        .is_none()
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerStagedLedgerDiffCreateInitAction {}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerStagedLedgerDiffCreateInitAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.with(false, |this| {
+            matches!(
+                this.current,
+                BlockProducerCurrentState::WonSlotProduceInit { .. }
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerStagedLedgerDiffCreatePendingAction {}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerStagedLedgerDiffCreatePendingAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.with(false, |this| {
+            matches!(
+                this.current,
+                BlockProducerCurrentState::WonSlotProduceInit { .. }
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerStagedLedgerDiffCreateSuccessAction {
+    /// Chain that we are extending, predecessor of the new block
+    /// being the last one.
+    pub chain: Vec<ArcBlockWithHash>,
+    pub output: BlockProducerStagedLedgerDiffCreateOutput,
+}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerStagedLedgerDiffCreateSuccessAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        !self.chain.is_empty()
+            && state.block_producer.with(false, |this| {
+                matches!(
+                    this.current,
+                    BlockProducerCurrentState::StagedLedgerDiffCreatePending { .. }
+                )
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerBlockUnprovenBuildAction {}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerBlockUnprovenBuildAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.with(false, |this| {
+            matches!(
+                this.current,
+                BlockProducerCurrentState::StagedLedgerDiffCreateSuccess { .. }
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerBlockProveInitAction {}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerBlockProveInitAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.with(false, |this| {
+            matches!(
+                this.current,
+                BlockProducerCurrentState::BlockUnprovenBuilt { .. }
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerBlockProvePendingAction {}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerBlockProvePendingAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.with(false, |this| {
+            matches!(
+                this.current,
+                BlockProducerCurrentState::BlockUnprovenBuilt { .. }
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerBlockProveSuccessAction {
+    pub block_hash: StateHash,
+    pub proof: Arc<MinaBaseProofStableV2>,
+}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerBlockProveSuccessAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state
+            .block_producer
+            .with(false, |this| match &this.current {
+                BlockProducerCurrentState::BlockUnprovenBuilt { block_hash, .. }
+                | BlockProducerCurrentState::BlockProvePending { block_hash, .. } => {
+                    block_hash == &self.block_hash
+                }
+                _ => false,
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerBlockProducedAction {}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerBlockProducedAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.with(false, |this| {
+            matches!(
+                this.current,
+                BlockProducerCurrentState::BlockProveSuccess { .. }
+            )
+        })
+    }
+}
+
+/// Inject produced block into our node, as if it was received from
+/// a peer, so that it goes through consensus and gets broadcasted.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerBlockInjectAction {}
+
+impl redux::EnablingCondition<crate::State> for BlockProducerBlockInjectAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.block_producer.produced_block().is_some()
+    }
+}
+
+macro_rules! impl_into_global_action {
+    ($a:ty) => {
+        impl From<$a> for crate::Action {
+            fn from(value: $a) -> Self {
+                Self::BlockProducer(value.into())
+            }
+        }
+    };
+}
+
+impl_into_global_action!(BlockProducerVrfEvaluationInitAction);
+impl_into_global_action!(BlockProducerVrfEvaluationPendingAction);
+impl_into_global_action!(BlockProducerVrfEvaluationSuccessAction);
+impl_into_global_action!(BlockProducerVrfEvaluationErrorAction);
+impl_into_global_action!(BlockProducerWonSlotSearchAction);
+impl_into_global_action!(BlockProducerWonSlotAction);
+impl_into_global_action!(BlockProducerWonSlotDiscardAction);
+impl_into_global_action!(BlockProducerWonSlotProduceInitAction);
+impl_into_global_action!(BlockProducerStagedLedgerDiffCreateInitAction);
+impl_into_global_action!(BlockProducerStagedLedgerDiffCreatePendingAction);
+impl_into_global_action!(BlockProducerStagedLedgerDiffCreateSuccessAction);
+impl_into_global_action!(BlockProducerBlockUnprovenBuildAction);
+impl_into_global_action!(BlockProducerBlockProveInitAction);
+impl_into_global_action!(BlockProducerBlockProvePendingAction);
+impl_into_global_action!(BlockProducerBlockProveSuccessAction);
+impl_into_global_action!(BlockProducerBlockProducedAction);
+impl_into_global_action!(BlockProducerBlockInjectAction);
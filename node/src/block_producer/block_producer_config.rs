@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerConfig {
+    /// Public key of the block producer. Secret key is kept in the
+    /// service.
+    pub pub_key: AccountPublicKey,
+    /// Account which receives coinbase rewards. Producer itself if
+    /// not set.
+    pub custom_coinbase_receiver: Option<AccountPublicKey>,
+    /// Duration of the slot in milliseconds (`block_window_duration_ms`
+    /// constraint constant of the network).
+    pub slot_duration_ms: u64,
+    /// Max number of transactions in the produced block
+    /// (`2^transaction_capacity_log_2`).
+    pub max_block_size: usize,
+}
+
+impl BlockProducerConfig {
+    pub fn coinbase_receiver(&self) -> &AccountPublicKey {
+        self.custom_coinbase_receiver
+            .as_ref()
+            .unwrap_or(&self.pub_key)
+    }
+
+    /// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/genesis_constants/genesis_constants.ml#L166
+    pub fn checkpoint_window_size_in_slots(&self) -> u32 {
+        const CHECKPOINT_WINDOWS_PER_YEAR: u64 = 12;
+        let slots_per_year = 365 * 24 * 60 * 60 * 1000 / self.slot_duration_ms;
+        (slots_per_year / CHECKPOINT_WINDOWS_PER_YEAR) as u32
+    }
+}
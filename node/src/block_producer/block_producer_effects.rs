@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use ledger::dummy::dummy_transaction_proof;
+use mina_p2p_messages::v2::MinaBaseProofStableV2;
+
+use crate::consensus::ConsensusBlockReceivedAction;
+use crate::{Service, Store};
+
+use super::{
+    best_tip_slot, BlockProducerAction, BlockProducerActionWithMeta,
+    BlockProducerBlockInjectAction, BlockProducerBlockProducedAction,
+    BlockProducerBlockProveInitAction, BlockProducerBlockProvePendingAction,
+    BlockProducerBlockProveSuccessAction, BlockProducerBlockUnprovenBuildAction,
+    BlockProducerCurrentState, BlockProducerStagedLedgerDiffCreateInitAction,
+    BlockProducerStagedLedgerDiffCreatePendingAction,
+    BlockProducerStagedLedgerDiffCreateSuccessAction, BlockProducerVrfEvaluationErrorAction,
+    BlockProducerVrfEvaluationPendingAction, BlockProducerVrfEvaluatorInput,
+    BlockProducerWonSlotAction, BlockProducerWonSlotDiscardAction,
+    BlockProducerWonSlotDiscardReason, BlockProducerWonSlotSearchAction, BlockProverInput,
+};
+
+pub fn block_producer_effects<S: Service>(
+    store: &mut Store<S>,
+    action: BlockProducerActionWithMeta,
+) {
+    let (action, meta) = action.split();
+
+    match action {
+        BlockProducerAction::VrfEvaluationInit(_) => {
+            let state = store.state();
+            let Some(best_tip) = state.transition_frontier.best_tip() else {
+                return;
+            };
+            let Some(config) = state.block_producer.config() else {
+                return;
+            };
+            let producer = config.pub_key.clone();
+            let slot_duration_ms = config.slot_duration_ms;
+            let Some(epoch_data) = state
+                .block_producer
+                .vrf_evaluation_next(best_tip, meta.time())
+            else {
+                return;
+            };
+
+            let protocol_state_body = &best_tip.header().protocol_state.body;
+            let consensus_state = &protocol_state_body.consensus_state;
+            let slots_per_epoch = consensus_state.curr_global_slot.slots_per_epoch.as_u32();
+            let best_tip_slot = best_tip_slot(best_tip);
+            let epoch_start = epoch_data.epoch * slots_per_epoch;
+            let input_slots = (best_tip_slot + 1).max(epoch_start)..(epoch_start + slots_per_epoch);
+            let global_slot_since_genesis_offset = consensus_state
+                .global_slot_since_genesis
+                .as_u32()
+                .saturating_sub(best_tip_slot);
+            let genesis_timestamp = protocol_state_body
+                .constants
+                .genesis_state_timestamp
+                .as_u64();
+
+            store.dispatch(BlockProducerVrfEvaluationPendingAction {
+                epoch_data: epoch_data.clone(),
+            });
+
+            let delegators = store
+                .service
+                .staking_ledger_delegators(&epoch_data.ledger_hash, &producer);
+            match delegators {
+                None => {
+                    store.dispatch(BlockProducerVrfEvaluationErrorAction {
+                        error: format!("staking ledger {} not found", epoch_data.ledger_hash),
+                    });
+                }
+                Some(delegators) => {
+                    store.service.vrf_evaluate(BlockProducerVrfEvaluatorInput {
+                        epoch_data,
+                        slots: input_slots,
+                        global_slot_since_genesis_offset,
+                        genesis_timestamp,
+                        slot_duration_ms,
+                        delegators,
+                    });
+                }
+            }
+        }
+        BlockProducerAction::VrfEvaluationPending(_) => {}
+        BlockProducerAction::VrfEvaluationSuccess(_) => {
+            store.dispatch(BlockProducerWonSlotSearchAction {});
+        }
+        BlockProducerAction::VrfEvaluationError(_) => {}
+        BlockProducerAction::WonSlotSearch(_) => {
+            let state = store.state();
+            let Some(best_tip) = state.transition_frontier.best_tip() else {
+                return;
+            };
+            let Some(won_slot) = state.block_producer.next_won_slot(best_tip, meta.time()) else {
+                return;
+            };
+            let won_slot = won_slot.clone();
+            store.dispatch(BlockProducerWonSlotAction { won_slot });
+        }
+        BlockProducerAction::WonSlot(_) => {}
+        BlockProducerAction::WonSlotDiscard(_) => {}
+        BlockProducerAction::WonSlotProduceInit(_) => {
+            store.dispatch(BlockProducerStagedLedgerDiffCreateInitAction {});
+        }
+        BlockProducerAction::StagedLedgerDiffCreateInit(_) => {
+            let state = store.state();
+            let (Some(won_slot), Some(config)) = (
+                state.block_producer.current_won_slot(),
+                state.block_producer.config(),
+            ) else {
+                return;
+            };
+            let best_chain = &state.transition_frontier.best_chain;
+            let Some(pred_block) = best_chain.last().cloned() else {
+                return;
+            };
+            let delta = pred_block
+                .header()
+                .protocol_state
+                .body
+                .constants
+                .delta
+                .as_u32() as usize;
+            let chain = best_chain[best_chain.len().saturating_sub(delta + 1)..].to_vec();
+
+            let global_slot_since_genesis = won_slot.global_slot_since_genesis;
+            let coinbase_receiver = config.coinbase_receiver().clone();
+            let transactions = state
+                .transaction_pool
+                .best_transactions(config.max_block_size)
+                .into_iter()
+                .map(|tx| (*tx.transaction).clone())
+                .collect();
+            let completed_snarks = state
+                .snark_pool
+                .range(..)
+                .filter_map(|(_, job)| Some((job.id.clone(), job.snark.as_ref()?.work.clone())))
+                .collect();
+
+            store.dispatch(BlockProducerStagedLedgerDiffCreatePendingAction {});
+
+            let result = store.service.staged_ledger_diff_create(
+                &pred_block,
+                global_slot_since_genesis,
+                &coinbase_receiver,
+                transactions,
+                completed_snarks,
+            );
+            match result {
+                Ok(output) => {
+                    store.dispatch(BlockProducerStagedLedgerDiffCreateSuccessAction {
+                        chain,
+                        output,
+                    });
+                }
+                Err(error) => {
+                    store.dispatch(BlockProducerWonSlotDiscardAction {
+                        reason: BlockProducerWonSlotDiscardReason::StagedLedgerDiffCreateError(
+                            error,
+                        ),
+                    });
+                }
+            }
+        }
+        BlockProducerAction::StagedLedgerDiffCreatePending(_) => {}
+        BlockProducerAction::StagedLedgerDiffCreateSuccess(_) => {
+            store.dispatch(BlockProducerBlockUnprovenBuildAction {});
+        }
+        BlockProducerAction::BlockUnprovenBuild(_) => {
+            store.dispatch(BlockProducerBlockProveInitAction {});
+        }
+        BlockProducerAction::BlockProveInit(_) => {
+            let state = store.state();
+            let Some(BlockProducerCurrentState::BlockUnprovenBuilt {
+                won_slot,
+                chain,
+                emitted_ledger_proof,
+                block,
+                block_hash,
+                ..
+            }) = state.block_producer.as_ref().map(|this| &this.current)
+            else {
+                return;
+            };
+            let block_hash = block_hash.clone();
+
+            if !state.config.proof_level.is_full() {
+                let proof = MinaBaseProofStableV2(dummy_transaction_proof().0.clone());
+                store.dispatch(BlockProducerBlockProveSuccessAction {
+                    block_hash,
+                    proof: Arc::new(proof),
+                });
+                return;
+            }
+
+            let Some(predecessor) = chain.last().cloned() else {
+                return;
+            };
+            let input = Box::new(BlockProverInput {
+                predecessor,
+                block: block.clone(),
+                ledger_proof: emitted_ledger_proof.clone(),
+                won_slot: won_slot.clone(),
+            });
+            store.dispatch(BlockProducerBlockProvePendingAction {});
+            store.service.prove(block_hash, input);
+        }
+        BlockProducerAction::BlockProvePending(_) => {}
+        BlockProducerAction::BlockProveSuccess(_) => {
+            store.dispatch(BlockProducerBlockProducedAction {});
+        }
+        BlockProducerAction::BlockProduced(_) => {
+            store.dispatch(BlockProducerBlockInjectAction {});
+        }
+        BlockProducerAction::BlockInject(_) => {
+            let Some(BlockProducerCurrentState::Injected { block, .. }) = store
+                .state()
+                .block_producer
+                .as_ref()
+                .map(|this| &this.current)
+            else {
+                return;
+            };
+            let block = block.clone();
+            store.dispatch(ConsensusBlockReceivedAction {
+                hash: block.hash,
+                block: block.block,
+                chain_proof: None,
+            });
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use mina_p2p_messages::v2::{MinaBaseProofStableV2, StateHash};
+use serde::{Deserialize, Serialize};
+
+use super::BlockProducerVrfEvaluatorOutput;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum BlockProducerEvent {
+    VrfEvaluator(Result<BlockProducerVrfEvaluatorOutput, String>),
+    BlockProve(StateHash, Result<Arc<MinaBaseProofStableV2>, String>),
+}
+
+impl std::fmt::Display for BlockProducerEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BlockProducer, ")?;
+        match self {
+            Self::VrfEvaluator(Ok(output)) => write!(
+                f,
+                "VrfEvaluator, Ok, epoch: {}, won_slots: {}",
+                output.epoch_data.epoch,
+                output.won_slots.len()
+            ),
+            Self::VrfEvaluator(Err(err)) => write!(f, "VrfEvaluator, Err, {err}"),
+            Self::BlockProve(hash, Ok(_)) => write!(f, "BlockProve, {hash}, Ok"),
+            Self::BlockProve(hash, Err(err)) => write!(f, "BlockProve, {hash}, Err, {err}"),
+        }
+    }
+}
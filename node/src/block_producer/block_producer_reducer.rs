@@ -0,0 +1,697 @@
+use std::sync::Arc;
+
+use ledger::vrf::{epoch_seed_update, VrfOutput};
+use mina_hasher::Fp;
+use mina_p2p_messages::v2::{
+    BlockTimeTimeStableV1, ConsensusGlobalSlotStableV1,
+    ConsensusProofOfStakeDataConsensusStateValueStableV2,
+    ConsensusProofOfStakeDataEpochDataNextValueVersionedValueStableV1,
+    ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStableV1,
+    ConsensusVrfOutputTruncatedStableV1, CurrencyAmountStableV1, DataHashLibStateHashStableV1,
+    MinaBaseEpochLedgerValueStableV1, MinaBaseEpochSeedStableV1, MinaBlockBlockStableV2,
+    MinaBlockHeaderStableV2, MinaNumbersGlobalSlotSinceGenesisMStableV1,
+    MinaNumbersGlobalSlotSinceHardForkMStableV1, MinaStateBlockchainStateValueStableV2,
+    MinaStateBlockchainStateValueStableV2LedgerProofStatement,
+    MinaStateBlockchainStateValueStableV2SignedAmount, MinaStateProtocolStateBodyValueStableV2,
+    MinaStateProtocolStateValueStableV2, SgnStableV1, StagedLedgerDiffBodyStableV1, StateBodyHash,
+    StateHash, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+};
+use openmina_core::block::{ArcBlockWithHash, BlockWithHash};
+use redux::ActionMeta;
+
+use crate::account::AccountPublicKey;
+use crate::consensus::GRACE_PERIOD_END;
+
+use super::{
+    BlockProducerAction, BlockProducerActionWithMetaRef, BlockProducerCurrentState,
+    BlockProducerEnabled, BlockProducerState, BlockProducerVrfEvaluatorStatus,
+    BlockProducerWonSlot, BlockWithoutProof,
+};
+
+impl BlockProducerState {
+    pub fn reducer(&mut self, action: BlockProducerActionWithMetaRef<'_>) {
+        let (action, meta) = action.split();
+        self.with_mut((), |this| this.reducer(meta, action));
+    }
+}
+
+impl BlockProducerEnabled {
+    fn reducer(&mut self, meta: ActionMeta, action: &BlockProducerAction) {
+        match action {
+            BlockProducerAction::VrfEvaluationInit(_) => {}
+            BlockProducerAction::VrfEvaluationPending(a) => {
+                self.vrf_evaluator.status = BlockProducerVrfEvaluatorStatus::Pending {
+                    time: meta.time(),
+                    epoch_data: a.epoch_data.clone(),
+                };
+            }
+            BlockProducerAction::VrfEvaluationSuccess(a) => {
+                let epoch = a.output.epoch_data.epoch;
+                let evaluator = &mut self.vrf_evaluator;
+                evaluator.status = BlockProducerVrfEvaluatorStatus::Idle { time: meta.time() };
+                // Keep only the previous epoch, which may still be
+                // the best tip's staking epoch.
+                evaluator
+                    .evaluated_epochs
+                    .retain(|e, _| *e + 1 >= epoch && *e != epoch);
+                evaluator
+                    .evaluated_epochs
+                    .insert(epoch, a.output.epoch_data.clone());
+                evaluator
+                    .won_slots
+                    .retain(|_, slot| slot.epoch + 1 >= epoch && slot.epoch != epoch);
+                evaluator.won_slots.extend(
+                    a.output
+                        .won_slots
+                        .iter()
+                        .map(|slot| (slot.global_slot, slot.clone())),
+                );
+            }
+            BlockProducerAction::VrfEvaluationError(a) => {
+                let BlockProducerVrfEvaluatorStatus::Pending { epoch_data, .. } =
+                    &self.vrf_evaluator.status
+                else {
+                    return;
+                };
+                self.vrf_evaluator.status = BlockProducerVrfEvaluatorStatus::Error {
+                    time: meta.time(),
+                    epoch_data: epoch_data.clone(),
+                    error: a.error.clone(),
+                };
+            }
+            BlockProducerAction::WonSlotSearch(_) => {}
+            BlockProducerAction::WonSlot(a) => {
+                self.current = BlockProducerCurrentState::WonSlot {
+                    time: meta.time(),
+                    won_slot: a.won_slot.clone(),
+                };
+            }
+            BlockProducerAction::WonSlotDiscard(a) => {
+                let Some(won_slot) = self.current.won_slot() else {
+                    return;
+                };
+                self.current = BlockProducerCurrentState::WonSlotDiscarded {
+                    time: meta.time(),
+                    won_slot: won_slot.clone(),
+                    reason: a.reason.clone(),
+                };
+            }
+            BlockProducerAction::WonSlotProduceInit(_) => {
+                let Some(won_slot) = self.current.won_slot() else {
+                    return;
+                };
+                self.current = BlockProducerCurrentState::WonSlotProduceInit {
+                    time: meta.time(),
+                    won_slot: won_slot.clone(),
+                };
+            }
+            BlockProducerAction::StagedLedgerDiffCreateInit(_) => {}
+            BlockProducerAction::StagedLedgerDiffCreatePending(_) => {
+                let Some(won_slot) = self.current.won_slot() else {
+                    return;
+                };
+                self.current = BlockProducerCurrentState::StagedLedgerDiffCreatePending {
+                    time: meta.time(),
+                    won_slot: won_slot.clone(),
+                };
+            }
+            BlockProducerAction::StagedLedgerDiffCreateSuccess(a) => {
+                let Some(won_slot) = self.current.won_slot() else {
+                    return;
+                };
+                self.current = BlockProducerCurrentState::StagedLedgerDiffCreateSuccess {
+                    time: meta.time(),
+                    won_slot: won_slot.clone(),
+                    chain: a.chain.clone(),
+                    diff: a.output.diff.clone(),
+                    diff_hash: a.output.diff_hash.clone(),
+                    staged_ledger_hash: a.output.staged_ledger_hash.clone(),
+                    emitted_ledger_proof: a.output.emitted_ledger_proof.clone(),
+                };
+            }
+            BlockProducerAction::BlockUnprovenBuild(_) => {
+                let BlockProducerCurrentState::StagedLedgerDiffCreateSuccess {
+                    won_slot,
+                    chain,
+                    diff,
+                    diff_hash,
+                    staged_ledger_hash,
+                    emitted_ledger_proof,
+                    ..
+                } = &self.current
+                else {
+                    return;
+                };
+                let Some(pred_block) = chain.last() else {
+                    return;
+                };
+                let pred_body = &pred_block.header().protocol_state.body;
+
+                let (ledger_proof_statement, supply_increase) = match emitted_ledger_proof {
+                    Some(proof) => {
+                        let stmt = &proof.0.statement;
+                        let ledger_proof_statement =
+                            MinaStateBlockchainStateValueStableV2LedgerProofStatement {
+                                source: stmt.source.clone(),
+                                target: stmt.target.clone(),
+                                connecting_ledger_left: stmt.connecting_ledger_left.clone(),
+                                connecting_ledger_right: stmt.connecting_ledger_right.clone(),
+                                supply_increase: stmt.supply_increase.clone(),
+                                fee_excess: stmt.fee_excess.clone(),
+                                sok_digest: (),
+                            };
+                        (ledger_proof_statement, Some(&stmt.supply_increase))
+                    }
+                    None => (
+                        pred_body.blockchain_state.ledger_proof_statement.clone(),
+                        None,
+                    ),
+                };
+
+                let genesis_timestamp = pred_body.constants.genesis_state_timestamp.as_u64();
+                let slot_time_ms =
+                    genesis_timestamp + won_slot.global_slot as u64 * self.config.slot_duration_ms;
+
+                let consensus_state = next_consensus_state(
+                    pred_block,
+                    won_slot,
+                    &self.config.pub_key,
+                    self.config.coinbase_receiver(),
+                    supply_increase,
+                    self.config.checkpoint_window_size_in_slots(),
+                );
+
+                let pred_consensus_state = &pred_body.consensus_state;
+                let genesis_state_hash =
+                    if pred_consensus_state.curr_global_slot.slot_number.as_u32() == 0 {
+                        pred_block.hash.clone()
+                    } else {
+                        pred_body.genesis_state_hash.clone()
+                    };
+
+                let protocol_state = MinaStateProtocolStateValueStableV2 {
+                    previous_state_hash: pred_block.hash.clone(),
+                    body: MinaStateProtocolStateBodyValueStableV2 {
+                        genesis_state_hash,
+                        blockchain_state: MinaStateBlockchainStateValueStableV2 {
+                            staged_ledger_hash: staged_ledger_hash.clone(),
+                            genesis_ledger_hash: pred_body
+                                .blockchain_state
+                                .genesis_ledger_hash
+                                .clone(),
+                            ledger_proof_statement,
+                            timestamp: BlockTimeTimeStableV1(
+                                UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
+                                    slot_time_ms.into(),
+                                ),
+                            ),
+                            body_reference: diff_hash.clone(),
+                        },
+                        consensus_state,
+                        constants: pred_body.constants.clone(),
+                    },
+                };
+                let block_hash = protocol_state.hash();
+
+                let block = BlockWithoutProof {
+                    protocol_state,
+                    delta_block_chain_proof: delta_block_chain_proof(chain),
+                    current_protocol_version: pred_block.header().current_protocol_version.clone(),
+                    proposed_protocol_version_opt: None,
+                    body: StagedLedgerDiffBodyStableV1 {
+                        staged_ledger_diff: diff.clone(),
+                    },
+                };
+
+                self.current = BlockProducerCurrentState::BlockUnprovenBuilt {
+                    time: meta.time(),
+                    won_slot: won_slot.clone(),
+                    chain: chain.clone(),
+                    emitted_ledger_proof: emitted_ledger_proof.clone(),
+                    block,
+                    block_hash,
+                };
+            }
+            BlockProducerAction::BlockProveInit(_) => {}
+            BlockProducerAction::BlockProvePending(_) => {
+                let BlockProducerCurrentState::BlockUnprovenBuilt {
+                    won_slot,
+                    chain,
+                    block,
+                    block_hash,
+                    ..
+                } = &self.current
+                else {
+                    return;
+                };
+                self.current = BlockProducerCurrentState::BlockProvePending {
+                    time: meta.time(),
+                    won_slot: won_slot.clone(),
+                    chain: chain.clone(),
+                    block: block.clone(),
+                    block_hash: block_hash.clone(),
+                };
+            }
+            BlockProducerAction::BlockProveSuccess(a) => {
+                let (BlockProducerCurrentState::BlockUnprovenBuilt {
+                    won_slot,
+                    chain,
+                    block,
+                    block_hash,
+                    ..
+                }
+                | BlockProducerCurrentState::BlockProvePending {
+                    won_slot,
+                    chain,
+                    block,
+                    block_hash,
+                    ..
+                }) = &self.current
+                else {
+                    return;
+                };
+                self.current = BlockProducerCurrentState::BlockProveSuccess {
+                    time: meta.time(),
+                    won_slot: won_slot.clone(),
+                    chain: chain.clone(),
+                    block: block.clone(),
+                    block_hash: block_hash.clone(),
+                    proof: a.proof.clone(),
+                };
+            }
+            BlockProducerAction::BlockProduced(_) => {
+                let BlockProducerCurrentState::BlockProveSuccess {
+                    won_slot,
+                    chain,
+                    block,
+                    proof,
+                    ..
+                } = &self.current
+                else {
+                    return;
+                };
+                let block = block.clone();
+                let block = MinaBlockBlockStableV2 {
+                    header: MinaBlockHeaderStableV2 {
+                        protocol_state: block.protocol_state,
+                        protocol_state_proof: (**proof).clone(),
+                        delta_block_chain_proof: block.delta_block_chain_proof,
+                        current_protocol_version: block.current_protocol_version,
+                        proposed_protocol_version_opt: block.proposed_protocol_version_opt,
+                    },
+                    body: block.body,
+                };
+                self.current = BlockProducerCurrentState::Produced {
+                    time: meta.time(),
+                    won_slot: won_slot.clone(),
+                    chain: chain.clone(),
+                    block: BlockWithHash::new(Arc::new(block)),
+                };
+            }
+            BlockProducerAction::BlockInject(_) => {
+                let BlockProducerCurrentState::Produced {
+                    won_slot,
+                    chain,
+                    block,
+                    ..
+                } = &self.current
+                else {
+                    return;
+                };
+                self.current = BlockProducerCurrentState::Injected {
+                    time: meta.time(),
+                    won_slot: won_slot.clone(),
+                    chain: chain.clone(),
+                    block: block.clone(),
+                };
+            }
+        }
+    }
+}
+
+/// Consensus state of the block that we produce on top of `pred_block`.
+///
+/// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/consensus/proof_of_stake.ml#L2134
+fn next_consensus_state(
+    pred_block: &ArcBlockWithHash,
+    won_slot: &BlockProducerWonSlot,
+    producer: &AccountPublicKey,
+    coinbase_receiver: &AccountPublicKey,
+    supply_increase: Option<&MinaStateBlockchainStateValueStableV2SignedAmount>,
+    checkpoint_window_size_in_slots: u32,
+) -> ConsensusProofOfStakeDataConsensusStateValueStableV2 {
+    let pred = &pred_block.header().protocol_state.body.consensus_state;
+    let slots_per_epoch = pred.curr_global_slot.slots_per_epoch.as_u32();
+    let pred_slot = pred.curr_global_slot.slot_number.as_u32();
+    let next_slot = won_slot.global_slot;
+    let (pred_epoch, next_epoch) = (pred_slot / slots_per_epoch, next_slot / slots_per_epoch);
+
+    let total_currency = {
+        let total = pred.total_currency.as_u64();
+        match supply_increase {
+            None => total,
+            Some(v) => match v.sgn {
+                SgnStableV1::Pos => total.saturating_add(v.magnitude.as_u64()),
+                SgnStableV1::Neg => total.saturating_sub(v.magnitude.as_u64()),
+            },
+        }
+    };
+    let total_currency = CurrencyAmountStableV1(UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
+        total_currency.into(),
+    ));
+
+    let (staking_epoch_data, mut next_epoch_data, epoch_count) = if next_epoch > pred_epoch {
+        let next = &pred.next_epoch_data;
+        let staking_epoch_data =
+            ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStableV1 {
+                ledger: next.ledger.clone(),
+                seed: next.seed.clone(),
+                start_checkpoint: next.start_checkpoint.clone(),
+                lock_checkpoint: next.lock_checkpoint.clone(),
+                epoch_length: next.epoch_length.clone(),
+            };
+        let next_epoch_data = ConsensusProofOfStakeDataEpochDataNextValueVersionedValueStableV1 {
+            ledger: MinaBaseEpochLedgerValueStableV1 {
+                hash: pred_block.snarked_ledger_hash().clone(),
+                total_currency: total_currency.clone(),
+            },
+            seed: next.seed.clone(),
+            start_checkpoint: pred_block.hash.clone(),
+            lock_checkpoint: DataHashLibStateHashStableV1(Fp::from(0u64).into()).into(),
+            epoch_length: 1u32.into(),
+        };
+        let epoch_count = pred.epoch_count.as_u32() + 1;
+        (staking_epoch_data, next_epoch_data, epoch_count)
+    } else {
+        let mut next_epoch_data = pred.next_epoch_data.clone();
+        next_epoch_data.epoch_length = (next_epoch_data.epoch_length.as_u32() + 1).into();
+        (
+            pred.staking_epoch_data.clone(),
+            next_epoch_data,
+            pred.epoch_count.as_u32(),
+        )
+    };
+
+    let vrf_output: Fp = won_slot.vrf_output.to_field();
+    if next_slot % slots_per_epoch < slots_per_epoch * 2 / 3 {
+        let seed = epoch_seed_update(next_epoch_data.seed.to_field(), vrf_output);
+        next_epoch_data.seed = MinaBaseEpochSeedStableV1(seed.into()).into();
+        next_epoch_data.lock_checkpoint = pred_block.hash.clone();
+    }
+
+    let slots_per_sub_window = pred_block
+        .header()
+        .protocol_state
+        .body
+        .constants
+        .slots_per_sub_window
+        .as_u32();
+    let (min_window_density, sub_window_densities) =
+        min_window_density_update(pred, pred_slot, next_slot, slots_per_sub_window);
+
+    let checkpoint_window_start = pred_slot - pred_slot % checkpoint_window_size_in_slots;
+    let has_ancestor_in_same_checkpoint_window =
+        next_slot < checkpoint_window_start + checkpoint_window_size_in_slots;
+
+    ConsensusProofOfStakeDataConsensusStateValueStableV2 {
+        blockchain_length: (pred.blockchain_length.as_u32() + 1).into(),
+        epoch_count: epoch_count.into(),
+        min_window_density: min_window_density.into(),
+        sub_window_densities: sub_window_densities.into_iter().map(Into::into).collect(),
+        last_vrf_output: ConsensusVrfOutputTruncatedStableV1(
+            VrfOutput::from_output(vrf_output)
+                .truncated
+                .0
+                .to_vec()
+                .into(),
+        ),
+        total_currency,
+        curr_global_slot: ConsensusGlobalSlotStableV1 {
+            slot_number: MinaNumbersGlobalSlotSinceHardForkMStableV1::SinceHardFork(
+                next_slot.into(),
+            ),
+            slots_per_epoch: pred.curr_global_slot.slots_per_epoch.clone(),
+        },
+        global_slot_since_genesis: MinaNumbersGlobalSlotSinceGenesisMStableV1::SinceGenesis(
+            won_slot.global_slot_since_genesis.into(),
+        ),
+        staking_epoch_data,
+        next_epoch_data,
+        has_ancestor_in_same_checkpoint_window,
+        block_stake_winner: won_slot.delegator.clone().into(),
+        block_creator: producer.clone().into(),
+        coinbase_receiver: coinbase_receiver.clone().into(),
+        // Supercharged coinbase isn't supported by the staged ledger
+        // yet, see `ledger_service::apply_block`.
+        supercharge_coinbase: false,
+    }
+}
+
+/// https://github.com/MinaProtocol/mina/blob/05c2f73d0f6e4f1341286843814ce02dcb3919e0/src/lib/consensus/proof_of_stake.ml#L1592
+fn min_window_density_update(
+    pred: &ConsensusProofOfStakeDataConsensusStateValueStableV2,
+    pred_slot: u32,
+    next_slot: u32,
+    slots_per_sub_window: u32,
+) -> (u32, Vec<u32>) {
+    let sub_windows_per_window = pred.sub_window_densities.len() as u32;
+    let pred_global_sub_window = pred_slot / slots_per_sub_window;
+    let next_global_sub_window = next_slot / slots_per_sub_window;
+    let pred_relative_sub_window = pred_global_sub_window % sub_windows_per_window;
+    let next_relative_sub_window = next_global_sub_window % sub_windows_per_window;
+    let same_sub_window = pred_global_sub_window == next_global_sub_window;
+    let overlapping_window =
+        pred_global_sub_window + sub_windows_per_window >= next_global_sub_window;
+
+    let mut sub_window_densities = pred
+        .sub_window_densities
+        .iter()
+        .enumerate()
+        .map(|(i, density)| {
+            let i = i as u32;
+            let gt_pred_sub_window = i > pred_relative_sub_window;
+            let lt_next_sub_window = i < next_relative_sub_window;
+            let within_range = if pred_relative_sub_window < next_relative_sub_window {
+                gt_pred_sub_window && lt_next_sub_window
+            } else {
+                gt_pred_sub_window || lt_next_sub_window
+            };
+            if same_sub_window || (overlapping_window && !within_range) {
+                density.as_u32()
+            } else {
+                0
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let pred_min_window_density = pred.min_window_density.as_u32();
+    let min_window_density = if same_sub_window || next_slot < GRACE_PERIOD_END {
+        pred_min_window_density
+    } else {
+        pred_min_window_density.min(sub_window_densities.iter().sum())
+    };
+
+    if let Some(density) = sub_window_densities.get_mut(next_relative_sub_window as usize) {
+        *density = if same_sub_window { *density + 1 } else { 1 };
+    }
+
+    (min_window_density, sub_window_densities)
+}
+
+/// Proof that the predecessor is a descendant of the block `delta`
+/// blocks before it.
+///
+/// `chain` contains the last `delta + 1` blocks of the chain ending
+/// with the predecessor of the new block.
+fn delta_block_chain_proof(chain: &[ArcBlockWithHash]) -> (StateHash, Vec<StateBodyHash>) {
+    let Some((first, rest)) = chain.split_first() else {
+        unreachable!("chain can't be empty");
+    };
+    let body_hashes = rest
+        .iter()
+        .map(|block| block.header().protocol_state.body.hash().into())
+        .collect();
+    (first.hash.clone(), body_hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::{scan_state::currency::Balance, AccountId, TokenId};
+    use mina_p2p_messages::v2::NonZeroCurvePoint;
+
+    use crate::account::AccountSecretKey;
+    use crate::block_producer::BlockProducerConfig;
+    use crate::transition_frontier::genesis_block;
+    use crate::NetworkConfig;
+
+    use super::*;
+
+    fn genesis(producer: &AccountPublicKey) -> ArcBlockWithHash {
+        let network = NetworkConfig::berkeley();
+        let account_id = AccountId::new(producer.as_ref().into(), TokenId::default());
+        let account =
+            ledger::Account::create_with(account_id, Balance::from_u64(1_000_000_000_000));
+        genesis_block(
+            &network.protocol_constants,
+            &network.constraint_constants,
+            &[account],
+        )
+        .unwrap()
+    }
+
+    fn won_slot(pred_block: &ArcBlockWithHash, global_slot: u32) -> BlockProducerWonSlot {
+        let producer = pred_block
+            .header()
+            .protocol_state
+            .body
+            .consensus_state
+            .block_creator
+            .clone();
+        BlockProducerWonSlot {
+            slot_time: redux::Timestamp::ZERO,
+            global_slot,
+            global_slot_since_genesis: global_slot,
+            epoch: 0,
+            staking_ledger_hash: pred_block.snarked_ledger_hash().clone(),
+            delegator: producer.into(),
+            delegator_index: 0,
+            delegator_stake: 1_000_000_000_000,
+            vrf_output: Fp::from(12345u64).into(),
+        }
+    }
+
+    fn densities(pred_densities: [u32; 11], pred_slot: u32, next_slot: u32) -> (u32, Vec<u32>) {
+        let producer = AccountSecretKey::rand().public_key();
+        let mut pred = genesis(&producer)
+            .header()
+            .protocol_state
+            .body
+            .consensus_state
+            .clone();
+        pred.sub_window_densities = pred_densities.into_iter().map(Into::into).collect();
+        pred.min_window_density = pred_densities.iter().sum::<u32>().into();
+        min_window_density_update(&pred, pred_slot, next_slot, 7)
+    }
+
+    #[test]
+    fn min_window_density_same_sub_window() {
+        // Slot 1500 is in the global sub window 214, relative one 5.
+        assert_eq!(
+            densities([7; 11], 1500, 1501),
+            (77, vec![7, 7, 7, 7, 7, 8, 7, 7, 7, 7, 7])
+        );
+    }
+
+    #[test]
+    fn min_window_density_next_sub_window() {
+        assert_eq!(
+            densities([7; 11], 1500, 1507),
+            (77, vec![7, 7, 7, 7, 7, 7, 1, 7, 7, 7, 7])
+        );
+    }
+
+    #[test]
+    fn min_window_density_skipped_sub_windows() {
+        // Sub windows 6 and 7 had no blocks.
+        assert_eq!(
+            densities([7; 11], 1500, 1521),
+            (63, vec![7, 7, 7, 7, 7, 7, 0, 0, 1, 7, 7])
+        );
+        // Wraps around the end of the window.
+        assert_eq!(
+            densities([7; 11], 1500, 1500 + 7 * 8),
+            (28, vec![0, 0, 1, 7, 7, 7, 0, 0, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn min_window_density_non_overlapping_window() {
+        let next_slot = 1500 + 7 * 11 * 2;
+        let next_relative_sub_window = (next_slot / 7 % 11) as usize;
+        let mut expected = vec![0; 11];
+        expected[next_relative_sub_window] = 1;
+        assert_eq!(densities([7; 11], 1500, next_slot), (0, expected));
+    }
+
+    #[test]
+    fn min_window_density_grace_period() {
+        assert_eq!(
+            densities([7; 11], 10, 30),
+            (77, vec![7, 7, 0, 0, 1, 7, 7, 7, 7, 7, 7])
+        );
+    }
+
+    #[test]
+    fn next_consensus_state_same_epoch() {
+        let producer = AccountSecretKey::rand().public_key();
+        let pred_block = genesis(&producer);
+        let pred = &pred_block.header().protocol_state.body.consensus_state;
+        let won_slot = won_slot(&pred_block, 5);
+
+        let next = next_consensus_state(&pred_block, &won_slot, &producer, &producer, None, 14600);
+
+        assert_eq!(
+            next.blockchain_length.as_u32(),
+            pred.blockchain_length.as_u32() + 1
+        );
+        assert_eq!(next.epoch_count.as_u32(), 0);
+        assert_eq!(next.curr_global_slot.slot_number.as_u32(), 5);
+        assert_eq!(next.global_slot_since_genesis.as_u32(), 5);
+        assert_eq!(next.total_currency, pred.total_currency);
+        assert_eq!(next.staking_epoch_data, pred.staking_epoch_data);
+        assert_eq!(
+            next.next_epoch_data.epoch_length.as_u32(),
+            pred.next_epoch_data.epoch_length.as_u32() + 1
+        );
+        // Within the first 2/3 of the epoch, seed is updated and the
+        // lock checkpoint moves.
+        assert_ne!(next.next_epoch_data.seed, pred.next_epoch_data.seed);
+        assert_eq!(next.next_epoch_data.lock_checkpoint, pred_block.hash);
+        assert!(next.has_ancestor_in_same_checkpoint_window);
+        assert_eq!(
+            next.block_creator,
+            NonZeroCurvePoint::from(producer.clone())
+        );
+        assert_eq!(next.coinbase_receiver, NonZeroCurvePoint::from(producer));
+    }
+
+    #[test]
+    fn next_consensus_state_new_epoch() {
+        let producer = AccountSecretKey::rand().public_key();
+        let pred_block = genesis(&producer);
+        let pred = &pred_block.header().protocol_state.body.consensus_state;
+        let slots_per_epoch = pred.curr_global_slot.slots_per_epoch.as_u32();
+        let won_slot = won_slot(&pred_block, slots_per_epoch + 1);
+
+        let next = next_consensus_state(&pred_block, &won_slot, &producer, &producer, None, 14600);
+
+        assert_eq!(next.epoch_count.as_u32(), 1);
+        assert_eq!(next.staking_epoch_data.ledger, pred.next_epoch_data.ledger);
+        assert_eq!(next.staking_epoch_data.seed, pred.next_epoch_data.seed);
+        assert_eq!(
+            next.staking_epoch_data.lock_checkpoint,
+            pred.next_epoch_data.lock_checkpoint
+        );
+        assert_eq!(
+            &next.next_epoch_data.ledger.hash,
+            pred_block.snarked_ledger_hash()
+        );
+        assert_eq!(next.next_epoch_data.start_checkpoint, pred_block.hash);
+        assert_eq!(next.next_epoch_data.epoch_length.as_u32(), 1);
+
+        // Predecessor is in the previous checkpoint window.
+        let next = next_consensus_state(&pred_block, &won_slot, &producer, &producer, None, 10);
+        assert!(!next.has_ancestor_in_same_checkpoint_window);
+    }
+
+    #[test]
+    fn checkpoint_window_size() {
+        let config = BlockProducerConfig {
+            pub_key: AccountSecretKey::rand().public_key(),
+            custom_coinbase_receiver: None,
+            slot_duration_ms: 180000,
+            max_block_size: 128,
+        };
+        assert_eq!(config.checkpoint_window_size_in_slots(), 14600);
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use ledger::vrf::{self, VrfMessage};
+use ledger::AccountIndex;
+use mina_hasher::Fp;
+use mina_p2p_messages::v2::{
+    ConsensusBodyReferenceStableV1, LedgerHash, LedgerProofProdStableV2,
+    MinaBaseStagedLedgerHashStableV1, MinaBaseUserCommandStableV2, StagedLedgerDiffDiffStableV2,
+    StateHash,
+};
+use mina_signer::Keypair;
+use openmina_core::block::ArcBlockWithHash;
+use openmina_core::snark::{Snark, SnarkJobId};
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+use crate::ledger::LEDGER_DEPTH;
+
+use super::{BlockProducerEpochData, BlockProducerWonSlot, BlockWithoutProof};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerDelegator {
+    pub pub_key: AccountPublicKey,
+    pub stake: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerVrfEvaluatorInput {
+    pub epoch_data: BlockProducerEpochData,
+    /// Global slots (since hard fork) to evaluate.
+    pub slots: Range<u32>,
+    /// Difference between the global slot since genesis and since
+    /// hard fork.
+    pub global_slot_since_genesis_offset: u32,
+    /// Genesis timestamp in milliseconds.
+    pub genesis_timestamp: u64,
+    /// Duration of the slot in milliseconds.
+    pub slot_duration_ms: u64,
+    /// Accounts delegating to the producer, by account index.
+    pub delegators: BTreeMap<u64, BlockProducerDelegator>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerVrfEvaluatorOutput {
+    pub epoch_data: BlockProducerEpochData,
+    pub won_slots: Vec<BlockProducerWonSlot>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerStagedLedgerDiffCreateOutput {
+    pub diff: StagedLedgerDiffDiffStableV2,
+    pub diff_hash: ConsensusBodyReferenceStableV1,
+    pub staged_ledger_hash: MinaBaseStagedLedgerHashStableV1,
+    pub emitted_ledger_proof: Option<Box<LedgerProofProdStableV2>>,
+}
+
+/// Everything the prover needs to produce the protocol state proof
+/// for the new block.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProverInput {
+    pub predecessor: ArcBlockWithHash,
+    pub block: BlockWithoutProof,
+    /// Ledger proof emitted by the staged ledger diff of the block.
+    pub ledger_proof: Option<Box<LedgerProofProdStableV2>>,
+    pub won_slot: BlockProducerWonSlot,
+}
+
+impl BlockProducerVrfEvaluatorInput {
+    /// Evaluates VRF for each slot and delegator and returns slots
+    /// which were won by any of the delegators.
+    pub fn evaluate(&self, keypair: &Keypair) -> BlockProducerVrfEvaluatorOutput {
+        let epoch_seed: Fp = self.epoch_data.seed.to_field();
+        let total_stake = self.epoch_data.total_currency;
+
+        let won_slots = self
+            .slots
+            .clone()
+            .filter_map(|global_slot| {
+                self.delegators.iter().find_map(|(index, delegator)| {
+                    let message = VrfMessage {
+                        global_slot,
+                        epoch_seed,
+                        delegator_index: AccountIndex(*index),
+                    };
+                    let output = vrf::evaluate(keypair, &message, LEDGER_DEPTH as u64);
+                    if !output.satisfies_threshold(delegator.stake, total_stake) {
+                        return None;
+                    }
+                    let slot_time_ms =
+                        self.genesis_timestamp + global_slot as u64 * self.slot_duration_ms;
+                    Some(BlockProducerWonSlot {
+                        slot_time: Timestamp::new(slot_time_ms * 1_000_000),
+                        global_slot,
+                        global_slot_since_genesis: global_slot
+                            + self.global_slot_since_genesis_offset,
+                        epoch: self.epoch_data.epoch,
+                        staking_ledger_hash: self.epoch_data.ledger_hash.clone(),
+                        delegator: delegator.pub_key.clone(),
+                        delegator_index: *index,
+                        delegator_stake: delegator.stake,
+                        vrf_output: output.output.into(),
+                    })
+                })
+            })
+            .collect();
+
+        BlockProducerVrfEvaluatorOutput {
+            epoch_data: self.epoch_data.clone(),
+            won_slots,
+        }
+    }
+}
+
+pub trait BlockProducerLedgerService: redux::Service {
+    /// Accounts in the staking ledger, which delegate to the producer,
+    /// by account index. `None` if the ledger isn't available.
+    fn staking_ledger_delegators(
+        &mut self,
+        staking_ledger_hash: &LedgerHash,
+        producer: &AccountPublicKey,
+    ) -> Option<BTreeMap<u64, BlockProducerDelegator>>;
+
+    /// Creates staged ledger diff on top of the staged ledger of the
+    /// `pred_block`, from the given transactions and completed snark
+    /// works, and applies it to get the resulting ledger hashes.
+    fn staged_ledger_diff_create(
+        &mut self,
+        pred_block: &ArcBlockWithHash,
+        global_slot_since_genesis: u32,
+        coinbase_receiver: &AccountPublicKey,
+        transactions: Vec<MinaBaseUserCommandStableV2>,
+        completed_snarks: BTreeMap<SnarkJobId, Snark>,
+    ) -> Result<BlockProducerStagedLedgerDiffCreateOutput, String>;
+}
+
+pub trait BlockProducerService: BlockProducerLedgerService {
+    /// Evaluates VRF with the producer's key for the given slots. The
+    /// result is sent back with [`super::BlockProducerEvent::VrfEvaluator`].
+    fn vrf_evaluate(&mut self, input: BlockProducerVrfEvaluatorInput);
+
+    /// Creates the protocol state proof for the block. The result is
+    /// sent back with [`super::BlockProducerEvent::BlockProve`].
+    fn prove(&mut self, block_hash: StateHash, input: Box<BlockProverInput>);
+}
@@ -0,0 +1,409 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use mina_p2p_messages::bigint::BigInt;
+use mina_p2p_messages::v2::{
+    ConsensusBodyReferenceStableV1, EpochSeed, LedgerHash, LedgerProofProdStableV2,
+    MinaBaseProofStableV2, MinaBaseStagedLedgerHashStableV1, MinaStateProtocolStateValueStableV2,
+    ProtocolVersionStableV1, StagedLedgerDiffBodyStableV1, StagedLedgerDiffDiffStableV2,
+    StateBodyHash, StateHash,
+};
+use openmina_core::block::ArcBlockWithHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+
+use super::{BlockProducerConfig, VRF_EVALUATION_RETRY_TIMEOUT};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerState(Option<BlockProducerEnabled>);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerEnabled {
+    pub config: BlockProducerConfig,
+    pub vrf_evaluator: BlockProducerVrfEvaluatorState,
+    pub current: BlockProducerCurrentState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerVrfEvaluatorState {
+    pub status: BlockProducerVrfEvaluatorStatus,
+    /// Epochs for which won slots were already evaluated, by epoch.
+    pub evaluated_epochs: BTreeMap<u32, BlockProducerEpochData>,
+    /// Won slots, by global slot (since hard fork).
+    pub won_slots: BTreeMap<u32, BlockProducerWonSlot>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum BlockProducerVrfEvaluatorStatus {
+    Idle {
+        time: Timestamp,
+    },
+    Pending {
+        time: Timestamp,
+        epoch_data: BlockProducerEpochData,
+    },
+    Error {
+        time: Timestamp,
+        epoch_data: BlockProducerEpochData,
+        error: String,
+    },
+}
+
+/// Data of the epoch, based on which slot winners are determined.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct BlockProducerEpochData {
+    pub epoch: u32,
+    pub seed: EpochSeed,
+    pub ledger_hash: LedgerHash,
+    pub total_currency: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerWonSlot {
+    /// Time when the slot starts.
+    pub slot_time: Timestamp,
+    pub global_slot: u32,
+    pub global_slot_since_genesis: u32,
+    pub epoch: u32,
+    pub staking_ledger_hash: LedgerHash,
+    /// Account, stake of which won the slot. Either the producer
+    /// itself or the account delegating to it.
+    pub delegator: AccountPublicKey,
+    pub delegator_index: u64,
+    pub delegator_stake: u64,
+    /// Full (not truncated) VRF output.
+    pub vrf_output: BigInt,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum BlockProducerWonSlotDiscardReason {
+    BestTipGlobalSlotHigher,
+    BestTipStakingLedgerDifferent,
+    StagedLedgerDiffCreateError(String),
+    BlockProveError(String),
+}
+
+/// Block, which is missing only the protocol state proof.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockWithoutProof {
+    pub protocol_state: MinaStateProtocolStateValueStableV2,
+    pub delta_block_chain_proof: (StateHash, Vec<StateBodyHash>),
+    pub current_protocol_version: ProtocolVersionStableV1,
+    pub proposed_protocol_version_opt: Option<ProtocolVersionStableV1>,
+    pub body: StagedLedgerDiffBodyStableV1,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum BlockProducerCurrentState {
+    Idle {
+        time: Timestamp,
+    },
+    WonSlot {
+        time: Timestamp,
+        won_slot: BlockProducerWonSlot,
+    },
+    WonSlotDiscarded {
+        time: Timestamp,
+        won_slot: BlockProducerWonSlot,
+        reason: BlockProducerWonSlotDiscardReason,
+    },
+    WonSlotProduceInit {
+        time: Timestamp,
+        won_slot: BlockProducerWonSlot,
+    },
+    StagedLedgerDiffCreatePending {
+        time: Timestamp,
+        won_slot: BlockProducerWonSlot,
+    },
+    StagedLedgerDiffCreateSuccess {
+        time: Timestamp,
+        won_slot: BlockProducerWonSlot,
+        /// Chain that we are extending, predecessor of the new block
+        /// being the last one.
+        chain: Vec<ArcBlockWithHash>,
+        diff: StagedLedgerDiffDiffStableV2,
+        diff_hash: ConsensusBodyReferenceStableV1,
+        staged_ledger_hash: MinaBaseStagedLedgerHashStableV1,
+        emitted_ledger_proof: Option<Box<LedgerProofProdStableV2>>,
+    },
+    BlockUnprovenBuilt {
+        time: Timestamp,
+        won_slot: BlockProducerWonSlot,
+        chain: Vec<ArcBlockWithHash>,
+        emitted_ledger_proof: Option<Box<LedgerProofProdStableV2>>,
+        block: BlockWithoutProof,
+        block_hash: StateHash,
+    },
+    BlockProvePending {
+        time: Timestamp,
+        won_slot: BlockProducerWonSlot,
+        chain: Vec<ArcBlockWithHash>,
+        block: BlockWithoutProof,
+        block_hash: StateHash,
+    },
+    BlockProveSuccess {
+        time: Timestamp,
+        won_slot: BlockProducerWonSlot,
+        chain: Vec<ArcBlockWithHash>,
+        block: BlockWithoutProof,
+        block_hash: StateHash,
+        proof: Arc<MinaBaseProofStableV2>,
+    },
+    Produced {
+        time: Timestamp,
+        won_slot: BlockProducerWonSlot,
+        chain: Vec<ArcBlockWithHash>,
+        block: ArcBlockWithHash,
+    },
+    Injected {
+        time: Timestamp,
+        won_slot: BlockProducerWonSlot,
+        chain: Vec<ArcBlockWithHash>,
+        block: ArcBlockWithHash,
+    },
+}
+
+impl BlockProducerState {
+    pub fn new(now: Timestamp, config: Option<BlockProducerConfig>) -> Self {
+        Self(config.map(|config| BlockProducerEnabled {
+            config,
+            vrf_evaluator: BlockProducerVrfEvaluatorState {
+                status: BlockProducerVrfEvaluatorStatus::Idle { time: now },
+                evaluated_epochs: Default::default(),
+                won_slots: Default::default(),
+            },
+            current: BlockProducerCurrentState::Idle { time: now },
+        }))
+    }
+
+    #[inline(always)]
+    pub(super) fn with<'a, F, R>(&'a self, default: R, fun: F) -> R
+    where
+        F: FnOnce(&'a BlockProducerEnabled) -> R,
+    {
+        self.0.as_ref().map_or(default, fun)
+    }
+
+    #[inline(always)]
+    pub(super) fn with_mut<F, R>(&mut self, default: R, fun: F) -> R
+    where
+        F: FnOnce(&mut BlockProducerEnabled) -> R,
+    {
+        self.0.as_mut().map_or(default, fun)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+
+    pub fn as_ref(&self) -> Option<&BlockProducerEnabled> {
+        self.0.as_ref()
+    }
+
+    pub fn config(&self) -> Option<&BlockProducerConfig> {
+        self.with(None, |this| Some(&this.config))
+    }
+
+    pub fn vrf_evaluator(&self) -> Option<&BlockProducerVrfEvaluatorState> {
+        self.with(None, |this| Some(&this.vrf_evaluator))
+    }
+
+    pub fn current_won_slot(&self) -> Option<&BlockProducerWonSlot> {
+        self.with(None, |this| this.current.won_slot())
+    }
+
+    /// Epoch data, slots of which need to be evaluated next, if any.
+    pub fn vrf_evaluation_next(
+        &self,
+        best_tip: &ArcBlockWithHash,
+        now: Timestamp,
+    ) -> Option<BlockProducerEpochData> {
+        self.with(None, |this| {
+            this.vrf_evaluator.next_epoch_to_evaluate(best_tip, now)
+        })
+    }
+
+    /// Next won slot, which is still ahead of the best tip and
+    /// hasn't passed yet.
+    pub fn next_won_slot(
+        &self,
+        best_tip: &ArcBlockWithHash,
+        now: Timestamp,
+    ) -> Option<&BlockProducerWonSlot> {
+        self.with(None, |this| {
+            if !this.current.is_idle() {
+                return None;
+            }
+            let min_slot = best_tip_slot(best_tip)
+                .max(this.current.won_slot().map_or(0, |s| s.global_slot))
+                + 1;
+            this.vrf_evaluator
+                .won_slots
+                .range(min_slot..)
+                .map(|(_, won_slot)| won_slot)
+                .find(|won_slot| !won_slot.is_passed(now, this.config.slot_duration_ms))
+        })
+    }
+
+    /// Reason why the won slot that we are waiting for should be
+    /// discarded, given the current best tip.
+    pub fn won_slot_discard_reason(
+        &self,
+        best_tip: &ArcBlockWithHash,
+    ) -> Option<BlockProducerWonSlotDiscardReason> {
+        self.with(None, |this| {
+            let BlockProducerCurrentState::WonSlot { won_slot, .. } = &this.current else {
+                return None;
+            };
+            if best_tip_slot(best_tip) >= won_slot.global_slot {
+                return Some(BlockProducerWonSlotDiscardReason::BestTipGlobalSlotHigher);
+            }
+            let epoch_data = std::iter::once(BlockProducerEpochData::staking(best_tip))
+                .chain(BlockProducerEpochData::next(best_tip))
+                .find(|data| data.epoch == won_slot.epoch);
+            match epoch_data {
+                Some(data) if data.ledger_hash == won_slot.staking_ledger_hash => None,
+                _ => Some(BlockProducerWonSlotDiscardReason::BestTipStakingLedgerDifferent),
+            }
+        })
+    }
+
+    /// Block that we produced, but didn't yet inject into our node.
+    pub fn produced_block(&self) -> Option<&ArcBlockWithHash> {
+        self.with(None, |this| match &this.current {
+            BlockProducerCurrentState::Produced { block, .. } => Some(block),
+            _ => None,
+        })
+    }
+
+    /// Whether the block with the given hash was produced by us.
+    pub fn is_produced_by_me(&self, block_hash: &StateHash) -> bool {
+        self.with(false, |this| match &this.current {
+            BlockProducerCurrentState::Produced { block, .. }
+            | BlockProducerCurrentState::Injected { block, .. } => &block.hash == block_hash,
+            _ => false,
+        })
+    }
+}
+
+impl BlockProducerVrfEvaluatorState {
+    fn next_epoch_to_evaluate(
+        &self,
+        best_tip: &ArcBlockWithHash,
+        now: Timestamp,
+    ) -> Option<BlockProducerEpochData> {
+        if matches!(self.status, BlockProducerVrfEvaluatorStatus::Pending { .. }) {
+            return None;
+        }
+        std::iter::once(BlockProducerEpochData::staking(best_tip))
+            .chain(BlockProducerEpochData::next(best_tip))
+            .filter(|data| self.evaluated_epochs.get(&data.epoch) != Some(data))
+            .find(|data| match &self.status {
+                BlockProducerVrfEvaluatorStatus::Error {
+                    time, epoch_data, ..
+                } if epoch_data == data => now
+                    .checked_sub(*time)
+                    .map_or(false, |dur| dur >= VRF_EVALUATION_RETRY_TIMEOUT),
+                _ => true,
+            })
+    }
+}
+
+impl BlockProducerEpochData {
+    /// Staking epoch data of the best tip's epoch.
+    pub fn staking(best_tip: &ArcBlockWithHash) -> Self {
+        let consensus_state = &best_tip.header().protocol_state.body.consensus_state;
+        let data = &consensus_state.staking_epoch_data;
+        Self {
+            epoch: best_tip_epoch(best_tip),
+            seed: data.seed.clone(),
+            ledger_hash: data.ledger.hash.clone(),
+            total_currency: data.ledger.total_currency.as_u64(),
+        }
+    }
+
+    /// Staking epoch data of the epoch following the best tip's one.
+    /// Available only once the best tip is past the seed update range,
+    /// since only then the seed of the next epoch is final.
+    pub fn next(best_tip: &ArcBlockWithHash) -> Option<Self> {
+        let consensus_state = &best_tip.header().protocol_state.body.consensus_state;
+        let slots_per_epoch = consensus_state.curr_global_slot.slots_per_epoch.as_u32();
+        if best_tip_slot(best_tip) % slots_per_epoch < slots_per_epoch * 2 / 3 {
+            return None;
+        }
+        let data = &consensus_state.next_epoch_data;
+        Some(Self {
+            epoch: best_tip_epoch(best_tip) + 1,
+            seed: data.seed.clone(),
+            ledger_hash: data.ledger.hash.clone(),
+            total_currency: data.ledger.total_currency.as_u64(),
+        })
+    }
+}
+
+impl BlockProducerWonSlot {
+    /// Whether the slot has already ended, so it's too late to
+    /// produce a block for it.
+    pub fn is_passed(&self, now: Timestamp, slot_duration_ms: u64) -> bool {
+        now.checked_sub(self.slot_time)
+            .map_or(false, |dur| dur >= Duration::from_millis(slot_duration_ms))
+    }
+
+    /// Whether the slot has started, so we can start producing a block.
+    pub fn is_started(&self, now: Timestamp) -> bool {
+        now.checked_sub(self.slot_time).is_some()
+    }
+}
+
+impl BlockProducerCurrentState {
+    pub fn won_slot(&self) -> Option<&BlockProducerWonSlot> {
+        match self {
+            Self::Idle { .. } => None,
+            Self::WonSlot { won_slot, .. }
+            | Self::WonSlotDiscarded { won_slot, .. }
+            | Self::WonSlotProduceInit { won_slot, .. }
+            | Self::StagedLedgerDiffCreatePending { won_slot, .. }
+            | Self::StagedLedgerDiffCreateSuccess { won_slot, .. }
+            | Self::BlockUnprovenBuilt { won_slot, .. }
+            | Self::BlockProvePending { won_slot, .. }
+            | Self::BlockProveSuccess { won_slot, .. }
+            | Self::Produced { won_slot, .. }
+            | Self::Injected { won_slot, .. } => Some(won_slot),
+        }
+    }
+
+    /// Whether we aren't busy with any won slot, so we can start
+    /// waiting for the next one.
+    pub fn is_idle(&self) -> bool {
+        matches!(
+            self,
+            Self::Idle { .. } | Self::WonSlotDiscarded { .. } | Self::Injected { .. }
+        )
+    }
+}
+
+pub(super) fn best_tip_slot(best_tip: &ArcBlockWithHash) -> u32 {
+    best_tip
+        .header()
+        .protocol_state
+        .body
+        .consensus_state
+        .curr_global_slot
+        .slot_number
+        .as_u32()
+}
+
+fn best_tip_epoch(best_tip: &ArcBlockWithHash) -> u32 {
+    let slots_per_epoch = best_tip
+        .header()
+        .protocol_state
+        .body
+        .consensus_state
+        .curr_global_slot
+        .slots_per_epoch
+        .as_u32();
+    best_tip_slot(best_tip) / slots_per_epoch
+}
@@ -0,0 +1,23 @@
+mod block_producer_config;
+pub use block_producer_config::*;
+
+mod block_producer_event;
+pub use block_producer_event::*;
+
+mod block_producer_state;
+pub use block_producer_state::*;
+
+mod block_producer_actions;
+pub use block_producer_actions::*;
+
+mod block_producer_reducer;
+pub use block_producer_reducer::*;
+
+mod block_producer_effects;
+pub use block_producer_effects::*;
+
+mod block_producer_service;
+pub use block_producer_service::*;
+
+/// How long to wait before retrying failed VRF evaluation.
+pub const VRF_EVALUATION_RETRY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
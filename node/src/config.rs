@@ -4,10 +4,12 @@ use mina_p2p_messages::v2::CurrencyFeeStableV1;
 use serde::{Deserialize, Serialize};
 
 use crate::account::AccountPublicKey;
+pub use crate::block_producer::BlockProducerConfig;
 pub use crate::ledger::LedgerConfig;
 pub use crate::p2p::P2pConfig;
 pub use crate::snark::SnarkConfig;
 pub use crate::snark_pool::SnarkPoolConfig;
+pub use crate::transaction_pool::TransactionPoolConfig;
 pub use crate::transition_frontier::TransitionFrontierConfig;
 pub use mina_p2p_messages::v2::MinaBaseProtocolConstantsCheckedValueStableV1 as ProtocolConstants;
 
@@ -19,6 +21,9 @@ pub struct Config {
     pub snark: SnarkConfig,
     pub p2p: P2pConfig,
     pub transition_frontier: TransitionFrontierConfig,
+    pub block_producer: Option<BlockProducerConfig>,
+    #[serde(default)]
+    pub transaction_pool: TransactionPoolConfig,
     pub global: GlobalConfig,
 }
 
@@ -26,6 +31,28 @@ pub struct Config {
 pub struct GlobalConfig {
     pub build: Box<BuildEnv>,
     pub snarker: Option<SnarkerConfig>,
+    #[serde(default)]
+    pub proof_level: ProofLevel,
+}
+
+/// Which proofs are created and checked by the node.
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofLevel {
+    /// Blocks are proven and proofs of received blocks are verified.
+    #[default]
+    Full,
+    /// Produced blocks get a dummy proof and proofs of received blocks
+    /// aren't verified. Only usable on private networks.
+    Check,
+    /// Same as `Check` for now.
+    None,
+}
+
+impl ProofLevel {
+    pub fn is_full(&self) -> bool {
+        matches!(self, Self::Full)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -35,6 +62,8 @@ pub struct SnarkerConfig {
     pub auto_commit: bool,
     /// External Mina snark worker executable path
     pub path: OsString,
+    /// Number of snark workers working on jobs concurrently.
+    pub workers: usize,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -93,7 +93,9 @@ impl redux::EnablingCondition<crate::State> for ConsensusBlockSnarkVerifySuccess
             .consensus
             .blocks
             .get(&self.hash)
-            .map_or(false, |block| block.status.is_snark_verify_pending())
+            .map_or(false, |block| {
+                block.status.is_received() || block.status.is_snark_verify_pending()
+            })
     }
 }
 
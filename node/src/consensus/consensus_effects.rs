@@ -1,3 +1,4 @@
+use crate::p2p::channels::{P2pChannelsService, P2pGossipAcceptance, P2pGossipValidationKey};
 use crate::transition_frontier::sync::{
     TransitionFrontierSyncBestTipUpdateAction, TransitionFrontierSyncInitAction,
 };
@@ -10,8 +11,9 @@ use crate::{
 
 use super::{
     ConsensusAction, ConsensusActionWithMeta, ConsensusBestTipUpdateAction,
-    ConsensusBlockSnarkVerifyPendingAction, ConsensusDetectForkRangeAction,
-    ConsensusLongRangeForkResolveAction, ConsensusShortRangeForkResolveAction,
+    ConsensusBlockSnarkVerifyPendingAction, ConsensusBlockSnarkVerifySuccessAction,
+    ConsensusDetectForkRangeAction, ConsensusLongRangeForkResolveAction,
+    ConsensusShortRangeForkResolveAction,
 };
 
 pub fn consensus_effects<S: crate::Service>(store: &mut Store<S>, action: ConsensusActionWithMeta) {
@@ -19,6 +21,20 @@ pub fn consensus_effects<S: crate::Service>(store: &mut Store<S>, action: Consen
 
     match action {
         ConsensusAction::BlockReceived(action) => {
+            let state = store.state();
+            // Proofs of blocks produced by us don't need to be verified.
+            // On networks with proof level other than full, block proofs
+            // are dummies, so they can't be verified either.
+            if !state.config.proof_level.is_full()
+                || state.block_producer.is_produced_by_me(&action.hash)
+            {
+                store.service.libp2p_gossip_validation_result(
+                    P2pGossipValidationKey::Block(action.hash.clone()),
+                    P2pGossipAcceptance::Accept,
+                );
+                store.dispatch(ConsensusBlockSnarkVerifySuccessAction { hash: action.hash });
+                return;
+            }
             let req_id = store.state().snark.block_verify.next_req_id();
             store.dispatch(SnarkBlockVerifyInitAction {
                 req_id,
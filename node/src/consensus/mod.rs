@@ -15,7 +15,7 @@ use mina_p2p_messages::v2::{
 };
 
 // TODO get constants from elsewher
-const GRACE_PERIOD_END: u32 = 1440;
+pub const GRACE_PERIOD_END: u32 = 1440;
 const SUB_WINDOWS_PER_WINDOW: u32 = 11;
 const SLOTS_PER_SUB_WINDOW: u32 = 7;
 
@@ -103,6 +103,20 @@ fn global_slot(b: &MinaConsensusState) -> u32 {
     b.curr_global_slot.slot_number.as_u32()
 }
 
+/// Whether the candidate block would be taken over the tip.
+pub fn consensus_take(
+    tip_cs: &MinaConsensusState,
+    candidate_cs: &MinaConsensusState,
+    tip_hash: &StateHash,
+    candidate_hash: &StateHash,
+) -> bool {
+    if is_short_range_fork(tip_cs, candidate_cs) {
+        short_range_fork_take(tip_cs, candidate_cs, tip_hash, candidate_hash).0
+    } else {
+        long_range_fork_take(tip_cs, candidate_cs, tip_hash, candidate_hash).0
+    }
+}
+
 fn short_range_fork_take(
     tip_cs: &MinaConsensusState,
     candidate_cs: &MinaConsensusState,
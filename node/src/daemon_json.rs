@@ -0,0 +1,379 @@
+//! Mina-compatible runtime configuration (`daemon.json`).
+//!
+//! Only the parts of the format which are relevant for our node are
+//! parsed, rest of the fields are ignored. Fields which are missing
+//! fall back to the values of the network, on top of which the config
+//! is applied (see [`crate::NetworkConfig::apply_daemon_json`]).
+
+use std::{path::Path, str::FromStr};
+
+use ledger::{
+    scan_state::{
+        currency::{Amount, Balance, Length, Nonce, Slot, SlotSpan},
+        scan_state::ForkConstants,
+    },
+    Account, AccountId, Timing, TokenId,
+};
+use mina_p2p_messages::v2::{
+    BlockTimeTimeStableV1, StateHash, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+};
+use mina_signer::CompressedPubKey;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::account::AccountPublicKey;
+use crate::config::ProofLevel;
+use crate::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+
+#[derive(thiserror::Error, Debug)]
+pub enum DaemonJsonError {
+    #[error("failed to read config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse config file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid genesis state timestamp `{0}`")]
+    InvalidTimestamp(String),
+    #[error("invalid currency amount `{0}`")]
+    InvalidAmount(String),
+    #[error("invalid public key `{0}`")]
+    InvalidPublicKey(String),
+    #[error("invalid peer address `{0}`")]
+    InvalidPeer(String),
+    #[error("invalid state hash `{0}`")]
+    InvalidStateHash(String),
+    #[error("invalid transaction capacity, either `2_to_the` or `txns_per_second_x10` expected")]
+    InvalidTransactionCapacity,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct DaemonJson {
+    pub daemon: Option<DaemonJsonDaemon>,
+    pub genesis: Option<DaemonJsonGenesis>,
+    pub proof: Option<DaemonJsonProof>,
+    pub ledger: Option<DaemonJsonLedger>,
+    /// Openmina extension. Seed peers to connect to.
+    pub peers: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct DaemonJsonDaemon {
+    /// Part of the genesis constants, so it only affects the chain id
+    /// (see [`crate::transition_frontier::chain_id`]).
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub txpool_max_size: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct DaemonJsonGenesis {
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub k: Option<u32>,
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub delta: Option<u32>,
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub slots_per_epoch: Option<u32>,
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub slots_per_sub_window: Option<u32>,
+    pub genesis_state_timestamp: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct DaemonJsonProof {
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub sub_windows_per_window: Option<u64>,
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub work_delay: Option<u64>,
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub block_window_duration_ms: Option<u64>,
+    pub transaction_capacity: Option<DaemonJsonTransactionCapacity>,
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub pending_coinbase_depth: Option<u64>,
+    pub coinbase_amount: Option<String>,
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub supercharged_coinbase_factor: Option<u64>,
+    pub account_creation_fee: Option<String>,
+    pub fork: Option<DaemonJsonFork>,
+    /// Doesn't affect genesis, so it's ignored by [`DaemonJson::changes_genesis`].
+    pub level: Option<ProofLevel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct DaemonJsonTransactionCapacity {
+    #[serde(rename = "2_to_the")]
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub two_to_the: Option<u64>,
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub txns_per_second_x10: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DaemonJsonFork {
+    pub previous_state_hash: StateHash,
+    #[serde(deserialize_with = "de_number")]
+    pub previous_length: u32,
+    #[serde(deserialize_with = "de_number")]
+    pub previous_global_slot: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct DaemonJsonLedger {
+    /// Without `accounts`, refers to the ledger of a known network.
+    pub name: Option<String>,
+    pub accounts: Option<Vec<DaemonJsonAccount>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DaemonJsonAccount {
+    pub pk: String,
+    pub balance: String,
+    pub delegate: Option<String>,
+    #[serde(default, deserialize_with = "de_number_opt")]
+    pub nonce: Option<u32>,
+    pub timing: Option<DaemonJsonTiming>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DaemonJsonTiming {
+    pub initial_minimum_balance: String,
+    #[serde(deserialize_with = "de_number")]
+    pub cliff_time: u32,
+    pub cliff_amount: String,
+    #[serde(deserialize_with = "de_number")]
+    pub vesting_period: u32,
+    pub vesting_increment: String,
+}
+
+impl DaemonJson {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, DaemonJsonError> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    /// Whether config changes anything that affects the genesis block.
+    /// Ledger which only has a name refers to the ledger of a known
+    /// network, so it doesn't change the genesis.
+    pub fn changes_genesis(&self) -> bool {
+        self.genesis.is_some()
+            || self.proof.as_ref().map_or(false, |p| p.changes_genesis())
+            || self.ledger.as_ref().map_or(false, |l| l.accounts.is_some())
+    }
+
+    pub fn peers(&self) -> Result<Option<Vec<P2pConnectionOutgoingInitOpts>>, DaemonJsonError> {
+        let Some(peers) = &self.peers else {
+            return Ok(None);
+        };
+        peers
+            .iter()
+            .map(|peer| {
+                peer.parse()
+                    .map_err(|_| DaemonJsonError::InvalidPeer(peer.clone()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+}
+
+impl DaemonJsonProof {
+    pub fn changes_genesis(&self) -> bool {
+        self.sub_windows_per_window.is_some()
+            || self.work_delay.is_some()
+            || self.block_window_duration_ms.is_some()
+            || self.transaction_capacity.is_some()
+            || self.pending_coinbase_depth.is_some()
+            || self.coinbase_amount.is_some()
+            || self.supercharged_coinbase_factor.is_some()
+            || self.account_creation_fee.is_some()
+            || self.fork.is_some()
+    }
+}
+
+impl DaemonJsonGenesis {
+    pub fn genesis_state_timestamp(
+        &self,
+    ) -> Result<Option<BlockTimeTimeStableV1>, DaemonJsonError> {
+        let Some(timestamp) = &self.genesis_state_timestamp else {
+            return Ok(None);
+        };
+        let ms = chrono::DateTime::parse_from_rfc3339(timestamp)
+            .or_else(|_| chrono::DateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%:z"))
+            .map_err(|_| DaemonJsonError::InvalidTimestamp(timestamp.clone()))?
+            .timestamp_millis();
+        let ms =
+            u64::try_from(ms).map_err(|_| DaemonJsonError::InvalidTimestamp(timestamp.clone()))?;
+        Ok(Some(BlockTimeTimeStableV1(
+            UnsignedExtendedUInt64Int64ForVersionTagsStableV1(ms.into()),
+        )))
+    }
+}
+
+impl DaemonJsonTransactionCapacity {
+    pub fn log_2(&self, block_window_duration_ms: u64) -> Result<u64, DaemonJsonError> {
+        match (self.two_to_the, self.txns_per_second_x10) {
+            (Some(log_2), None) => Ok(log_2),
+            (None, Some(txns_per_second_x10)) => {
+                const MAX_COINBASES: u64 = 2;
+                let max_user_commands = txns_per_second_x10 * block_window_duration_ms / 10_000;
+                let max_transactions = max_user_commands + MAX_COINBASES;
+                Ok(max_transactions.next_power_of_two().trailing_zeros() as u64)
+            }
+            _ => Err(DaemonJsonError::InvalidTransactionCapacity),
+        }
+    }
+}
+
+impl DaemonJsonFork {
+    pub fn to_fork_constants(&self) -> Result<ForkConstants, DaemonJsonError> {
+        let previous_state_hash = self
+            .previous_state_hash
+            .to_fp()
+            .map_err(|_| DaemonJsonError::InvalidStateHash(self.previous_state_hash.to_string()))?;
+        Ok(ForkConstants {
+            previous_state_hash,
+            previous_length: Length::from_u32(self.previous_length),
+            previous_global_slot: Slot::from_u32(self.previous_global_slot),
+        })
+    }
+}
+
+impl DaemonJsonLedger {
+    pub fn accounts(&self) -> Result<Option<Vec<Account>>, DaemonJsonError> {
+        let Some(accounts) = &self.accounts else {
+            return Ok(None);
+        };
+        accounts
+            .iter()
+            .map(DaemonJsonAccount::to_account)
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+
+    /// Loads accounts of the ledger exported with `mina ledger export`,
+    /// which uses the same account format as the runtime config.
+    pub fn load_exported_accounts<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<Vec<Account>, DaemonJsonError> {
+        let file = std::fs::File::open(path)?;
+        let accounts: Vec<DaemonJsonAccount> =
+            serde_json::from_reader(std::io::BufReader::new(file))?;
+        accounts.iter().map(DaemonJsonAccount::to_account).collect()
+    }
+}
+
+impl DaemonJsonAccount {
+    pub fn to_account(&self) -> Result<Account, DaemonJsonError> {
+        let public_key = parse_public_key(&self.pk)?;
+        let balance = Balance::from_u64(parse_amount(&self.balance)?);
+        let mut account =
+            Account::create_with(AccountId::new(public_key, TokenId::default()), balance);
+        if let Some(delegate) = &self.delegate {
+            account.delegate = Some(parse_public_key(delegate)?);
+        }
+        if let Some(nonce) = self.nonce {
+            account.nonce = Nonce::from_u32(nonce);
+        }
+        if let Some(timing) = &self.timing {
+            account.timing = Timing::Timed {
+                initial_minimum_balance: Balance::from_u64(parse_amount(
+                    &timing.initial_minimum_balance,
+                )?),
+                cliff_time: Slot::from_u32(timing.cliff_time),
+                cliff_amount: Amount::from_u64(parse_amount(&timing.cliff_amount)?),
+                vesting_period: SlotSpan::from_u32(timing.vesting_period),
+                vesting_increment: Amount::from_u64(parse_amount(&timing.vesting_increment)?),
+            };
+        }
+        Ok(account)
+    }
+}
+
+fn parse_public_key(s: &str) -> Result<CompressedPubKey, DaemonJsonError> {
+    let key: AccountPublicKey = s
+        .parse()
+        .map_err(|_| DaemonJsonError::InvalidPublicKey(s.to_owned()))?;
+    Ok(key.as_ref().into())
+}
+
+/// Parses amount in mina (e.g. `"1000.5"`) into nanomina.
+pub fn parse_amount(s: &str) -> Result<u64, DaemonJsonError> {
+    const DECIMALS: usize = 9;
+    let err = || DaemonJsonError::InvalidAmount(s.to_owned());
+
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    if whole.is_empty() && frac.is_empty() || frac.len() > DECIMALS {
+        return Err(err());
+    }
+    let parse = |s: &str| match s {
+        "" => Ok(0),
+        s if s.bytes().all(|b| b.is_ascii_digit()) => s.parse::<u64>().map_err(|_| err()),
+        _ => Err(err()),
+    };
+    let frac = parse(frac)? * 10u64.pow((DECIMALS - frac.len()) as u32);
+    parse(whole)?
+        .checked_mul(10u64.pow(DECIMALS as u32))
+        .and_then(|whole| whole.checked_add(frac))
+        .ok_or_else(err)
+}
+
+/// Mina encodes numbers in json either as numbers or as strings.
+fn de_number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+{
+    use serde::de::Error;
+
+    let s = match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => s,
+        v => return Err(D::Error::custom(format!("expected number, found: {v}"))),
+    };
+    s.parse()
+        .map_err(|_| D::Error::custom(format!("invalid number: {s}")))
+}
+
+fn de_number_opt<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+{
+    de_number(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_amount_test() {
+        assert_eq!(parse_amount("0").unwrap(), 0);
+        assert_eq!(parse_amount("1").unwrap(), 1_000_000_000);
+        assert_eq!(parse_amount("1000.5").unwrap(), 1_000_500_000_000);
+        assert_eq!(parse_amount("0.000000001").unwrap(), 1);
+        assert_eq!(parse_amount(".25").unwrap(), 250_000_000);
+        assert!(parse_amount("0.0000000001").is_err());
+        assert!(parse_amount("1,5").is_err());
+        assert!(parse_amount("").is_err());
+        assert!(parse_amount("-1").is_err());
+    }
+
+    #[test]
+    fn transaction_capacity_log_2() {
+        let capacity = |two_to_the, txns_per_second_x10| DaemonJsonTransactionCapacity {
+            two_to_the,
+            txns_per_second_x10,
+        };
+        assert_eq!(capacity(Some(7), None).log_2(180000).unwrap(), 7);
+        // 180 seconds * 0.5 txns per second = 90 txns + 2 coinbases.
+        assert_eq!(capacity(None, Some(5)).log_2(180000).unwrap(), 7);
+        assert!(capacity(None, None).log_2(180000).is_err());
+    }
+
+    #[test]
+    fn name_only_ledger_doesnt_change_genesis() {
+        let config: DaemonJson =
+            serde_json::from_str(r#"{ "ledger": { "name": "berkeley" } }"#).unwrap();
+        assert!(!config.changes_genesis());
+
+        let config: DaemonJson =
+            serde_json::from_str(r#"{ "ledger": { "name": "private", "accounts": [] } }"#).unwrap();
+        assert!(config.changes_genesis());
+    }
+}
@@ -1,5 +1,10 @@
-use p2p::discovery::P2pDiscoveryInitAction;
+use p2p::discovery::{P2pDiscoveryInitAction, P2pDiscoveryKademliaInitAction};
 
+use crate::block_producer::{
+    block_producer_effects, BlockProducerVrfEvaluationInitAction,
+    BlockProducerWonSlotDiscardAction, BlockProducerWonSlotProduceInitAction,
+    BlockProducerWonSlotSearchAction,
+};
 use crate::consensus::consensus_effects;
 use crate::event_source::event_source_effects;
 use crate::external_snark_worker::{
@@ -11,13 +16,16 @@ use crate::p2p::channels::rpc::{
     P2pChannelsRpcRequestSendAction, P2pChannelsRpcTimeoutAction, P2pRpcKind, P2pRpcRequest,
 };
 use crate::p2p::channels::snark::P2pChannelsSnarkRequestSendAction;
+use crate::p2p::channels::transaction::P2pChannelsTransactionRequestSendAction;
+use crate::p2p::channels::P2pChannelsService;
 use crate::p2p::connection::incoming::P2pConnectionIncomingTimeoutAction;
 use crate::p2p::connection::outgoing::{
     P2pConnectionOutgoingRandomInitAction, P2pConnectionOutgoingReconnectAction,
     P2pConnectionOutgoingTimeoutAction,
 };
 use crate::p2p::p2p_effects;
-use crate::rpc::rpc_effects;
+use crate::p2p::peer::{P2pPeerForgiveAction, P2pPeerUnbanAction};
+use crate::rpc::{rpc_effects, RpcUnsubscribeAction};
 use crate::snark::snark_effects;
 use crate::snark_pool::candidate::{
     SnarkPoolCandidateWorkFetchAllAction, SnarkPoolCandidateWorkVerifyNextAction,
@@ -25,12 +33,14 @@ use crate::snark_pool::candidate::{
 use crate::snark_pool::{
     snark_pool_effects, SnarkPoolCheckTimeoutsAction, SnarkPoolP2pSendAllAction,
 };
+use crate::transaction_pool::{transaction_pool_effects, TransactionPoolP2pSendAllAction};
 use crate::transition_frontier::sync::TransitionFrontierSyncBlocksNextApplyInitAction;
 use crate::transition_frontier::transition_frontier_effects;
 use crate::watched_accounts::watched_accounts_effects;
 use crate::{Action, ActionWithMeta, Service, Store};
 
 pub const MAX_PEER_PENDING_SNARKS: usize = 32;
+pub const MAX_PEER_TRANSACTIONS_REQUEST: u8 = 32;
 
 pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
     store.service.recorder().action(&action);
@@ -45,7 +55,9 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
     match action {
         Action::CheckTimeouts(_) => {
             // TODO(binier): create init action and dispatch this there.
-            store.dispatch(ExternalSnarkWorkerStartAction {});
+            for worker_id in store.state().external_snark_worker.ids() {
+                store.dispatch(ExternalSnarkWorkerStartAction { worker_id });
+            }
 
             let now = store.state().time();
             let p2p_connection_timeouts: Vec<_> = store
@@ -69,6 +81,32 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
                 };
             }
 
+            let expired_bans = store.state().p2p.peer_bans_expired(now);
+            for peer_id in expired_bans {
+                store.dispatch(P2pPeerUnbanAction { peer_id });
+            }
+            let recovered = store.state().p2p.peer_reputations_recovered(now);
+            for peer_id in recovered {
+                store.dispatch(P2pPeerForgiveAction { peer_id });
+            }
+
+            store.service.libp2p_gossip_validation_check_timeouts();
+
+            // Subscribers that went away without any events being sent to
+            // them since, would otherwise be kept forever.
+            let closed_subscriptions = store
+                .state()
+                .rpc
+                .subscriptions
+                .keys()
+                .copied()
+                .filter(|rpc_id| store.service.event_subscriber_is_closed(*rpc_id))
+                .collect::<Vec<_>>();
+            for rpc_id in closed_subscriptions {
+                store.dispatch(RpcUnsubscribeAction { rpc_id });
+            }
+
+            store.dispatch(P2pDiscoveryKademliaInitAction { now });
             store.dispatch(P2pConnectionOutgoingRandomInitAction {});
 
             let reconnect_actions: Vec<_> = store
@@ -85,6 +123,19 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
 
             store.dispatch(SnarkPoolCheckTimeoutsAction {});
             store.dispatch(SnarkPoolP2pSendAllAction {});
+            store.dispatch(TransactionPoolP2pSendAllAction {});
+
+            store.dispatch(BlockProducerVrfEvaluationInitAction {});
+            let state = store.state();
+            let won_slot_discard_reason = state
+                .transition_frontier
+                .best_tip()
+                .and_then(|best_tip| state.block_producer.won_slot_discard_reason(best_tip));
+            if let Some(reason) = won_slot_discard_reason {
+                store.dispatch(BlockProducerWonSlotDiscardAction { reason });
+            }
+            store.dispatch(BlockProducerWonSlotSearchAction {});
+            store.dispatch(BlockProducerWonSlotProduceInitAction {});
 
             // TODO(binier): refactor
             let state = store.state();
@@ -143,6 +194,12 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
                 .filter(|(_, limit)| *limit > 0)
                 .map(|(peer_id, limit)| (*peer_id, limit.min(u8::MAX as usize) as u8))
                 .collect::<Vec<_>>();
+            let transaction_reqs = state
+                .p2p
+                .ready_peers_iter()
+                .filter(|(_, p)| p.channels.transaction.can_send_request())
+                .map(|(peer_id, _)| *peer_id)
+                .collect::<Vec<_>>();
             let ids = state
                 .p2p
                 .ready_peers_iter()
@@ -153,6 +210,13 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
                 store.dispatch(P2pChannelsSnarkRequestSendAction { peer_id, limit });
             }
 
+            for peer_id in transaction_reqs {
+                store.dispatch(P2pChannelsTransactionRequestSendAction {
+                    peer_id,
+                    limit: MAX_PEER_TRANSACTIONS_REQUEST,
+                });
+            }
+
             for peer_id in ids {
                 store.dispatch(P2pDiscoveryInitAction { peer_id });
             }
@@ -165,8 +229,16 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
             // TODO(binier): remove once ledger communication is async.
             store.dispatch(TransitionFrontierSyncBlocksNextApplyInitAction {});
 
-            store.dispatch(ExternalSnarkWorkerStartTimeoutAction { now: meta.time() });
-            store.dispatch(ExternalSnarkWorkerWorkTimeoutAction { now: meta.time() });
+            for worker_id in store.state().external_snark_worker.ids() {
+                store.dispatch(ExternalSnarkWorkerStartTimeoutAction {
+                    worker_id,
+                    now: meta.time(),
+                });
+                store.dispatch(ExternalSnarkWorkerWorkTimeoutAction {
+                    worker_id,
+                    now: meta.time(),
+                });
+            }
         }
         Action::EventSource(action) => {
             event_source_effects(store, meta.with_action(action));
@@ -186,6 +258,12 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
         Action::SnarkPool(action) => {
             snark_pool_effects(store, meta.with_action(action));
         }
+        Action::TransactionPool(action) => {
+            transaction_pool_effects(store, meta.with_action(action));
+        }
+        Action::BlockProducer(action) => {
+            block_producer_effects(store, meta.with_action(action));
+        }
         Action::Rpc(action) => {
             rpc_effects(store, meta.with_action(action));
         }
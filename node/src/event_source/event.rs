@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use crate::external_snark_worker::ExternalSnarkWorkerEvent;
+use crate::block_producer::BlockProducerEvent;
+use crate::external_snark_worker::{ExternalSnarkWorkerEvent, ExternalSnarkWorkerId};
 pub use crate::p2p::{P2pConnectionEvent, P2pEvent};
 pub use crate::rpc::{RpcId, RpcRequest};
 pub use crate::snark::SnarkEvent;
@@ -10,7 +11,8 @@ pub enum Event {
     P2p(P2pEvent),
     Snark(SnarkEvent),
     Rpc(RpcId, RpcRequest),
-    ExternalSnarkWorker(ExternalSnarkWorkerEvent),
+    ExternalSnarkWorker(ExternalSnarkWorkerId, ExternalSnarkWorkerEvent),
+    BlockProducer(BlockProducerEvent),
 }
 
 impl std::fmt::Display for Event {
@@ -21,7 +23,7 @@ impl std::fmt::Display for Event {
             Self::Rpc(id, req) => {
                 write!(f, "Rpc, {id}, ")?;
                 match req {
-                    RpcRequest::StateGet => write!(f, "StateGet"),
+                    RpcRequest::StateGet(..) => write!(f, "StateGet"),
                     RpcRequest::ActionStatsGet(query) => write!(f, "ActionStatsGet, {query:?}"),
                     RpcRequest::SyncStatsGet(query) => write!(f, "SyncStatsGet, {query:?}"),
                     RpcRequest::P2pConnectionOutgoing(opts) => {
@@ -43,12 +45,37 @@ impl std::fmt::Display for Event {
                     }
                     RpcRequest::SnarkerJobSpec { job_id } => write!(f, "SnarkerJobSpec, {job_id}"),
                     RpcRequest::SnarkerWorkers => write!(f, "SnarkerWorkers"),
+                    RpcRequest::SnarkerWorkGet => write!(f, "SnarkerWorkGet"),
+                    RpcRequest::SnarkerWorkSubmit(work) => {
+                        write!(f, "SnarkerWorkSubmit, {}", work.prover)
+                    }
+                    RpcRequest::TransactionNonceGet { public_key } => {
+                        write!(f, "TransactionNonceGet, {public_key}")
+                    }
+                    RpcRequest::TransactionSend(_) => write!(f, "TransactionSend"),
+                    RpcRequest::StakeGet(query) => {
+                        write!(f, "StakeGet, {:?}, {}", query.ledger, query.public_key)
+                    }
+                    RpcRequest::BestChainGet { max_length } => {
+                        write!(f, "BestChainGet, {max_length}")
+                    }
+                    RpcRequest::BlockGet(query) => write!(f, "BlockGet, {query:?}"),
+                    RpcRequest::AccountGet(query) => {
+                        write!(f, "AccountGet, {}", query.public_key)
+                    }
+                    RpcRequest::TransactionPoolGet => write!(f, "TransactionPoolGet"),
+                    RpcRequest::NodeStatusGet => write!(f, "NodeStatusGet"),
+                    RpcRequest::MetricsGet => write!(f, "MetricsGet"),
+                    RpcRequest::Subscribe(_) => write!(f, "Subscribe"),
+                    RpcRequest::PeersReputationGet => write!(f, "PeersReputationGet"),
+                    RpcRequest::PeerBan(query) => write!(f, "PeerBan, {}", query.peer_id),
+                    RpcRequest::PeerUnban { peer_id } => write!(f, "PeerUnban, {peer_id}"),
                     RpcRequest::HealthCheck => write!(f, "HealthCheck"),
                     RpcRequest::ReadinessCheck => write!(f, "ReadinessCheck"),
                 }
             }
-            Self::ExternalSnarkWorker(event) => {
-                write!(f, "ExternalSnarkWorker, ")?;
+            Self::ExternalSnarkWorker(worker_id, event) => {
+                write!(f, "ExternalSnarkWorker, {worker_id}, ")?;
 
                 match event {
                     ExternalSnarkWorkerEvent::Started => write!(f, "Started"),
@@ -59,6 +86,7 @@ impl std::fmt::Display for Event {
                     ExternalSnarkWorkerEvent::Error(_) => write!(f, "Error"),
                 }
             }
+            Self::BlockProducer(v) => v.fmt(f),
         }
     }
 }
@@ -1,4 +1,9 @@
 use crate::action::CheckTimeoutsAction;
+use crate::block_producer::{
+    BlockProducerBlockProveSuccessAction, BlockProducerEvent,
+    BlockProducerVrfEvaluationErrorAction, BlockProducerVrfEvaluationSuccessAction,
+    BlockProducerWonSlotDiscardAction, BlockProducerWonSlotDiscardReason,
+};
 use crate::external_snark_worker::{
     ExternalSnarkWorkerErrorAction, ExternalSnarkWorkerEvent, ExternalSnarkWorkerKilledAction,
     ExternalSnarkWorkerStartedAction, ExternalSnarkWorkerWorkCancelledAction,
@@ -10,6 +15,9 @@ use crate::p2p::channels::snark::{
     P2pChannelsSnarkLibp2pReceivedAction, P2pChannelsSnarkReadyAction,
 };
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentReadyAction;
+use crate::p2p::channels::transaction::{
+    P2pChannelsTransactionLibp2pReceivedAction, P2pChannelsTransactionReadyAction,
+};
 use crate::p2p::channels::{ChannelId, P2pChannelsMessageReceivedAction};
 use crate::p2p::connection::incoming::{
     P2pConnectionIncomingAnswerSdpCreateErrorAction,
@@ -26,14 +34,21 @@ use crate::p2p::connection::{P2pConnectionErrorResponse, P2pConnectionResponse};
 use crate::p2p::disconnection::{
     P2pDisconnectionFinishAction, P2pDisconnectionInitAction, P2pDisconnectionReason,
 };
-use crate::p2p::P2pChannelEvent;
+use crate::p2p::discovery::{
+    P2pDiscoveryKademliaAddRouteAction, P2pDiscoveryKademliaFailureAction,
+    P2pDiscoveryKademliaSuccessAction,
+};
+use crate::p2p::{P2pChannelEvent, P2pDiscoveryEvent};
 use crate::rpc::{
-    RpcActionStatsGetAction, RpcGlobalStateGetAction, RpcHealthCheckAction,
-    RpcP2pConnectionIncomingInitAction, RpcP2pConnectionOutgoingInitAction,
-    RpcReadinessCheckAction, RpcRequest, RpcScanStateSummaryGetAction,
-    RpcSnarkPoolAvailableJobsGetAction, RpcSnarkPoolJobGetAction, RpcSnarkerConfigGetAction,
-    RpcSnarkerJobCommitAction, RpcSnarkerJobSpecAction, RpcSnarkersWorkersGetAction,
-    RpcSyncStatsGetAction,
+    RpcAccountGetAction, RpcActionStatsGetAction, RpcBestChainGetAction, RpcBlockGetAction,
+    RpcGlobalStateGetAction, RpcHealthCheckAction, RpcMetricsGetAction, RpcNodeStatusGetAction,
+    RpcP2pConnectionIncomingInitAction, RpcP2pConnectionOutgoingInitAction, RpcPeerBanAction,
+    RpcPeerUnbanAction, RpcPeersReputationGetAction, RpcReadinessCheckAction, RpcRequest,
+    RpcScanStateSummaryGetAction, RpcSnarkPoolAvailableJobsGetAction, RpcSnarkPoolJobGetAction,
+    RpcSnarkerConfigGetAction, RpcSnarkerJobCommitAction, RpcSnarkerJobSpecAction,
+    RpcSnarkerWorkGetAction, RpcSnarkerWorkSubmitAction, RpcSnarkersWorkersGetAction,
+    RpcStakeGetAction, RpcSubscribeAction, RpcSyncStatsGetAction, RpcTransactionNonceGetAction,
+    RpcTransactionPoolGetAction, RpcTransactionSendAction,
 };
 use crate::snark::block_verify::{SnarkBlockVerifyErrorAction, SnarkBlockVerifySuccessAction};
 use crate::snark::work_verify::{SnarkWorkVerifyErrorAction, SnarkWorkVerifySuccessAction};
@@ -93,10 +108,20 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                     },
                     P2pConnectionEvent::AnswerReceived(peer_id, res) => match res {
                         P2pConnectionResponse::Accepted(answer) => {
-                            store.dispatch(P2pConnectionOutgoingAnswerRecvSuccessAction {
-                                peer_id,
-                                answer,
-                            });
+                            match store.state().p2p.outgoing_answer_check(peer_id, &answer) {
+                                Ok(()) => {
+                                    store.dispatch(P2pConnectionOutgoingAnswerRecvSuccessAction {
+                                        peer_id,
+                                        answer,
+                                    });
+                                }
+                                Err(reason) => {
+                                    store.dispatch(P2pConnectionOutgoingAnswerRecvErrorAction {
+                                        peer_id,
+                                        error: P2pConnectionErrorResponse::Rejected(reason),
+                                    });
+                                }
+                            }
                         }
                         P2pConnectionResponse::Rejected(reason) => {
                             store.dispatch(P2pConnectionOutgoingAnswerRecvErrorAction {
@@ -151,6 +176,10 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                                 store
                                     .dispatch(P2pChannelsSnarkJobCommitmentReadyAction { peer_id });
                             }
+                            ChannelId::TransactionPropagation => {
+                                // TODO(binier): maybe dispatch success and then ready.
+                                store.dispatch(P2pChannelsTransactionReadyAction { peer_id });
+                            }
                             ChannelId::Rpc => {
                                 // TODO(binier): maybe dispatch success and then ready.
                                 store.dispatch(P2pChannelsRpcReadyAction { peer_id });
@@ -179,11 +208,29 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                             nonce,
                         });
                     }
+                    P2pChannelEvent::Libp2pTransactionReceived(peer_id, transactions, nonce) => {
+                        store.dispatch(P2pChannelsTransactionLibp2pReceivedAction {
+                            peer_id,
+                            transactions,
+                            nonce,
+                        });
+                    }
                     P2pChannelEvent::Closed(peer_id, chan_id) => {
                         let reason = P2pDisconnectionReason::P2pChannelClosed(chan_id);
                         store.dispatch(P2pDisconnectionInitAction { peer_id, reason });
                     }
                 },
+                P2pEvent::Discovery(e) => match e {
+                    P2pDiscoveryEvent::AddRoute(peer_id, addresses) => {
+                        store.dispatch(P2pDiscoveryKademliaAddRouteAction { peer_id, addresses });
+                    }
+                    P2pDiscoveryEvent::DidFindPeers(peers) => {
+                        store.dispatch(P2pDiscoveryKademliaSuccessAction { peers });
+                    }
+                    P2pDiscoveryEvent::DidFindPeersError(description) => {
+                        store.dispatch(P2pDiscoveryKademliaFailureAction { description });
+                    }
+                },
             },
             Event::Snark(event) => match event {
                 SnarkEvent::BlockVerify(req_id, result) => match result {
@@ -204,8 +251,8 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 },
             },
             Event::Rpc(rpc_id, e) => match e {
-                RpcRequest::StateGet => {
-                    store.dispatch(RpcGlobalStateGetAction { rpc_id });
+                RpcRequest::StateGet(query) => {
+                    store.dispatch(RpcGlobalStateGetAction { rpc_id, query });
                 }
                 RpcRequest::ActionStatsGet(query) => {
                     store.dispatch(RpcActionStatsGetAction { rpc_id, query });
@@ -243,6 +290,54 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::SnarkerWorkers => {
                     store.dispatch(RpcSnarkersWorkersGetAction { rpc_id });
                 }
+                RpcRequest::SnarkerWorkGet => {
+                    store.dispatch(RpcSnarkerWorkGetAction { rpc_id });
+                }
+                RpcRequest::SnarkerWorkSubmit(work) => {
+                    store.dispatch(RpcSnarkerWorkSubmitAction { rpc_id, work });
+                }
+                RpcRequest::TransactionNonceGet { public_key } => {
+                    store.dispatch(RpcTransactionNonceGetAction { rpc_id, public_key });
+                }
+                RpcRequest::TransactionSend(transaction) => {
+                    store.dispatch(RpcTransactionSendAction {
+                        rpc_id,
+                        transaction,
+                    });
+                }
+                RpcRequest::StakeGet(query) => {
+                    store.dispatch(RpcStakeGetAction { rpc_id, query });
+                }
+                RpcRequest::BestChainGet { max_length } => {
+                    store.dispatch(RpcBestChainGetAction { rpc_id, max_length });
+                }
+                RpcRequest::BlockGet(query) => {
+                    store.dispatch(RpcBlockGetAction { rpc_id, query });
+                }
+                RpcRequest::AccountGet(query) => {
+                    store.dispatch(RpcAccountGetAction { rpc_id, query });
+                }
+                RpcRequest::TransactionPoolGet => {
+                    store.dispatch(RpcTransactionPoolGetAction { rpc_id });
+                }
+                RpcRequest::NodeStatusGet => {
+                    store.dispatch(RpcNodeStatusGetAction { rpc_id });
+                }
+                RpcRequest::MetricsGet => {
+                    store.dispatch(RpcMetricsGetAction { rpc_id });
+                }
+                RpcRequest::Subscribe(query) => {
+                    store.dispatch(RpcSubscribeAction { rpc_id, query });
+                }
+                RpcRequest::PeersReputationGet => {
+                    store.dispatch(RpcPeersReputationGetAction { rpc_id });
+                }
+                RpcRequest::PeerBan(query) => {
+                    store.dispatch(RpcPeerBanAction { rpc_id, query });
+                }
+                RpcRequest::PeerUnban { peer_id } => {
+                    store.dispatch(RpcPeerUnbanAction { rpc_id, peer_id });
+                }
                 RpcRequest::HealthCheck => {
                     store.dispatch(RpcHealthCheckAction { rpc_id });
                 }
@@ -250,29 +345,46 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                     store.dispatch(RpcReadinessCheckAction { rpc_id });
                 }
             },
-            Event::ExternalSnarkWorker(e) => match e {
+            Event::ExternalSnarkWorker(worker_id, e) => match e {
                 ExternalSnarkWorkerEvent::Started => {
-                    store.dispatch(ExternalSnarkWorkerStartedAction {});
+                    store.dispatch(ExternalSnarkWorkerStartedAction { worker_id });
                 }
                 ExternalSnarkWorkerEvent::Killed => {
-                    store.dispatch(ExternalSnarkWorkerKilledAction {});
+                    store.dispatch(ExternalSnarkWorkerKilledAction { worker_id });
                 }
                 ExternalSnarkWorkerEvent::WorkResult(result) => {
-                    store.dispatch(ExternalSnarkWorkerWorkResultAction { result });
+                    store.dispatch(ExternalSnarkWorkerWorkResultAction { worker_id, result });
                 }
                 ExternalSnarkWorkerEvent::WorkError(error) => {
-                    store.dispatch(ExternalSnarkWorkerWorkErrorAction { error });
+                    store.dispatch(ExternalSnarkWorkerWorkErrorAction { worker_id, error });
                 }
                 ExternalSnarkWorkerEvent::WorkCancelled => {
-                    store.dispatch(ExternalSnarkWorkerWorkCancelledAction {});
+                    store.dispatch(ExternalSnarkWorkerWorkCancelledAction { worker_id });
                 }
                 ExternalSnarkWorkerEvent::Error(error) => {
                     store.dispatch(ExternalSnarkWorkerErrorAction {
+                        worker_id,
                         error,
                         permanent: false,
                     });
                 }
             },
+            Event::BlockProducer(e) => match e {
+                BlockProducerEvent::VrfEvaluator(Ok(output)) => {
+                    store.dispatch(BlockProducerVrfEvaluationSuccessAction { output });
+                }
+                BlockProducerEvent::VrfEvaluator(Err(error)) => {
+                    store.dispatch(BlockProducerVrfEvaluationErrorAction { error });
+                }
+                BlockProducerEvent::BlockProve(block_hash, Ok(proof)) => {
+                    store.dispatch(BlockProducerBlockProveSuccessAction { block_hash, proof });
+                }
+                BlockProducerEvent::BlockProve(_, Err(error)) => {
+                    store.dispatch(BlockProducerWonSlotDiscardAction {
+                        reason: BlockProducerWonSlotDiscardReason::BlockProveError(error),
+                    });
+                }
+            },
         },
         EventSourceAction::WaitTimeout(_) => {
             store.dispatch(CheckTimeoutsAction {});
@@ -4,9 +4,12 @@ use openmina_core::snark::SnarkJobId;
 use redux::{EnablingCondition, Timestamp};
 use serde::{Deserialize, Serialize};
 
-use crate::{external_snark_worker::ExternalSnarkWorker, snark_pool::JobSummary, State};
+use crate::{snark_pool::JobSummary, State};
 
-use super::{ExternalSnarkWorkerError, ExternalSnarkWorkerState, ExternalSnarkWorkerWorkError};
+use super::{
+    ExternalSnarkWorker, ExternalSnarkWorkerError, ExternalSnarkWorkerId, ExternalSnarkWorkerState,
+    ExternalSnarkWorkerWorkError,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, derive_more::From)]
 pub enum ExternalSnarkWorkerAction {
@@ -29,44 +32,75 @@ pub enum ExternalSnarkWorkerAction {
     Error(ExternalSnarkWorkerErrorAction),
 }
 
+impl ExternalSnarkWorkerAction {
+    pub fn worker_id(&self) -> ExternalSnarkWorkerId {
+        match self {
+            Self::Start(a) => a.worker_id,
+            Self::Started(a) => a.worker_id,
+            Self::StartTimeout(a) => a.worker_id,
+            Self::Kill(a) => a.worker_id,
+            Self::Killed(a) => a.worker_id,
+            Self::SubmitWork(a) => a.worker_id,
+            Self::WorkResult(a) => a.worker_id,
+            Self::WorkError(a) => a.worker_id,
+            Self::WorkTimeout(a) => a.worker_id,
+            Self::CancelWork(a) => a.worker_id,
+            Self::WorkCancelled(a) => a.worker_id,
+            Self::PruneWork(a) => a.worker_id,
+            Self::Error(a) => a.worker_id,
+        }
+    }
+}
+
 pub type ExternalSnarkWorkerActionWithMeta = redux::ActionWithMeta<ExternalSnarkWorkerAction>;
 pub type ExternalSnarkWorkerActionWithMetaRef<'a> =
     redux::ActionWithMeta<&'a ExternalSnarkWorkerAction>;
 
+fn worker_state(state: &State, worker_id: ExternalSnarkWorkerId) -> Option<&ExternalSnarkWorker> {
+    state.external_snark_worker.get(worker_id)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkerStartAction {}
+pub struct ExternalSnarkWorkerStartAction {
+    pub worker_id: ExternalSnarkWorkerId,
+}
 
 impl EnablingCondition<State> for ExternalSnarkWorkerStartAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
         state.config.snarker.is_some()
-            && matches!(
-                state.external_snark_worker.0.state,
-                ExternalSnarkWorkerState::None
-            )
+            && worker_state(state, self.worker_id)
+                .map_or(false, |worker| worker.can_start(state.time()))
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkerStartedAction {}
+pub struct ExternalSnarkWorkerStartedAction {
+    pub worker_id: ExternalSnarkWorkerId,
+}
 
 impl EnablingCondition<State> for ExternalSnarkWorkerStartedAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        matches!(
-            state.external_snark_worker.0.state,
-            ExternalSnarkWorkerState::Starting
-        )
+        worker_state(state, self.worker_id).map_or(false, |worker| {
+            matches!(worker.state, ExternalSnarkWorkerState::Starting)
+        })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalSnarkWorkerStartTimeoutAction {
+    pub worker_id: ExternalSnarkWorkerId,
     pub now: Timestamp,
 }
 
 impl EnablingCondition<State> for ExternalSnarkWorkerStartTimeoutAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
         const TIMEOUT: Duration = Duration::from_secs(120);
-        let ExternalSnarkWorker { state, timestamp } = &state.external_snark_worker.0;
+        let Some(ExternalSnarkWorker {
+            state, timestamp, ..
+        }) = worker_state(state, self.worker_id)
+        else {
+            return false;
+        };
         matches!(state, ExternalSnarkWorkerState::Starting)
             && self
                 .now
@@ -76,118 +110,130 @@ impl EnablingCondition<State> for ExternalSnarkWorkerStartTimeoutAction {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkerKillAction {}
+pub struct ExternalSnarkWorkerKillAction {
+    pub worker_id: ExternalSnarkWorkerId,
+}
 
 impl EnablingCondition<State> for ExternalSnarkWorkerKillAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        match &state.external_snark_worker.0.state {
-            ExternalSnarkWorkerState::Error(_, false)
-            | ExternalSnarkWorkerState::None
-            | ExternalSnarkWorkerState::Killing => false,
+        worker_state(state, self.worker_id).map_or(false, |worker| match &worker.state {
+            ExternalSnarkWorkerState::None | ExternalSnarkWorkerState::Killing => false,
             _ => true,
-        }
+        })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkerKilledAction {}
+pub struct ExternalSnarkWorkerKilledAction {
+    pub worker_id: ExternalSnarkWorkerId,
+}
 
 impl EnablingCondition<State> for ExternalSnarkWorkerKilledAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        matches!(
-            state.external_snark_worker.0.state,
-            ExternalSnarkWorkerState::Killing
-        )
+        worker_state(state, self.worker_id).map_or(false, |worker| {
+            matches!(worker.state, ExternalSnarkWorkerState::Killing)
+        })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalSnarkWorkerErrorAction {
+    pub worker_id: ExternalSnarkWorkerId,
     pub error: ExternalSnarkWorkerError,
     pub permanent: bool,
 }
 
 impl EnablingCondition<State> for ExternalSnarkWorkerErrorAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        true
+        worker_state(state, self.worker_id).is_some()
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalSnarkWorkerSubmitWorkAction {
+    pub worker_id: ExternalSnarkWorkerId,
     pub job_id: SnarkJobId,
     pub summary: JobSummary,
 }
 
 impl EnablingCondition<State> for ExternalSnarkWorkerSubmitWorkAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        state.external_snark_worker.is_idle()
+        worker_state(state, self.worker_id).map_or(false, |worker| worker.is_idle())
+            && state
+                .external_snark_worker
+                .working_on(&self.job_id)
+                .is_none()
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkerCancelWorkAction {}
+pub struct ExternalSnarkWorkerCancelWorkAction {
+    pub worker_id: ExternalSnarkWorkerId,
+}
 
 impl EnablingCondition<State> for ExternalSnarkWorkerCancelWorkAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        matches!(
-            state.external_snark_worker.0.state,
-            ExternalSnarkWorkerState::Working(..)
-        )
+        worker_state(state, self.worker_id).map_or(false, |worker| {
+            matches!(worker.state, ExternalSnarkWorkerState::Working(..))
+        })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkerWorkCancelledAction {}
+pub struct ExternalSnarkWorkerWorkCancelledAction {
+    pub worker_id: ExternalSnarkWorkerId,
+}
 
 impl EnablingCondition<State> for ExternalSnarkWorkerWorkCancelledAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        matches!(
-            state.external_snark_worker.0.state,
-            ExternalSnarkWorkerState::Cancelling(_)
-        )
+        worker_state(state, self.worker_id).map_or(false, |worker| {
+            matches!(worker.state, ExternalSnarkWorkerState::Cancelling(_))
+        })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalSnarkWorkerWorkResultAction {
+    pub worker_id: ExternalSnarkWorkerId,
     pub result: super::SnarkWorkResult,
 }
 
 impl EnablingCondition<State> for ExternalSnarkWorkerWorkResultAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        matches!(
-            state.external_snark_worker.0.state,
-            ExternalSnarkWorkerState::Working(..)
-        )
+        worker_state(state, self.worker_id).map_or(false, |worker| {
+            matches!(worker.state, ExternalSnarkWorkerState::Working(..))
+        })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalSnarkWorkerWorkErrorAction {
+    pub worker_id: ExternalSnarkWorkerId,
     pub error: ExternalSnarkWorkerWorkError,
 }
 
 impl EnablingCondition<State> for ExternalSnarkWorkerWorkErrorAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        matches!(
-            state.external_snark_worker.0.state,
-            ExternalSnarkWorkerState::Working(..)
-        )
+        worker_state(state, self.worker_id).map_or(false, |worker| {
+            matches!(worker.state, ExternalSnarkWorkerState::Working(..))
+        })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalSnarkWorkerWorkTimeoutAction {
+    pub worker_id: ExternalSnarkWorkerId,
     pub now: Timestamp,
 }
 
 impl EnablingCondition<State> for ExternalSnarkWorkerWorkTimeoutAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        if let ExternalSnarkWorkerState::Working(_, summary) = &state.external_snark_worker.0.state
-        {
+        let Some(worker) = worker_state(state, self.worker_id) else {
+            return false;
+        };
+        if let ExternalSnarkWorkerState::Working(_, summary) = &worker.state {
             self.now
-                .checked_sub(state.external_snark_worker.0.timestamp)
+                .checked_sub(worker.timestamp)
                 .map_or(false, |d| d > summary.estimated_duration())
         } else {
             false
@@ -196,16 +242,20 @@ impl EnablingCondition<State> for ExternalSnarkWorkerWorkTimeoutAction {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkerPruneWorkAction {}
+pub struct ExternalSnarkWorkerPruneWorkAction {
+    pub worker_id: ExternalSnarkWorkerId,
+}
 
 impl EnablingCondition<State> for ExternalSnarkWorkerPruneWorkAction {
     fn is_enabled(&self, #[allow(unused_variables)] state: &State) -> bool {
-        matches!(
-            state.external_snark_worker.0.state,
-            ExternalSnarkWorkerState::WorkReady(..)
-                | ExternalSnarkWorkerState::WorkError(..)
-                | ExternalSnarkWorkerState::Cancelled(..)
-        )
+        worker_state(state, self.worker_id).map_or(false, |worker| {
+            matches!(
+                worker.state,
+                ExternalSnarkWorkerState::WorkReady(..)
+                    | ExternalSnarkWorkerState::WorkError(..)
+                    | ExternalSnarkWorkerState::Cancelled(..)
+            )
+        })
     }
 }
 
@@ -8,8 +8,8 @@ use crate::{
 use super::{
     available_job_to_snark_worker_spec, ExternalSnarkWorkerAction,
     ExternalSnarkWorkerActionWithMeta, ExternalSnarkWorkerCancelWorkAction,
-    ExternalSnarkWorkerErrorAction, ExternalSnarkWorkerKillAction,
-    ExternalSnarkWorkerWorkErrorAction,
+    ExternalSnarkWorkerError, ExternalSnarkWorkerErrorAction, ExternalSnarkWorkerKillAction,
+    ExternalSnarkWorkerKilledAction, ExternalSnarkWorkerWorkErrorAction,
 };
 
 pub fn external_snark_worker_effects<S: crate::Service>(
@@ -17,6 +17,7 @@ pub fn external_snark_worker_effects<S: crate::Service>(
     action: ExternalSnarkWorkerActionWithMeta,
 ) {
     let (action, _) = action.split();
+    let worker_id = action.worker_id();
     match action {
         ExternalSnarkWorkerAction::Start(_) => {
             let Some(config) = &store.state.get().config.snarker else {
@@ -24,8 +25,12 @@ pub fn external_snark_worker_effects<S: crate::Service>(
             };
             let public_key = config.public_key.clone().into();
             let fee = config.fee.clone();
-            if let Err(err) = store.service.start(&config.path, public_key, fee) {
+            if let Err(err) = store
+                .service
+                .start(worker_id, &config.path, public_key, fee)
+            {
                 store.dispatch(ExternalSnarkWorkerErrorAction {
+                    worker_id,
                     error: err,
                     permanent: true,
                 });
@@ -36,21 +41,29 @@ pub fn external_snark_worker_effects<S: crate::Service>(
         }
         ExternalSnarkWorkerAction::StartTimeout(_) => {
             store.dispatch(ExternalSnarkWorkerErrorAction {
+                worker_id,
                 error: super::ExternalSnarkWorkerError::StartTimeout,
                 permanent: true,
             });
         }
-        ExternalSnarkWorkerAction::Kill(_) => {
-            if let Err(err) = store.service().kill() {
+        ExternalSnarkWorkerAction::Kill(_) => match store.service().kill(worker_id) {
+            Ok(()) => {}
+            // worker is already gone, e.g. crashed.
+            Err(ExternalSnarkWorkerError::NotRunning) => {
+                store.dispatch(ExternalSnarkWorkerKilledAction { worker_id });
+            }
+            Err(err) => {
                 store.dispatch(ExternalSnarkWorkerErrorAction {
+                    worker_id,
                     error: err,
                     permanent: true,
                 });
             }
-        }
+        },
         ExternalSnarkWorkerAction::Killed(_) => {}
         ExternalSnarkWorkerAction::Error(_action) => {
-            store.dispatch(ExternalSnarkWorkerKillAction {});
+            // Killed worker will be restarted after a backoff.
+            store.dispatch(ExternalSnarkWorkerKillAction { worker_id });
         }
         ExternalSnarkWorkerAction::SubmitWork(action) => {
             let job_id = &action.job_id;
@@ -63,12 +76,18 @@ pub fn external_snark_worker_effects<S: crate::Service>(
             ) {
                 Ok(v) => v,
                 Err(err) => {
-                    store.dispatch(ExternalSnarkWorkerWorkErrorAction { error: err.into() });
+                    store.dispatch(ExternalSnarkWorkerWorkErrorAction {
+                        worker_id,
+                        error: err.into(),
+                    });
                     return;
                 }
             };
-            if let Err(err) = store.service().submit(input) {
-                store.dispatch(ExternalSnarkWorkerWorkErrorAction { error: err.into() });
+            if let Err(err) = store.service().submit(worker_id, input) {
+                store.dispatch(ExternalSnarkWorkerWorkErrorAction {
+                    worker_id,
+                    error: err.into(),
+                });
                 return;
             }
         }
@@ -86,17 +105,18 @@ pub fn external_snark_worker_effects<S: crate::Service>(
             let sender = store.state().p2p.config.identity_pub_key.peer_id();
             // Directly add snark to the snark pool as it's produced by us.
             store.dispatch(SnarkPoolWorkAddAction { snark, sender });
-            store.dispatch(ExternalSnarkWorkerPruneWorkAction {});
+            store.dispatch(ExternalSnarkWorkerPruneWorkAction { worker_id });
         }
         ExternalSnarkWorkerAction::WorkError(_) => {
-            store.dispatch(ExternalSnarkWorkerPruneWorkAction {});
+            store.dispatch(ExternalSnarkWorkerPruneWorkAction { worker_id });
         }
         ExternalSnarkWorkerAction::WorkTimeout(_) => {
-            store.dispatch(ExternalSnarkWorkerCancelWorkAction {});
+            store.dispatch(ExternalSnarkWorkerCancelWorkAction { worker_id });
         }
         ExternalSnarkWorkerAction::CancelWork(_) => {
-            if let Err(err) = store.service().cancel() {
+            if let Err(err) = store.service().cancel(worker_id) {
                 store.dispatch(ExternalSnarkWorkerErrorAction {
+                    worker_id,
                     error: err.into(),
                     permanent: true,
                 });
@@ -104,7 +124,7 @@ pub fn external_snark_worker_effects<S: crate::Service>(
             }
         }
         ExternalSnarkWorkerAction::WorkCancelled(_) => {
-            store.dispatch(ExternalSnarkWorkerPruneWorkAction {});
+            store.dispatch(ExternalSnarkWorkerPruneWorkAction { worker_id });
         }
         ExternalSnarkWorkerAction::PruneWork(_) => {
             store.dispatch(SnarkPoolAutoCreateCommitmentAction {});
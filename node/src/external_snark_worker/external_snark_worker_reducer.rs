@@ -5,7 +5,10 @@ use super::{
 
 impl ExternalSnarkWorkers {
     pub fn reducer(&mut self, action: ExternalSnarkWorkerActionWithMetaRef<'_>) {
-        self.0.reducer(action)
+        let Some(worker) = self.get_mut(action.action().worker_id()) else {
+            return;
+        };
+        worker.reducer(action)
     }
 }
 
@@ -30,6 +33,7 @@ impl ExternalSnarkWorker {
             }
             ExternalSnarkWorkerAction::Error(a) => {
                 self.state = ExternalSnarkWorkerState::Error(a.error.clone(), a.permanent);
+                self.failures = self.failures.saturating_add(1);
             }
             ExternalSnarkWorkerAction::SubmitWork(action) => {
                 self.state = ExternalSnarkWorkerState::Working(
@@ -43,6 +47,7 @@ impl ExternalSnarkWorker {
                 };
                 self.state =
                     ExternalSnarkWorkerState::WorkReady(job_id.clone(), action.result.clone());
+                self.failures = 0;
             }
             ExternalSnarkWorkerAction::WorkError(action) => {
                 let ExternalSnarkWorkerState::Working(job_id, _) = &self.state else {
@@ -1,17 +1,29 @@
+use std::time::Duration;
+
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use crate::snark_pool::JobSummary;
 
-use super::{ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkId, SnarkWorkResult};
+use super::{
+    ExternalSnarkWorkerError, ExternalSnarkWorkerId, ExternalSnarkWorkerWorkError, SnarkWorkId,
+    SnarkWorkResult,
+};
+
+/// Delay before restarting the worker after the first failure. Doubles
+/// with each consecutive failure, up to [`RESTART_BACKOFF_MAX`].
+const RESTART_BACKOFF_MIN: Duration = Duration::from_secs(1);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(5 * 60);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkers(pub(crate) ExternalSnarkWorker);
+pub struct ExternalSnarkWorkers(pub(crate) Vec<ExternalSnarkWorker>);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalSnarkWorker {
     pub(crate) state: ExternalSnarkWorkerState,
     pub(crate) timestamp: Timestamp,
+    /// Number of failures since the last successfully produced work.
+    pub(crate) failures: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,36 +44,133 @@ pub enum ExternalSnarkWorkerState {
 }
 
 impl ExternalSnarkWorkers {
-    pub fn new(now: Timestamp) -> Self {
-        ExternalSnarkWorkers(ExternalSnarkWorker {
-            state: ExternalSnarkWorkerState::None,
-            timestamp: now,
-        })
+    pub fn new(now: Timestamp, workers: usize) -> Self {
+        ExternalSnarkWorkers(
+            (0..workers)
+                .map(|_| ExternalSnarkWorker {
+                    state: ExternalSnarkWorkerState::None,
+                    timestamp: now,
+                    failures: 0,
+                })
+                .collect(),
+        )
     }
 
-    pub fn is_idle(&self) -> bool {
-        match self.0.state {
-            ExternalSnarkWorkerState::Idle => true,
-            _ => false,
-        }
+    pub fn get(&self, id: ExternalSnarkWorkerId) -> Option<&ExternalSnarkWorker> {
+        self.0.get(id)
+    }
+
+    pub(crate) fn get_mut(
+        &mut self,
+        id: ExternalSnarkWorkerId,
+    ) -> Option<&mut ExternalSnarkWorker> {
+        self.0.get_mut(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ExternalSnarkWorkerId, &ExternalSnarkWorker)> {
+        self.0.iter().enumerate()
+    }
+
+    pub fn ids(&self) -> std::ops::Range<ExternalSnarkWorkerId> {
+        0..self.0.len()
     }
 
     pub fn has_idle(&self) -> bool {
         self.available() > 0
     }
 
+    /// Number of workers, which are ready to accept a job.
     pub fn available(&self) -> usize {
-        if matches!(self.0.state, ExternalSnarkWorkerState::Idle) {
-            1
-        } else {
-            0
-        }
+        self.0.iter().filter(|worker| worker.is_idle()).count()
+    }
+
+    pub fn first_idle(&self) -> Option<ExternalSnarkWorkerId> {
+        self.iter()
+            .find(|(_, worker)| worker.is_idle())
+            .map(|(id, _)| id)
+    }
+
+    pub fn working_job_ids(&self) -> impl Iterator<Item = (ExternalSnarkWorkerId, &SnarkWorkId)> {
+        self.iter()
+            .filter_map(|(id, worker)| Some((id, worker.working_job_id()?)))
+    }
+
+    /// Worker working on the job with the given id.
+    pub fn working_on(&self, job_id: &SnarkWorkId) -> Option<ExternalSnarkWorkerId> {
+        self.working_job_ids()
+            .find(|(_, id)| *id == job_id)
+            .map(|(worker_id, _)| worker_id)
+    }
+}
+
+impl ExternalSnarkWorker {
+    pub fn state(&self) -> &ExternalSnarkWorkerState {
+        &self.state
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    pub fn is_idle(&self) -> bool {
+        matches!(self.state, ExternalSnarkWorkerState::Idle)
     }
 
     pub fn working_job_id(&self) -> Option<&SnarkWorkId> {
-        match &self.0.state {
+        match &self.state {
             ExternalSnarkWorkerState::Working(job_id, _) => Some(job_id),
             _ => None,
         }
     }
+
+    /// Delay after the last state change, before the worker can be
+    /// (re)started.
+    pub fn restart_backoff(&self) -> Duration {
+        match self.failures {
+            0 => Duration::ZERO,
+            n => RESTART_BACKOFF_MIN
+                .saturating_mul(1 << (n - 1).min(16))
+                .min(RESTART_BACKOFF_MAX),
+        }
+    }
+
+    pub fn can_start(&self, now: Timestamp) -> bool {
+        matches!(self.state, ExternalSnarkWorkerState::None)
+            && now
+                .checked_sub(self.timestamp)
+                .map_or(self.failures == 0, |d| d >= self.restart_backoff())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use redux::Timestamp;
+
+    use super::{ExternalSnarkWorkers, RESTART_BACKOFF_MAX};
+
+    #[test]
+    fn restart_backoff() {
+        let now = Timestamp::new(0);
+        let mut workers = ExternalSnarkWorkers::new(now, 2);
+        assert_eq!(workers.available(), 0);
+
+        let worker = workers.get_mut(1).unwrap();
+        assert!(worker.can_start(now));
+
+        worker.failures = 1;
+        assert!(!worker.can_start(now));
+        assert!(worker.can_start(Timestamp::new(1_000_000_000)));
+
+        worker.failures = 3;
+        assert_eq!(worker.restart_backoff(), Duration::from_secs(4));
+
+        worker.failures = 100;
+        assert_eq!(worker.restart_backoff(), RESTART_BACKOFF_MAX);
+    }
 }
@@ -8,6 +8,9 @@ use openmina_core::snark::SnarkJobId;
 
 pub type SnarkWorkId = SnarkJobId;
 
+/// Index of the snark worker in the node's pool of workers.
+pub type ExternalSnarkWorkerId = usize;
+
 /// TODO use more slim type `OneOrTwo`...
 pub type SnarkWorkSpec = SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances;
 
@@ -0,0 +1,343 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::Path,
+    sync::{Arc, Condvar, Mutex},
+};
+
+use ledger::{
+    ondisk::Database,
+    scan_state::{pending_coinbase::PendingCoinbase, scan_state::ScanState},
+    Account, AccountIndex, Address, BaseLedger, Mask,
+};
+use mina_hasher::Fp;
+use mina_p2p_messages::{
+    binprot::{BinProtRead, BinProtWrite},
+    v2::{LedgerHash, MinaStateProtocolStateValueStableV2, StateHash},
+};
+use openmina_core::block::{ArcBlockWithHash, Block, BlockWithHash};
+
+use crate::p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
+
+use super::{ledger_service::staged_ledger_aux_and_pending_coinbases, LEDGER_DEPTH};
+
+const ROOT_SNARKED_LEDGER_HASH_KEY: &[u8] = b"root_snarked_ledger_hash";
+/// Number of accounts of the root snarked ledger, which are stored
+/// under separate keys (see `root_account_key`).
+const ROOT_SNARKED_LEDGER_LEN_KEY: &[u8] = b"root_snarked_ledger_len";
+const ROOT_STAGED_LEDGER_KEY: &[u8] = b"root_staged_ledger";
+const BEST_CHAIN_KEY: &[u8] = b"best_chain";
+const EPOCH_LEDGERS_KEY: &[u8] = b"epoch_ledgers";
+
+/// On-disk storage of the transition frontier root ledgers, epoch
+/// ledgers and the best chain, so that node doesn't need to resync
+/// from scratch after a restart.
+pub struct LedgerPersistence {
+    db: Database,
+    root_snarked_ledger_hash: Option<LedgerHash>,
+    root_snarked_ledger_len: usize,
+    /// Hashes of the persisted root snarked ledger accounts, so that only
+    /// the changed accounts are written. Unknown after opening, so the
+    /// first save writes all of them.
+    root_snarked_ledger_accounts: Vec<Fp>,
+    epoch_ledgers: Vec<LedgerHash>,
+    best_chain: Vec<StateHash>,
+}
+
+/// Transition frontier to be saved by [`LedgerPersistenceWorker`].
+pub struct LedgerPersistenceRequest {
+    /// Root ledgers, passed only if the root changed since the last
+    /// request.
+    pub root: Option<LedgerPersistenceRoot>,
+    /// Staking and next epoch ledgers of the best tip. Their accounts
+    /// are only read and written if they weren't saved before.
+    pub epoch_ledgers: Vec<(LedgerHash, Mask)>,
+    pub best_chain: Vec<ArcBlockWithHash>,
+}
+
+/// Root ledgers of the transition frontier. Converting them into what
+/// gets stored is left to the worker thread.
+pub struct LedgerPersistenceRoot {
+    /// Only the accounts which changed since the last save are written.
+    pub snarked_ledger: (LedgerHash, Mask),
+    pub staged_ledger_hash: LedgerHash,
+    pub scan_state: ScanState,
+    pub pending_coinbase: PendingCoinbase,
+    pub needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+}
+
+/// Saves the transition frontier on a separate thread, so that encoding
+/// and writing it doesn't block the state machine. If the thread falls
+/// behind, only the latest request is saved.
+///
+/// Dropping the worker waits for the pending request to be saved.
+pub struct LedgerPersistenceWorker {
+    shared: Arc<(Mutex<WorkerQueue>, Condvar)>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct WorkerQueue {
+    pending: Option<LedgerPersistenceRequest>,
+    closed: bool,
+}
+
+/// Data from which the transition frontier can be restored.
+pub struct LedgerPersistenceData {
+    pub root_snarked_ledger_hash: LedgerHash,
+    pub root_snarked_ledger: Vec<Account>,
+    pub root_staged_ledger: StagedLedgerAuxAndPendingCoinbases,
+    pub epoch_ledgers: Vec<(LedgerHash, Vec<Account>)>,
+    pub best_chain: Vec<ArcBlockWithHash>,
+}
+
+impl LedgerPersistence {
+    pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let mut db = Database::create(dir)?;
+        let root_snarked_ledger_hash = get(&mut db, ROOT_SNARKED_LEDGER_HASH_KEY)?;
+        let root_snarked_ledger_len =
+            get::<u64>(&mut db, ROOT_SNARKED_LEDGER_LEN_KEY)?.unwrap_or_default() as usize;
+        let epoch_ledgers = get(&mut db, EPOCH_LEDGERS_KEY)?.unwrap_or_default();
+        let best_chain = get(&mut db, BEST_CHAIN_KEY)?.unwrap_or_default();
+        Ok(Self {
+            db,
+            root_snarked_ledger_hash,
+            root_snarked_ledger_len,
+            root_snarked_ledger_accounts: vec![],
+            epoch_ledgers,
+            best_chain,
+        })
+    }
+
+    pub fn root_snarked_ledger_hash(&self) -> Option<&LedgerHash> {
+        self.root_snarked_ledger_hash.as_ref()
+    }
+
+    /// Loads persisted data. Returns `None` if nothing was persisted yet.
+    pub fn load(&mut self) -> io::Result<Option<LedgerPersistenceData>> {
+        let Some(root_snarked_ledger_hash) = self.root_snarked_ledger_hash.clone() else {
+            return Ok(None);
+        };
+        if self.best_chain.is_empty() {
+            return Ok(None);
+        }
+        let root_snarked_ledger = (0..self.root_snarked_ledger_len)
+            .map(|index| {
+                get(&mut self.db, &root_account_key(index))?
+                    .ok_or_else(|| missing("root snarked ledger account"))
+            })
+            .collect::<io::Result<_>>()?;
+        let root_staged_ledger = get(&mut self.db, ROOT_STAGED_LEDGER_KEY)?
+            .ok_or_else(|| missing("root staged ledger"))?;
+        let epoch_ledgers = self
+            .epoch_ledgers
+            .iter()
+            .map(|hash| {
+                let accounts = get(&mut self.db, &epoch_ledger_key(hash))?
+                    .ok_or_else(|| missing("epoch ledger"))?;
+                Ok((hash.clone(), accounts))
+            })
+            .collect::<io::Result<_>>()?;
+        let best_chain = self
+            .best_chain
+            .iter()
+            .map(|hash| {
+                let block: Block =
+                    get(&mut self.db, &block_key(hash))?.ok_or_else(|| missing("block"))?;
+                Ok(BlockWithHash::new(Arc::new(block)))
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(Some(LedgerPersistenceData {
+            root_snarked_ledger_hash,
+            root_snarked_ledger,
+            root_staged_ledger,
+            epoch_ledgers,
+            best_chain,
+        }))
+    }
+
+    /// Persists the transition frontier. Accounts of the root snarked
+    /// ledger are only written if they changed since the last save, those
+    /// of the epoch ledgers if they weren't saved yet. Previously saved
+    /// epoch ledgers which aren't in the request are removed.
+    fn save(&mut self, req: LedgerPersistenceRequest) -> io::Result<()> {
+        let mut to_set = vec![];
+        let mut to_remove = vec![];
+        let mut ledgers_changed = false;
+
+        let mut root_snarked_ledger = None;
+        if let Some(root) = req.root {
+            let root_staged_ledger = staged_ledger_aux_and_pending_coinbases(
+                root.staged_ledger_hash,
+                &root.scan_state,
+                &root.pending_coinbase,
+                &root.needed_protocol_states,
+            )
+            .ok_or_else(|| missing("root staged ledger needed protocol states"))?;
+            to_set.push((ROOT_STAGED_LEDGER_KEY.into(), encode(&root_staged_ledger)?));
+
+            let (hash, mut mask) = root.snarked_ledger;
+            if self.root_snarked_ledger_hash.as_ref() != Some(&hash) {
+                let len = mask.num_accounts();
+                let mut accounts = Vec::with_capacity(len);
+                for index in 0..len {
+                    let account_index = AccountIndex(index as u64);
+                    let account_hash = mask
+                        .get_inner_hash_at_addr(Address::from_index(account_index, LEDGER_DEPTH))
+                        .map_err(|_| missing("root snarked ledger account"))?;
+                    if self.root_snarked_ledger_accounts.get(index) != Some(&account_hash) {
+                        let account = mask
+                            .get_at_index(account_index)
+                            .ok_or_else(|| missing("root snarked ledger account"))?;
+                        to_set.push((root_account_key(index), encode(account.as_ref())?));
+                    }
+                    accounts.push(account_hash);
+                }
+                for index in len..self.root_snarked_ledger_len {
+                    to_remove.push(root_account_key(index));
+                }
+                to_set.push((ROOT_SNARKED_LEDGER_HASH_KEY.into(), encode(&hash)?));
+                to_set.push((ROOT_SNARKED_LEDGER_LEN_KEY.into(), encode(&(len as u64))?));
+                ledgers_changed = true;
+                root_snarked_ledger = Some((hash, accounts));
+            }
+        }
+
+        let mut epoch_ledgers = Vec::with_capacity(req.epoch_ledgers.len());
+        for (hash, mask) in req.epoch_ledgers {
+            if !self.epoch_ledgers.contains(&hash) {
+                to_set.push((epoch_ledger_key(&hash), encode(&mask.to_list())?));
+                ledgers_changed = true;
+            }
+            epoch_ledgers.push(hash);
+        }
+        for hash in &self.epoch_ledgers {
+            if !epoch_ledgers.contains(hash) {
+                to_remove.push(epoch_ledger_key(hash));
+                ledgers_changed = true;
+            }
+        }
+        to_set.push((EPOCH_LEDGERS_KEY.into(), encode(&epoch_ledgers)?));
+
+        let best_chain = req.best_chain;
+        for block in &best_chain {
+            if !self.best_chain.contains(&block.hash) {
+                to_set.push((block_key(&block.hash), encode(block.block.as_ref())?));
+            }
+        }
+        for hash in &self.best_chain {
+            if !best_chain.iter().any(|b| &b.hash == hash) {
+                to_remove.push(block_key(hash));
+            }
+        }
+        let best_chain = best_chain.iter().map(|b| b.hash.clone()).collect();
+        to_set.push((BEST_CHAIN_KEY.into(), encode(&best_chain)?));
+
+        self.db.set_batch(to_set, to_remove)?;
+
+        self.best_chain = best_chain;
+        self.epoch_ledgers = epoch_ledgers;
+        if let Some((hash, accounts)) = root_snarked_ledger {
+            self.root_snarked_ledger_hash = Some(hash);
+            self.root_snarked_ledger_len = accounts.len();
+            self.root_snarked_ledger_accounts = accounts;
+        }
+        // Previous ledgers take up most of the space, so reclaim it.
+        if ledgers_changed {
+            self.db.gc()?;
+        }
+        Ok(())
+    }
+}
+
+impl LedgerPersistenceWorker {
+    pub fn spawn(mut persistence: LedgerPersistence) -> Self {
+        let shared = Arc::new((Mutex::new(WorkerQueue::default()), Condvar::new()));
+        let worker_shared = shared.clone();
+        let thread = std::thread::Builder::new()
+            .name("openmina_ledger_persistence".to_owned())
+            .spawn(move || {
+                let (queue, cvar) = &*worker_shared;
+                loop {
+                    let req = {
+                        let mut queue = queue.lock().expect("poisoned");
+                        loop {
+                            if let Some(req) = queue.pending.take() {
+                                break req;
+                            }
+                            if queue.closed {
+                                return;
+                            }
+                            queue = cvar.wait(queue).expect("poisoned");
+                        }
+                    };
+                    if let Err(err) = persistence.save(req) {
+                        openmina_core::log::warn!(openmina_core::log::system_time(); kind = "TransitionFrontierPersist", summary = format!("failed to persist transition frontier: {err}"));
+                    }
+                }
+            })
+            .expect("failed to spawn ledger persistence thread");
+        Self {
+            shared,
+            thread: Some(thread),
+        }
+    }
+
+    /// Replaces the request waiting to be saved, if any. Root ledgers of
+    /// the replaced request are kept if the new one doesn't have them.
+    pub fn save(&self, mut req: LedgerPersistenceRequest) {
+        let (queue, cvar) = &*self.shared;
+        let mut queue = queue.lock().expect("poisoned");
+        if req.root.is_none() {
+            req.root = queue.pending.take().and_then(|pending| pending.root);
+        }
+        queue.pending = Some(req);
+        drop(queue);
+        cvar.notify_one();
+    }
+}
+
+impl Drop for LedgerPersistenceWorker {
+    fn drop(&mut self) {
+        let (queue, cvar) = &*self.shared;
+        if let Ok(mut queue) = queue.lock() {
+            queue.closed = true;
+        }
+        cvar.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn block_key(hash: &StateHash) -> Box<[u8]> {
+    format!("block/{hash}").into_bytes().into()
+}
+
+fn root_account_key(index: usize) -> Box<[u8]> {
+    format!("root_snarked_ledger/{index}").into_bytes().into()
+}
+
+fn epoch_ledger_key(hash: &LedgerHash) -> Box<[u8]> {
+    format!("epoch_ledger/{hash}").into_bytes().into()
+}
+
+fn missing(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("persisted {what} missing"))
+}
+
+fn encode<T: BinProtWrite>(value: &T) -> io::Result<Box<[u8]>> {
+    let mut buf = vec![];
+    value.binprot_write(&mut buf)?;
+    Ok(buf.into())
+}
+
+fn get<T: BinProtRead>(db: &mut Database, key: &[u8]) -> io::Result<Option<T>> {
+    let Some(bytes) = db.get(key)? else {
+        return Ok(None);
+    };
+    T::binprot_read(&mut bytes.as_ref())
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("{err:?}")))
+}
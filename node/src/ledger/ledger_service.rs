@@ -1,76 +1,126 @@
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     path::Path,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use ledger::{
     scan_state::{
-        currency::{Amount, Fee, Slot},
+        currency::Slot,
+        pending_coinbase::PendingCoinbase,
         scan_state::{
+            transaction_snark::{work, OneOrTwo},
             AvailableJobMessage, ConstraintConstants, JobValueBase, JobValueMerge,
-            JobValueWithIndex,
+            JobValueWithIndex, ScanState,
+        },
+        transaction_logic::{
+            local_state::LocalState,
+            protocol_state::protocol_state_view,
+            signed_command::{self, SignedCommand},
+            verifiable, TransactionStatus, WithStatus,
         },
-        transaction_logic::{local_state::LocalState, protocol_state::protocol_state_view},
     },
+    sparse_ledger::SparseLedger,
     staged_ledger::{
         diff::Diff,
         staged_ledger::{SkipVerification, StagedLedger},
+        validate_block::block_body_hash,
     },
-    verifier::Verifier,
-    AccountIndex, BaseLedger, Mask, TreeVersion,
+    verifier::{common::CheckResult, Verifier},
+    AccountId, AccountIndex, BaseLedger, Mask, TokenId, TreeVersion,
 };
 use mina_hasher::Fp;
 use mina_p2p_messages::v2::{
-    DataHashLibStateHashStableV1, LedgerHash, MinaBaseAccountBinableArgStableV2,
-    MinaBaseLedgerHash0StableV1, MinaBaseSokMessageStableV1, MinaBaseStagedLedgerHashStableV1,
-    MinaLedgerSyncLedgerAnswerStableV2, MinaLedgerSyncLedgerQueryStableV1,
-    MinaStateBlockchainStateValueStableV2LedgerProofStatement, MinaStateProtocolStateValueStableV2,
-    MinaTransactionTransactionStableV2, StateHash,
+    Blake2MakeStableV1, ConsensusBodyReferenceStableV1, DataHashLibStateHashStableV1, LedgerHash,
+    MinaBaseAccountBinableArgStableV2, MinaBaseLedgerHash0StableV1, MinaBaseSokMessageStableV1,
+    MinaBaseSparseLedgerBaseStableV2, MinaBaseStagedLedgerHashStableV1,
+    MinaBaseUserCommandStableV2, MinaLedgerSyncLedgerAnswerStableV2,
+    MinaLedgerSyncLedgerQueryStableV1, MinaStateBlockchainStateValueStableV2LedgerProofStatement,
+    MinaStateProtocolStateValueStableV2, MinaTransactionTransactionStableV2,
+    StagedLedgerDiffDiffStableV2, StateHash, TransactionSnarkWorkTStableV2,
 };
 use mina_signer::CompressedPubKey;
-use openmina_core::{block::ArcBlockWithHash, snark::SnarkJobId};
+use openmina_core::{
+    block::ArcBlockWithHash,
+    snark::{Snark, SnarkJobId},
+};
 
+use crate::account::AccountPublicKey;
+use crate::block_producer::{
+    BlockProducerDelegator, BlockProducerLedgerService, BlockProducerStagedLedgerDiffCreateOutput,
+};
+use crate::transaction_pool::{
+    TransactionPoolAccount, TransactionPoolService, TransactionPoolValidateError,
+};
 use crate::transition_frontier::sync::ledger::staged::StagedLedgerAuxAndPendingCoinbasesValid;
 use crate::transition_frontier::sync::ledger::staged::TransitionFrontierSyncLedgerStagedService;
+use crate::transition_frontier::sync::ledger::SyncLedgerTargetKind;
 use crate::transition_frontier::TransitionFrontierService;
 use crate::{
     p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases, transition_frontier::CommitResult,
 };
 use crate::{
     rpc::{
-        RpcLedgerService, RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryScanStateJob,
-        RpcScanStateSummaryScanStateJobKind, RpcSnarkPoolJobSnarkWorkDone,
+        RpcAccountGetQuery, RpcLedgerService, RpcScanStateSummaryBlockTransaction,
+        RpcScanStateSummaryScanStateJob, RpcScanStateSummaryScanStateJobKind,
+        RpcSnarkPoolJobSnarkWorkDone, RpcStake, RpcStakeDelegator, RpcStakeGetResponse,
     },
     transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedService,
 };
 
-use super::{ledger_empty_hash_at_depth, LedgerAddress, LEDGER_DEPTH};
-
-const CONSTRAINT_CONSTANTS: ConstraintConstants = ConstraintConstants {
-    sub_windows_per_window: 11,
-    ledger_depth: 35,
-    work_delay: 2,
-    block_window_duration_ms: 180000,
-    transaction_capacity_log_2: 7,
-    pending_coinbase_depth: 5,
-    coinbase_amount: Amount::from_u64(720000000000),
-    supercharged_coinbase_factor: 2,
-    account_creation_fee: Fee::from_u64(1000000000),
-    fork: None,
+use super::{
+    ledger_empty_hash_at_depth, LedgerAddress, LedgerPersistence, LedgerPersistenceRequest,
+    LedgerPersistenceRoot, LedgerPersistenceWorker, LEDGER_DEPTH,
 };
+use crate::{ProofLevel, BERKELEY_CONSTRAINT_CONSTANTS};
 
 fn ledger_hash(depth: usize, left: Fp, right: Fp) -> Fp {
     let height = LEDGER_DEPTH - depth - 1;
     ledger::V2::hash_node(height, left, right)
 }
 
-#[derive(Default)]
 pub struct LedgerCtx {
+    constraint_constants: ConstraintConstants,
+    /// Verifies ledger proofs of the reconstructed scan states.
+    verifier: Verifier,
     snarked_ledgers: BTreeMap<LedgerHash, Mask>,
     additional_snarked_ledgers: BTreeMap<LedgerHash, Mask>,
     staged_ledgers: BTreeMap<LedgerHash, StagedLedger>,
     sync: LedgerSyncState,
+    persistence: Option<LedgerPersistence>,
+    /// Started on the first [`LedgerService::persist`], after the
+    /// transition frontier had a chance to be restored.
+    persistence_worker: Option<LedgerPersistenceWorker>,
+    /// Root block of the last persisted transition frontier, root
+    /// ledgers are only passed to the worker when it changes.
+    persisted_root: Option<StateHash>,
+    /// Epoch ledgers served to peers, as building them is expensive.
+    /// They are built on a separate thread, see [`EpochLedgersCache`].
+    epoch_ledgers_cache: Arc<Mutex<EpochLedgersCache>>,
+    /// Delegators in the epoch ledgers, indexed on the first stake
+    /// lookup. At most [`EPOCH_LEDGERS_CACHE_SIZE`] are kept, oldest
+    /// ones are evicted first.
+    epoch_ledgers_delegators: VecDeque<(LedgerHash, EpochLedgerDelegators)>,
+}
+
+/// Epoch ledgers in the order they were requested in, `None` while the
+/// ledger is being built. At most [`EPOCH_LEDGERS_CACHE_SIZE`] are kept,
+/// oldest ones are evicted first.
+type EpochLedgersCache = VecDeque<(LedgerHash, Option<Arc<MinaBaseSparseLedgerBaseStableV2>>)>;
+
+/// Enough for the staking and next epoch ledgers.
+const EPOCH_LEDGERS_CACHE_SIZE: usize = 2;
+
+/// Default token accounts of an epoch ledger along with their indexes,
+/// grouped by their delegate.
+type EpochLedgerDelegators = BTreeMap<CompressedPubKey, Vec<(AccountIndex, RpcStakeDelegator)>>;
+
+/// Transition frontier restored from the persisted data.
+pub struct PersistedTransitionFrontier {
+    pub best_chain: Vec<ArcBlockWithHash>,
+    pub needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    /// Epoch ledgers of the best tip which are present after restoring.
+    pub epoch_ledgers: BTreeSet<LedgerHash>,
 }
 
 #[derive(Default)]
@@ -79,6 +129,24 @@ struct LedgerSyncState {
     staged_ledgers: BTreeMap<LedgerHash, StagedLedger>,
 }
 
+impl Default for LedgerCtx {
+    fn default() -> Self {
+        Self {
+            constraint_constants: BERKELEY_CONSTRAINT_CONSTANTS,
+            verifier: Verifier::new(),
+            snarked_ledgers: Default::default(),
+            additional_snarked_ledgers: Default::default(),
+            staged_ledgers: Default::default(),
+            sync: Default::default(),
+            persistence: None,
+            persistence_worker: None,
+            persisted_root: None,
+            epoch_ledgers_cache: Default::default(),
+            epoch_ledgers_delegators: Default::default(),
+        }
+    }
+}
+
 impl LedgerCtx {
     pub fn new_with_additional_snarked_ledgers<P>(path: P) -> Self
     where
@@ -117,6 +185,169 @@ impl LedgerCtx {
         }
     }
 
+    pub fn with_constraint_constants(mut self, constraint_constants: ConstraintConstants) -> Self {
+        self.constraint_constants = constraint_constants;
+        self
+    }
+
+    /// Ledger proofs are only fully verified with [`ProofLevel::Full`],
+    /// otherwise only their sok digests are checked.
+    pub fn with_proof_level(mut self, proof_level: ProofLevel) -> Self {
+        self.verifier = if proof_level.is_full() {
+            Verifier::new()
+        } else {
+            Verifier::digest_only()
+        };
+        self
+    }
+
+    /// Make genesis ledger available for syncing and for serving it
+    /// to peers.
+    pub fn with_genesis_ledger(mut self, accounts: Vec<ledger::Account>) -> Self {
+        let mut mask = Mask::new_root(ledger::Database::create(LEDGER_DEPTH as u8));
+        for account in accounts {
+            let account_id = account.id();
+            mask.get_or_create_account(account_id, account).unwrap();
+        }
+        let hash = MinaBaseLedgerHash0StableV1(mask.merkle_root().into()).into();
+        self.additional_snarked_ledgers.insert(hash, mask);
+        self
+    }
+
+    /// Make ledgers of the locally created genesis block available, so
+    /// that blocks can be applied on top of it. Genesis ledger must be
+    /// added beforehand with [`Self::with_genesis_ledger`].
+    pub fn with_genesis_block(mut self, block: &ArcBlockWithHash) -> Result<Self, String> {
+        let mask = self
+            .additional_snarked_ledgers
+            .get(block.snarked_ledger_hash())
+            .ok_or("genesis ledger missing")?
+            .copy();
+        let mut staged_ledger =
+            StagedLedger::create_exn(self.constraint_constants.clone(), mask.copy())?;
+        let ledger_hashes = MinaBaseStagedLedgerHashStableV1::from(&staged_ledger.hash());
+        if &ledger_hashes != block.staged_ledger_hashes() {
+            return Err("genesis staged ledger hash mismatch".to_owned());
+        }
+
+        self.snarked_ledgers
+            .insert(block.snarked_ledger_hash().clone(), mask);
+        self.staged_ledgers
+            .insert(block.staged_ledger_hash().clone(), staged_ledger);
+        Ok(self)
+    }
+
+    /// Persist transition frontier root ledgers and the best chain in
+    /// the given directory, so that they can be restored after restart.
+    pub fn with_persistence<P>(mut self, dir: P) -> std::io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        self.persistence = Some(LedgerPersistence::open(dir)?);
+        Ok(self)
+    }
+
+    /// Reconstructs root ledgers from the persisted data and reapplies
+    /// blocks of the persisted best chain on top of them.
+    ///
+    /// Returns `Ok(None)` if persistence is disabled or nothing was
+    /// persisted yet.
+    pub fn restore_transition_frontier(
+        &mut self,
+    ) -> Result<Option<PersistedTransitionFrontier>, String> {
+        use ledger::Database;
+
+        let Some(persistence) = self.persistence.as_mut() else {
+            return Ok(None);
+        };
+        let Some(data) = persistence.load().map_err(|err| err.to_string())? else {
+            return Ok(None);
+        };
+        let parts = data.root_staged_ledger;
+        let root = data
+            .best_chain
+            .first()
+            .ok_or("persisted best chain empty")?;
+        if root.snarked_ledger_hash() != &data.root_snarked_ledger_hash
+            || root.staged_ledger_hash() != &parts.staged_ledger_hash
+        {
+            return Err("persisted root ledgers don't match the root block".to_owned());
+        }
+
+        let mut snarked_ledger = Mask::new_root(Database::create(LEDGER_DEPTH as u8));
+        for account in data.root_snarked_ledger {
+            let account_id = account.id();
+            snarked_ledger
+                .get_or_create_account(account_id, account)
+                .map_err(|err| format!("{err:?}"))?;
+        }
+        if snarked_ledger.merkle_root() != data.root_snarked_ledger_hash.0.to_field() {
+            return Err("persisted root snarked ledger hash mismatch".to_owned());
+        }
+
+        let mut staged_ledger = reconstruct_staged_ledger(
+            &self.constraint_constants,
+            &self.verifier,
+            snarked_ledger.copy(),
+            &parts,
+        )?;
+        let mut staged_ledgers = BTreeMap::new();
+        for (pred_block, block) in data.best_chain.iter().zip(data.best_chain.iter().skip(1)) {
+            staged_ledgers.insert(
+                pred_block.staged_ledger_hash().clone(),
+                staged_ledger.clone(),
+            );
+            let ledger_hashes = apply_block(
+                &self.constraint_constants,
+                &self.verifier,
+                &mut staged_ledger,
+                block,
+                pred_block,
+            )?;
+            if &ledger_hashes != block.staged_ledger_hashes() {
+                return Err(format!(
+                    "staged ledger hash mismatch after applying block {}",
+                    block.hash
+                ));
+            }
+        }
+        let best_tip = data.best_chain.last().unwrap_or(root);
+        staged_ledgers.insert(best_tip.staged_ledger_hash().clone(), staged_ledger);
+
+        self.snarked_ledgers
+            .insert(data.root_snarked_ledger_hash, snarked_ledger);
+        self.staged_ledgers.extend(staged_ledgers);
+
+        for (hash, accounts) in data.epoch_ledgers {
+            let mut mask = Mask::new_root(Database::create(LEDGER_DEPTH as u8));
+            for account in accounts {
+                let account_id = account.id();
+                mask.get_or_create_account(account_id, account)
+                    .map_err(|err| format!("{err:?}"))?;
+            }
+            if mask.merkle_root() != hash.0.to_field() {
+                return Err(format!("persisted epoch ledger {hash} hash mismatch"));
+            }
+            self.snarked_ledgers.insert(hash, mask);
+        }
+        let epoch_ledgers = best_epoch_ledger_hashes(best_tip)
+            .into_iter()
+            .filter(|hash| self.epoch_ledger_mask(hash).is_some())
+            .cloned()
+            .collect();
+
+        let needed_protocol_states = parts
+            .needed_blocks
+            .into_iter()
+            .map(|state| (state.hash(), state))
+            .collect();
+        Ok(Some(PersistedTransitionFrontier {
+            best_chain: data.best_chain,
+            needed_protocol_states,
+            epoch_ledgers,
+        }))
+    }
+
     fn mask(&self, hash: &LedgerHash) -> Option<(Mask, bool)> {
         self.snarked_ledgers
             .get(hash)
@@ -126,6 +357,50 @@ impl LedgerCtx {
             .or_else(|| self.sync.mask(hash))
     }
 
+    /// Synced snarked ledger or one of the additional ledgers, which
+    /// can be used as an epoch ledger.
+    fn epoch_ledger_mask(&self, hash: &LedgerHash) -> Option<Mask> {
+        self.mask(hash)
+            .filter(|(_, is_synced)| *is_synced)
+            .map(|(mask, _)| mask)
+            .or_else(|| self.additional_snarked_ledgers.get(hash).cloned())
+    }
+
+    fn epoch_ledger_delegators(&mut self, hash: &LedgerHash) -> Option<&EpochLedgerDelegators> {
+        if !self.epoch_ledgers_delegators.iter().any(|(h, _)| h == hash) {
+            let mask = self.epoch_ledger_mask(hash)?;
+            let mut delegators = EpochLedgerDelegators::new();
+            for index in 0..mask.num_accounts() as u64 {
+                let Some(account) = mask.get_at_index(AccountIndex(index)) else {
+                    continue;
+                };
+                let Some(delegate) = account.delegate.as_ref() else {
+                    continue;
+                };
+                if !account.token_id.is_default() {
+                    continue;
+                }
+                delegators.entry(delegate.clone()).or_default().push((
+                    AccountIndex(index),
+                    RpcStakeDelegator {
+                        public_key: account.public_key.clone().into(),
+                        balance: account.balance.as_u64(),
+                    },
+                ));
+            }
+
+            if self.epoch_ledgers_delegators.len() >= EPOCH_LEDGERS_CACHE_SIZE {
+                self.epoch_ledgers_delegators.pop_front();
+            }
+            self.epoch_ledgers_delegators
+                .push_back((hash.clone(), delegators));
+        }
+        self.epoch_ledgers_delegators
+            .iter()
+            .find(|(h, _)| h == hash)
+            .map(|(_, delegators)| delegators)
+    }
+
     fn staged_ledger_mut(&mut self, hash: &LedgerHash) -> Option<&mut StagedLedger> {
         match self.staged_ledgers.get_mut(&hash) {
             Some(v) => Some(v),
@@ -134,6 +409,15 @@ impl LedgerCtx {
     }
 }
 
+/// Staking and next epoch ledger hashes of the block.
+fn best_epoch_ledger_hashes(block: &ArcBlockWithHash) -> [&LedgerHash; 2] {
+    [
+        SyncLedgerTargetKind::StakingLedger,
+        SyncLedgerTargetKind::NextEpochLedger,
+    ]
+    .map(|kind| kind.ledger_hash(block))
+}
+
 impl LedgerSyncState {
     fn mask(&self, hash: &LedgerHash) -> Option<(Mask, bool)> {
         self.snarked_ledgers
@@ -162,17 +446,121 @@ impl LedgerCtx {
     }
 }
 
+fn reconstruct_staged_ledger(
+    constraint_constants: &ConstraintConstants,
+    verifier: &Verifier,
+    snarked_ledger: Mask,
+    parts: &StagedLedgerAuxAndPendingCoinbases,
+) -> Result<StagedLedger, String> {
+    let states = parts
+        .needed_blocks
+        .iter()
+        .map(|state| {
+            let hash = state.hash().to_fp().map_err(|err| format!("{err:?}"))?;
+            Ok((hash, state.clone()))
+        })
+        .collect::<Result<BTreeMap<_, _>, String>>()?;
+    let scan_state: ScanState = (&parts.scan_state).into();
+    // Staged ledger construction expects every needed state to be present.
+    if let Some(hash) = scan_state
+        .required_state_hashes()
+        .into_iter()
+        .find(|hash| !states.contains_key(hash))
+    {
+        let hash: StateHash = DataHashLibStateHashStableV1(hash.into()).into();
+        return Err(format!(
+            "protocol state {hash} needed by the scan state missing"
+        ));
+    }
+
+    StagedLedger::of_scan_state_pending_coinbases_and_snarked_ledger(
+        (),
+        constraint_constants,
+        *verifier,
+        scan_state,
+        snarked_ledger,
+        LocalState::empty(),
+        parts.staged_ledger_hash.0.to_field(),
+        (&parts.pending_coinbase).into(),
+        |key| states[&key].clone(),
+    )
+}
+
+/// Returns `None` if any of the protocol states needed by the scan state
+/// is missing from `protocol_states`.
+pub(super) fn staged_ledger_aux_and_pending_coinbases(
+    ledger_hash: LedgerHash,
+    scan_state: &ScanState,
+    pending_coinbase: &PendingCoinbase,
+    protocol_states: &BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+) -> Option<StagedLedgerAuxAndPendingCoinbases> {
+    let needed_blocks = scan_state
+        .required_state_hashes()
+        .into_iter()
+        .map(|fp| DataHashLibStateHashStableV1(fp.into()))
+        .map(|hash| protocol_states.get(&hash.into()).ok_or(()).cloned())
+        .collect::<Result<_, _>>()
+        .ok()?;
+    Some(StagedLedgerAuxAndPendingCoinbases {
+        scan_state: scan_state.into(),
+        staged_ledger_hash: ledger_hash,
+        pending_coinbase: pending_coinbase.into(),
+        needed_blocks,
+    })
+}
+
+/// Applies block on top of the staged ledger of its predecessor and
+/// returns resulting staged ledger hashes.
+fn apply_block(
+    constraint_constants: &ConstraintConstants,
+    verifier: &Verifier,
+    staged_ledger: &mut StagedLedger,
+    block: &ArcBlockWithHash,
+    pred_block: &ArcBlockWithHash,
+) -> Result<MinaBaseStagedLedgerHashStableV1, String> {
+    let global_slot = block.global_slot();
+    let prev_protocol_state = &pred_block.header().protocol_state;
+    let prev_state_view = protocol_state_view(prev_protocol_state);
+
+    let consensus_state = &block.header().protocol_state.body.consensus_state;
+    let coinbase_receiver: CompressedPubKey = (&consensus_state.coinbase_receiver).into();
+    let _supercharge_coinbase = consensus_state.supercharge_coinbase;
+
+    // FIXME: Using `supercharge_coinbase` (from block) above does not work
+    let supercharge_coinbase = false;
+
+    let diff: Diff = (&block.block.body.staged_ledger_diff).into();
+
+    let result = staged_ledger
+        .apply(
+            None,
+            constraint_constants,
+            Slot::from_u32(global_slot),
+            diff,
+            (),
+            verifier,
+            &prev_state_view,
+            ledger::scan_state::protocol_state::hashes(prev_protocol_state),
+            coinbase_receiver,
+            supercharge_coinbase,
+        )
+        .map_err(|err| format!("{err:?}"))?;
+    Ok(MinaBaseStagedLedgerHashStableV1::from(
+        &result.hash_after_applying,
+    ))
+}
+
 pub trait LedgerService: redux::Service {
     fn ctx(&self) -> &LedgerCtx;
     fn ctx_mut(&mut self) -> &mut LedgerCtx;
 }
 
 impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
-    fn hashes_set(
+    fn hashes_check(
         &mut self,
         snarked_ledger_hash: LedgerHash,
         parent: &LedgerAddress,
-        (left, right): (LedgerHash, LedgerHash),
+        (left, right): &(LedgerHash, LedgerHash),
     ) -> Result<(), ()> {
         let (left, right) = (left.0.to_field(), right.0.to_field());
         let hash = ledger_hash(parent.length(), left, right);
@@ -182,7 +570,20 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerSnarkedService for T {
         if hash != mask.get_inner_hash_at_addr(parent.clone())? {
             return Err(());
         }
+        Ok(())
+    }
+
+    fn hashes_set(
+        &mut self,
+        snarked_ledger_hash: LedgerHash,
+        parent: &LedgerAddress,
+        hashes: (LedgerHash, LedgerHash),
+    ) -> Result<(), ()> {
+        self.hashes_check(snarked_ledger_hash.clone(), parent, &hashes)?;
 
+        let (left, right) = hashes;
+        let (left, right) = (left.0.to_field(), right.0.to_field());
+        let mask = self.ctx_mut().sync.snarked_ledger_mut(snarked_ledger_hash);
         mask.set_cached_hash_unchecked(&parent.child_left(), left);
         mask.set_cached_hash_unchecked(&parent.child_right(), right);
 
@@ -228,7 +629,21 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerStagedService for T {
             .as_ref()
             .map(|p| p.staged_ledger_hash.clone())
             .unwrap_or_else(|| snarked_ledger_hash.clone());
-        let snarked_ledger = self.ctx_mut().sync.snarked_ledger_mut(snarked_ledger_hash);
+        let ctx = self.ctx_mut();
+        if !ctx.sync.snarked_ledgers.contains_key(&snarked_ledger_hash) {
+            // Snarked ledger wasn't synced, because we already have it.
+            if let Some(mask) = ctx
+                .snarked_ledgers
+                .get(&snarked_ledger_hash)
+                .or_else(|| ctx.additional_snarked_ledgers.get(&snarked_ledger_hash))
+            {
+                let mask = mask.copy();
+                ctx.sync
+                    .snarked_ledgers
+                    .insert(snarked_ledger_hash.clone(), mask);
+            }
+        }
+        let snarked_ledger = ctx.sync.snarked_ledger_mut(snarked_ledger_hash);
         // TODO(binier): TMP. Remove for prod version.
         snarked_ledger
             .validate_inner_hashes()
@@ -236,26 +651,11 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerStagedService for T {
 
         let mask = snarked_ledger.copy();
 
+        let constraint_constants = &ctx.constraint_constants;
         let staged_ledger = if let Some(parts) = parts {
-            let states = parts
-                .needed_blocks
-                .iter()
-                .map(|state| (state.hash().to_fp().unwrap(), state.clone()))
-                .collect::<BTreeMap<_, _>>();
-
-            StagedLedger::of_scan_state_pending_coinbases_and_snarked_ledger(
-                (),
-                &CONSTRAINT_CONSTANTS,
-                Verifier,
-                (&parts.scan_state).into(),
-                mask,
-                LocalState::empty(),
-                parts.staged_ledger_hash.0.to_field(),
-                (&parts.pending_coinbase).into(),
-                |key| states.get(&key).cloned().unwrap(),
-            )?
+            reconstruct_staged_ledger(constraint_constants, &ctx.verifier, mask, &parts)?
         } else {
-            StagedLedger::create_exn(CONSTRAINT_CONSTANTS.clone(), mask)?
+            StagedLedger::create_exn(constraint_constants.clone(), mask)?
         };
 
         self.ctx_mut()
@@ -279,35 +679,14 @@ impl<T: LedgerService> TransitionFrontierService for T {
             .ok_or_else(|| "parent staged ledger missing")?
             .clone();
 
-        let global_slot = block.global_slot();
-        let prev_protocol_state = &pred_block.header().protocol_state;
-        let prev_state_view = protocol_state_view(prev_protocol_state);
-
-        let consensus_state = &block.header().protocol_state.body.consensus_state;
-        let coinbase_receiver: CompressedPubKey = (&consensus_state.coinbase_receiver).into();
-        let _supercharge_coinbase = consensus_state.supercharge_coinbase;
-
-        // FIXME: Using `supercharge_coinbase` (from block) above does not work
-        let supercharge_coinbase = false;
-
-        let diff: Diff = (&block.block.body.staged_ledger_diff).into();
-
-        let result = staged_ledger
-            .apply(
-                // TODO(binier): SEC
-                Some(SkipVerification::All),
-                &CONSTRAINT_CONSTANTS,
-                Slot::from_u32(global_slot),
-                diff,
-                (),
-                &Verifier,
-                &prev_state_view,
-                ledger::scan_state::protocol_state::hashes(prev_protocol_state),
-                coinbase_receiver,
-                supercharge_coinbase,
-            )
-            .map_err(|err| format!("{err:?}"))?;
-        let ledger_hashes = MinaBaseStagedLedgerHashStableV1::from(&result.hash_after_applying);
+        let ctx = self.ctx();
+        let ledger_hashes = apply_block(
+            &ctx.constraint_constants,
+            &ctx.verifier,
+            &mut staged_ledger,
+            &block,
+            &pred_block,
+        )?;
 
         // TODO(binier): return error if not matching.
         let expected_ledger_hashes = block.staged_ledger_hashes();
@@ -327,6 +706,7 @@ impl<T: LedgerService> TransitionFrontierService for T {
     fn commit(
         &mut self,
         ledgers_to_keep: BTreeSet<LedgerHash>,
+        ledger_syncing: Option<&LedgerHash>,
         new_root: &ArcBlockWithHash,
         new_best_tip: &ArcBlockWithHash,
     ) -> CommitResult {
@@ -334,8 +714,14 @@ impl<T: LedgerService> TransitionFrontierService for T {
 
         ctx.snarked_ledgers
             .retain(|hash, _| ledgers_to_keep.contains(hash));
+        let mut sync_snarked_ledgers = std::mem::take(&mut ctx.sync.snarked_ledgers);
+        if let Some((hash, mask)) =
+            ledger_syncing.and_then(|hash| sync_snarked_ledgers.remove_entry(hash))
+        {
+            ctx.sync.snarked_ledgers.insert(hash, mask);
+        }
         ctx.snarked_ledgers.extend(
-            std::mem::take(&mut ctx.sync.snarked_ledgers)
+            sync_snarked_ledgers
                 .into_iter()
                 .filter(|(hash, _)| ledgers_to_keep.contains(hash)),
         );
@@ -440,23 +826,293 @@ impl<T: LedgerService> TransitionFrontierService for T {
     ) -> Option<Arc<StagedLedgerAuxAndPendingCoinbases>> {
         let ctx = self.ctx_mut();
         let ledger = ctx.staged_ledger_mut(&ledger_hash)?;
-        let needed_blocks = ledger
-            .scan_state()
-            .required_state_hashes()
-            .into_iter()
-            .map(|fp| DataHashLibStateHashStableV1(fp.into()))
-            .map(|hash| protocol_states.get(&hash.into()).ok_or(()).cloned())
-            .collect::<Result<_, _>>()
-            .ok()?;
-        Some(
-            StagedLedgerAuxAndPendingCoinbases {
-                scan_state: (ledger.scan_state()).into(),
-                staged_ledger_hash: ledger_hash,
-                pending_coinbase: (ledger.pending_coinbase_collection()).into(),
-                needed_blocks,
-            }
-            .into(),
+        staged_ledger_aux_and_pending_coinbases(
+            ledger_hash,
+            ledger.scan_state(),
+            ledger.pending_coinbase_collection(),
+            &protocol_states,
         )
+        .map(Arc::new)
+    }
+
+    fn epoch_ledger(&mut self, hash: LedgerHash) -> Option<Arc<MinaBaseSparseLedgerBaseStableV2>> {
+        let ctx = self.ctx();
+        let mut cache = ctx.epoch_ledgers_cache.lock().expect("poisoned");
+        if let Some((_, ledger)) = cache.iter().find(|(h, _)| h == &hash) {
+            return ledger.clone();
+        }
+
+        let mask = ctx.epoch_ledger_mask(&hash)?;
+        if cache.len() >= EPOCH_LEDGERS_CACHE_SIZE {
+            cache.pop_front();
+        }
+        cache.push_back((hash.clone(), None));
+        drop(cache);
+
+        let cache = ctx.epoch_ledgers_cache.clone();
+        std::thread::Builder::new()
+            .name("openmina_epoch_ledger".to_owned())
+            .spawn(move || {
+                let account_ids = mask.accounts().into_iter().collect::<Vec<AccountId>>();
+                let sparse_ledger = SparseLedger::of_ledger_subset_exn(mask, &account_ids);
+                let ledger = Arc::new(MinaBaseSparseLedgerBaseStableV2::from(&sparse_ledger));
+                let mut cache = cache.lock().expect("poisoned");
+                // Unless evicted in the meantime.
+                if let Some((_, entry)) = cache.iter_mut().find(|(h, _)| h == &hash) {
+                    *entry = Some(ledger);
+                }
+            })
+            .expect("failed to spawn epoch ledger thread");
+        None
+    }
+
+    fn persist(
+        &mut self,
+        best_chain: &[ArcBlockWithHash],
+        needed_protocol_states: &BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    ) -> Result<(), String> {
+        let Some(root) = best_chain.first() else {
+            return Ok(());
+        };
+        let ctx = self.ctx_mut();
+        if ctx.persistence.is_none() && ctx.persistence_worker.is_none() {
+            return Ok(());
+        }
+        let root_ledgers = if ctx.persisted_root.as_ref() != Some(&root.hash) {
+            let snarked_ledger_hash = root.snarked_ledger_hash();
+            let snarked_ledger = ctx
+                .snarked_ledgers
+                .get(snarked_ledger_hash)
+                .ok_or("root snarked ledger missing")?
+                .clone();
+            let staged_ledger = ctx
+                .staged_ledger_mut(root.staged_ledger_hash())
+                .ok_or("root staged ledger missing")?;
+            // Converted into the p2p representation on the worker thread.
+            Some(LedgerPersistenceRoot {
+                snarked_ledger: (snarked_ledger_hash.clone(), snarked_ledger),
+                staged_ledger_hash: root.staged_ledger_hash().clone(),
+                scan_state: staged_ledger.scan_state().clone(),
+                pending_coinbase: staged_ledger.pending_coinbase_collection().clone(),
+                needed_protocol_states: needed_protocol_states.clone(),
+            })
+        } else {
+            None
+        };
+        // Epoch ledgers which are still being synced are saved next time.
+        let epoch_ledgers = best_chain
+            .last()
+            .into_iter()
+            .flat_map(best_epoch_ledger_hashes)
+            .filter_map(|hash| Some((hash.clone(), ctx.epoch_ledger_mask(hash)?)))
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .collect();
+        let worker = match ctx.persistence.take() {
+            Some(persistence) => ctx
+                .persistence_worker
+                .insert(LedgerPersistenceWorker::spawn(persistence)),
+            None => ctx
+                .persistence_worker
+                .as_mut()
+                .ok_or("persistence worker missing")?,
+        };
+        worker.save(LedgerPersistenceRequest {
+            root: root_ledgers,
+            epoch_ledgers,
+            best_chain: best_chain.to_vec(),
+        });
+        ctx.persisted_root = Some(root.hash.clone());
+        Ok(())
+    }
+}
+
+impl<T: LedgerService> TransactionPoolService for T {
+    fn transaction_pool_validate(
+        &mut self,
+        staged_ledger_hash: &LedgerHash,
+        transaction: &MinaBaseUserCommandStableV2,
+        pool_next_nonce: Option<u32>,
+        pool_pending_cost: u64,
+    ) -> Result<(), TransactionPoolValidateError> {
+        let MinaBaseUserCommandStableV2::SignedCommand(cmd) = transaction else {
+            return Err(TransactionPoolValidateError::ZkappCommandUnsupported);
+        };
+        let Some((mask, _)) = self.ctx().mask(staged_ledger_hash) else {
+            return Err(TransactionPoolValidateError::LedgerNotFound);
+        };
+        let cmd = SignedCommand::from(cmd);
+
+        let account = mask
+            .location_of_account(&cmd.fee_payer())
+            .and_then(|addr| mask.get(addr))
+            .ok_or(TransactionPoolValidateError::AccountNotFound)?;
+
+        let nonce = cmd.nonce().as_u32();
+        let min_nonce = account.nonce.as_u32();
+        let max_nonce = pool_next_nonce.unwrap_or(min_nonce).max(min_nonce);
+        if nonce < min_nonce || nonce > max_nonce {
+            return Err(TransactionPoolValidateError::InvalidNonce {
+                nonce,
+                min_nonce,
+                max_nonce,
+            });
+        }
+
+        let amount = match &cmd.payload.body {
+            signed_command::Body::Payment(payment) => payment.amount.as_u64(),
+            signed_command::Body::StakeDelegation(_) => 0,
+        };
+        let cost = cmd.fee().as_u64().saturating_add(amount);
+        // Pool transactions with lower nonces get applied first.
+        let balance = account.balance.as_u64().saturating_sub(pool_pending_cost);
+        if balance < cost {
+            return Err(TransactionPoolValidateError::InsufficientBalance { balance, cost });
+        }
+
+        let cmd = WithStatus {
+            data: verifiable::UserCommand::SignedCommand(Box::new(cmd)),
+            status: TransactionStatus::Applied,
+        };
+        match ledger::verifier::common::check(cmd) {
+            CheckResult::Valid(_) => Ok(()),
+            CheckResult::InvalidKeys(_) => Err(TransactionPoolValidateError::InvalidKeys),
+            _ => Err(TransactionPoolValidateError::InvalidSignature),
+        }
+    }
+
+    fn transaction_pool_accounts(
+        &mut self,
+        staged_ledger_hash: &LedgerHash,
+        accounts: Vec<AccountPublicKey>,
+    ) -> BTreeMap<AccountPublicKey, TransactionPoolAccount> {
+        let Some((mask, _)) = self.ctx().mask(staged_ledger_hash) else {
+            return Default::default();
+        };
+        accounts
+            .into_iter()
+            .filter_map(|public_key| {
+                let account_id = AccountId::new(public_key.as_ref().into(), TokenId::default());
+                let account = mask
+                    .location_of_account(&account_id)
+                    .and_then(|addr| mask.get(addr))?;
+                let account = TransactionPoolAccount {
+                    nonce: account.nonce.as_u32(),
+                    balance: account.balance.as_u64(),
+                };
+                Some((public_key, account))
+            })
+            .collect()
+    }
+}
+
+impl<T: LedgerService> BlockProducerLedgerService for T {
+    fn staking_ledger_delegators(
+        &mut self,
+        staking_ledger_hash: &LedgerHash,
+        producer: &AccountPublicKey,
+    ) -> Option<BTreeMap<u64, BlockProducerDelegator>> {
+        let producer: CompressedPubKey = producer.as_ref().into();
+        let delegators = self
+            .ctx_mut()
+            .epoch_ledger_delegators(staking_ledger_hash)?
+            .get(&producer)
+            .into_iter()
+            .flatten()
+            .map(|(index, delegator)| {
+                let delegator = BlockProducerDelegator {
+                    pub_key: delegator.public_key.clone(),
+                    stake: delegator.balance,
+                };
+                (index.0, delegator)
+            })
+            .collect();
+        Some(delegators)
+    }
+
+    fn staged_ledger_diff_create(
+        &mut self,
+        pred_block: &ArcBlockWithHash,
+        global_slot_since_genesis: u32,
+        coinbase_receiver: &AccountPublicKey,
+        transactions: Vec<MinaBaseUserCommandStableV2>,
+        completed_snarks: BTreeMap<SnarkJobId, Snark>,
+    ) -> Result<BlockProducerStagedLedgerDiffCreateOutput, String> {
+        let mut staged_ledger = self
+            .ctx_mut()
+            .staged_ledger_mut(&pred_block.staged_ledger_hash())
+            .ok_or_else(|| "parent staged ledger missing")?
+            .clone();
+
+        let constraint_constants = &self.ctx().constraint_constants;
+        let global_slot = Slot::from_u32(global_slot_since_genesis);
+        let coinbase_receiver: CompressedPubKey = coinbase_receiver.as_ref().into();
+        let pred_protocol_state = &pred_block.header().protocol_state;
+        let pred_state_view = protocol_state_view(pred_protocol_state);
+        // Supercharged coinbase for unlocked accounts isn't supported yet.
+        let supercharge_coinbase = false;
+
+        let transactions_by_fee = transactions.iter().map(Into::into).collect();
+
+        let get_completed_work = |statement: &work::Statement| {
+            let (first, second) = match statement {
+                OneOrTwo::One(s) => (s, s),
+                OneOrTwo::Two((s1, s2)) => (s1, s2),
+            };
+            let job_id: SnarkJobId = (&(&first.source).into(), &(&second.target).into()).into();
+            let snark = completed_snarks.get(&job_id)?;
+            let work = TransactionSnarkWorkTStableV2 {
+                fee: snark.fee.clone(),
+                proofs: (*snark.proofs).clone(),
+                prover: snark.snarker.clone(),
+            };
+            Some(work::Checked::from(&work))
+        };
+
+        let (diff, _invalid_txs) = staged_ledger
+            .create_diff(
+                constraint_constants,
+                global_slot,
+                Some(false),
+                coinbase_receiver.clone(),
+                (),
+                &pred_state_view,
+                transactions_by_fee,
+                get_completed_work,
+                supercharge_coinbase,
+            )
+            .map_err(|err| format!("{err:?}"))?;
+        let diff = diff.forget();
+
+        let diff_v2 = StagedLedgerDiffDiffStableV2::from(&diff);
+        let diff_hash = block_body_hash(&diff_v2).map_err(|err| format!("{err:?}"))?;
+
+        let result = staged_ledger
+            .apply(
+                // diff is created from already verified transactions and snarks.
+                Some(SkipVerification::All),
+                constraint_constants,
+                global_slot,
+                diff,
+                (),
+                &self.ctx().verifier,
+                &pred_state_view,
+                ledger::scan_state::protocol_state::hashes(pred_protocol_state),
+                coinbase_receiver,
+                supercharge_coinbase,
+            )
+            .map_err(|err| format!("{err:?}"))?;
+
+        Ok(BlockProducerStagedLedgerDiffCreateOutput {
+            diff: diff_v2,
+            diff_hash: ConsensusBodyReferenceStableV1(Blake2MakeStableV1(
+                diff_hash.as_slice().into(),
+            )),
+            staged_ledger_hash: MinaBaseStagedLedgerHashStableV1::from(&result.hash_after_applying),
+            emitted_ledger_proof: result
+                .ledger_proof
+                .map(|(proof, _)| Box::new((&proof).into())),
+        })
     }
 }
 
@@ -594,14 +1250,269 @@ impl<T: LedgerService> RpcLedgerService for T {
             })
             .collect()
     }
+
+    fn stake_get(
+        &mut self,
+        epoch: u32,
+        ledger_hash: LedgerHash,
+        public_key: AccountPublicKey,
+    ) -> RpcStakeGetResponse {
+        let ctx = self.ctx_mut();
+        let mask = ctx.epoch_ledger_mask(&ledger_hash)?;
+        let account_id = AccountId::new(public_key.as_ref().into(), TokenId::default());
+        let account = mask
+            .location_of_account(&account_id)
+            .and_then(|addr| mask.get(addr))?;
+        let key: CompressedPubKey = public_key.as_ref().into();
+
+        let delegators = ctx
+            .epoch_ledger_delegators(&ledger_hash)?
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .map(|(_, delegator)| delegator.clone())
+            .collect::<Vec<_>>();
+
+        Some(RpcStake {
+            epoch,
+            ledger_hash,
+            public_key,
+            balance: account.balance.as_u64(),
+            delegate: account.delegate.clone().map(Into::into),
+            delegated_stake: delegators.iter().map(|v| v.balance).sum(),
+            delegators,
+        })
+    }
+
+    fn account_get(
+        &self,
+        staged_ledger_hash: &LedgerHash,
+        query: &RpcAccountGetQuery,
+    ) -> Option<MinaBaseAccountBinableArgStableV2> {
+        let (mask, _) = self.ctx().mask(staged_ledger_hash)?;
+        let token_id = query
+            .token_id
+            .as_ref()
+            .map_or_else(TokenId::default, |token_id| TokenId::from(&**token_id));
+        let account_id = AccountId::new(query.public_key.as_ref().into(), token_id);
+        let account = mask
+            .location_of_account(&account_id)
+            .and_then(|addr| mask.get(addr))?;
+        Some((&*account).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use ledger::scan_state::currency::Balance;
     use mina_p2p_messages::v2::MinaBaseLedgerHash0StableV1;
 
+    use crate::account::AccountSecretKey;
+    use crate::transition_frontier::genesis_block;
+    use crate::NetworkConfig;
+
     use super::*;
 
+    struct TestLedgerService(LedgerCtx);
+
+    impl redux::Service for TestLedgerService {}
+
+    impl LedgerService for TestLedgerService {
+        fn ctx(&self) -> &LedgerCtx {
+            &self.0
+        }
+
+        fn ctx_mut(&mut self) -> &mut LedgerCtx {
+            &mut self.0
+        }
+    }
+
+    #[test]
+    fn persist_and_restore_transition_frontier() {
+        let dir =
+            std::env::temp_dir().join(format!("openmina-frontier-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let network = NetworkConfig::berkeley();
+        let accounts = (0..3)
+            .map(|_| {
+                let public_key = AccountSecretKey::rand().public_key();
+                let account_id = AccountId::new(public_key.as_ref().into(), TokenId::default());
+                ledger::Account::create_with(account_id, Balance::from_u64(1_000_000_000_000))
+            })
+            .collect::<Vec<_>>();
+        let block = genesis_block(
+            &network.protocol_constants,
+            &network.constraint_constants,
+            &accounts,
+        )
+        .unwrap();
+
+        let ctx = LedgerCtx::default()
+            .with_genesis_ledger(accounts)
+            .with_genesis_block(&block)
+            .unwrap()
+            .with_persistence(&dir)
+            .unwrap();
+        let mut service = TestLedgerService(ctx);
+        service
+            .persist(&[block.clone()], &Default::default())
+            .unwrap();
+        // Waits for the persistence worker to save the frontier.
+        drop(service);
+
+        let mut ctx = LedgerCtx::default().with_persistence(&dir).unwrap();
+        let restored = ctx
+            .restore_transition_frontier()
+            .unwrap()
+            .expect("persisted frontier must be restored");
+        assert_eq!(
+            restored
+                .best_chain
+                .iter()
+                .map(|b| b.hash.clone())
+                .collect::<Vec<_>>(),
+            vec![block.hash.clone()]
+        );
+        assert!(ctx.mask(block.snarked_ledger_hash()).is_some());
+        assert!(ctx.mask(block.staged_ledger_hash()).is_some());
+        // Genesis epoch ledgers are restored too, without the genesis config.
+        let epoch_ledgers = best_epoch_ledger_hashes(&block);
+        assert_eq!(
+            restored.epoch_ledgers,
+            epoch_ledgers.into_iter().cloned().collect::<BTreeSet<_>>()
+        );
+        assert!(epoch_ledgers
+            .into_iter()
+            .all(|hash| ctx.epoch_ledger_mask(hash).is_some()));
+
+        drop(ctx);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn epoch_ledger_is_cached() {
+        let accounts = (0..3)
+            .map(|_| {
+                let public_key = AccountSecretKey::rand().public_key();
+                let account_id = AccountId::new(public_key.as_ref().into(), TokenId::default());
+                ledger::Account::create_with(account_id, Balance::from_u64(1_000_000_000_000))
+            })
+            .collect::<Vec<_>>();
+        let mut service = TestLedgerService(LedgerCtx::default().with_genesis_ledger(accounts));
+        let hash = service
+            .0
+            .additional_snarked_ledgers
+            .keys()
+            .next()
+            .unwrap()
+            .clone();
+
+        // Built in the background, so not available on the first request.
+        assert!(service.epoch_ledger(hash.clone()).is_none());
+        let ledger = (0..100)
+            .find_map(|_| {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                service.epoch_ledger(hash.clone())
+            })
+            .expect("epoch ledger must be built");
+        assert!(Arc::ptr_eq(&ledger, &service.epoch_ledger(hash).unwrap()));
+
+        let unknown = "jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr"
+            .parse::<LedgerHash>()
+            .unwrap();
+        assert!(service.epoch_ledger(unknown).is_none());
+        assert_eq!(service.0.epoch_ledgers_cache.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn epoch_ledger_delegators_evict_oldest() {
+        let ledgers = (0..3)
+            .map(|i| {
+                let public_key = AccountSecretKey::rand().public_key();
+                let account_id = AccountId::new(public_key.as_ref().into(), TokenId::default());
+                vec![ledger::Account::create_with(
+                    account_id,
+                    Balance::from_u64(1_000_000_000 * (i + 1)),
+                )]
+            })
+            .collect::<Vec<_>>();
+        let mut ctx = LedgerCtx::default();
+        for accounts in ledgers {
+            ctx = ctx.with_genesis_ledger(accounts);
+        }
+        let mut hashes = ctx
+            .additional_snarked_ledgers
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        // Request in the reverse lexicographic order.
+        hashes.sort();
+        hashes.reverse();
+        for hash in &hashes {
+            assert!(ctx.epoch_ledger_delegators(hash).is_some());
+        }
+        let cached = ctx
+            .epoch_ledgers_delegators
+            .iter()
+            .map(|(hash, _)| hash.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(cached, hashes[1..]);
+    }
+
+    #[test]
+    fn stake_get_indexes_delegators_once() {
+        let keys = (0..3)
+            .map(|_| AccountSecretKey::rand().public_key())
+            .collect::<Vec<_>>();
+        let accounts = keys
+            .iter()
+            .map(|public_key| {
+                let account_id = AccountId::new(public_key.as_ref().into(), TokenId::default());
+                let mut account =
+                    ledger::Account::create_with(account_id, Balance::from_u64(1_000_000_000_000));
+                // Everyone delegates to the first account.
+                account.delegate = Some(keys[0].as_ref().into());
+                account
+            })
+            .collect::<Vec<_>>();
+        let mut service = TestLedgerService(LedgerCtx::default().with_genesis_ledger(accounts));
+        let hash = service
+            .0
+            .additional_snarked_ledgers
+            .keys()
+            .next()
+            .unwrap()
+            .clone();
+
+        let stake = service.stake_get(1, hash.clone(), keys[0].clone()).unwrap();
+        assert_eq!(stake.balance, 1_000_000_000_000);
+        assert_eq!(stake.delegated_stake, 3_000_000_000_000);
+        let mut delegators = stake
+            .delegators
+            .iter()
+            .map(|d| d.public_key.clone())
+            .collect::<Vec<_>>();
+        delegators.sort();
+        let mut expected = keys.clone();
+        expected.sort();
+        assert_eq!(delegators, expected);
+
+        let stake = service.stake_get(1, hash.clone(), keys[1].clone()).unwrap();
+        assert_eq!(stake.delegated_stake, 0);
+        assert!(stake.delegators.is_empty());
+        assert_eq!(service.0.epoch_ledgers_delegators.len(), 1);
+
+        let delegators = service.staking_ledger_delegators(&hash, &keys[0]).unwrap();
+        assert_eq!(delegators.len(), 3);
+        assert!(delegators.values().all(|d| d.stake == 1_000_000_000_000));
+        assert!(service
+            .staking_ledger_delegators(&hash, &keys[1])
+            .unwrap()
+            .is_empty());
+        assert_eq!(service.0.epoch_ledgers_delegators.len(), 1);
+    }
+
     #[test]
     fn test_ledger_hash() {
         IntoIterator::into_iter([(
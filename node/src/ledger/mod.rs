@@ -4,6 +4,9 @@ pub use ledger_config::*;
 mod ledger_service;
 pub use ledger_service::*;
 
+mod ledger_persistence;
+pub use ledger_persistence::*;
+
 pub use ledger::AccountIndex as LedgerAccountIndex;
 pub use ledger::Address as LedgerAddress;
 
@@ -10,6 +10,9 @@ pub use action_kind::ActionKind;
 mod config;
 pub use config::*;
 
+mod network_config;
+pub use network_config::*;
+
 mod state;
 pub use state::State;
 
@@ -26,7 +29,9 @@ pub mod recorder;
 pub mod stats;
 
 pub mod account;
+pub mod block_producer;
 pub mod consensus;
+pub mod daemon_json;
 pub mod event_source;
 pub mod external_snark_worker;
 pub mod ledger;
@@ -35,6 +40,7 @@ pub mod p2p;
 pub mod rpc;
 pub mod snark;
 pub mod snark_pool;
+pub mod transaction_pool;
 pub mod transition_frontier;
 pub mod watched_accounts;
 
@@ -1,7 +1,9 @@
+use crate::block_producer::BlockProducerAction;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
 use crate::p2p::channels::rpc::P2pChannelsRpcAction;
 use crate::p2p::channels::snark::P2pChannelsSnarkAction;
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
+use crate::p2p::channels::transaction::P2pChannelsTransactionAction;
 use crate::p2p::channels::P2pChannelsAction;
 use crate::p2p::connection::incoming::P2pConnectionIncomingAction;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingAction;
@@ -11,6 +13,7 @@ use crate::p2p::discovery::P2pDiscoveryAction;
 use crate::p2p::P2pAction;
 use crate::snark::work_verify::SnarkWorkVerifyAction;
 use crate::snark::SnarkAction;
+use crate::transaction_pool::TransactionPoolAction;
 use crate::{Action, ActionWithMetaRef, Service, Store};
 
 pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_>) {
@@ -274,6 +277,23 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                         peer_id = action.peer_id.to_string()
                     );
                 }
+                P2pDiscoveryAction::KademliaInit(_) => {}
+                P2pDiscoveryAction::KademliaAddRoute(_) => {}
+                P2pDiscoveryAction::KademliaSuccess(action) => {
+                    openmina_core::log::debug!(
+                        meta.time();
+                        kind = kind.to_string(),
+                        summary = format!("found {} peers", action.peers.len())
+                    );
+                }
+                P2pDiscoveryAction::KademliaFailure(action) => {
+                    openmina_core::log::warn!(
+                        meta.time();
+                        kind = kind.to_string(),
+                        summary = "kademlia lookup failed",
+                        error = action.description.clone()
+                    );
+                }
             },
             P2pAction::Channels(action) => match action {
                 P2pChannelsAction::MessageReceived(_) => {}
@@ -334,6 +354,25 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     }
                     _ => {}
                 },
+                P2pChannelsAction::Transaction(action) => match action {
+                    P2pChannelsTransactionAction::Init(action) => {
+                        openmina_core::log::debug!(
+                            meta.time();
+                            kind = kind.to_string(),
+                            summary = format!("peer_id: {}", action.peer_id),
+                            peer_id = action.peer_id.to_string()
+                        );
+                    }
+                    P2pChannelsTransactionAction::Ready(action) => {
+                        openmina_core::log::debug!(
+                            meta.time();
+                            kind = kind.to_string(),
+                            summary = format!("peer_id: {}", action.peer_id),
+                            peer_id = action.peer_id.to_string()
+                        );
+                    }
+                    _ => {}
+                },
                 P2pChannelsAction::Rpc(action) => match action {
                     P2pChannelsRpcAction::Init(action) => {
                         openmina_core::log::debug!(
@@ -388,6 +427,7 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     openmina_core::log::debug!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = a.worker_id(),
                         trace_action = serde_json::to_string(&a).ok()
                     )
                 }
@@ -395,25 +435,29 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     openmina_core::log::info!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = a.worker_id,
                         work_id = a.job_id.to_string(),
                     )
                 }
-                ExternalSnarkWorkerAction::WorkResult(_) => {
+                ExternalSnarkWorkerAction::WorkResult(a) => {
                     openmina_core::log::info!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = a.worker_id,
                     )
                 }
-                ExternalSnarkWorkerAction::CancelWork(_) => {
+                ExternalSnarkWorkerAction::CancelWork(a) => {
                     openmina_core::log::info!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = a.worker_id,
                     )
                 }
                 ExternalSnarkWorkerAction::WorkError(a) => {
                     openmina_core::log::warn!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = a.worker_id,
                         error = a.error.to_string(),
                     )
                 }
@@ -421,23 +465,48 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                     openmina_core::log::info!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = a.worker_id,
                         error = a.error.to_string(),
                     )
                 }
-                ExternalSnarkWorkerAction::StartTimeout(_) => {
+                ExternalSnarkWorkerAction::StartTimeout(a) => {
                     openmina_core::log::warn!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = a.worker_id,
                     )
                 }
-                ExternalSnarkWorkerAction::WorkTimeout(_) => {
+                ExternalSnarkWorkerAction::WorkTimeout(a) => {
                     openmina_core::log::warn!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = a.worker_id,
                     )
                 }
             }
         }
+        Action::TransactionPool(a) => match a {
+            TransactionPoolAction::Add(a) => {
+                openmina_core::log::debug!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("hash: {}", a.hash),
+                    hash = a.hash.to_string(),
+                    peer_id = a.sender.map(|v| v.to_string())
+                );
+            }
+            TransactionPoolAction::Reject(a) => {
+                openmina_core::log::debug!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("hash: {}, error: {}", a.hash, a.error),
+                    hash = a.hash.to_string(),
+                    peer_id = a.sender.map(|v| v.to_string()),
+                    error = a.error.to_string()
+                );
+            }
+            _ => {}
+        },
         Action::Snark(a) => match a {
             SnarkAction::WorkVerify(a) => match a {
                 SnarkWorkVerifyAction::Init(a) => {
@@ -480,6 +549,56 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             },
             _ => {}
         },
+        Action::BlockProducer(a) => match a {
+            BlockProducerAction::VrfEvaluationSuccess(a) => {
+                openmina_core::log::info!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("epoch: {}, won slots: {}", a.output.epoch_data.epoch, a.output.won_slots.len()),
+                    epoch = a.output.epoch_data.epoch,
+                    won_slots = a.output.won_slots.len(),
+                );
+            }
+            BlockProducerAction::VrfEvaluationError(a) => {
+                openmina_core::log::warn!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("error: {}", a.error),
+                    error = a.error.clone(),
+                );
+            }
+            BlockProducerAction::WonSlot(a) => {
+                openmina_core::log::info!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("slot: {}, slot since genesis: {}", a.won_slot.global_slot, a.won_slot.global_slot_since_genesis),
+                    slot = a.won_slot.global_slot,
+                    slot_since_genesis = a.won_slot.global_slot_since_genesis,
+                    delegator = a.won_slot.delegator.to_string(),
+                );
+            }
+            BlockProducerAction::WonSlotDiscard(a) => {
+                openmina_core::log::warn!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("reason: {:?}", a.reason),
+                    reason = format!("{:?}", a.reason),
+                );
+            }
+            BlockProducerAction::BlockProduced(_) => {
+                let Some(block) = store.state().block_producer.produced_block() else {
+                    return;
+                };
+                openmina_core::log::info!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("hash: {}, height: {}", block.hash, block.height()),
+                    hash = block.hash.to_string(),
+                    height = block.height(),
+                );
+            }
+            _ => {}
+        },
         _ => {}
     }
 }
@@ -0,0 +1,274 @@
+use std::{fmt, str::FromStr};
+
+use ledger::{
+    scan_state::{
+        currency::{Amount, Fee},
+        scan_state::ConstraintConstants,
+    },
+    Account,
+};
+use mina_p2p_messages::v2::{
+    BlockTimeTimeStableV1, StateHash, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+};
+use openmina_core::block::ArcBlockWithHash;
+
+use crate::config::ProofLevel;
+use crate::daemon_json::{parse_amount, DaemonJson, DaemonJsonError};
+use crate::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use crate::transition_frontier::{chain_id, genesis_block};
+use crate::ProtocolConstants;
+
+pub const BERKELEY_CONSTRAINT_CONSTANTS: ConstraintConstants = ConstraintConstants {
+    sub_windows_per_window: 11,
+    ledger_depth: 35,
+    work_delay: 2,
+    block_window_duration_ms: 180000,
+    transaction_capacity_log_2: 7,
+    pending_coinbase_depth: 5,
+    coinbase_amount: Amount::from_u64(720000000000),
+    supercharged_coinbase_factor: 2,
+    account_creation_fee: Fee::from_u64(1000000000),
+    fork: None,
+};
+
+/// Default `daemon.txpool_max_size` of the runtime config.
+pub const TXPOOL_MAX_SIZE: u32 = 3000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkId {
+    Berkeley,
+}
+
+impl NetworkId {
+    pub fn iter_all() -> impl Iterator<Item = Self> {
+        [Self::Berkeley].into_iter()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Berkeley => "berkeley",
+        }
+    }
+
+    /// Network id used for signing transactions, determines the
+    /// signature prefix (`CodaSignature` for testnets).
+    pub fn signature_network_id(self) -> mina_signer::NetworkId {
+        match self {
+            Self::Berkeley => mina_signer::NetworkId::TESTNET,
+        }
+    }
+}
+
+impl fmt::Display for NetworkId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NetworkId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::iter_all()
+            .find(|id| id.as_str() == s)
+            .ok_or_else(|| format!("unknown network `{s}`"))
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum NetworkConfigError {
+    #[error(transparent)]
+    DaemonJson(#[from] DaemonJsonError),
+    #[error("unknown ledger `{0}`, its accounts must be set in the config")]
+    UnknownLedger(String),
+    #[error("config changes genesis, so genesis ledger accounts must be set in it")]
+    GenesisLedgerMissing,
+    #[error("`{0}` must be positive")]
+    ZeroConstant(&'static str),
+    #[error("failed to create genesis block: {0}")]
+    GenesisBlock(String),
+}
+
+/// Parameters of the network, that node connects to.
+#[derive(Debug, Clone)]
+pub struct NetworkConfig {
+    pub name: String,
+    pub chain_id: String,
+    pub genesis_state_hash: StateHash,
+    pub protocol_constants: ProtocolConstants,
+    pub constraint_constants: ConstraintConstants,
+    /// Genesis ledger accounts. If `None`, genesis ledger isn't known
+    /// upfront and it must be synced from peers.
+    pub genesis_ledger: Option<Vec<Account>>,
+    /// Genesis block created locally from the genesis ledger, if the
+    /// network is a private one.
+    pub genesis_block: Option<ArcBlockWithHash>,
+    pub seed_peers: Vec<P2pConnectionOutgoingInitOpts>,
+    pub proof_level: ProofLevel,
+}
+
+impl NetworkConfig {
+    pub fn new(id: NetworkId) -> Self {
+        match id {
+            NetworkId::Berkeley => Self::berkeley(),
+        }
+    }
+
+    pub fn berkeley() -> Self {
+        Self {
+            name: NetworkId::Berkeley.to_string(),
+            chain_id: "3c41383994b87449625df91769dff7b507825c064287d30fada9286f3f1cb15e"
+                .to_owned(),
+            genesis_state_hash: "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ"
+                .parse()
+                .unwrap(),
+            protocol_constants: ProtocolConstants {
+                k: 290u32.into(),
+                slots_per_epoch: 7140u32.into(),
+                slots_per_sub_window: 7u32.into(),
+                delta: 0u32.into(),
+                // 2023-02-23T20:00:01Z
+                genesis_state_timestamp: BlockTimeTimeStableV1(
+                    UnsignedExtendedUInt64Int64ForVersionTagsStableV1(1677182401000u64.into()),
+                ),
+            },
+            constraint_constants: BERKELEY_CONSTRAINT_CONSTANTS,
+            genesis_ledger: None,
+            genesis_block: None,
+            seed_peers: [
+                "/2ajh5CpZCHdv7tmMrotVnLjQXuhcuCzqKosdDmvN3tNTScw2fsd/http/65.109.110.75/10000",
+
+                "/dns4/seed-1.berkeley.o1test.net/tcp/10000/p2p/12D3KooWAdgYL6hv18M3iDBdaK1dRygPivSfAfBNDzie6YqydVbs",
+                "/dns4/seed-2.berkeley.o1test.net/tcp/10001/p2p/12D3KooWLjs54xHzVmMmGYb7W5RVibqbwD1co7M2ZMfPgPm7iAag",
+                "/dns4/seed-3.berkeley.o1test.net/tcp/10002/p2p/12D3KooWEiGVAFC7curXWXiGZyMWnZK9h8BKr88U8D5PKV3dXciv",
+
+                "/dns4/webrtc2.webnode.openmina.com/tcp/443/p2p/12D3KooWFpqySZDHx7k5FMjdwmrU3TLhDbdADECCautBcEGtG4fr",
+                "/dns4/webrtc2.webnode.openmina.com/tcp/4431/p2p/12D3KooWJBeXosFxdBwe2mbKRjgRG69ERaUTpS9qo9NRkoE8kBpj",
+
+                "/ip4/78.27.236.28/tcp/8302/p2p/12D3KooWDLNXPq28An4s2QaPZX5ftem1AfaCWuxHHJq97opeWxLy",
+            ]
+            .into_iter()
+            .map(|s| s.parse().unwrap())
+            .collect(),
+            proof_level: ProofLevel::Full,
+        }
+    }
+
+    /// Overrides network parameters with the ones set in the runtime config.
+    ///
+    /// If the config changes genesis, network is considered private and
+    /// its genesis block is created locally from the genesis ledger, with
+    /// the chain id derived from it.
+    pub fn apply_daemon_json(mut self, config: DaemonJson) -> Result<Self, NetworkConfigError> {
+        let is_private = config.changes_genesis();
+        if is_private {
+            self.genesis_ledger = None;
+            self.genesis_block = None;
+            self.seed_peers = vec![];
+        }
+
+        if let Some(genesis) = &config.genesis {
+            let constants = &mut self.protocol_constants;
+            if let Some(k) = genesis.k {
+                constants.k = k.into();
+            }
+            if let Some(delta) = genesis.delta {
+                constants.delta = delta.into();
+            }
+            if let Some(slots_per_epoch) = genesis.slots_per_epoch {
+                constants.slots_per_epoch = slots_per_epoch.into();
+            }
+            if let Some(slots_per_sub_window) = genesis.slots_per_sub_window {
+                constants.slots_per_sub_window = slots_per_sub_window.into();
+            }
+            if let Some(timestamp) = genesis.genesis_state_timestamp()? {
+                constants.genesis_state_timestamp = timestamp;
+            }
+        }
+
+        if let Some(proof) = &config.proof {
+            let constants = &mut self.constraint_constants;
+            macro_rules! set {
+                ($($field:ident),+) => {
+                    $(if let Some(v) = proof.$field {
+                        constants.$field = v;
+                    })+
+                };
+            }
+            set!(
+                sub_windows_per_window,
+                work_delay,
+                block_window_duration_ms,
+                pending_coinbase_depth,
+                supercharged_coinbase_factor
+            );
+            if let Some(capacity) = &proof.transaction_capacity {
+                constants.transaction_capacity_log_2 =
+                    capacity.log_2(constants.block_window_duration_ms)?;
+            }
+            if let Some(amount) = &proof.coinbase_amount {
+                constants.coinbase_amount = Amount::from_u64(parse_amount(amount)?);
+            }
+            if let Some(fee) = &proof.account_creation_fee {
+                constants.account_creation_fee = Fee::from_u64(parse_amount(fee)?);
+            }
+            if let Some(fork) = &proof.fork {
+                constants.fork = Some(fork.to_fork_constants()?);
+            }
+            if let Some(level) = proof.level {
+                self.proof_level = level;
+            }
+        }
+
+        if let Some(ledger) = &config.ledger {
+            match (&ledger.name, ledger.accounts()?) {
+                (name, Some(accounts)) => {
+                    self.genesis_ledger = Some(accounts);
+                    if let Some(name) = name {
+                        self.name = name.clone();
+                    }
+                }
+                // Refers to the ledger of the network being configured.
+                (Some(name), None) if name != &self.name => {
+                    return Err(NetworkConfigError::UnknownLedger(name.clone()));
+                }
+                (_, None) => {}
+            }
+        }
+
+        if let Some(peers) = config.peers()? {
+            self.seed_peers = peers;
+        }
+
+        // Both are used as divisors, or to size the sub window densities.
+        if self.constraint_constants.sub_windows_per_window == 0 {
+            return Err(NetworkConfigError::ZeroConstant("sub_windows_per_window"));
+        }
+        if self.protocol_constants.slots_per_sub_window.as_u32() == 0 {
+            return Err(NetworkConfigError::ZeroConstant("slots_per_sub_window"));
+        }
+
+        if is_private {
+            let accounts = self
+                .genesis_ledger
+                .as_ref()
+                .ok_or(NetworkConfigError::GenesisLedgerMissing)?;
+            let block = genesis_block(
+                &self.protocol_constants,
+                &self.constraint_constants,
+                accounts,
+            )
+            .map_err(NetworkConfigError::GenesisBlock)?;
+            let txpool_max_size = config
+                .daemon
+                .as_ref()
+                .and_then(|daemon| daemon.txpool_max_size)
+                .unwrap_or(TXPOOL_MAX_SIZE);
+            self.genesis_state_hash = block.hash.clone();
+            self.chain_id = chain_id(&block.hash, &self.protocol_constants, txpool_max_size);
+            self.genesis_block = Some(block);
+        }
+
+        Ok(self)
+    }
+}
@@ -4,6 +4,7 @@ pub mod best_tip;
 pub mod rpc;
 pub mod snark;
 pub mod snark_job_commitment;
+pub mod transaction;
 
 mod p2p_channels_actions;
 pub use p2p_channels_actions::*;
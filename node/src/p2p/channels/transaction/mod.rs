@@ -0,0 +1,4 @@
+pub use ::p2p::channels::transaction::*;
+
+mod p2p_channels_transaction_actions;
+pub use p2p_channels_transaction_actions::*;
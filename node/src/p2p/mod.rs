@@ -9,6 +9,12 @@ pub mod peer;
 mod p2p_effects;
 pub use p2p_effects::*;
 
+mod p2p_peer_store;
+pub use p2p_peer_store::*;
+
+mod p2p_peer_store_service;
+pub use p2p_peer_store_service::*;
+
 impl<S> redux::SubStore<crate::State, P2pState> for crate::Store<S>
 where
     S: redux::Service,
@@ -80,8 +86,17 @@ impl_into_global_action!(connection::incoming::P2pConnectionIncomingSuccessActio
 impl_into_global_action!(disconnection::P2pDisconnectionInitAction);
 impl_into_global_action!(disconnection::P2pDisconnectionFinishAction);
 
+impl_into_global_action!(peer::P2pPeerPenalizeAction);
+impl_into_global_action!(peer::P2pPeerBanAction);
+impl_into_global_action!(peer::P2pPeerUnbanAction);
+impl_into_global_action!(peer::P2pPeerForgiveAction);
+
 impl_into_global_action!(discovery::P2pDiscoveryInitAction);
 impl_into_global_action!(discovery::P2pDiscoverySuccessAction);
+impl_into_global_action!(discovery::P2pDiscoveryKademliaInitAction);
+impl_into_global_action!(discovery::P2pDiscoveryKademliaAddRouteAction);
+impl_into_global_action!(discovery::P2pDiscoveryKademliaSuccessAction);
+impl_into_global_action!(discovery::P2pDiscoveryKademliaFailureAction);
 
 impl_into_global_action!(channels::P2pChannelsMessageReceivedAction);
 
@@ -93,6 +108,17 @@ impl_into_global_action!(channels::best_tip::P2pChannelsBestTipReceivedAction);
 impl_into_global_action!(channels::best_tip::P2pChannelsBestTipRequestReceivedAction);
 impl_into_global_action!(channels::best_tip::P2pChannelsBestTipResponseSendAction);
 
+impl_into_global_action!(channels::transaction::P2pChannelsTransactionInitAction);
+impl_into_global_action!(channels::transaction::P2pChannelsTransactionPendingAction);
+impl_into_global_action!(channels::transaction::P2pChannelsTransactionReadyAction);
+impl_into_global_action!(channels::transaction::P2pChannelsTransactionRequestSendAction);
+impl_into_global_action!(channels::transaction::P2pChannelsTransactionPromiseReceivedAction);
+impl_into_global_action!(channels::transaction::P2pChannelsTransactionReceivedAction);
+impl_into_global_action!(channels::transaction::P2pChannelsTransactionRequestReceivedAction);
+impl_into_global_action!(channels::transaction::P2pChannelsTransactionResponseSendAction);
+impl_into_global_action!(channels::transaction::P2pChannelsTransactionLibp2pReceivedAction);
+impl_into_global_action!(channels::transaction::P2pChannelsTransactionLibp2pBroadcastAction);
+
 impl_into_global_action!(channels::snark::P2pChannelsSnarkInitAction);
 impl_into_global_action!(channels::snark::P2pChannelsSnarkPendingAction);
 impl_into_global_action!(channels::snark::P2pChannelsSnarkReadyAction);
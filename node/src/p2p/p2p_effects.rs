@@ -1,17 +1,23 @@
-use mina_p2p_messages::v2::{MinaLedgerSyncLedgerAnswerStableV2, StateHash};
-use openmina_core::block::BlockWithHash;
+use mina_p2p_messages::v2::{
+    ConsensusProofOfStakeDataConsensusStateValueStableV2 as MinaConsensusState,
+    MinaBaseStateBodyHashStableV1, MinaLedgerSyncLedgerAnswerStableV2, StateHash,
+};
+use openmina_core::block::{ArcBlockWithHash, BlockWithHash};
 
-use crate::consensus::{ConsensusBlockChainProofUpdateAction, ConsensusBlockReceivedAction};
+use crate::consensus::{
+    consensus_take, ConsensusBlockChainProofUpdateAction, ConsensusBlockReceivedAction,
+};
 use crate::rpc::{
-    RpcP2pConnectionIncomingErrorAction, RpcP2pConnectionIncomingRespondAction,
-    RpcP2pConnectionIncomingSuccessAction, RpcP2pConnectionOutgoingErrorAction,
-    RpcP2pConnectionOutgoingSuccessAction,
+    RpcEvent, RpcEventNotifyAction, RpcP2pConnectionIncomingErrorAction,
+    RpcP2pConnectionIncomingRespondAction, RpcP2pConnectionIncomingSuccessAction,
+    RpcP2pConnectionOutgoingErrorAction, RpcP2pConnectionOutgoingSuccessAction,
 };
 use crate::snark_pool::candidate::{
     SnarkPoolCandidateInfoReceivedAction, SnarkPoolCandidatePeerPruneAction,
     SnarkPoolCandidateWorkReceivedAction,
 };
 use crate::snark_pool::SnarkPoolJobCommitmentAddAction;
+use crate::transaction_pool::TransactionPoolReceivedAction;
 use crate::transition_frontier::sync::ledger::snarked::{
     PeerLedgerQueryError, PeerLedgerQueryResponse,
     TransitionFrontierSyncLedgerSnarkedPeerQueryErrorAction,
@@ -26,33 +32,38 @@ use crate::transition_frontier::sync::ledger::staged::{
 use crate::transition_frontier::sync::{
     PeerBlockFetchError, TransitionFrontierSyncBlocksPeerQueryErrorAction,
     TransitionFrontierSyncBlocksPeerQuerySuccessAction,
-    TransitionFrontierSyncBlocksPeersQueryAction,
+    TransitionFrontierSyncBlocksPeersQueryAction, TransitionFrontierSyncState,
 };
 use crate::watched_accounts::{
     WatchedAccountLedgerInitialState, WatchedAccountsLedgerInitialStateGetError,
     WatchedAccountsLedgerInitialStateGetErrorAction,
 };
-use crate::{Service, Store};
+use crate::{Service, State, Store};
 
 use super::channels::best_tip::{P2pChannelsBestTipAction, P2pChannelsBestTipResponseSendAction};
 use super::channels::rpc::{
     BestTipWithProof, P2pChannelsRpcAction, P2pChannelsRpcRequestSendAction,
-    P2pChannelsRpcResponseSendAction, P2pRpcRequest, P2pRpcResponse,
+    P2pChannelsRpcResponseSendAction, P2pNodeStatus, P2pNodeSyncStatus, P2pRpcRequest,
+    P2pRpcResponse,
 };
 use super::channels::snark::P2pChannelsSnarkAction;
 use super::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentAction;
-use super::channels::P2pChannelsAction;
+use super::channels::transaction::P2pChannelsTransactionAction;
+use super::channels::{
+    P2pChannelsAction, P2pChannelsService, P2pGossipAcceptance, P2pGossipValidationKey,
+};
 use super::connection::incoming::{
     P2pConnectionIncomingAction, P2pConnectionIncomingAnswerSendSuccessAction,
 };
 use super::connection::outgoing::P2pConnectionOutgoingAction;
 use super::connection::{P2pConnectionAction, P2pConnectionResponse};
 use super::disconnection::{
-    P2pDisconnectionAction, P2pDisconnectionInitAction, P2pDisconnectionReason,
+    P2pDisconnectedState, P2pDisconnectionAction, P2pDisconnectionInitAction,
+    P2pDisconnectionReason,
 };
 use super::discovery::{P2pDiscoveryAction, P2pDiscoveryInitAction, P2pDiscoverySuccessAction};
-use super::peer::P2pPeerAction;
-use super::{P2pAction, P2pActionWithMeta};
+use super::peer::{P2pPeerAction, P2pPeerOffense, P2pPeerPenalizeAction};
+use super::{P2pAction, P2pActionWithMeta, P2pPeerStoreUpdate, P2pState, PeerId};
 
 use p2p::P2pPeerStatus;
 
@@ -103,19 +114,37 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                 }
                 P2pConnectionOutgoingAction::Error(action) => {
                     let p2p = &store.state().p2p;
+                    let dial_opts = p2p
+                        .peers
+                        .get(&action.peer_id)
+                        .and_then(|p| p.dial_opts.clone());
                     if let Some(rpc_id) = p2p.peer_connection_rpc_id(&action.peer_id) {
                         store.dispatch(RpcP2pConnectionOutgoingErrorAction {
                             rpc_id,
                             error: action.error.clone(),
                         });
                     }
+                    let update = P2pPeerStoreUpdate::ConnectionFailed {
+                        peer_id: action.peer_id,
+                        dial_opts,
+                    };
+                    peer_store_update(store, &meta, update);
                     // action.effects(&meta, store);
                 }
                 P2pConnectionOutgoingAction::Success(action) => {
                     let p2p = &store.state().p2p;
+                    let dial_opts = p2p
+                        .peers
+                        .get(&action.peer_id)
+                        .and_then(|p| p.dial_opts.clone());
                     if let Some(rpc_id) = p2p.peer_connection_rpc_id(&action.peer_id) {
                         store.dispatch(RpcP2pConnectionOutgoingSuccessAction { rpc_id });
                     }
+                    let update = P2pPeerStoreUpdate::Connected {
+                        peer_id: action.peer_id,
+                        dial_opts,
+                    };
+                    peer_store_update(store, &meta, update);
                     action.effects(&meta, store);
                 }
             },
@@ -168,17 +197,55 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                 }
                 P2pConnectionIncomingAction::Success(action) => {
                     let p2p = &store.state().p2p;
+                    let dial_opts = p2p
+                        .peers
+                        .get(&action.peer_id)
+                        .and_then(|p| p.dial_opts.clone());
                     if let Some(rpc_id) = p2p.peer_connection_rpc_id(&action.peer_id) {
                         store.dispatch(RpcP2pConnectionIncomingSuccessAction { rpc_id });
                     }
+                    let update = P2pPeerStoreUpdate::Connected {
+                        peer_id: action.peer_id,
+                        dial_opts,
+                    };
+                    peer_store_update(store, &meta, update);
                     action.effects(&meta, store);
                 }
             },
         },
         P2pAction::Disconnection(action) => match action {
-            P2pDisconnectionAction::Init(action) => action.effects(&meta, store),
+            P2pDisconnectionAction::Init(action) => {
+                action.effects(&meta, store);
+                // Finish is dispatched by the effects above, so this
+                // overwrites the generic reason recorded there.
+                let state = P2pDisconnectedState {
+                    reason: action.reason.to_string(),
+                };
+                let update = P2pPeerStoreUpdate::Disconnected {
+                    peer_id: action.peer_id,
+                    state,
+                };
+                peer_store_update(store, &meta, update);
+            }
             P2pDisconnectionAction::Finish(action) => {
-                if let Some(s) = store.state().transition_frontier.sync.root_ledger() {
+                if let Some(stats) = store.service.stats() {
+                    stats.p2p_peer_disconnected(&action.peer_id);
+                }
+                store.dispatch(RpcEventNotifyAction {
+                    event: RpcEvent::PeerDisconnected {
+                        peer_id: action.peer_id,
+                    },
+                });
+                let state = P2pDisconnectedState {
+                    reason: "connection closed".to_owned(),
+                };
+                let update = P2pPeerStoreUpdate::Disconnected {
+                    peer_id: action.peer_id,
+                    state,
+                };
+                peer_store_update(store, &meta, update);
+
+                if let Some(s) = store.state().transition_frontier.sync.ledger() {
                     let rpc_ids = s
                         .snarked()
                         .map(|s| s.peer_query_pending_rpc_ids(&action.peer_id).collect())
@@ -266,6 +333,18 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
             }
             P2pDiscoveryAction::Timeout(_) => {}
             P2pDiscoveryAction::Success(_) => {}
+            P2pDiscoveryAction::KademliaInit(_) => {
+                store.service.kademlia_lookup_random();
+            }
+            P2pDiscoveryAction::KademliaAddRoute(action) => {
+                let update = P2pPeerStoreUpdate::AddressesFound {
+                    peer_id: action.peer_id,
+                    addresses: action.addresses,
+                };
+                peer_store_update(store, &meta, update);
+            }
+            P2pDiscoveryAction::KademliaSuccess(_) => {}
+            P2pDiscoveryAction::KademliaFailure(_) => {}
         },
         P2pAction::Channels(action) => match action {
             P2pChannelsAction::MessageReceived(action) => {
@@ -330,6 +409,58 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                     action.effects(&meta, store);
                 }
             },
+            P2pChannelsAction::Transaction(action) => match action {
+                P2pChannelsTransactionAction::Init(action) => {
+                    action.effects(&meta, store);
+                }
+                P2pChannelsTransactionAction::Pending(_) => {}
+                P2pChannelsTransactionAction::Ready(action) => {
+                    action.effects(&meta, store);
+                }
+                P2pChannelsTransactionAction::RequestSend(action) => {
+                    action.effects(&meta, store);
+                }
+                P2pChannelsTransactionAction::PromiseReceived(_) => {}
+                P2pChannelsTransactionAction::Received(action) => {
+                    action.effects(&meta, store);
+                    // TODO: zkapp command hashing isn't supported yet.
+                    if let Ok(hash) = action.transaction.hash() {
+                        store.dispatch(TransactionPoolReceivedAction {
+                            hash,
+                            transaction: action.transaction,
+                            sender: Some(action.peer_id),
+                        });
+                    }
+                }
+                P2pChannelsTransactionAction::RequestReceived(_) => {}
+                P2pChannelsTransactionAction::ResponseSend(action) => {
+                    action.effects(&meta, store);
+                }
+                P2pChannelsTransactionAction::Libp2pReceived(action) => {
+                    for transaction in action.transactions {
+                        // TODO: zkapp command hashing isn't supported yet.
+                        if let Ok(hash) = transaction.hash() {
+                            let key = P2pGossipValidationKey::Transaction(hash.clone());
+                            let received = store.dispatch(TransactionPoolReceivedAction {
+                                hash,
+                                transaction,
+                                sender: Some(action.peer_id),
+                            });
+                            if !received {
+                                // Already in the pool or we have no
+                                // best tip to validate it against.
+                                store.service.libp2p_gossip_validation_result(
+                                    key,
+                                    P2pGossipAcceptance::Ignore,
+                                );
+                            }
+                        }
+                    }
+                }
+                P2pChannelsTransactionAction::Libp2pBroadcast(action) => {
+                    action.effects(&meta, store);
+                }
+            },
             P2pChannelsAction::SnarkJobCommitment(action) => match action {
                 P2pChannelsSnarkJobCommitmentAction::Init(action) => {
                     action.effects(&meta, store);
@@ -371,9 +502,19 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                     store.dispatch(TransitionFrontierSyncBlocksPeersQueryAction {});
                 }
                 P2pChannelsRpcAction::RequestSend(action) => {
+                    if let Some(stats) = store.service.stats() {
+                        stats.p2p_rpc_request_sent(
+                            meta.time(),
+                            action.peer_id,
+                            action.request.kind(),
+                        );
+                    }
                     action.effects(&meta, store);
                 }
                 P2pChannelsRpcAction::Timeout(action) => {
+                    if let Some(stats) = store.service.stats() {
+                        stats.p2p_rpc_timeout(&action.peer_id);
+                    }
                     store.dispatch(TransitionFrontierSyncLedgerSnarkedPeerQueryErrorAction {
                         peer_id: action.peer_id,
                         rpc_id: action.id,
@@ -391,12 +532,19 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                         rpc_id: action.id,
                         error: PeerBlockFetchError::Timeout,
                     });
+                    store.dispatch(P2pPeerPenalizeAction {
+                        peer_id: action.peer_id,
+                        offense: P2pPeerOffense::RpcTimeout,
+                    });
                     store.dispatch(P2pDisconnectionInitAction {
                         peer_id: action.peer_id,
                         reason: P2pDisconnectionReason::TransitionFrontierRpcTimeout,
                     });
                 }
                 P2pChannelsRpcAction::ResponseReceived(action) => {
+                    if let Some(stats) = store.service.stats() {
+                        stats.p2p_rpc_response_received(meta.time(), &action.peer_id);
+                    }
                     action.effects(&meta, store);
                     match action.response.as_ref() {
                         None => {
@@ -454,13 +602,40 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                         }
                         Some(P2pRpcResponse::LedgerQuery(answer)) => match answer {
                             MinaLedgerSyncLedgerAnswerStableV2::ChildHashesAre(left, right) => {
+                                let hashes = (left.clone(), right.clone());
+                                let query = store.state().transition_frontier.sync.ledger();
+                                let query = query.and_then(|s| {
+                                    let snarked = s.snarked()?;
+                                    let (address, _) =
+                                        snarked.peer_query_get(&action.peer_id, action.id)?;
+                                    Some((s.snarked_ledger_hash().clone(), address.clone()))
+                                });
+                                let is_valid = query.map_or(true, |(ledger_hash, address)| {
+                                    store
+                                        .service
+                                        .hashes_check(ledger_hash, &address, &hashes)
+                                        .is_ok()
+                                });
+                                if !is_valid {
+                                    store.dispatch(
+                                        TransitionFrontierSyncLedgerSnarkedPeerQueryErrorAction {
+                                            peer_id: action.peer_id,
+                                            rpc_id: action.id,
+                                            error: PeerLedgerQueryError::InvalidData,
+                                        },
+                                    );
+                                    store.dispatch(P2pPeerPenalizeAction {
+                                        peer_id: action.peer_id,
+                                        offense: P2pPeerOffense::InvalidLedgerHashes,
+                                    });
+                                    return;
+                                }
                                 store.dispatch(
                                     TransitionFrontierSyncLedgerSnarkedPeerQuerySuccessAction {
                                         peer_id: action.peer_id,
                                         rpc_id: action.id,
                                         response: PeerLedgerQueryResponse::ChildHashes(
-                                            left.clone(),
-                                            right.clone(),
+                                            hashes.0, hashes.1,
                                         ),
                                     },
                                 );
@@ -507,6 +682,10 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                                 peers: peers.clone(),
                             });
                         }
+                        // We only serve these for now.
+                        Some(P2pRpcResponse::TransitionChainProof(..))
+                        | Some(P2pRpcResponse::NodeStatus(_))
+                        | Some(P2pRpcResponse::EpochLedger(_)) => {}
                     }
                     store.dispatch(TransitionFrontierSyncLedgerSnarkedPeersQueryAction {});
                     store.dispatch(TransitionFrontierSyncLedgerStagedPartsPeerFetchInitAction {});
@@ -516,21 +695,18 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                     match action.request {
                         P2pRpcRequest::BestTipWithProof => {
                             let best_chain = &store.state().transition_frontier.best_chain;
-                            let response = None.or_else(|| {
-                                let best_tip = best_chain.last()?;
-                                let mut chain_iter = best_chain.iter();
-                                let root_block = chain_iter.next()?;
-                                // TODO(binier): cache body hashes
-                                let body_hashes = chain_iter
-                                    .map(|b| b.block.header.protocol_state.body.hash())
-                                    .collect();
-
-                                Some(BestTipWithProof {
-                                    best_tip: best_tip.block.clone(),
-                                    proof: (body_hashes, root_block.block.clone()),
-                                })
+                            let response = best_tip_with_proof(best_chain)
+                                .map(P2pRpcResponse::BestTipWithProof);
+                            store.dispatch(P2pChannelsRpcResponseSendAction {
+                                peer_id: action.peer_id,
+                                id: action.id,
+                                response,
                             });
-                            let response = response.map(P2pRpcResponse::BestTipWithProof);
+                        }
+                        P2pRpcRequest::Ancestry(hash, consensus_state) => {
+                            let best_chain = &store.state().transition_frontier.best_chain;
+                            let response = ancestry_proof(best_chain, &hash, &consensus_state)
+                                .map(P2pRpcResponse::BestTipWithProof);
                             store.dispatch(P2pChannelsRpcResponseSendAction {
                                 peer_id: action.peer_id,
                                 id: action.id,
@@ -635,6 +811,52 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                                 response,
                             });
                         }
+                        P2pRpcRequest::TransitionChainProof(hash) => {
+                            let best_chain = &store.state().transition_frontier.best_chain;
+                            let response = transition_chain_proof(best_chain, &hash).map(
+                                |(first_hash, body_hashes)| {
+                                    P2pRpcResponse::TransitionChainProof(first_hash, body_hashes)
+                                },
+                            );
+                            store.dispatch(P2pChannelsRpcResponseSendAction {
+                                peer_id: action.peer_id,
+                                id: action.id,
+                                response,
+                            });
+                        }
+                        P2pRpcRequest::NodeStatus => {
+                            let response = node_status(store.state(), meta.time())
+                                .map(|status| P2pRpcResponse::NodeStatus(Box::new(status)));
+                            store.dispatch(P2pChannelsRpcResponseSendAction {
+                                peer_id: action.peer_id,
+                                id: action.id,
+                                response,
+                            });
+                        }
+                        P2pRpcRequest::EpochLedger(ledger_hash) => {
+                            // Only staking and next epoch ledgers of the
+                            // best tip are served, like in the OCaml node.
+                            let is_epoch_ledger = store
+                                .state()
+                                .transition_frontier
+                                .best_tip()
+                                .map_or(false, |best_tip| {
+                                    let consensus_state =
+                                        &best_tip.header().protocol_state.body.consensus_state;
+                                    consensus_state.staking_epoch_data.ledger.hash == ledger_hash
+                                        || consensus_state.next_epoch_data.ledger.hash
+                                            == ledger_hash
+                                });
+                            let response = Some(ledger_hash)
+                                .filter(|_| is_epoch_ledger)
+                                .and_then(|hash| store.service.epoch_ledger(hash))
+                                .map(P2pRpcResponse::EpochLedger);
+                            store.dispatch(P2pChannelsRpcResponseSendAction {
+                                peer_id: action.peer_id,
+                                id: action.id,
+                                response,
+                            });
+                        }
                     }
                 }
                 P2pChannelsRpcAction::ResponseSend(action) => {
@@ -644,6 +866,19 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
         },
         P2pAction::Peer(action) => match action {
             P2pPeerAction::Ready(action) => {
+                let address = store
+                    .state()
+                    .p2p
+                    .peers
+                    .get(&action.peer_id)
+                    .and_then(|peer| peer.dial_opts.as_ref())
+                    .map(|opts| opts.to_string());
+                store.dispatch(RpcEventNotifyAction {
+                    event: RpcEvent::PeerConnected {
+                        peer_id: action.peer_id,
+                        address,
+                    },
+                });
                 action.effects(&meta, store);
             }
             P2pPeerAction::BestTipUpdate(action) => {
@@ -656,6 +891,262 @@ pub fn p2p_effects<S: Service>(store: &mut Store<S>, action: P2pActionWithMeta)
                 store.dispatch(TransitionFrontierSyncLedgerStagedPartsPeerFetchInitAction {});
                 store.dispatch(TransitionFrontierSyncBlocksPeersQueryAction {});
             }
+            P2pPeerAction::Penalize(action) => {
+                action.effects(&meta, store);
+            }
+            P2pPeerAction::Ban(action) => {
+                action.effects(&meta, store);
+            }
+            P2pPeerAction::Unban(action) => {
+                action.effects(&meta, store);
+            }
+            P2pPeerAction::Forgive(_) => {}
         },
     }
 }
+
+/// Records the update in the persisted peer address book.
+fn peer_store_update<S: Service>(
+    store: &mut Store<S>,
+    meta: &redux::ActionMeta,
+    update: P2pPeerStoreUpdate,
+) {
+    let Some(peer_store) = store.service.peer_store() else {
+        return;
+    };
+    if let Err(err) = peer_store.update(update, meta.time()) {
+        openmina_core::log::warn!(meta.time(); kind = "P2pPeerStoreSave", summary = format!("failed to save peer store: {err}"));
+    }
+}
+
+/// Our best tip with the body hashes of the blocks leading to it from
+/// the root block, which is included too.
+fn best_tip_with_proof(best_chain: &[ArcBlockWithHash]) -> Option<BestTipWithProof> {
+    let (root_block, chain) = best_chain.split_first()?;
+    let best_tip = best_chain.last()?;
+    // TODO(binier): cache body hashes
+    let body_hashes = chain
+        .iter()
+        .map(|b| b.block.header.protocol_state.body.hash())
+        .collect();
+
+    Some(BestTipWithProof {
+        best_tip: best_tip.block.clone(),
+        proof: (body_hashes, root_block.block.clone()),
+    })
+}
+
+/// Like the OCaml node, our best tip with proof is only sent in response
+/// to the ancestry query, if it would be taken over the peer's block.
+fn ancestry_proof(
+    best_chain: &[ArcBlockWithHash],
+    hash: &StateHash,
+    consensus_state: &MinaConsensusState,
+) -> Option<BestTipWithProof> {
+    let best_tip = best_chain.last()?;
+    let best_tip_cs = &best_tip.header().protocol_state.body.consensus_state;
+    if !consensus_take(consensus_state, best_tip_cs, hash, &best_tip.hash) {
+        return None;
+    }
+    best_tip_with_proof(best_chain)
+}
+
+/// Hash of our root block and the body hashes of the blocks following
+/// it, up to the one with the given hash.
+fn transition_chain_proof(
+    best_chain: &[ArcBlockWithHash],
+    hash: &StateHash,
+) -> Option<(StateHash, Vec<MinaBaseStateBodyHashStableV1>)> {
+    let index = best_chain.iter().position(|b| &b.hash == hash)?;
+    let first_hash = best_chain.first()?.hash.clone();
+    let body_hashes = best_chain[1..=index]
+        .iter()
+        .map(|b| b.block.header.protocol_state.body.hash())
+        .collect();
+    Some((first_hash, body_hashes))
+}
+
+/// Sync status of the node, as reported by the Mina daemon.
+pub fn node_sync_status(state: &State) -> P2pNodeSyncStatus {
+    match &state.transition_frontier.sync {
+        _ if !state.p2p.any_ready_peers() => P2pNodeSyncStatus::Connecting,
+        TransitionFrontierSyncState::Idle => P2pNodeSyncStatus::Listening,
+        TransitionFrontierSyncState::Init { .. }
+        | TransitionFrontierSyncState::StakingLedgerPending { .. }
+        | TransitionFrontierSyncState::StakingLedgerSuccess { .. }
+        | TransitionFrontierSyncState::NextEpochLedgerPending { .. }
+        | TransitionFrontierSyncState::NextEpochLedgerSuccess { .. }
+        | TransitionFrontierSyncState::RootLedgerPending { .. }
+        | TransitionFrontierSyncState::RootLedgerSuccess { .. } => P2pNodeSyncStatus::Bootstrap,
+        TransitionFrontierSyncState::BlocksPending { .. }
+        | TransitionFrontierSyncState::BlocksSuccess { .. } => P2pNodeSyncStatus::Catchup,
+        TransitionFrontierSyncState::Synced { .. } => P2pNodeSyncStatus::Synced,
+    }
+}
+
+/// Status of the node as reported by `get_node_status` rpc. `None`
+/// if we don't have a best tip yet.
+fn node_status(state: &State, now: redux::Timestamp) -> Option<P2pNodeStatus> {
+    let best_chain = &state.transition_frontier.best_chain;
+    let best_tip = best_chain.last()?;
+
+    Some(P2pNodeStatus {
+        sync_status: node_sync_status(state),
+        peers: state.p2p.ready_peers(),
+        block_producers: state
+            .block_producer
+            .config()
+            .map(|config| config.pub_key.clone().into())
+            .into_iter()
+            .collect(),
+        best_tip_hash: best_tip.hash.clone(),
+        banned_peers: banned_peers(&state.p2p, now),
+        k_block_hashes_and_timestamps: best_chain
+            .iter()
+            .map(|b| {
+                let timestamp_ms = u64::from(b.timestamp()) / 1_000_000;
+                (b.hash.clone(), timestamp_ms.to_string())
+            })
+            .collect(),
+        git_commit: state.config.build.git.commit_hash.clone(),
+        block_height: Some(best_tip.height()),
+    })
+}
+
+/// Currently banned peers with the time (in milliseconds) their ban
+/// expires at.
+fn banned_peers(p2p: &P2pState, now: redux::Timestamp) -> Vec<(PeerId, i64)> {
+    p2p.reputation
+        .iter()
+        .filter_map(|(peer_id, reputation)| {
+            let ban = reputation.ban.as_ref().filter(|ban| !ban.is_expired(now))?;
+            let banned_until = u64::from(ban.since) / 1_000_000 + ban.duration.as_millis() as u64;
+            Some((*peer_id, banned_until as i64))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use ledger::{scan_state::currency::Balance, AccountId, TokenId};
+
+    use crate::account::AccountSecretKey;
+    use crate::p2p::channels::ChannelId;
+    use crate::p2p::identity::SecretKey;
+    use crate::p2p::{P2pConfig, P2pPeerBan, P2pPeerReputation};
+    use crate::transition_frontier::genesis_block;
+    use crate::NetworkConfig;
+
+    use super::*;
+
+    /// Chain of `len` blocks starting with the genesis block. Only the
+    /// fields needed for hashes to link up are updated.
+    fn chain(len: usize) -> Vec<ArcBlockWithHash> {
+        let network = NetworkConfig::berkeley();
+        let producer = AccountSecretKey::rand().public_key();
+        let account_id = AccountId::new(producer.as_ref().into(), TokenId::default());
+        let account =
+            ledger::Account::create_with(account_id, Balance::from_u64(1_000_000_000_000));
+        let genesis = genesis_block(
+            &network.protocol_constants,
+            &network.constraint_constants,
+            &[account],
+        )
+        .unwrap();
+
+        std::iter::successors(Some(genesis), |pred| {
+            let mut block = (*pred.block).clone();
+            let protocol_state = &mut block.header.protocol_state;
+            protocol_state.previous_state_hash = pred.hash.clone();
+            let consensus_state = &mut protocol_state.body.consensus_state;
+            consensus_state.blockchain_length = (pred.height() + 1).into();
+            Some(BlockWithHash::new(Arc::new(block)))
+        })
+        .take(len)
+        .collect()
+    }
+
+    fn chain_hash(
+        first_hash: StateHash,
+        body_hashes: &[MinaBaseStateBodyHashStableV1],
+    ) -> StateHash {
+        body_hashes.iter().fold(first_hash, |pred_hash, body_hash| {
+            StateHash::from_hashes(&pred_hash, body_hash)
+        })
+    }
+
+    #[test]
+    fn transition_chain_proof_reconstructs_hash() {
+        let chain = chain(4);
+        for block in &chain {
+            let (first_hash, body_hashes) = transition_chain_proof(&chain, &block.hash).unwrap();
+            assert_eq!(first_hash, chain[0].hash);
+            assert_eq!(chain_hash(first_hash, &body_hashes), block.hash);
+        }
+
+        let unknown = chain(5).pop().unwrap();
+        assert!(transition_chain_proof(&chain[..2], &chain[2].hash).is_none());
+        assert!(transition_chain_proof(&chain, &unknown.hash).is_none());
+    }
+
+    #[test]
+    fn best_tip_with_proof_reconstructs_best_tip_hash() {
+        let chain = chain(4);
+        let proof = best_tip_with_proof(&chain).unwrap();
+        let (body_hashes, root_block) = &proof.proof;
+        assert_eq!(root_block.hash(), chain[0].hash);
+        assert_eq!(proof.best_tip.hash(), chain[3].hash);
+        assert_eq!(chain_hash(root_block.hash(), body_hashes), chain[3].hash);
+
+        assert!(best_tip_with_proof(&[]).is_none());
+    }
+
+    #[test]
+    fn ancestry_proof_only_for_better_best_tip() {
+        let chain = chain(4);
+        let consensus_state =
+            |block: &ArcBlockWithHash| block.header().protocol_state.body.consensus_state.clone();
+
+        let older = &chain[1];
+        let proof = ancestry_proof(&chain, &older.hash, &consensus_state(older)).unwrap();
+        assert_eq!(proof.best_tip.hash(), chain[3].hash);
+
+        let best_tip = &chain[3];
+        assert!(ancestry_proof(&chain, &best_tip.hash, &consensus_state(best_tip)).is_none());
+        assert!(ancestry_proof(&chain[..3], &best_tip.hash, &consensus_state(best_tip)).is_none());
+    }
+
+    #[test]
+    fn banned_peers_reports_ban_expiry_in_millis() {
+        let mut p2p = P2pState::new(P2pConfig {
+            identity_pub_key: SecretKey::rand().public_key(),
+            initial_peers: vec![],
+            enabled_channels: ChannelId::iter_all().collect(),
+            max_peers: 10,
+            reputation: Default::default(),
+            kademlia: Default::default(),
+            gossipsub: Default::default(),
+        });
+        let banned = |since_secs: u64| P2pPeerReputation {
+            ban: Some(P2pPeerBan {
+                since: redux::Timestamp::new(since_secs * 1_000_000_000),
+                duration: Duration::from_secs(60),
+            }),
+            ..Default::default()
+        };
+        let (peer_a, peer_b, peer_c) = (
+            PeerId::from_bytes([1; 32]),
+            PeerId::from_bytes([2; 32]),
+            PeerId::from_bytes([3; 32]),
+        );
+        p2p.reputation.insert(peer_a, banned(10));
+        p2p.reputation.insert(peer_b, banned(100));
+        p2p.reputation.insert(peer_c, Default::default());
+
+        let now = redux::Timestamp::new(120 * 1_000_000_000);
+        assert_eq!(banned_peers(&p2p, now), vec![(peer_b, 160_000)]);
+    }
+}
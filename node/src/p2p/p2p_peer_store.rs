@@ -0,0 +1,378 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fs, io};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use crate::p2p::disconnection::P2pDisconnectedState;
+use crate::p2p::PeerId;
+
+/// Entries we haven't connected to (or learned about) for this long
+/// are dropped when the store is loaded.
+const PRUNE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// Entries with this many failed connection attempts in a row are
+/// dropped when the store is loaded.
+const PRUNE_AFTER_FAILURES: u32 = 20;
+/// Max addresses kept per peer.
+const MAX_ADDRESSES: usize = 8;
+/// Max peers kept in the store. Once reached, the least recently
+/// active peer is evicted to make room for a new one.
+const MAX_ENTRIES: usize = 1000;
+/// Changes are written to disk at most this often.
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Address book of the peers we have seen, with their connection
+/// history. Persisted in the work dir, so that after a restart node
+/// reconnects to the peers it knew, instead of only to the seed nodes.
+///
+/// Pending changes are written to disk when the store is dropped.
+pub struct P2pPeerStore {
+    path: PathBuf,
+    peers: BTreeMap<PeerId, P2pPeerStoreEntry>,
+    changed: bool,
+    last_save: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pPeerStoreEntry {
+    /// Most recently used addresses come first.
+    pub addresses: Vec<P2pConnectionOutgoingInitOpts>,
+    pub first_seen: Timestamp,
+    pub last_connected: Option<Timestamp>,
+    /// Failed outgoing connection attempts since the last successful
+    /// connection.
+    pub failures: u32,
+    pub last_disconnect: Option<P2pDisconnectedState>,
+}
+
+#[derive(Debug, Clone)]
+pub enum P2pPeerStoreUpdate {
+    /// Peer's addresses were discovered.
+    AddressesFound {
+        peer_id: PeerId,
+        addresses: Vec<P2pConnectionOutgoingInitOpts>,
+    },
+    Connected {
+        peer_id: PeerId,
+        dial_opts: Option<P2pConnectionOutgoingInitOpts>,
+    },
+    ConnectionFailed {
+        peer_id: PeerId,
+        dial_opts: Option<P2pConnectionOutgoingInitOpts>,
+    },
+    Disconnected {
+        peer_id: PeerId,
+        state: P2pDisconnectedState,
+    },
+}
+
+impl P2pPeerStoreEntry {
+    fn new(now: Timestamp) -> Self {
+        Self {
+            addresses: vec![],
+            first_seen: now,
+            last_connected: None,
+            failures: 0,
+            last_disconnect: None,
+        }
+    }
+
+    fn last_active(&self) -> Timestamp {
+        self.last_connected.unwrap_or(self.first_seen)
+    }
+
+    /// Entries with the lowest key get evicted first: peers we never
+    /// connected to, then the least recently active ones.
+    fn eviction_key(&self) -> (bool, Timestamp) {
+        (self.last_connected.is_some(), self.last_active())
+    }
+
+    fn is_dead(&self, now: Timestamp) -> bool {
+        self.failures >= PRUNE_AFTER_FAILURES
+            || now
+                .checked_sub(self.last_active())
+                .map_or(false, |d| d > PRUNE_AFTER)
+    }
+
+    fn add_address(&mut self, opts: P2pConnectionOutgoingInitOpts, most_recent: bool) {
+        match self.addresses.iter().position(|v| v == &opts) {
+            Some(i) if most_recent => {
+                let opts = self.addresses.remove(i);
+                self.addresses.insert(0, opts);
+            }
+            Some(_) => {}
+            None if most_recent => self.addresses.insert(0, opts),
+            None => self.addresses.push(opts),
+        }
+        self.addresses.truncate(MAX_ADDRESSES);
+    }
+}
+
+impl P2pPeerStore {
+    /// Loads the store from `path`, or starts with an empty one if the
+    /// file doesn't exist yet. Long-dead entries are pruned.
+    pub fn open<P: AsRef<Path>>(path: P, now: Timestamp) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+        let peers: BTreeMap<PeerId, P2pPeerStoreEntry> = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Default::default(),
+            Err(err) => return Err(err),
+        };
+        let len = peers.len();
+        let peers: BTreeMap<_, _> = peers
+            .into_iter()
+            .filter(|(_, entry)| !entry.is_dead(now) && !entry.addresses.is_empty())
+            .collect();
+        let mut store = Self {
+            changed: peers.len() != len,
+            path,
+            peers,
+            last_save: None,
+        };
+        while store.peers.len() > MAX_ENTRIES {
+            store.evict();
+        }
+        Ok(store)
+    }
+
+    /// Entry of the peer, inserted if it doesn't exist yet. If the store
+    /// is full, an entry is evicted to make room for it.
+    fn entry(&mut self, peer_id: PeerId, now: Timestamp) -> &mut P2pPeerStoreEntry {
+        if !self.peers.contains_key(&peer_id) && self.peers.len() >= MAX_ENTRIES {
+            self.evict();
+        }
+        self.peers
+            .entry(peer_id)
+            .or_insert_with(|| P2pPeerStoreEntry::new(now))
+    }
+
+    fn evict(&mut self) {
+        let peer_id = self
+            .peers
+            .iter()
+            .min_by_key(|(_, entry)| entry.eviction_key())
+            .map(|(peer_id, _)| *peer_id);
+        if let Some(peer_id) = peer_id {
+            self.peers.remove(&peer_id);
+            self.changed = true;
+        }
+    }
+
+    pub fn get(&self, peer_id: &PeerId) -> Option<&P2pPeerStoreEntry> {
+        self.peers.get(peer_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    /// Peers to seed `P2pState::known_peers` with. Most recently
+    /// connected peers come first, peers we never connected to last.
+    pub fn initial_peers(&self) -> Vec<P2pConnectionOutgoingInitOpts> {
+        let mut peers = self
+            .peers
+            .values()
+            .filter_map(|entry| Some((entry, entry.addresses.first()?)))
+            .collect::<Vec<_>>();
+        peers.sort_by(|(a, _), (b, _)| {
+            b.last_connected
+                .cmp(&a.last_connected)
+                .then(a.failures.cmp(&b.failures))
+        });
+        peers.into_iter().map(|(_, opts)| opts.clone()).collect()
+    }
+
+    /// Applies the update and writes the store to disk, if enough time
+    /// has passed since the last write.
+    pub fn update(&mut self, update: P2pPeerStoreUpdate, now: Timestamp) -> io::Result<()> {
+        match update {
+            P2pPeerStoreUpdate::AddressesFound { peer_id, addresses } => {
+                let entry = self.entry(peer_id, now);
+                for opts in addresses {
+                    entry.add_address(opts, false);
+                }
+            }
+            P2pPeerStoreUpdate::Connected { peer_id, dial_opts } => {
+                let entry = self.entry(peer_id, now);
+                if let Some(opts) = dial_opts {
+                    entry.add_address(opts, true);
+                }
+                entry.last_connected = Some(now);
+                entry.failures = 0;
+            }
+            P2pPeerStoreUpdate::ConnectionFailed { peer_id, dial_opts } => {
+                let entry = self.entry(peer_id, now);
+                if let Some(opts) = dial_opts {
+                    entry.add_address(opts, false);
+                }
+                entry.failures = entry.failures.saturating_add(1);
+            }
+            P2pPeerStoreUpdate::Disconnected { peer_id, state } => {
+                let Some(entry) = self.peers.get_mut(&peer_id) else {
+                    return Ok(());
+                };
+                entry.last_disconnect = Some(state);
+            }
+        }
+        self.changed = true;
+
+        let save_needed = self
+            .last_save
+            .and_then(|t| now.checked_sub(t))
+            .map_or(true, |d| d >= SAVE_INTERVAL);
+        if save_needed {
+            self.save(now)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the store to disk, if it changed since the last write.
+    pub fn save(&mut self, now: Timestamp) -> io::Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        let bytes = serde_json::to_vec_pretty(&self.peers)?;
+        // Write to a temporary file first, so that the store doesn't
+        // get corrupted if node is killed in the middle of the write.
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.changed = false;
+        self.last_save = Some(now);
+        Ok(())
+    }
+}
+
+impl Drop for P2pPeerStore {
+    fn drop(&mut self) {
+        if let Err(err) = self.save(Timestamp::global_now()) {
+            openmina_core::log::warn!(
+                Timestamp::global_now();
+                kind = "P2pPeerStoreSave",
+                summary = format!("failed to save peer store on shutdown: {err}")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::p2p::identity::SecretKey;
+
+    fn opts(peer_id: PeerId, port: u16) -> P2pConnectionOutgoingInitOpts {
+        format!("/{peer_id}/http/127.0.0.1/{port}").parse().unwrap()
+    }
+
+    #[test]
+    fn peer_store_persists_and_prunes() {
+        let path = std::env::temp_dir().join(format!(
+            "openmina-peer-store-test-{}.json",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        let at = |secs: u64| Timestamp::new(secs * 1_000_000_000);
+        let now = at(1_000_000);
+        let good = SecretKey::rand().public_key().peer_id();
+        let dead = SecretKey::rand().public_key().peer_id();
+
+        let mut store = P2pPeerStore::open(&path, now).unwrap();
+        assert!(store.is_empty());
+        store
+            .update(
+                P2pPeerStoreUpdate::AddressesFound {
+                    peer_id: dead,
+                    addresses: vec![opts(dead, 3000)],
+                },
+                now,
+            )
+            .unwrap();
+        for _ in 0..PRUNE_AFTER_FAILURES {
+            let update = P2pPeerStoreUpdate::ConnectionFailed {
+                peer_id: dead,
+                dial_opts: None,
+            };
+            store.update(update, now).unwrap();
+        }
+        let update = P2pPeerStoreUpdate::Connected {
+            peer_id: good,
+            dial_opts: Some(opts(good, 3001)),
+        };
+        store.update(update, now).unwrap();
+        let update = P2pPeerStoreUpdate::Disconnected {
+            peer_id: good,
+            state: P2pDisconnectedState {
+                reason: "banned".to_owned(),
+            },
+        };
+        store.update(update, now).unwrap();
+        store.save(now).unwrap();
+
+        let store = P2pPeerStore::open(&path, at(1_000_060)).unwrap();
+        assert_eq!(store.initial_peers(), vec![opts(good, 3001)]);
+        let entry = store.get(&good).unwrap();
+        assert_eq!(entry.last_connected, Some(now));
+        assert_eq!(entry.last_disconnect.as_ref().unwrap().reason, "banned");
+
+        let store = P2pPeerStore::open(&path, at(1_000_000 + 2 * PRUNE_AFTER.as_secs())).unwrap();
+        assert!(store.is_empty());
+
+        drop(store);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn peer_store_evicts_when_full() {
+        let path = std::env::temp_dir().join(format!(
+            "openmina-peer-store-evict-test-{}.json",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        let at = |secs: u64| Timestamp::new(secs * 1_000_000_000);
+        let peer_id = |i: usize| {
+            let mut bytes = [0; 32];
+            bytes[..8].copy_from_slice(&(i as u64).to_be_bytes());
+            PeerId::from_bytes(bytes)
+        };
+
+        let mut store = P2pPeerStore::open(&path, at(0)).unwrap();
+        let connected = peer_id(0);
+        let update = P2pPeerStoreUpdate::Connected {
+            peer_id: connected,
+            dial_opts: Some(opts(connected, 3000)),
+        };
+        store.update(update, at(0)).unwrap();
+        for i in 1..=MAX_ENTRIES {
+            let update = P2pPeerStoreUpdate::AddressesFound {
+                peer_id: peer_id(i),
+                addresses: vec![opts(peer_id(i), 3000)],
+            };
+            store.update(update, at(i as u64)).unwrap();
+        }
+
+        assert_eq!(store.len(), MAX_ENTRIES);
+        // Peer we connected to is kept, the oldest discovered one isn't.
+        assert!(store.get(&connected).is_some());
+        assert!(store.get(&peer_id(1)).is_none());
+        assert!(store.get(&peer_id(MAX_ENTRIES)).is_some());
+
+        // Pending changes get flushed on drop.
+        drop(store);
+        let store = P2pPeerStore::open(&path, at(MAX_ENTRIES as u64)).unwrap();
+        assert_eq!(store.len(), MAX_ENTRIES);
+        assert!(store.get(&peer_id(MAX_ENTRIES)).is_some());
+
+        let _ = fs::remove_file(&path);
+    }
+}
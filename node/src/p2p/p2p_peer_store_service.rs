@@ -0,0 +1,6 @@
+use super::P2pPeerStore;
+
+pub trait P2pPeerStoreService: redux::Service {
+    /// Persisted peer address book. `None` if it isn't enabled.
+    fn peer_store(&mut self) -> Option<&mut P2pPeerStore>;
+}
@@ -1,8 +1,9 @@
 mod rpc_state;
 use mina_p2p_messages::v2::{
-    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseTransactionStatusStableV2,
-    MinaBaseUserCommandStableV2, MinaTransactionTransactionStableV2,
-    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, StateHash, TransactionHash,
+    LedgerHash, MinaBaseAccountBinableArgStableV2, MinaBaseSignedCommandPayloadBodyStableV2,
+    MinaBaseTransactionStatusStableV2, MinaBaseUserCommandStableV2,
+    MinaTransactionTransactionStableV2, SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+    SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery, StateHash, TokenIdKeyHash, TransactionHash,
 };
 pub use rpc_state::*;
 
@@ -20,28 +21,39 @@ pub use rpc_service::*;
 
 mod rpc_impls;
 
+mod rpc_state_filter;
+pub use rpc_state_filter::state_get;
+
 use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
 use ledger::scan_state::scan_state::AvailableJobMessage;
 use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
+use openmina_core::block::ArcBlockWithHash;
 pub use openmina_core::requests::{RpcId, RpcIdType};
 use openmina_core::snark::SnarkJobId;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
 
+use crate::account::AccountPublicKey;
 use crate::external_snark_worker::{
     ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkSpecError,
 };
+use crate::p2p::channels::rpc::{P2pNodeSyncStatus, P2pRpcKind};
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
-use crate::p2p::PeerId;
+use crate::p2p::peer::P2pPeerOffense;
+use crate::p2p::{P2pPeerBan, PeerId};
 use crate::snark_pool::{JobCommitment, JobSummary};
 use crate::stats::actions::{ActionStatsForBlock, ActionStatsSnapshot};
+use crate::stats::durations::{DurationHistogram, P2pRpcKindStats};
 use crate::stats::sync::SyncStatsSnapshot;
+use crate::transaction_pool::TransactionState;
 use crate::State;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RpcRequest {
-    StateGet,
+    StateGet(RpcStateGetQuery),
     ActionStatsGet(ActionStatsQuery),
     SyncStatsGet(SyncStatsQuery),
     P2pConnectionOutgoing(P2pConnectionOutgoingInitOpts),
@@ -53,10 +65,35 @@ pub enum RpcRequest {
     SnarkerJobCommit { job_id: SnarkJobId },
     SnarkerJobSpec { job_id: SnarkJobId },
     SnarkerWorkers,
+    SnarkerWorkGet,
+    SnarkerWorkSubmit(Box<SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery>),
+    TransactionNonceGet { public_key: AccountPublicKey },
+    TransactionSend(Box<MinaBaseUserCommandStableV2>),
+    StakeGet(RpcStakeGetQuery),
+    BestChainGet { max_length: u32 },
+    BlockGet(RpcBlockGetQuery),
+    AccountGet(RpcAccountGetQuery),
+    TransactionPoolGet,
+    NodeStatusGet,
+    MetricsGet,
+    Subscribe(RpcSubscribeQuery),
+    PeersReputationGet,
+    PeerBan(RpcPeerBanQuery),
+    PeerUnban { peer_id: PeerId },
     HealthCheck,
     ReadinessCheck,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RpcStateGetQuery {
+    /// Path to the part of the state to get, e.g. `transition_frontier.sync`
+    /// or `p2p.peers.*.status`. Whole state if `None`.
+    pub path: Option<String>,
+    /// Replace deeply nested objects and arrays with their size.
+    #[serde(default)]
+    pub summary: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ActionStatsQuery {
     SinceStart,
@@ -76,6 +113,41 @@ pub enum RpcScanStateSummaryGetQuery {
     ForBlockWithHeight(u32),
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RpcSubscribeQuery {
+    /// Kinds of events to send to the subscriber, all if empty.
+    pub kinds: BTreeSet<RpcEventKind>,
+    /// Accounts for which `AccountUpdated` events are sent.
+    pub accounts: Vec<AccountPublicKey>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcStakeGetQuery {
+    pub ledger: RpcStakeLedger,
+    pub public_key: AccountPublicKey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum RpcStakeLedger {
+    /// Staking ledger of the best tip's epoch.
+    Staking,
+    /// Staking ledger of the epoch following the best tip's one.
+    NextEpoch,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcBlockGetQuery {
+    WithHash(StateHash),
+    WithHeight(u32),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcAccountGetQuery {
+    pub public_key: AccountPublicKey,
+    /// If `None`, account of the default (MINA) token is returned.
+    pub token_id: Option<TokenIdKeyHash>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "kind")]
 pub enum ActionStatsResponse {
@@ -203,7 +275,38 @@ pub enum RpcSnarkerJobSpecResponse {
     JobNotFound,
 }
 
-pub type RpcStateGetResponse = Box<State>;
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcSnarkerWorkSubmitResponse {
+    Ok,
+    JobNotFound,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum RpcStateGetValue {
+    /// Whole state, serialized only when it's sent to the client.
+    Full(Box<State>),
+    /// Part of the state selected by the query.
+    Selected(serde_json::Value),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
+pub enum RpcStateGetError {
+    #[error("path not found in the state: {_0}")]
+    PathNotFound(String),
+    #[error("failed to serialize the state: {_0}")]
+    Serialization(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcTransactionSendResponse {
+    Ok { hash: TransactionHash },
+    Rejected { error: String },
+}
+
+pub type RpcStateGetResponse = Result<RpcStateGetValue, RpcStateGetError>;
 pub type RpcActionStatsGetResponse = Option<ActionStatsResponse>;
 pub type RpcSyncStatsGetResponse = Option<Vec<SyncStatsSnapshot>>;
 pub type RpcP2pConnectionOutgoingResponse = Result<(), String>;
@@ -211,6 +314,224 @@ pub type RpcScanStateSummaryGetResponse = Option<RpcScanStateSummary>;
 pub type RpcSnarkPoolGetResponse = Vec<RpcSnarkPoolJobSummary>;
 pub type RpcSnarkPoolJobGetResponse = Option<RpcSnarkPoolJobFull>;
 pub type RpcSnarkerConfigGetResponse = Option<RpcSnarkerConfig>;
+/// Job for a remote snark worker, `None` if there is no available job.
+pub type RpcSnarkerWorkGetResponse = SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse;
+/// Nonce for the next transaction of the account, taking into account
+/// its transactions in the pool. `None` if the account doesn't exist.
+pub type RpcTransactionNonceGetResponse = Option<u32>;
+/// `None` if we don't have the ledger or the account isn't in it.
+pub type RpcStakeGetResponse = Option<RpcStake>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcStake {
+    pub epoch: u32,
+    pub ledger_hash: LedgerHash,
+    pub public_key: AccountPublicKey,
+    pub balance: u64,
+    pub delegate: Option<AccountPublicKey>,
+    /// Accounts delegating their stake to this one, including itself
+    /// if it isn't delegating to someone else.
+    pub delegators: Vec<RpcStakeDelegator>,
+    /// Sum of the delegators' balances.
+    pub delegated_stake: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcStakeDelegator {
+    pub public_key: AccountPublicKey,
+    pub balance: u64,
+}
+
+/// Blocks of the best chain, from the oldest to the best tip.
+pub type RpcBestChainGetResponse = Vec<ArcBlockWithHash>;
+/// `None` if the block isn't in our best chain.
+pub type RpcBlockGetResponse = Option<ArcBlockWithHash>;
+/// `None` if we don't have a best tip or the account isn't in its
+/// staged ledger.
+pub type RpcAccountGetResponse = Option<RpcAccount>;
+/// Transactions in the pool, in the order they were added.
+pub type RpcTransactionPoolGetResponse = Vec<TransactionState>;
+pub type RpcNodeStatusGetResponse = RpcNodeStatus;
+pub type RpcMetricsGetResponse = RpcMetrics;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcAccount {
+    pub account: Box<MinaBaseAccountBinableArgStableV2>,
+    /// Nonce for the next transaction of the account, taking into
+    /// account its transactions in the pool.
+    pub inferred_nonce: u32,
+    /// Best tip, from the staged ledger of which the account was taken.
+    pub block_height: u32,
+    pub state_hash: StateHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcNodeStatus {
+    pub sync_status: P2pNodeSyncStatus,
+    pub best_tip: Option<RpcNodeStatusBestTip>,
+    /// Height of the best block we received, which isn't necessarily
+    /// applied yet.
+    pub highest_block_length_received: Option<u32>,
+    pub peers: Vec<RpcNodeStatusPeer>,
+    pub block_producer: Option<AccountPublicKey>,
+    pub snarker: Option<AccountPublicKey>,
+    pub snark_work_fee: Option<u64>,
+    pub commit_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcNodeStatusBestTip {
+    pub hash: StateHash,
+    pub height: u32,
+    pub global_slot: u32,
+    /// Merkle root of the staged ledger, `non_snark.ledger_hash` of the
+    /// blockchain state.
+    pub staged_ledger_hash: LedgerHash,
+    pub snarked_ledger_hash: LedgerHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcNodeStatusPeer {
+    pub peer_id: PeerId,
+    /// Address the peer can be dialed at, if known.
+    pub address: Option<String>,
+}
+
+/// Event sent to the subscribers (see `RpcRequest::Subscribe`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcEvent {
+    BestTip {
+        hash: StateHash,
+        height: u32,
+        global_slot: u32,
+    },
+    SyncPhase {
+        phase: RpcSyncPhase,
+    },
+    PeerConnected {
+        peer_id: PeerId,
+        /// Address the peer can be dialed at, if known.
+        address: Option<String>,
+    },
+    PeerDisconnected {
+        peer_id: PeerId,
+    },
+    SnarkJobAdded {
+        job_id: SnarkJobId,
+    },
+    SnarkJobCompleted {
+        job_id: SnarkJobId,
+        snarker: NonZeroCurvePoint,
+        fee: CurrencyFeeStableV1,
+    },
+    /// Watched account was affected by the new best tip.
+    AccountUpdated {
+        public_key: AccountPublicKey,
+        block_hash: StateHash,
+        block_height: u32,
+        /// Default token account in the best tip's staged ledger, `None`
+        /// if we don't have the ledger or the account isn't in it.
+        account: Option<Box<MinaBaseAccountBinableArgStableV2>>,
+        /// Commands of the block which affected the account.
+        transactions: Vec<TransactionHash>,
+    },
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    Debug,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    Clone,
+    Copy,
+)]
+pub enum RpcEventKind {
+    BestTip,
+    SyncPhase,
+    PeerConnected,
+    PeerDisconnected,
+    SnarkJobAdded,
+    SnarkJobCompleted,
+    AccountUpdated,
+}
+
+impl RpcEvent {
+    pub fn kind(&self) -> RpcEventKind {
+        match self {
+            Self::BestTip { .. } => RpcEventKind::BestTip,
+            Self::SyncPhase { .. } => RpcEventKind::SyncPhase,
+            Self::PeerConnected { .. } => RpcEventKind::PeerConnected,
+            Self::PeerDisconnected { .. } => RpcEventKind::PeerDisconnected,
+            Self::SnarkJobAdded { .. } => RpcEventKind::SnarkJobAdded,
+            Self::SnarkJobCompleted { .. } => RpcEventKind::SnarkJobCompleted,
+            Self::AccountUpdated { .. } => RpcEventKind::AccountUpdated,
+        }
+    }
+}
+
+/// Phase of the transition frontier sync, which the node entered.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum RpcSyncPhase {
+    StakingLedger,
+    NextEpochLedger,
+    RootLedger,
+    Blocks,
+    Synced,
+}
+
+/// Values exported by the node's metrics endpoint. Stats are `None`
+/// if the node doesn't collect them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcMetrics {
+    /// Stats of actions since the start of the node.
+    pub actions: Option<ActionStatsSnapshot>,
+    /// Latencies and timeouts of our requests to peers.
+    pub p2p_rpc: BTreeMap<P2pRpcKind, P2pRpcKindStats>,
+    pub block_apply: Option<DurationHistogram>,
+    /// Stats of the latest sync (bootstrap or catchup).
+    pub last_sync: Option<SyncStatsSnapshot>,
+    pub peers: Vec<RpcMetricsPeers>,
+    pub snark_pool: RpcMetricsSnarkPool,
+    pub transaction_pool_size: usize,
+    pub best_tip_height: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcMetricsPeers {
+    pub transport: RpcPeerTransport,
+    pub status: RpcPeerStatus,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Copy)]
+pub enum RpcPeerTransport {
+    WebRTC,
+    LibP2P,
+    /// We don't know the peer's address.
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Copy)]
+pub enum RpcPeerStatus {
+    Connecting,
+    Ready,
+    Disconnected,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcMetricsSnarkPool {
+    pub jobs: usize,
+    /// Jobs that some snarker committed to do.
+    pub committed_jobs: usize,
+    /// Jobs for which we already have a snark.
+    pub snarks: usize,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcSnarkerConfig {
@@ -222,6 +543,8 @@ pub struct RpcSnarkerConfig {
 pub struct RpcSnarkWorker {
     pub time: Option<Timestamp>,
     pub id: Option<String>,
+    /// Number of failures since the last successfully produced work.
+    pub failures: u32,
     pub status: RpcSnarkWorkerStatus,
 }
 
@@ -281,5 +604,27 @@ impl From<&MinaBaseUserCommandStableV2> for RpcScanStateSummaryBlockTransactionK
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPeerBanQuery {
+    pub peer_id: PeerId,
+    /// If `None`, ban duration from the config is used.
+    pub duration: Option<Duration>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPeerReputation {
+    pub peer_id: PeerId,
+    pub is_connected: bool,
+    pub score: i32,
+    pub last_offense: Option<P2pPeerOffense>,
+    pub ban: Option<P2pPeerBan>,
+}
+
+pub type RpcPeersReputationGetResponse = Vec<RpcPeerReputation>;
+/// `false` if the action wasn't enabled, e.g. the peer is ourselves.
+pub type RpcPeerBanResponse = bool;
+/// `false` if the peer isn't banned.
+pub type RpcPeerUnbanResponse = bool;
+
 pub type RpcHealthCheckResponse = Result<(), String>;
 pub type RpcReadinessCheckResponse = Result<(), String>;
@@ -1,12 +1,21 @@
+use mina_p2p_messages::v2::{
+    MinaBaseUserCommandStableV2, SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery,
+};
 use openmina_core::snark::SnarkJobId;
 use serde::{Deserialize, Serialize};
 
+use crate::account::AccountPublicKey;
 use crate::external_snark_worker::SnarkWorkId;
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts};
 use crate::p2p::connection::P2pConnectionResponse;
+use crate::p2p::PeerId;
 
-use super::{ActionStatsQuery, RpcId, RpcScanStateSummaryGetQuery, SyncStatsQuery};
+use super::{
+    ActionStatsQuery, RpcAccountGetQuery, RpcBlockGetQuery, RpcEvent, RpcId, RpcPeerBanQuery,
+    RpcScanStateSummaryGetQuery, RpcStakeGetQuery, RpcStateGetQuery, RpcSubscribeQuery,
+    SyncStatsQuery,
+};
 
 pub type RpcActionWithMeta = redux::ActionWithMeta<RpcAction>;
 pub type RpcActionWithMetaRef<'a> = redux::ActionWithMeta<&'a RpcAction>;
@@ -40,6 +49,28 @@ pub enum RpcAction {
     SnarkerJobSpec(RpcSnarkerJobSpecAction),
 
     SnarkerWorkersGet(RpcSnarkersWorkersGetAction),
+    SnarkerWorkGet(RpcSnarkerWorkGetAction),
+    SnarkerWorkSubmit(RpcSnarkerWorkSubmitAction),
+
+    TransactionNonceGet(RpcTransactionNonceGetAction),
+    TransactionSend(RpcTransactionSendAction),
+
+    StakeGet(RpcStakeGetAction),
+
+    BestChainGet(RpcBestChainGetAction),
+    BlockGet(RpcBlockGetAction),
+    AccountGet(RpcAccountGetAction),
+    TransactionPoolGet(RpcTransactionPoolGetAction),
+    NodeStatusGet(RpcNodeStatusGetAction),
+    MetricsGet(RpcMetricsGetAction),
+
+    Subscribe(RpcSubscribeAction),
+    EventNotify(RpcEventNotifyAction),
+    Unsubscribe(RpcUnsubscribeAction),
+
+    PeersReputationGet(RpcPeersReputationGetAction),
+    PeerBan(RpcPeerBanAction),
+    PeerUnban(RpcPeerUnbanAction),
 
     HealthCheck(RpcHealthCheckAction),
     ReadinessCheck(RpcReadinessCheckAction),
@@ -50,6 +81,7 @@ pub enum RpcAction {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcGlobalStateGetAction {
     pub rpc_id: RpcId,
+    pub query: RpcStateGetQuery,
 }
 
 impl redux::EnablingCondition<crate::State> for RpcGlobalStateGetAction {}
@@ -255,6 +287,153 @@ pub struct RpcSnarkersWorkersGetAction {
 
 impl redux::EnablingCondition<crate::State> for RpcSnarkersWorkersGetAction {}
 
+/// Remote snark worker asks for a job (`get_work`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSnarkerWorkGetAction {
+    pub rpc_id: RpcId,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcSnarkerWorkGetAction {}
+
+/// Remote snark worker submits the work it produced (`submit_work`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSnarkerWorkSubmitAction {
+    pub rpc_id: RpcId,
+    pub work: Box<SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery>,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcSnarkerWorkSubmitAction {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcTransactionNonceGetAction {
+    pub rpc_id: RpcId,
+    pub public_key: AccountPublicKey,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcTransactionNonceGetAction {}
+
+/// Add the transaction, signed by the client, to the transaction pool.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcTransactionSendAction {
+    pub rpc_id: RpcId,
+    pub transaction: Box<MinaBaseUserCommandStableV2>,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcTransactionSendAction {}
+
+/// Stake of the account and its delegators in one of the epoch ledgers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcStakeGetAction {
+    pub rpc_id: RpcId,
+    pub query: RpcStakeGetQuery,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcStakeGetAction {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcBestChainGetAction {
+    pub rpc_id: RpcId,
+    pub max_length: u32,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcBestChainGetAction {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcBlockGetAction {
+    pub rpc_id: RpcId,
+    pub query: RpcBlockGetQuery,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcBlockGetAction {}
+
+/// Account in the staged ledger of the best tip.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcAccountGetAction {
+    pub rpc_id: RpcId,
+    pub query: RpcAccountGetQuery,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcAccountGetAction {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcTransactionPoolGetAction {
+    pub rpc_id: RpcId,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcTransactionPoolGetAction {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcNodeStatusGetAction {
+    pub rpc_id: RpcId,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcNodeStatusGetAction {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcMetricsGetAction {
+    pub rpc_id: RpcId,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcMetricsGetAction {}
+
+/// Subscribe to the node's events, which are sent to the subscriber
+/// until it goes away.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSubscribeAction {
+    pub rpc_id: RpcId,
+    pub query: RpcSubscribeQuery,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcSubscribeAction {}
+
+/// Send the event to the subscribers interested in it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcEventNotifyAction {
+    pub event: RpcEvent,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcEventNotifyAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        !state.rpc.subscriptions.is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcUnsubscribeAction {
+    pub rpc_id: RpcId,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcUnsubscribeAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.rpc.subscriptions.contains_key(&self.rpc_id)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPeersReputationGetAction {
+    pub rpc_id: RpcId,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcPeersReputationGetAction {}
+
+/// Manually ban the peer.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPeerBanAction {
+    pub rpc_id: RpcId,
+    pub query: RpcPeerBanQuery,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcPeerBanAction {}
+
+/// Manually lift the ban of the peer.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcPeerUnbanAction {
+    pub rpc_id: RpcId,
+    pub peer_id: PeerId,
+}
+
+impl redux::EnablingCondition<crate::State> for RpcPeerUnbanAction {}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcHealthCheckAction {
     pub rpc_id: RpcId,
@@ -312,6 +491,27 @@ impl_into_global_action!(
     RpcSnarkerJobSpecAction,
 
     RpcSnarkersWorkersGetAction,
+    RpcSnarkerWorkGetAction,
+    RpcSnarkerWorkSubmitAction,
+
+    RpcTransactionNonceGetAction,
+    RpcTransactionSendAction,
+
+    RpcStakeGetAction,
+
+    RpcBestChainGetAction,
+    RpcBlockGetAction,
+    RpcAccountGetAction,
+    RpcTransactionPoolGetAction,
+    RpcNodeStatusGetAction,
+    RpcMetricsGetAction,
+    RpcSubscribeAction,
+    RpcEventNotifyAction,
+    RpcUnsubscribeAction,
+
+    RpcPeersReputationGetAction,
+    RpcPeerBanAction,
+    RpcPeerUnbanAction,
 
     RpcHealthCheckAction,
     RpcReadinessCheckAction,
@@ -1,22 +1,41 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
 use std::time::Duration;
 
-use mina_p2p_messages::v2::MinaBaseTransactionStatusStableV2;
+use ledger::TokenId;
+use mina_p2p_messages::v2::{
+    MinaBaseTransactionStatusStableV2, SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0,
+};
+use openmina_core::snark::{Snark, SnarkJobCommitment};
 
+use crate::block_producer::BlockProducerEpochData;
 use crate::external_snark_worker::available_job_to_snark_worker_spec;
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitAction;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingInitAction;
 use crate::p2p::connection::P2pConnectionResponse;
-use crate::snark_pool::SnarkPoolCommitmentCreateAction;
+use crate::p2p::node_sync_status;
+use crate::p2p::peer::{P2pPeerBanAction, P2pPeerUnbanAction};
+use crate::p2p::P2pPeerStatus;
+use crate::snark::work_verify::SnarkWorkVerifyInitAction;
+use crate::snark_pool::{SnarkPoolCommitmentCreateAction, SnarkPoolJobCommitmentAddAction};
+use crate::transaction_pool::{transaction_pool_validate, TransactionPoolAddAction};
+use crate::transition_frontier::sync::ledger::SyncLedgerTargetKind;
+use crate::watched_accounts::account_relevant_transactions_in_diff_iter;
 use crate::{Service, Store};
 
 use super::{
-    ActionStatsQuery, ActionStatsResponse, RpcAction, RpcActionWithMeta, RpcFinishAction,
+    state_get, ActionStatsQuery, ActionStatsResponse, RpcAccount, RpcAccountGetQuery, RpcAction,
+    RpcActionWithMeta, RpcBlockGetQuery, RpcEvent, RpcEventKind, RpcFinishAction, RpcMetrics,
+    RpcMetricsPeers, RpcMetricsSnarkPool, RpcNodeStatus, RpcNodeStatusBestTip, RpcNodeStatusPeer,
     RpcP2pConnectionIncomingErrorAction, RpcP2pConnectionIncomingPendingAction,
     RpcP2pConnectionIncomingRespondAction, RpcP2pConnectionOutgoingPendingAction,
-    RpcScanStateSummary, RpcScanStateSummaryBlock, RpcScanStateSummaryBlockTransaction,
+    RpcPeerReputation, RpcPeerStatus, RpcPeerTransport, RpcScanStateSummary,
+    RpcScanStateSummaryBlock, RpcScanStateSummaryBlockTransaction,
     RpcScanStateSummaryBlockTransactionKind, RpcScanStateSummaryGetQuery,
     RpcScanStateSummaryScanStateJob, RpcSnarkPoolJobFull, RpcSnarkPoolJobSnarkWork,
     RpcSnarkPoolJobSummary, RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse,
+    RpcSnarkerWorkSubmitResponse, RpcStakeLedger, RpcTransactionSendResponse, RpcUnsubscribeAction,
 };
 
 macro_rules! respond_or_log {
@@ -32,9 +51,8 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
 
     match action {
         RpcAction::GlobalStateGet(action) => {
-            let _ = store
-                .service
-                .respond_state_get(action.rpc_id, store.state.get());
+            let response = state_get(store.state.get(), &action.query);
+            let _ = store.service.respond_state_get(action.rpc_id, response);
         }
         RpcAction::ActionStatsGet(action) => match action.query {
             ActionStatsQuery::SinceStart => {
@@ -362,14 +380,441 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
             }
         }
         RpcAction::SnarkerWorkersGet(action) => {
-            let the_only = store.state().external_snark_worker.0.clone();
+            let workers = store
+                .state()
+                .external_snark_worker
+                .iter()
+                .map(|(id, worker)| (id, worker.clone()).into())
+                .collect();
+            if store
+                .service()
+                .respond_snarker_workers(action.rpc_id, workers)
+                .is_err()
+            {
+                return;
+            }
+        }
+        RpcAction::SnarkerWorkGet(action) => {
+            let state = store.state.get();
+            let job = state.config.snarker.as_ref().and_then(|config| {
+                let job = state
+                    .snark_pool
+                    .available_jobs_with_highest_priority(1)
+                    .into_iter()
+                    .next()?;
+                let instances = available_job_to_snark_worker_spec(
+                    job.job.clone(),
+                    &state.transition_frontier,
+                )
+                .map_err(|err| {
+                    openmina_core::log::warn!(meta.time(); kind = "RpcSnarkerWorkGet", summary = "failed to create job spec", job_id = job.id.to_string(), error = err.to_string());
+                })
+                .ok()?;
+                let spec = SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0 {
+                    instances,
+                    fee: config.fee.clone(),
+                };
+                Some((job.id.clone(), spec, config.public_key.clone().into()))
+            });
+            let Some((job_id, spec, public_key)) = job else {
+                respond_or_log!(
+                    store.service().respond_snarker_work_get(
+                        action.rpc_id,
+                        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(None)
+                    ),
+                    meta.time()
+                );
+                return;
+            };
+            let commitment = SnarkJobCommitment::new(
+                meta.time_as_nanos() / 1_000_000,
+                job_id,
+                spec.fee.clone(),
+                public_key,
+            );
+            let response = SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((
+                spec,
+                commitment.snarker.clone(),
+            )));
             if store
                 .service()
-                .respond_snarker_workers(action.rpc_id, vec![the_only.into()])
+                .respond_snarker_work_get(action.rpc_id, response)
                 .is_err()
             {
                 return;
             }
+            // Commit to the job on behalf of the remote worker, so that
+            // neither local workers nor other snarkers take it.
+            store.dispatch(SnarkPoolJobCommitmentAddAction {
+                commitment,
+                sender: store.state().p2p.config.identity_pub_key.peer_id(),
+            });
+        }
+        RpcAction::SnarkerWorkSubmit(action) => {
+            let work = *action.work;
+            let state = store.state();
+            // Work is credited to this node's snarker, no matter what the
+            // worker claims, as that's who the job was handed out for.
+            let snark = state.config.snarker.as_ref().map(|config| Snark {
+                snarker: config.public_key.clone().into(),
+                fee: config.fee.clone(),
+                proofs: Arc::new(work.proofs),
+            });
+            let Some(snark) = snark.filter(|snark| state.snark_pool.contains(&snark.job_id()))
+            else {
+                respond_or_log!(
+                    store.service().respond_snarker_work_submit(
+                        action.rpc_id,
+                        RpcSnarkerWorkSubmitResponse::JobNotFound
+                    ),
+                    meta.time()
+                );
+                return;
+            };
+            let req_id = state.snark.work_verify.next_req_id();
+            let sender = state.p2p.config.identity_pub_key.peer_id();
+            if store
+                .service()
+                .respond_snarker_work_submit(action.rpc_id, RpcSnarkerWorkSubmitResponse::Ok)
+                .is_err()
+            {
+                return;
+            }
+            // Remote workers aren't trusted, so the work gets verified
+            // like the one received from peers, before it's added to the
+            // snark pool in `snark_effects`.
+            store.dispatch(SnarkWorkVerifyInitAction {
+                req_id,
+                batch: vec![snark],
+                sender: sender.to_string(),
+            });
+        }
+        RpcAction::TransactionNonceGet(action) => {
+            let state = store.state();
+            let pool_next_nonce = state.transaction_pool.next_nonce(&action.public_key);
+            let staged_ledger_hash = state
+                .transition_frontier
+                .best_tip()
+                .map(|best_tip| best_tip.staged_ledger_hash().clone());
+            let nonce = staged_ledger_hash.and_then(|staged_ledger_hash| {
+                let accounts = store.service.transaction_pool_accounts(
+                    &staged_ledger_hash,
+                    vec![action.public_key.clone()],
+                );
+                let account = accounts.get(&action.public_key)?;
+                Some(pool_next_nonce.map_or(account.nonce, |nonce| nonce.max(account.nonce)))
+            });
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_transaction_nonce_get(action.rpc_id, nonce),
+                meta.time()
+            );
+        }
+        RpcAction::TransactionSend(action) => {
+            let hash = transaction_pool_validate(store, &action.transaction)
+                .map_err(|err| err.to_string())
+                .and_then(|_| action.transaction.hash().map_err(|err| err.to_string()));
+            let response = match hash {
+                Ok(hash) => {
+                    let added = store.dispatch(TransactionPoolAddAction {
+                        hash: hash.clone(),
+                        transaction: action.transaction,
+                        sender: None,
+                    });
+                    if added || store.state().transaction_pool.contains(&hash) {
+                        RpcTransactionSendResponse::Ok { hash }
+                    } else {
+                        RpcTransactionSendResponse::Rejected {
+                            error: "transaction with the same nonce and an equal or higher fee is already in the pool".to_owned(),
+                        }
+                    }
+                }
+                Err(error) => RpcTransactionSendResponse::Rejected { error },
+            };
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_transaction_send(action.rpc_id, response),
+                meta.time()
+            );
+        }
+        RpcAction::StakeGet(action) => {
+            let epoch_data = store
+                .state()
+                .transition_frontier
+                .best_tip()
+                .map(|best_tip| {
+                    let staking = BlockProducerEpochData::staking(best_tip);
+                    match action.query.ledger {
+                        RpcStakeLedger::Staking => (staking.epoch, staking.ledger_hash),
+                        RpcStakeLedger::NextEpoch => (
+                            staking.epoch + 1,
+                            SyncLedgerTargetKind::NextEpochLedger
+                                .ledger_hash(best_tip)
+                                .clone(),
+                        ),
+                    }
+                });
+            let stake = epoch_data.and_then(|(epoch, ledger_hash)| {
+                store
+                    .service
+                    .stake_get(epoch, ledger_hash, action.query.public_key)
+            });
+            respond_or_log!(
+                store.service().respond_stake_get(action.rpc_id, stake),
+                meta.time()
+            );
+        }
+        RpcAction::BestChainGet(action) => {
+            let best_chain = &store.state().transition_frontier.best_chain;
+            let skip = best_chain.len().saturating_sub(action.max_length as usize);
+            let blocks = best_chain[skip..].to_vec();
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_best_chain_get(action.rpc_id, blocks),
+                meta.time()
+            );
+        }
+        RpcAction::BlockGet(action) => {
+            let block = store
+                .state()
+                .transition_frontier
+                .best_chain
+                .iter()
+                .find(|block| match &action.query {
+                    RpcBlockGetQuery::WithHash(hash) => block.hash() == hash,
+                    RpcBlockGetQuery::WithHeight(height) => block.height() == *height,
+                })
+                .cloned();
+            respond_or_log!(
+                store.service().respond_block_get(action.rpc_id, block),
+                meta.time()
+            );
+        }
+        RpcAction::AccountGet(action) => {
+            let state = store.state();
+            // Only accounts of the default token pay fees, so only those
+            // can have transactions in the pool.
+            let is_default_token = action
+                .query
+                .token_id
+                .as_ref()
+                .map_or(true, |token_id| TokenId::from(&**token_id).is_default());
+            let pool_next_nonce = state
+                .transaction_pool
+                .next_nonce(&action.query.public_key)
+                .filter(|_| is_default_token);
+            let account = state.transition_frontier.best_tip().and_then(|best_tip| {
+                let account = store
+                    .service
+                    .account_get(best_tip.staged_ledger_hash(), &action.query)?;
+                let nonce = account.nonce.as_u32();
+                Some(RpcAccount {
+                    account: Box::new(account),
+                    inferred_nonce: pool_next_nonce.map_or(nonce, |next| next.max(nonce)),
+                    block_height: best_tip.height(),
+                    state_hash: best_tip.hash().clone(),
+                })
+            });
+            respond_or_log!(
+                store.service().respond_account_get(action.rpc_id, account),
+                meta.time()
+            );
+        }
+        RpcAction::TransactionPoolGet(action) => {
+            let transactions = store
+                .state()
+                .transaction_pool
+                .range(..)
+                .map(|(_, tx)| tx.clone())
+                .collect();
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_transaction_pool_get(action.rpc_id, transactions),
+                meta.time()
+            );
+        }
+        RpcAction::NodeStatusGet(action) => {
+            let state = store.state();
+            let peers = state
+                .p2p
+                .peers
+                .iter()
+                .filter(|(_, peer)| peer.status.as_ready().is_some())
+                .map(|(peer_id, peer)| RpcNodeStatusPeer {
+                    peer_id: *peer_id,
+                    address: peer.dial_opts.as_ref().map(|opts| opts.to_string()),
+                })
+                .collect();
+            let snarker = state.config.snarker.as_ref();
+            let status = RpcNodeStatus {
+                sync_status: node_sync_status(state),
+                best_tip: state.transition_frontier.best_tip().map(|best_tip| {
+                    RpcNodeStatusBestTip {
+                        hash: best_tip.hash().clone(),
+                        height: best_tip.height(),
+                        global_slot: best_tip.global_slot(),
+                        staged_ledger_hash: best_tip
+                            .staged_ledger_hashes()
+                            .non_snark
+                            .ledger_hash
+                            .clone(),
+                        snarked_ledger_hash: best_tip.snarked_ledger_hash().clone(),
+                    }
+                }),
+                highest_block_length_received: state.consensus.best_tip().map(|b| b.height()),
+                peers,
+                block_producer: state
+                    .block_producer
+                    .config()
+                    .map(|config| config.pub_key.clone()),
+                snarker: snarker.map(|config| config.public_key.clone()),
+                snark_work_fee: snarker.map(|config| config.fee.as_u64()),
+                commit_id: state.config.build.git.commit_hash.clone(),
+            };
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_node_status_get(action.rpc_id, status),
+                meta.time()
+            );
+        }
+        RpcAction::MetricsGet(action) => {
+            let stats = store.service.stats();
+            let actions = stats.as_ref().map(|s| s.collect_action_stats_since_start());
+            let p2p_rpc = stats
+                .as_ref()
+                .map(|s| s.collect_p2p_rpc_stats())
+                .unwrap_or_default();
+            let block_apply = stats.as_ref().map(|s| s.collect_block_apply_durations());
+            let last_sync = stats.and_then(|s| s.collect_sync_stats(Some(1)).pop());
+
+            let state = store.state.get();
+            let mut peers = BTreeMap::<_, usize>::new();
+            for peer in state.p2p.peers.values() {
+                let transport = match &peer.dial_opts {
+                    None => RpcPeerTransport::Unknown,
+                    Some(opts) if opts.is_libp2p() => RpcPeerTransport::LibP2P,
+                    Some(_) => RpcPeerTransport::WebRTC,
+                };
+                let status = match &peer.status {
+                    P2pPeerStatus::Connecting(_) => RpcPeerStatus::Connecting,
+                    P2pPeerStatus::Ready(_) => RpcPeerStatus::Ready,
+                    P2pPeerStatus::Disconnected { .. } => RpcPeerStatus::Disconnected,
+                };
+                *peers.entry((transport, status)).or_default() += 1;
+            }
+            let snark_pool = state.snark_pool.range(..).fold(
+                RpcMetricsSnarkPool {
+                    jobs: 0,
+                    committed_jobs: 0,
+                    snarks: 0,
+                },
+                |mut acc, (_, job)| {
+                    acc.jobs += 1;
+                    acc.committed_jobs += job.commitment.is_some() as usize;
+                    acc.snarks += job.snark.is_some() as usize;
+                    acc
+                },
+            );
+            let metrics = RpcMetrics {
+                actions,
+                p2p_rpc,
+                block_apply,
+                last_sync,
+                peers: peers
+                    .into_iter()
+                    .map(|((transport, status), count)| RpcMetricsPeers {
+                        transport,
+                        status,
+                        count,
+                    })
+                    .collect(),
+                snark_pool,
+                transaction_pool_size: state.transaction_pool.len(),
+                best_tip_height: state.transition_frontier.best_tip().map(|b| b.height()),
+            };
+            respond_or_log!(
+                store.service().respond_metrics_get(action.rpc_id, metrics),
+                meta.time()
+            );
+        }
+        RpcAction::Subscribe(_) => {}
+        RpcAction::EventNotify(action) => {
+            let subscribers = store
+                .state()
+                .rpc
+                .subscriptions
+                .iter()
+                .filter(|(_, sub)| sub.is_subscribed_to(action.event.kind()))
+                .map(|(rpc_id, _)| *rpc_id)
+                .collect::<Vec<_>>();
+            for rpc_id in subscribers {
+                if store
+                    .service
+                    .respond_event(rpc_id, action.event.clone())
+                    .is_err()
+                {
+                    store.dispatch(RpcUnsubscribeAction { rpc_id });
+                }
+            }
+
+            if let RpcEvent::BestTip { .. } = &action.event {
+                watched_accounts_notify(store);
+            }
+        }
+        RpcAction::Unsubscribe(action) => {
+            store.service.event_subscriber_remove(action.rpc_id);
+        }
+        RpcAction::PeersReputationGet(action) => {
+            let p2p = &store.state().p2p;
+            let recovery_interval = p2p.config.reputation.score_recovery_interval;
+            let peer_ids = p2p
+                .peers
+                .keys()
+                .chain(p2p.reputation.keys())
+                .collect::<BTreeSet<_>>();
+            let peers = peer_ids
+                .into_iter()
+                .map(|peer_id| {
+                    let reputation = p2p.reputation.get(peer_id).cloned().unwrap_or_default();
+                    RpcPeerReputation {
+                        peer_id: *peer_id,
+                        is_connected: p2p.get_ready_peer(peer_id).is_some(),
+                        score: reputation.score_at(meta.time(), recovery_interval),
+                        last_offense: reputation.last_offense,
+                        ban: reputation.ban,
+                    }
+                })
+                .collect();
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_peers_reputation_get(action.rpc_id, peers),
+                meta.time()
+            );
+        }
+        RpcAction::PeerBan(action) => {
+            let banned = store.dispatch(P2pPeerBanAction {
+                peer_id: action.query.peer_id,
+                duration: action.query.duration,
+            });
+            respond_or_log!(
+                store.service().respond_peer_ban(action.rpc_id, banned),
+                meta.time()
+            );
+        }
+        RpcAction::PeerUnban(action) => {
+            let unbanned = store.dispatch(P2pPeerUnbanAction {
+                peer_id: action.peer_id,
+            });
+            respond_or_log!(
+                store.service().respond_peer_unban(action.rpc_id, unbanned),
+                meta.time()
+            );
         }
         RpcAction::HealthCheck(action) => {
             let some_peers = store
@@ -421,3 +866,60 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
         RpcAction::Finish(_) => {}
     }
 }
+
+/// Sends `AccountUpdated` events for the watched accounts affected by
+/// the new best tip.
+fn watched_accounts_notify<S: Service>(store: &mut Store<S>) {
+    let state = store.state.get();
+    let Some(best_tip) = state.transition_frontier.best_tip().cloned() else {
+        return;
+    };
+    let coinbase_receiver = &best_tip
+        .header()
+        .protocol_state
+        .body
+        .consensus_state
+        .coinbase_receiver;
+    let mut updates = vec![];
+    for (rpc_id, sub) in &state.rpc.subscriptions {
+        if !sub.is_subscribed_to(RpcEventKind::AccountUpdated) {
+            continue;
+        }
+        for public_key in &sub.query.accounts {
+            let pub_key = public_key.as_ref();
+            let transactions =
+                account_relevant_transactions_in_diff_iter(pub_key, best_tip.staged_ledger_diff())
+                    .filter_map(|tx| tx.hash)
+                    .collect::<Vec<_>>();
+            let is_affected = !transactions.is_empty()
+                || coinbase_receiver == pub_key
+                || best_tip
+                    .completed_works_iter()
+                    .any(|w| &w.prover == pub_key);
+            if is_affected {
+                updates.push((*rpc_id, public_key.clone(), transactions));
+            }
+        }
+    }
+
+    for (rpc_id, public_key, transactions) in updates {
+        let query = RpcAccountGetQuery {
+            public_key: public_key.clone(),
+            token_id: None,
+        };
+        let account = store
+            .service
+            .account_get(best_tip.staged_ledger_hash(), &query)
+            .map(Box::new);
+        let event = RpcEvent::AccountUpdated {
+            public_key,
+            block_hash: best_tip.hash().clone(),
+            block_height: best_tip.height(),
+            account,
+            transactions,
+        };
+        if store.service.respond_event(rpc_id, event).is_err() {
+            store.dispatch(RpcUnsubscribeAction { rpc_id });
+        }
+    }
+}
@@ -1,12 +1,15 @@
-use crate::external_snark_worker::{ExternalSnarkWorker, ExternalSnarkWorkerState};
+use crate::external_snark_worker::{
+    ExternalSnarkWorker, ExternalSnarkWorkerId, ExternalSnarkWorkerState,
+};
 
 use super::{RpcSnarkWorker, RpcSnarkWorkerStatus};
 
-impl From<ExternalSnarkWorker> for RpcSnarkWorker {
-    fn from(source: ExternalSnarkWorker) -> Self {
+impl From<(ExternalSnarkWorkerId, ExternalSnarkWorker)> for RpcSnarkWorker {
+    fn from((id, source): (ExternalSnarkWorkerId, ExternalSnarkWorker)) -> Self {
         Self {
             time: Some(source.timestamp),
-            id: Some("single".into()),
+            id: Some(id.to_string()),
+            failures: source.failures,
             status: source.state.into(),
         }
     }
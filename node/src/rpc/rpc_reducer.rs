@@ -1,5 +1,6 @@
 use super::{
     RpcAction, RpcActionWithMetaRef, RpcRequest, RpcRequestState, RpcRequestStatus, RpcState,
+    RpcSubscriptionState,
 };
 
 impl RpcState {
@@ -73,6 +74,31 @@ impl RpcState {
             RpcAction::SnarkerJobCommit(_) => {}
             RpcAction::SnarkerJobSpec(_) => {}
             RpcAction::SnarkerWorkersGet(_) => {}
+            RpcAction::SnarkerWorkGet(_) => {}
+            RpcAction::SnarkerWorkSubmit(_) => {}
+            RpcAction::TransactionNonceGet(_) => {}
+            RpcAction::TransactionSend(_) => {}
+            RpcAction::StakeGet(_) => {}
+            RpcAction::BestChainGet(_) => {}
+            RpcAction::BlockGet(_) => {}
+            RpcAction::AccountGet(_) => {}
+            RpcAction::TransactionPoolGet(_) => {}
+            RpcAction::NodeStatusGet(_) => {}
+            RpcAction::MetricsGet(_) => {}
+            RpcAction::Subscribe(content) => {
+                let subscription = RpcSubscriptionState {
+                    time: meta.time(),
+                    query: content.query.clone(),
+                };
+                self.subscriptions.insert(content.rpc_id, subscription);
+            }
+            RpcAction::EventNotify(_) => {}
+            RpcAction::Unsubscribe(content) => {
+                self.subscriptions.remove(&content.rpc_id);
+            }
+            RpcAction::PeersReputationGet(_) => {}
+            RpcAction::PeerBan(_) => {}
+            RpcAction::PeerUnban(_) => {}
             RpcAction::HealthCheck(_) => {}
             RpcAction::ReadinessCheck(_) => {}
             RpcAction::Finish(action) => {
@@ -1,15 +1,20 @@
-use mina_p2p_messages::v2::LedgerHash;
+use mina_p2p_messages::v2::{LedgerHash, MinaBaseAccountBinableArgStableV2};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::account::AccountPublicKey;
 use crate::p2p::connection::P2pConnectionResponse;
-use crate::State;
 
 use super::{
-    RpcActionStatsGetResponse, RpcHealthCheckResponse, RpcId, RpcP2pConnectionOutgoingResponse,
-    RpcReadinessCheckResponse, RpcScanStateSummaryGetResponse, RpcScanStateSummaryScanStateJob,
-    RpcSnarkPoolGetResponse, RpcSnarkPoolJobGetResponse, RpcSnarkerJobCommitResponse,
-    RpcSnarkerJobSpecResponse, RpcSnarkerWorkersResponse, RpcSyncStatsGetResponse,
+    RpcAccountGetQuery, RpcAccountGetResponse, RpcActionStatsGetResponse, RpcBestChainGetResponse,
+    RpcBlockGetResponse, RpcEvent, RpcHealthCheckResponse, RpcId, RpcMetricsGetResponse,
+    RpcNodeStatusGetResponse, RpcP2pConnectionOutgoingResponse, RpcPeerBanResponse,
+    RpcPeerUnbanResponse, RpcPeersReputationGetResponse, RpcReadinessCheckResponse,
+    RpcScanStateSummaryGetResponse, RpcScanStateSummaryScanStateJob, RpcSnarkPoolGetResponse,
+    RpcSnarkPoolJobGetResponse, RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse,
+    RpcSnarkerWorkGetResponse, RpcSnarkerWorkSubmitResponse, RpcSnarkerWorkersResponse,
+    RpcStakeGetResponse, RpcStateGetResponse, RpcSyncStatsGetResponse,
+    RpcTransactionNonceGetResponse, RpcTransactionPoolGetResponse, RpcTransactionSendResponse,
 };
 
 #[derive(Error, Serialize, Deserialize, Debug, Clone)]
@@ -27,10 +32,29 @@ pub trait RpcLedgerService: redux::Service {
         &self,
         staged_ledger_hash: LedgerHash,
     ) -> Vec<Vec<RpcScanStateSummaryScanStateJob>>;
+
+    /// Looks up the account's stake in the synced epoch ledger.
+    fn stake_get(
+        &mut self,
+        epoch: u32,
+        ledger_hash: LedgerHash,
+        public_key: AccountPublicKey,
+    ) -> RpcStakeGetResponse;
+
+    /// Looks up the account in the staged ledger.
+    fn account_get(
+        &self,
+        staged_ledger_hash: &LedgerHash,
+        query: &RpcAccountGetQuery,
+    ) -> Option<MinaBaseAccountBinableArgStableV2>;
 }
 
 pub trait RpcService: RpcLedgerService {
-    fn respond_state_get(&mut self, rpc_id: RpcId, response: &State) -> Result<(), RespondError>;
+    fn respond_state_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcStateGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_action_stats_get(
         &mut self,
         rpc_id: RpcId,
@@ -91,6 +115,83 @@ pub trait RpcService: RpcLedgerService {
         rpc_id: RpcId,
         response: RpcSnarkerWorkersResponse,
     ) -> Result<(), RespondError>;
+    fn respond_snarker_work_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkerWorkGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_snarker_work_submit(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkerWorkSubmitResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_transaction_nonce_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransactionNonceGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_transaction_send(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransactionSendResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_stake_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcStakeGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_best_chain_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBestChainGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_account_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcAccountGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_transaction_pool_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransactionPoolGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_node_status_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcNodeStatusGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_metrics_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcMetricsGetResponse,
+    ) -> Result<(), RespondError>;
+    /// Sends the event to the subscriber. If it fails, the subscription
+    /// is dropped.
+    fn respond_event(&mut self, rpc_id: RpcId, event: RpcEvent) -> Result<(), RespondError>;
+    /// Whether the subscriber went away without unsubscribing.
+    fn event_subscriber_is_closed(&self, rpc_id: RpcId) -> bool;
+    /// Drops the channel to the subscriber, which ends its subscription.
+    fn event_subscriber_remove(&mut self, rpc_id: RpcId);
+    fn respond_peers_reputation_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPeersReputationGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_peer_ban(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPeerBanResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_peer_unban(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcPeerUnbanResponse,
+    ) -> Result<(), RespondError>;
     fn respond_health_check(
         &mut self,
         rpc_id: RpcId,
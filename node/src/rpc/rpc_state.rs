@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use super::{RpcId, RpcRequest};
+use super::{RpcEventKind, RpcId, RpcRequest, RpcSubscribeQuery};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcRequestState {
@@ -41,15 +41,29 @@ impl RpcRequestStatus {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSubscriptionState {
+    pub time: redux::Timestamp,
+    pub query: RpcSubscribeQuery,
+}
+
+impl RpcSubscriptionState {
+    pub fn is_subscribed_to(&self, kind: RpcEventKind) -> bool {
+        self.query.kinds.is_empty() || self.query.kinds.contains(&kind)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcState {
     pub requests: BTreeMap<RpcId, RpcRequestState>,
+    pub subscriptions: BTreeMap<RpcId, RpcSubscriptionState>,
 }
 
 impl RpcState {
     pub fn new() -> Self {
         Self {
             requests: Default::default(),
+            subscriptions: Default::default(),
         }
     }
 }
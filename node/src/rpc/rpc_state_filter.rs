@@ -0,0 +1,373 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::p2p::PeerId;
+use crate::transition_frontier::sync::TransitionFrontierSyncState;
+use crate::transition_frontier::TransitionFrontierState;
+use crate::State;
+
+use super::{RpcStateGetError, RpcStateGetQuery, RpcStateGetResponse, RpcStateGetValue};
+
+/// In summary mode, objects and arrays nested deeper than this below the
+/// selected value are replaced with their size.
+const SUMMARY_DEPTH: usize = 2;
+
+/// Serializes the part of the state selected by `query.path`.
+///
+/// Path is a list of object keys or array indexes separated by `.`, where
+/// `*` selects every entry, e.g. `p2p.peers.*.status`. Only the part of the
+/// state reachable through typed fields (see `typed_select`) gets
+/// serialized, the rest of the path is selected from the serialized value.
+/// Summary of the whole state is built from typed fields (see
+/// `state_summary`), without serializing the state.
+pub fn state_get(state: &State, query: &RpcStateGetQuery) -> RpcStateGetResponse {
+    let path = query.path.as_deref().unwrap_or_default();
+    let segments = path
+        .split('.')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    let summary = query.summary;
+
+    match segments[..] {
+        [] | ["*"] if summary => state_summary(state).map(RpcStateGetValue::Selected),
+        [] => Ok(RpcStateGetValue::Full(Box::new(state.clone()))),
+        ["*", ref rest @ ..] => STATE_FIELDS
+            .iter()
+            .map(|field| {
+                let segments = [&[*field][..], rest].concat();
+                Ok((
+                    field.to_string(),
+                    typed_get(state, &segments, path, summary)?,
+                ))
+            })
+            .collect::<Result<Map<_, _>, _>>()
+            .map(|fields| RpcStateGetValue::Selected(Value::Object(fields))),
+        _ => typed_get(state, &segments, path, summary).map(RpcStateGetValue::Selected),
+    }
+}
+
+fn typed_get(
+    state: &State,
+    segments: &[&str],
+    path: &str,
+    summary: bool,
+) -> Result<Value, RpcStateGetError> {
+    let (value, consumed) = typed_select(state, segments, path)?;
+    select(value, &segments[consumed..], path, summary)
+}
+
+/// Serializes the deepest part of the state selected by `segments` which
+/// can be reached through typed fields, so that frequently requested parts
+/// don't need the whole top-level field serialized. Returns the value with
+/// the number of consumed segments.
+fn typed_select(
+    state: &State,
+    segments: &[&str],
+    path: &str,
+) -> Result<(Value, usize), RpcStateGetError> {
+    let not_found = || RpcStateGetError::PathNotFound(path.to_owned());
+    let peers = &state.p2p.peers;
+    let transition_frontier = &state.transition_frontier;
+    let best_chain = &transition_frontier.best_chain;
+
+    Ok(match segments {
+        [] => return Err(not_found()),
+        ["p2p", "peers"] | ["p2p", "peers", "*", ..] => (to_value(peers)?, 2),
+        ["p2p", "peers", peer_id, ..] => {
+            let peer = peer_id
+                .parse::<PeerId>()
+                .ok()
+                .and_then(|peer_id| peers.get(&peer_id))
+                .ok_or_else(not_found)?;
+            (to_value(peer)?, 3)
+        }
+        ["transition_frontier", "sync", ..] => (to_value(&transition_frontier.sync)?, 2),
+        ["transition_frontier", "best_chain"] | ["transition_frontier", "best_chain", "*", ..] => {
+            (to_value(best_chain)?, 2)
+        }
+        ["transition_frontier", "best_chain", index, ..] => {
+            let block = index
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| best_chain.get(i))
+                .ok_or_else(not_found)?;
+            (to_value(block)?, 3)
+        }
+        ["transition_frontier", field, ..] if *field != "*" => (
+            transition_frontier_field_to_value(transition_frontier, field)
+                .ok_or_else(not_found)??,
+            2,
+        ),
+        [field, ..] => (
+            state_field_to_value(state, field).ok_or_else(not_found)??,
+            1,
+        ),
+    })
+}
+
+/// Top-level fields of the state, as accepted by `state_field_to_value`.
+const STATE_FIELDS: [&str; 13] = [
+    "config",
+    "p2p",
+    "snark",
+    "consensus",
+    "transition_frontier",
+    "snark_pool",
+    "transaction_pool",
+    "block_producer",
+    "rpc",
+    "external_snark_worker",
+    "watched_accounts",
+    "last_action",
+    "applied_actions_count",
+];
+
+fn state_field_to_value(state: &State, field: &str) -> Option<Result<Value, RpcStateGetError>> {
+    // Destructured so that new fields of the state aren't missed here.
+    let State {
+        config,
+        p2p,
+        snark,
+        consensus,
+        transition_frontier,
+        snark_pool,
+        transaction_pool,
+        block_producer,
+        rpc,
+        external_snark_worker,
+        watched_accounts,
+        last_action,
+        applied_actions_count,
+    } = state;
+    Some(match field {
+        "config" => to_value(config),
+        "p2p" => to_value(p2p),
+        "snark" => to_value(snark),
+        "consensus" => to_value(consensus),
+        "transition_frontier" => to_value(transition_frontier),
+        "snark_pool" => to_value(snark_pool),
+        "transaction_pool" => to_value(transaction_pool),
+        "block_producer" => to_value(block_producer),
+        "rpc" => to_value(rpc),
+        "external_snark_worker" => to_value(external_snark_worker),
+        "watched_accounts" => to_value(watched_accounts),
+        "last_action" => to_value(last_action),
+        "applied_actions_count" => to_value(applied_actions_count),
+        _ => return None,
+    })
+}
+
+fn transition_frontier_field_to_value(
+    transition_frontier: &TransitionFrontierState,
+    field: &str,
+) -> Option<Result<Value, RpcStateGetError>> {
+    let TransitionFrontierState {
+        config,
+        best_chain,
+        needed_protocol_states,
+        epoch_ledgers,
+        sync,
+    } = transition_frontier;
+    Some(match field {
+        "config" => to_value(config),
+        "best_chain" => to_value(best_chain),
+        "needed_protocol_states" => to_value(needed_protocol_states),
+        "epoch_ledgers" => to_value(epoch_ledgers),
+        "sync" => to_value(sync),
+        _ => return None,
+    })
+}
+
+/// Counts and small values describing the state, so that the summary
+/// doesn't need the whole state serialized.
+fn state_summary(state: &State) -> Result<Value, RpcStateGetError> {
+    let State {
+        config,
+        p2p,
+        snark,
+        consensus,
+        transition_frontier,
+        snark_pool,
+        transaction_pool,
+        block_producer,
+        rpc,
+        external_snark_worker,
+        watched_accounts,
+        last_action,
+        applied_actions_count,
+    } = state;
+    let best_tip = transition_frontier.best_tip();
+    Ok(json!({
+        "config": summarize(to_value(config)?, SUMMARY_DEPTH),
+        "p2p": {
+            "peers": p2p.peers.len(),
+            "ready_peers": p2p.ready_peers_iter().count(),
+            "known_peers": p2p.known_peers.len(),
+            "banned_peers": p2p.reputation.values().filter(|r| r.ban.is_some()).count(),
+        },
+        "snark": {
+            "block_verify_jobs": snark.block_verify.jobs.len(),
+            "work_verify_jobs": snark.work_verify.jobs.len(),
+        },
+        "consensus": {
+            "blocks": consensus.blocks.len(),
+            "best_tip": to_value(&consensus.best_tip)?,
+        },
+        "transition_frontier": {
+            "sync_phase": sync_phase(&transition_frontier.sync),
+            "best_chain": transition_frontier.best_chain.len(),
+            "best_tip_height": best_tip.map(|block| block.height()),
+            "best_tip_hash": to_value(&best_tip.map(|block| block.hash()))?,
+            "epoch_ledgers": to_value(&transition_frontier.epoch_ledgers)?,
+        },
+        "snark_pool": {
+            "jobs": snark_pool.range(..).count(),
+            "snarks": snark_pool.range(..).filter(|(_, job)| job.snark.is_some()).count(),
+        },
+        "transaction_pool": {
+            "transactions": transaction_pool.len(),
+        },
+        "block_producer": {
+            "enabled": block_producer.is_enabled(),
+        },
+        "rpc": {
+            "requests": rpc.requests.len(),
+            "subscriptions": rpc.subscriptions.len(),
+        },
+        "external_snark_worker": {
+            "workers": external_snark_worker.0.len(),
+        },
+        "watched_accounts": watched_accounts.iter().count(),
+        "last_action": to_value(last_action)?,
+        "applied_actions_count": applied_actions_count,
+    }))
+}
+
+fn sync_phase(sync: &TransitionFrontierSyncState) -> &'static str {
+    match sync {
+        TransitionFrontierSyncState::Idle => "Idle",
+        TransitionFrontierSyncState::Init { .. } => "Init",
+        TransitionFrontierSyncState::StakingLedgerPending { .. } => "StakingLedgerPending",
+        TransitionFrontierSyncState::StakingLedgerSuccess { .. } => "StakingLedgerSuccess",
+        TransitionFrontierSyncState::NextEpochLedgerPending { .. } => "NextEpochLedgerPending",
+        TransitionFrontierSyncState::NextEpochLedgerSuccess { .. } => "NextEpochLedgerSuccess",
+        TransitionFrontierSyncState::RootLedgerPending { .. } => "RootLedgerPending",
+        TransitionFrontierSyncState::RootLedgerSuccess { .. } => "RootLedgerSuccess",
+        TransitionFrontierSyncState::BlocksPending { .. } => "BlocksPending",
+        TransitionFrontierSyncState::BlocksSuccess { .. } => "BlocksSuccess",
+        TransitionFrontierSyncState::Synced { .. } => "Synced",
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, RpcStateGetError> {
+    serde_json::to_value(value).map_err(|err| RpcStateGetError::Serialization(err.to_string()))
+}
+
+/// Selects the value at `segments`. Values matched with `*` are collected
+/// into an object keyed by the matched keys (or indexes for arrays).
+fn select(
+    value: Value,
+    segments: &[&str],
+    path: &str,
+    summary: bool,
+) -> Result<Value, RpcStateGetError> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Ok(if summary {
+            summarize(value, SUMMARY_DEPTH)
+        } else {
+            value
+        });
+    };
+
+    match (*segment, value) {
+        ("*", Value::Object(map)) => map
+            .into_iter()
+            .map(|(key, value)| Ok((key, select(value, rest, path, summary)?)))
+            .collect::<Result<Map<_, _>, _>>()
+            .map(Value::Object),
+        ("*", Value::Array(items)) => items
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| Ok((i.to_string(), select(value, rest, path, summary)?)))
+            .collect::<Result<Map<_, _>, _>>()
+            .map(Value::Object),
+        // Matching everything in a scalar selects nothing.
+        ("*", _) => Ok(Value::Object(Map::new())),
+        (key, Value::Object(mut map)) => match map.remove(key) {
+            Some(value) => select(value, rest, path, summary),
+            None => Err(RpcStateGetError::PathNotFound(path.to_owned())),
+        },
+        (index, Value::Array(mut items)) => match index.parse::<usize>() {
+            Ok(i) if i < items.len() => select(items.swap_remove(i), rest, path, summary),
+            _ => Err(RpcStateGetError::PathNotFound(path.to_owned())),
+        },
+        _ => Err(RpcStateGetError::PathNotFound(path.to_owned())),
+    }
+}
+
+fn summarize(value: Value, depth: usize) -> Value {
+    match value {
+        Value::Object(map) if depth == 0 => Value::String(format!("{{{} fields}}", map.len())),
+        Value::Array(items) if depth == 0 => Value::String(format!("[{} items]", items.len())),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, summarize(value, depth - 1)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|value| summarize(value, depth - 1))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn select_path(value: Value, path: &str, summary: bool) -> Result<Value, RpcStateGetError> {
+        let segments = path.split('.').collect::<Vec<_>>();
+        select(value, &segments, path, summary)
+    }
+
+    #[test]
+    fn select_with_wildcard() {
+        let peers = json!({
+            "peers": {
+                "a": { "status": "Ready", "dial_opts": null },
+                "b": { "status": "Connecting", "dial_opts": null },
+            }
+        });
+        assert_eq!(
+            select_path(peers, "peers.*.status", false).unwrap(),
+            json!({ "a": "Ready", "b": "Connecting" })
+        );
+    }
+
+    #[test]
+    fn select_array_index() {
+        let value = json!({ "chain": [{ "height": 1 }, { "height": 2 }] });
+        assert_eq!(
+            select_path(value.clone(), "chain.1.height", false).unwrap(),
+            json!(2)
+        );
+        assert!(matches!(
+            select_path(value, "chain.2.height", false),
+            Err(RpcStateGetError::PathNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn select_summary() {
+        let value = json!({ "sync": { "phase": { "blocks": [1, 2, 3] }, "time": 5 } });
+        assert_eq!(
+            select_path(value, "sync", true).unwrap(),
+            json!({ "phase": { "blocks": "[3 items]" }, "time": 5 })
+        );
+    }
+}
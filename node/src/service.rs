@@ -1,12 +1,16 @@
+pub use crate::block_producer::BlockProducerService;
 pub use crate::event_source::EventSourceService;
 use crate::external_snark_worker::ExternalSnarkWorkerService;
 pub use crate::p2p::channels::P2pChannelsService;
 pub use crate::p2p::connection::P2pConnectionService;
 pub use crate::p2p::disconnection::P2pDisconnectionService;
+pub use crate::p2p::discovery::P2pDiscoveryService;
+pub use crate::p2p::P2pPeerStoreService;
 pub use crate::recorder::Recorder;
 pub use crate::rpc::RpcService;
 pub use crate::snark::block_verify::SnarkBlockVerifyService;
 pub use crate::snark::work_verify::SnarkWorkVerifyService;
+pub use crate::transaction_pool::TransactionPoolService;
 pub use crate::transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedService;
 pub use crate::transition_frontier::sync::ledger::staged::TransitionFrontierSyncLedgerStagedService;
 pub use crate::transition_frontier::TransitionFrontierService;
@@ -22,9 +26,13 @@ pub trait Service:
     + P2pConnectionService
     + P2pDisconnectionService
     + P2pChannelsService
+    + P2pDiscoveryService
+    + P2pPeerStoreService
     + TransitionFrontierSyncLedgerSnarkedService
     + TransitionFrontierSyncLedgerStagedService
     + TransitionFrontierService
+    + TransactionPoolService
+    + BlockProducerService
     + RpcService
     + ExternalSnarkWorkerService
 {
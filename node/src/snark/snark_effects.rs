@@ -1,12 +1,13 @@
 use crate::consensus::ConsensusBlockSnarkVerifySuccessAction;
+use crate::p2p::channels::{P2pChannelsService, P2pGossipAcceptance, P2pGossipValidationKey};
 use crate::snark_pool::candidate::{
     SnarkPoolCandidateWorkVerifyErrorAction, SnarkPoolCandidateWorkVerifySuccessAction,
 };
 use crate::snark_pool::SnarkPoolWorkAddAction;
 use crate::{Service, Store};
 
-use super::block_verify::SnarkBlockVerifyAction;
-use super::work_verify::SnarkWorkVerifyAction;
+use super::block_verify::{SnarkBlockVerifyAction, SnarkBlockVerifyError};
+use super::work_verify::{SnarkWorkVerifyAction, SnarkWorkVerifyError};
 use super::{SnarkAction, SnarkActionWithMeta};
 
 pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMeta) {
@@ -19,14 +20,30 @@ pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMe
             }
             SnarkBlockVerifyAction::Pending(_) => {}
             SnarkBlockVerifyAction::Error(a) => {
+                let req = store.state().snark.block_verify.jobs.get(a.req_id);
+                if let Some(req) = req {
+                    let key = P2pGossipValidationKey::Block(req.block().hash_ref().clone());
+                    let acceptance = match a.error {
+                        SnarkBlockVerifyError::ValidatorThreadCrashed => {
+                            P2pGossipAcceptance::Ignore
+                        }
+                        _ => P2pGossipAcceptance::Reject,
+                    };
+                    store
+                        .service
+                        .libp2p_gossip_validation_result(key, acceptance);
+                }
                 a.effects(&meta, store);
             }
             SnarkBlockVerifyAction::Success(a) => {
                 let req = store.state().snark.block_verify.jobs.get(a.req_id);
                 let Some(req) = req else { return };
-                store.dispatch(ConsensusBlockSnarkVerifySuccessAction {
-                    hash: req.block().hash_ref().clone(),
-                });
+                let hash = req.block().hash_ref().clone();
+                store.service.libp2p_gossip_validation_result(
+                    P2pGossipValidationKey::Block(hash.clone()),
+                    P2pGossipAcceptance::Accept,
+                );
+                store.dispatch(ConsensusBlockSnarkVerifySuccessAction { hash });
                 a.effects(&meta, store);
             }
             SnarkBlockVerifyAction::Finish(_) => {}
@@ -37,27 +54,62 @@ pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMe
             }
             SnarkWorkVerifyAction::Pending(_) => {}
             SnarkWorkVerifyAction::Error(a) => {
-                let req = store.state().snark.work_verify.jobs.get(a.req_id);
+                let state = store.state();
+                let req = state.snark.work_verify.jobs.get(a.req_id);
                 let Some(req) = req else { return };
                 let sender = req.sender().parse().unwrap();
+                if sender == state.p2p.config.identity_pub_key.peer_id() {
+                    // submitted by a remote snark worker.
+                    openmina_core::log::warn!(meta.time();
+                        kind = "SnarkWorkVerifyError",
+                        summary = "remote snark worker submitted invalid work",
+                        error = format!("{:?}", a.error));
+                    a.effects(&meta, store);
+                    return;
+                }
+                let acceptance = match a.error {
+                    SnarkWorkVerifyError::ValidatorThreadCrashed => P2pGossipAcceptance::Ignore,
+                    _ => P2pGossipAcceptance::Reject,
+                };
+                let keys = req.batch().iter().map(P2pGossipValidationKey::snark);
+                for key in keys.collect::<Vec<_>>() {
+                    store
+                        .service
+                        .libp2p_gossip_validation_result(key, acceptance);
+                }
 
                 store.dispatch(SnarkPoolCandidateWorkVerifyErrorAction {
                     peer_id: sender,
                     verify_id: a.req_id,
+                    error: a.error.clone(),
                 });
                 a.effects(&meta, store);
             }
             SnarkWorkVerifyAction::Success(a) => {
-                let req = store.state().snark.work_verify.jobs.get(a.req_id);
+                let state = store.state();
+                let req = state.snark.work_verify.jobs.get(a.req_id);
                 let Some(req) = req else { return };
                 let sender = req.sender().parse().unwrap();
                 let batch = req.batch().to_vec();
 
+                if sender == state.p2p.config.identity_pub_key.peer_id() {
+                    // submitted by a remote snark worker.
+                    for snark in batch {
+                        store.dispatch(SnarkPoolWorkAddAction { snark, sender });
+                    }
+                    a.effects(&meta, store);
+                    return;
+                }
+
                 store.dispatch(SnarkPoolCandidateWorkVerifySuccessAction {
                     peer_id: sender,
                     verify_id: a.req_id,
                 });
                 for snark in batch {
+                    let key = P2pGossipValidationKey::snark(&snark);
+                    store
+                        .service
+                        .libp2p_gossip_validation_result(key, P2pGossipAcceptance::Accept);
                     store.dispatch(SnarkPoolWorkAddAction { snark, sender });
                 }
                 a.effects(&meta, store);
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
-use crate::snark::work_verify::SnarkWorkVerifyId;
+use crate::snark::work_verify::{SnarkWorkVerifyError, SnarkWorkVerifyId};
 
 use super::SnarkPoolCandidateState;
 
@@ -152,6 +152,7 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolCandidateWorkVerifyPend
 pub struct SnarkPoolCandidateWorkVerifyErrorAction {
     pub peer_id: PeerId,
     pub verify_id: SnarkWorkVerifyId,
+    pub error: SnarkWorkVerifyError,
 }
 
 impl redux::EnablingCondition<crate::State> for SnarkPoolCandidateWorkVerifyErrorAction {
@@ -1,10 +1,11 @@
 use std::collections::BTreeMap;
 
 use redux::ActionMeta;
-use snark::work_verify::SnarkWorkVerifyInitAction;
+use snark::work_verify::{SnarkWorkVerifyError, SnarkWorkVerifyInitAction};
 
 use crate::p2p::channels::rpc::{P2pChannelsRpcRequestSendAction, P2pRpcRequest};
 use crate::p2p::disconnection::{P2pDisconnectionInitAction, P2pDisconnectionReason};
+use crate::p2p::peer::{P2pPeerOffense, P2pPeerPenalizeAction};
 use crate::Store;
 
 use super::{
@@ -90,7 +91,15 @@ impl SnarkPoolCandidateWorkVerifyNextAction {
 
 impl SnarkPoolCandidateWorkVerifyErrorAction {
     pub fn effects<S: redux::Service>(self, _: &ActionMeta, store: &mut Store<S>) {
-        // TODO(binier): blacklist peer
+        match self.error {
+            SnarkWorkVerifyError::VerificationFailed => {}
+            // Not the peer's fault.
+            SnarkWorkVerifyError::ValidatorThreadCrashed => return,
+        }
+        store.dispatch(P2pPeerPenalizeAction {
+            peer_id: self.peer_id,
+            offense: P2pPeerOffense::InvalidSnark,
+        });
         store.dispatch(P2pDisconnectionInitAction {
             peer_id: self.peer_id,
             reason: P2pDisconnectionReason::SnarkPoolVerifyError,
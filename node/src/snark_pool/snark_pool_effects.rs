@@ -7,6 +7,7 @@ use crate::p2p::channels::snark::{
     P2pChannelsSnarkLibp2pBroadcastAction, P2pChannelsSnarkResponseSendAction,
 };
 use crate::p2p::channels::snark_job_commitment::P2pChannelsSnarkJobCommitmentResponseSendAction;
+use crate::rpc::{RpcEvent, RpcEventNotifyAction};
 use crate::{Service, State, Store};
 
 use super::candidate::snark_pool_candidate_effects;
@@ -24,15 +25,25 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
             snark_pool_candidate_effects(store, meta.with_action(action))
         }
         SnarkPoolAction::JobsUpdate(_) => {
+            let added_jobs = store.state().snark_pool.last_added_jobs.clone();
+            for job_id in added_jobs {
+                store.dispatch(RpcEventNotifyAction {
+                    event: RpcEvent::SnarkJobAdded { job_id },
+                });
+            }
+
             let state = store.state();
-            if let Some(job_id) = state.external_snark_worker.working_job_id() {
-                if !state.snark_pool.contains(job_id) {
-                    // job is no longer needed.
-                    store.dispatch(ExternalSnarkWorkerCancelWorkAction {});
-                }
-            } else {
-                store.dispatch(SnarkPoolAutoCreateCommitmentAction {});
+            let obsolete_works = state
+                .external_snark_worker
+                .working_job_ids()
+                .filter(|(_, job_id)| !state.snark_pool.contains(job_id))
+                .map(|(worker_id, _)| worker_id)
+                .collect::<Vec<_>>();
+            for worker_id in obsolete_works {
+                // job is no longer needed.
+                store.dispatch(ExternalSnarkWorkerCancelWorkAction { worker_id });
             }
+            store.dispatch(SnarkPoolAutoCreateCommitmentAction {});
         }
         SnarkPoolAction::AutoCreateCommitment(_) => {
             let state = store.state();
@@ -55,7 +66,11 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
             let Some(summary) = store.state().snark_pool.job_summary(&a.job_id) else {
                 return;
             };
+            let Some(worker_id) = store.state().external_snark_worker.first_idle() else {
+                return;
+            };
             if store.dispatch(ExternalSnarkWorkerSubmitWorkAction {
+                worker_id,
                 job_id: a.job_id.clone(),
                 summary,
             }) {
@@ -76,31 +91,33 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
         }
         SnarkPoolAction::CommitmentAdd(a) => {
             let state = store.state();
-            if let Some(job_id) = state.external_snark_worker.working_job_id() {
+            if let Some(worker_id) = state.external_snark_worker.working_on(&a.commitment.job_id) {
                 let Some(config) = store.state.get().config.snarker.as_ref() else {
                     return;
                 };
-                if &a.commitment.job_id == job_id
-                    && &a.commitment.snarker != config.public_key.as_ref()
-                {
-                    store.dispatch(ExternalSnarkWorkerCancelWorkAction {});
+                if &a.commitment.snarker != config.public_key.as_ref() {
+                    store.dispatch(ExternalSnarkWorkerCancelWorkAction { worker_id });
                 }
             }
         }
         SnarkPoolAction::WorkAdd(a) => {
+            store.dispatch(RpcEventNotifyAction {
+                event: RpcEvent::SnarkJobCompleted {
+                    job_id: a.snark.job_id(),
+                    snarker: a.snark.snarker.clone(),
+                    fee: a.snark.fee.clone(),
+                },
+            });
             let state = store.state();
-            if let Some(job_id) = state
-                .external_snark_worker
-                .working_job_id()
-                .filter(|job_id| *job_id == &a.snark.job_id())
-            {
+            let job_id = a.snark.job_id();
+            if let Some(worker_id) = state.external_snark_worker.working_on(&job_id) {
                 if let Some(commitment) = state
                     .snark_pool
-                    .get(job_id)
+                    .get(&job_id)
                     .and_then(|job| job.commitment.as_ref())
                 {
                     if a.snark > commitment.commitment {
-                        store.dispatch(ExternalSnarkWorkerCancelWorkAction {});
+                        store.dispatch(ExternalSnarkWorkerCancelWorkAction { worker_id });
                     }
                 }
             }
@@ -20,6 +20,7 @@ impl SnarkPoolState {
                     .collect::<BTreeMap<_, _>>();
 
                 self.retain(|id| jobs_map.remove(id).map(|(order, _)| order));
+                self.last_added_jobs = jobs_map.keys().cloned().collect();
                 for (id, (order, job)) in jobs_map {
                     self.insert(JobState {
                         time: meta.time(),
@@ -19,6 +19,8 @@ pub struct SnarkPoolState {
     by_ledger_hash_index: BTreeMap<SnarkJobId, u64>,
     pub candidates: SnarkPoolCandidatesState,
     pub(super) last_check_timeouts: Timestamp,
+    /// Jobs which were inserted by the latest jobs update.
+    pub(super) last_added_jobs: Vec<SnarkJobId>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -62,6 +64,7 @@ impl SnarkPoolState {
             by_ledger_hash_index: Default::default(),
             candidates: SnarkPoolCandidatesState::new(),
             last_check_timeouts: Timestamp::ZERO,
+            last_added_jobs: Vec::new(),
         }
     }
 
@@ -292,6 +295,8 @@ mod ser {
         list: BTreeMap<u64, JobState>,
         candidates: SnarkPoolCandidatesState,
         last_check_timeouts: Timestamp,
+        #[serde(default)]
+        last_added_jobs: Vec<SnarkJobId>,
     }
 
     impl Serialize for super::SnarkPoolState {
@@ -299,12 +304,13 @@ mod ser {
         where
             S: serde::Serializer,
         {
-            let mut s = serializer.serialize_struct("SnarkPool", 5)?;
+            let mut s = serializer.serialize_struct("SnarkPool", 6)?;
             s.serialize_field("config", &self.config)?;
             s.serialize_field("counter", &self.counter)?;
             s.serialize_field("list", &self.list)?;
             s.serialize_field("candidates", &self.candidates)?;
             s.serialize_field("last_check_timeouts", &self.last_check_timeouts)?;
+            s.serialize_field("last_added_jobs", &self.last_added_jobs)?;
             s.end()
         }
     }
@@ -322,6 +328,7 @@ mod ser {
                 by_ledger_hash_index,
                 candidates: v.candidates,
                 last_check_timeouts: v.last_check_timeouts,
+                last_added_jobs: v.last_added_jobs,
             })
         }
     }
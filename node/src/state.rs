@@ -1,6 +1,7 @@
 use redux::{ActionMeta, Timestamp};
 use serde::{Deserialize, Serialize};
 
+pub use crate::block_producer::BlockProducerState;
 use crate::config::GlobalConfig;
 pub use crate::consensus::ConsensusState;
 use crate::external_snark_worker::ExternalSnarkWorkers;
@@ -8,6 +9,7 @@ pub use crate::p2p::P2pState;
 pub use crate::rpc::RpcState;
 pub use crate::snark::SnarkState;
 pub use crate::snark_pool::SnarkPoolState;
+pub use crate::transaction_pool::TransactionPoolState;
 pub use crate::transition_frontier::TransitionFrontierState;
 pub use crate::watched_accounts::WatchedAccountsState;
 use crate::ActionWithMeta;
@@ -22,6 +24,8 @@ pub struct State {
     pub consensus: ConsensusState,
     pub transition_frontier: TransitionFrontierState,
     pub snark_pool: SnarkPoolState,
+    pub transaction_pool: TransactionPoolState,
+    pub block_producer: BlockProducerState,
     pub rpc: RpcState,
     pub external_snark_worker: ExternalSnarkWorkers,
 
@@ -38,11 +42,16 @@ impl State {
         Self {
             p2p: P2pState::new(config.p2p),
             snark_pool: SnarkPoolState::new(),
+            transaction_pool: TransactionPoolState::new(config.transaction_pool),
+            block_producer: BlockProducerState::new(now, config.block_producer),
             snark: SnarkState::new(config.snark),
             consensus: ConsensusState::new(),
-            transition_frontier: TransitionFrontierState::new(config.transition_frontier),
+            transition_frontier: TransitionFrontierState::new(config.transition_frontier, now),
             rpc: RpcState::new(),
-            external_snark_worker: ExternalSnarkWorkers::new(now),
+            external_snark_worker: ExternalSnarkWorkers::new(
+                now,
+                config.global.snarker.as_ref().map_or(0, |c| c.workers),
+            ),
 
             watched_accounts: WatchedAccountsState::new(),
 
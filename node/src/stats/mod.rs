@@ -8,13 +8,23 @@ mod stats_sync;
 pub mod sync {
     pub use super::stats_sync::*;
 }
-use sync::{SyncStats, SyncStatsSnapshot, SyncingLedger};
+use sync::{SyncBlockStatus, SyncStats, SyncStatsSnapshot, SyncingLedger};
+
+mod stats_durations;
+pub mod durations {
+    pub use super::stats_durations::*;
+}
+use durations::{DurationHistogram, P2pRpcKindStats, P2pRpcStats};
+
+use std::collections::BTreeMap;
 
 use std::collections::VecDeque;
 
-use openmina_core::block::{ArcBlockWithHash, Block, BlockWithHash};
+use openmina_core::block::{ArcBlockWithHash, Block, BlockHash, BlockWithHash};
 use redux::{ActionMeta, ActionWithMeta, Timestamp};
 
+use crate::p2p::channels::rpc::P2pRpcKind;
+use crate::p2p::PeerId;
 use crate::transition_frontier::sync::TransitionFrontierSyncBlockState;
 use crate::ActionKind;
 
@@ -24,19 +34,18 @@ pub struct Stats {
     last_action: ActionKindWithMeta,
     action_stats: ActionStats,
     sync_stats: SyncStats,
+    p2p_rpc_stats: P2pRpcStats,
+    block_apply_durations: DurationHistogram,
 }
 
 impl Stats {
-    pub fn new() -> Self {
+    pub fn new(genesis_block_hash: BlockHash) -> Self {
         let mut action_stats_per_block = VecDeque::new();
         action_stats_per_block.push_back(ActionStatsForBlock {
             id: 0,
             time: Timestamp::ZERO,
             block_level: 1,
-            // TODO(binier): use configured genesis hash.
-            block_hash: "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ"
-                .parse()
-                .unwrap(),
+            block_hash: genesis_block_hash,
             cpu_idle: 0,
             cpu_busy: 0,
             stats: Default::default(),
@@ -48,6 +57,8 @@ impl Stats {
                 per_block: action_stats_per_block,
             },
             sync_stats: Default::default(),
+            p2p_rpc_stats: Default::default(),
+            block_apply_durations: Default::default(),
         }
     }
 
@@ -71,6 +82,16 @@ impl Stats {
 
     pub fn syncing_block_update(&mut self, state: &TransitionFrontierSyncBlockState) -> &mut Self {
         self.sync_stats.block_update(state);
+        let block = self.sync_stats.block(state.block_hash());
+        if let Some(block) = block.filter(|b| matches!(b.status, SyncBlockStatus::Applied)) {
+            if let Some(duration) = block
+                .apply_end
+                .zip(block.apply_start)
+                .and_then(|(end, start)| end.checked_sub(start))
+            {
+                self.block_apply_durations.add(duration.as_nanos() as u64);
+            }
+        }
         self
     }
 
@@ -92,6 +113,31 @@ impl Stats {
         self
     }
 
+    pub fn p2p_rpc_request_sent(
+        &mut self,
+        time: Timestamp,
+        peer_id: PeerId,
+        kind: P2pRpcKind,
+    ) -> &mut Self {
+        self.p2p_rpc_stats.request_sent(time, peer_id, kind);
+        self
+    }
+
+    pub fn p2p_rpc_response_received(&mut self, time: Timestamp, peer_id: &PeerId) -> &mut Self {
+        self.p2p_rpc_stats.response_received(time, peer_id);
+        self
+    }
+
+    pub fn p2p_rpc_timeout(&mut self, peer_id: &PeerId) -> &mut Self {
+        self.p2p_rpc_stats.timeout(peer_id);
+        self
+    }
+
+    pub fn p2p_peer_disconnected(&mut self, peer_id: &PeerId) -> &mut Self {
+        self.p2p_rpc_stats.peer_disconnected(peer_id);
+        self
+    }
+
     pub fn collect_action_stats_since_start(&self) -> ActionStatsSnapshot {
         self.action_stats.since_start.clone()
     }
@@ -113,10 +159,13 @@ impl Stats {
             .first()
             .and_then(|stats| stats.synced)
     }
-}
 
-impl Default for Stats {
-    fn default() -> Self {
-        Self::new()
+    pub fn collect_p2p_rpc_stats(&self) -> BTreeMap<P2pRpcKind, P2pRpcKindStats> {
+        self.p2p_rpc_stats.collect_stats()
+    }
+
+    /// Durations of applying blocks to the transition frontier.
+    pub fn collect_block_apply_durations(&self) -> DurationHistogram {
+        self.block_apply_durations.clone()
     }
 }
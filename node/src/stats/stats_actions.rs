@@ -80,6 +80,15 @@ impl ActionStatsSnapshot {
         }
         self.0[kind_i].add(duration);
     }
+
+    /// Stats per action kind, skipping the `None` action.
+    pub fn iter(&self) -> impl Iterator<Item = (ActionKind, &ActionStatsForRanges)> {
+        self.0
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(i, v)| Some((ActionKind::try_from(i as u16).ok()?, v)))
+    }
 }
 
 impl Serialize for ActionStatsSnapshot {
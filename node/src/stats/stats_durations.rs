@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::channels::rpc::P2pRpcKind;
+use crate::p2p::PeerId;
+
+/// Upper bounds (inclusive) of the histogram buckets in nanoseconds.
+pub const DURATION_BUCKETS: [u64; 12] = [
+    1_000_000,
+    5_000_000,
+    10_000_000,
+    50_000_000,
+    100_000_000,
+    500_000_000,
+    1_000_000_000,
+    5_000_000_000,
+    10_000_000_000,
+    30_000_000_000,
+    60_000_000_000,
+    120_000_000_000,
+];
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct DurationHistogram {
+    /// Number of durations in each of `DURATION_BUCKETS`, not including
+    /// durations from the previous buckets.
+    pub buckets: [u64; DURATION_BUCKETS.len()],
+    /// Total number of durations, including those above the last bucket.
+    pub count: u64,
+    /// Sum of durations in nanoseconds.
+    pub sum: u64,
+}
+
+impl DurationHistogram {
+    pub fn add(&mut self, duration: u64) {
+        if let Some(i) = DURATION_BUCKETS.iter().position(|bound| duration <= *bound) {
+            self.buckets[i] += 1;
+        }
+        self.count += 1;
+        self.sum += duration;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct P2pRpcKindStats {
+    /// Durations from sending the request till receiving the response.
+    pub latency: DurationHistogram,
+    /// Number of requests which peers failed to respond to in time.
+    pub timeouts: u64,
+}
+
+#[derive(Default)]
+pub struct P2pRpcStats {
+    /// Outgoing requests waiting for the response, per peer. We can have
+    /// only one such request per peer.
+    pending: BTreeMap<PeerId, (P2pRpcKind, Timestamp)>,
+    per_kind: BTreeMap<P2pRpcKind, P2pRpcKindStats>,
+}
+
+impl P2pRpcStats {
+    pub fn request_sent(&mut self, time: Timestamp, peer_id: PeerId, kind: P2pRpcKind) {
+        self.pending.insert(peer_id, (kind, time));
+    }
+
+    pub fn response_received(&mut self, time: Timestamp, peer_id: &PeerId) {
+        let Some((kind, sent)) = self.pending.remove(peer_id) else {
+            return;
+        };
+        let duration = time.checked_sub(sent).unwrap_or_default();
+        self.per_kind
+            .entry(kind)
+            .or_default()
+            .latency
+            .add(duration.as_nanos() as u64);
+    }
+
+    pub fn timeout(&mut self, peer_id: &PeerId) {
+        let Some((kind, _)) = self.pending.remove(peer_id) else {
+            return;
+        };
+        self.per_kind.entry(kind).or_default().timeouts += 1;
+    }
+
+    /// Forgets the pending request to the peer, as its response will never
+    /// arrive and the peer id may be reused by the next connection.
+    pub fn peer_disconnected(&mut self, peer_id: &PeerId) {
+        self.pending.remove(peer_id);
+    }
+
+    pub fn collect_stats(&self) -> BTreeMap<P2pRpcKind, P2pRpcKindStats> {
+        self.per_kind.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disconnect_clears_pending_request() {
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let mut stats = P2pRpcStats::default();
+
+        stats.request_sent(Timestamp::new(0), peer_id, P2pRpcKind::BestTipWithProof);
+        stats.peer_disconnected(&peer_id);
+        assert!(stats.pending.is_empty());
+
+        // Response from the next connection with the same peer id must not
+        // be attributed to the request sent before the disconnect.
+        stats.response_received(Timestamp::new(5_000_000_000), &peer_id);
+        assert!(stats.collect_stats().is_empty());
+
+        stats.request_sent(Timestamp::new(0), peer_id, P2pRpcKind::BestTipWithProof);
+        stats.response_received(Timestamp::new(2_000_000), &peer_id);
+        let latency = &stats.collect_stats()[&P2pRpcKind::BestTipWithProof].latency;
+        assert_eq!(latency.count, 1);
+        assert_eq!(latency.buckets[1], 1);
+        assert_eq!(latency.sum, 2_000_000);
+    }
+}
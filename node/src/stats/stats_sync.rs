@@ -243,6 +243,12 @@ impl SyncStats {
         self
     }
 
+    /// Stats of the block in the current sync.
+    pub fn block(&self, hash: &StateHash) -> Option<&SyncBlock> {
+        let snapshot = self.snapshots.back()?;
+        snapshot.blocks.iter().find(|b| &b.hash == hash)
+    }
+
     pub fn synced(&mut self, time: Timestamp) -> &mut Self {
         let Some(snapshot) = self.snapshots.back_mut() else {
             return self;
@@ -0,0 +1,17 @@
+mod transaction_pool_config;
+pub use transaction_pool_config::*;
+
+mod transaction_pool_state;
+pub use transaction_pool_state::*;
+
+mod transaction_pool_actions;
+pub use transaction_pool_actions::*;
+
+mod transaction_pool_reducer;
+pub use transaction_pool_reducer::*;
+
+mod transaction_pool_effects;
+pub use transaction_pool_effects::*;
+
+mod transaction_pool_service;
+pub use transaction_pool_service::*;
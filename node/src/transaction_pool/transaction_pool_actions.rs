@@ -0,0 +1,129 @@
+use mina_p2p_messages::v2::{MinaBaseUserCommandStableV2, TransactionHash};
+use serde::{Deserialize, Serialize};
+
+use crate::p2p::PeerId;
+
+use super::TransactionPoolValidateError;
+
+pub type TransactionPoolActionWithMeta = redux::ActionWithMeta<TransactionPoolAction>;
+pub type TransactionPoolActionWithMetaRef<'a> = redux::ActionWithMeta<&'a TransactionPoolAction>;
+
+#[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
+pub enum TransactionPoolAction {
+    Received(TransactionPoolReceivedAction),
+    Add(TransactionPoolAddAction),
+    Reject(TransactionPoolRejectAction),
+    Remove(TransactionPoolRemoveAction),
+    BestTipUpdate(TransactionPoolBestTipUpdateAction),
+    P2pSendAll(TransactionPoolP2pSendAllAction),
+    P2pSend(TransactionPoolP2pSendAction),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolReceivedAction {
+    pub hash: TransactionHash,
+    pub transaction: Box<MinaBaseUserCommandStableV2>,
+    /// Peer from which we received the transaction. `None` if it was
+    /// submitted to our node directly.
+    pub sender: Option<PeerId>,
+}
+
+impl redux::EnablingCondition<crate::State> for TransactionPoolReceivedAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.transition_frontier.best_tip().is_some()
+            && !state.transaction_pool.contains(&self.hash)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolAddAction {
+    pub hash: TransactionHash,
+    pub transaction: Box<MinaBaseUserCommandStableV2>,
+    pub sender: Option<PeerId>,
+}
+
+impl redux::EnablingCondition<crate::State> for TransactionPoolAddAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        let MinaBaseUserCommandStableV2::SignedCommand(cmd) = self.transaction.as_ref() else {
+            return false;
+        };
+        let pool = &state.transaction_pool;
+        let common = &cmd.payload.common;
+        !pool.contains(&self.hash)
+            && pool
+                .get_by_fee_payer_and_nonce(
+                    &common.fee_payer_pk.clone().into(),
+                    common.nonce.as_u32(),
+                )
+                // Replace existing transaction only if fee is higher.
+                .map_or(true, |cur| common.fee.as_u64() > cur.fee)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolRejectAction {
+    pub hash: TransactionHash,
+    pub sender: Option<PeerId>,
+    pub error: TransactionPoolValidateError,
+}
+
+impl redux::EnablingCondition<crate::State> for TransactionPoolRejectAction {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolRemoveAction {
+    pub hash: TransactionHash,
+}
+
+impl redux::EnablingCondition<crate::State> for TransactionPoolRemoveAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.transaction_pool.contains(&self.hash)
+    }
+}
+
+/// Revalidate transactions in the pool against the new best tip and
+/// remove ones which can no longer be applied.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolBestTipUpdateAction {}
+
+impl redux::EnablingCondition<crate::State> for TransactionPoolBestTipUpdateAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.transition_frontier.best_tip().is_some() && !state.transaction_pool.is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolP2pSendAllAction {}
+
+impl redux::EnablingCondition<crate::State> for TransactionPoolP2pSendAllAction {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolP2pSendAction {
+    pub peer_id: PeerId,
+}
+
+impl redux::EnablingCondition<crate::State> for TransactionPoolP2pSendAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        state.p2p.get_ready_peer(&self.peer_id).map_or(false, |p| {
+            let (next_index, limit) = p.channels.transaction.next_send_index_and_limit();
+            limit > 0 && next_index <= state.transaction_pool.last_index()
+        })
+    }
+}
+
+macro_rules! impl_into_global_action {
+    ($a:ty) => {
+        impl From<$a> for crate::Action {
+            fn from(value: $a) -> Self {
+                Self::TransactionPool(value.into())
+            }
+        }
+    };
+}
+
+impl_into_global_action!(TransactionPoolReceivedAction);
+impl_into_global_action!(TransactionPoolAddAction);
+impl_into_global_action!(TransactionPoolRejectAction);
+impl_into_global_action!(TransactionPoolRemoveAction);
+impl_into_global_action!(TransactionPoolBestTipUpdateAction);
+impl_into_global_action!(TransactionPoolP2pSendAllAction);
+impl_into_global_action!(TransactionPoolP2pSendAction);
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionPoolConfig {
+    /// Maximum amount of transactions kept in the pool. Once reached,
+    /// transactions with the lowest fee get evicted.
+    pub max_size: usize,
+    /// Minimum fee (in nanomina) for the transaction to be accepted.
+    pub min_fee: u64,
+}
+
+impl Default for TransactionPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 3000,
+            min_fee: 1_000_000,
+        }
+    }
+}
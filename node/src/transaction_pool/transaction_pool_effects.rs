@@ -0,0 +1,161 @@
+use mina_p2p_messages::v2::MinaBaseUserCommandStableV2;
+
+use crate::p2p::channels::transaction::{
+    P2pChannelsTransactionLibp2pBroadcastAction, P2pChannelsTransactionResponseSendAction,
+};
+use crate::p2p::channels::{P2pChannelsService, P2pGossipAcceptance, P2pGossipValidationKey};
+use crate::{Service, Store};
+
+use super::{
+    TransactionPoolAction, TransactionPoolActionWithMeta, TransactionPoolAddAction,
+    TransactionPoolP2pSendAction, TransactionPoolP2pSendAllAction, TransactionPoolRejectAction,
+    TransactionPoolRemoveAction, TransactionPoolValidateError,
+};
+
+pub fn transaction_pool_effects<S: Service>(
+    store: &mut Store<S>,
+    action: TransactionPoolActionWithMeta,
+) {
+    let (action, _) = action.split();
+
+    match action {
+        TransactionPoolAction::Received(a) => {
+            let key = P2pGossipValidationKey::Transaction(a.hash.clone());
+            let from_libp2p = a
+                .sender
+                .map_or(false, |peer_id| store.state().p2p.is_libp2p_peer(&peer_id));
+            let acceptance = match transaction_pool_validate(store, &a.transaction) {
+                Ok(()) => {
+                    let added = store.dispatch(TransactionPoolAddAction {
+                        hash: a.hash,
+                        transaction: a.transaction,
+                        sender: a.sender,
+                    });
+                    if added {
+                        P2pGossipAcceptance::Accept
+                    } else {
+                        // Has lower fee than the one already in the pool.
+                        P2pGossipAcceptance::Ignore
+                    }
+                }
+                Err(error) => {
+                    let acceptance = if error.is_invalid() {
+                        P2pGossipAcceptance::Reject
+                    } else {
+                        // Peer might be at a different best tip.
+                        P2pGossipAcceptance::Ignore
+                    };
+                    store.dispatch(TransactionPoolRejectAction {
+                        hash: a.hash,
+                        sender: a.sender,
+                        error,
+                    });
+                    acceptance
+                }
+            };
+            if from_libp2p {
+                store
+                    .service
+                    .libp2p_gossip_validation_result(key, acceptance);
+            }
+        }
+        TransactionPoolAction::Add(a) => {
+            if !store.state().transaction_pool.contains(&a.hash) {
+                return;
+            }
+            store.dispatch(TransactionPoolP2pSendAllAction {});
+            // Gossipsub forwards messages from libp2p peers on its own.
+            let from_libp2p = a
+                .sender
+                .map_or(false, |peer_id| store.state().p2p.is_libp2p_peer(&peer_id));
+            if !from_libp2p {
+                store.dispatch(P2pChannelsTransactionLibp2pBroadcastAction {
+                    transaction: a.transaction,
+                    nonce: 0,
+                });
+            }
+        }
+        TransactionPoolAction::Reject(_) => {}
+        TransactionPoolAction::Remove(_) => {}
+        TransactionPoolAction::BestTipUpdate(_) => {
+            let state = store.state();
+            let Some(best_tip) = state.transition_frontier.best_tip() else {
+                return;
+            };
+            let staged_ledger_hash = best_tip.staged_ledger_hash().clone();
+            let fee_payers = state.transaction_pool.fee_payers().cloned().collect();
+
+            let accounts = store
+                .service
+                .transaction_pool_accounts(&staged_ledger_hash, fee_payers);
+            let invalid = store
+                .state()
+                .transaction_pool
+                .invalid_for_accounts(&accounts);
+            for hash in invalid {
+                store.dispatch(TransactionPoolRemoveAction { hash });
+            }
+        }
+        TransactionPoolAction::P2pSendAll(_) => {
+            for peer_id in store.state().p2p.ready_peers() {
+                store.dispatch(TransactionPoolP2pSendAction { peer_id });
+            }
+        }
+        TransactionPoolAction::P2pSend(a) => {
+            let state = store.state();
+            let Some(peer) = state.p2p.get_ready_peer(&a.peer_id) else {
+                return;
+            };
+
+            let index_and_limit = peer.channels.transaction.next_send_index_and_limit();
+            let (transactions, first_index, last_index) =
+                state.transaction_pool.data_to_send(index_and_limit);
+
+            store.dispatch(P2pChannelsTransactionResponseSendAction {
+                peer_id: a.peer_id,
+                transactions,
+                first_index,
+                last_index,
+            });
+        }
+    }
+}
+
+/// Validates the transaction against the staged ledger of the best tip
+/// and the transactions from the same fee payer already in the pool.
+pub fn transaction_pool_validate<S: Service>(
+    store: &mut Store<S>,
+    transaction: &MinaBaseUserCommandStableV2,
+) -> Result<(), TransactionPoolValidateError> {
+    let state = store.state();
+    let Some(best_tip) = state.transition_frontier.best_tip() else {
+        return Err(TransactionPoolValidateError::LedgerNotFound);
+    };
+    let staged_ledger_hash = best_tip.staged_ledger_hash().clone();
+
+    match transaction {
+        MinaBaseUserCommandStableV2::ZkappCommand(_) => {
+            Err(TransactionPoolValidateError::ZkappCommandUnsupported)
+        }
+        MinaBaseUserCommandStableV2::SignedCommand(cmd) => {
+            let common = &cmd.payload.common;
+            let fee = common.fee.as_u64();
+            let min_fee = state.transaction_pool.config().min_fee;
+            let fee_payer = common.fee_payer_pk.clone().into();
+            let pool_next_nonce = state.transaction_pool.next_nonce(&fee_payer);
+            let pool_pending_cost = state
+                .transaction_pool
+                .pending_cost(&fee_payer, common.nonce.as_u32());
+            if fee < min_fee {
+                Err(TransactionPoolValidateError::FeeTooLow { fee, min_fee })
+            } else {
+                store.service.transaction_pool_validate(
+                    &staged_ledger_hash,
+                    transaction,
+                    pool_next_nonce,
+                    pool_pending_cost,
+                )
+            }
+        }
+    }
+}
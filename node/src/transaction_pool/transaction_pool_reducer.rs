@@ -0,0 +1,31 @@
+use super::{
+    TransactionPoolAction, TransactionPoolActionWithMetaRef, TransactionPoolState, TransactionState,
+};
+
+impl TransactionPoolState {
+    pub fn reducer(&mut self, action: TransactionPoolActionWithMetaRef<'_>) {
+        let (action, meta) = action.split();
+        match action {
+            TransactionPoolAction::Received(_) => {}
+            TransactionPoolAction::Add(a) => {
+                let Some(tx) = TransactionState::new(
+                    meta.time(),
+                    a.hash.clone(),
+                    a.transaction.clone(),
+                    a.sender,
+                ) else {
+                    return;
+                };
+                self.insert(tx);
+                self.evict_overflow();
+            }
+            TransactionPoolAction::Reject(_) => {}
+            TransactionPoolAction::Remove(a) => {
+                self.remove_with_dependents(&a.hash);
+            }
+            TransactionPoolAction::BestTipUpdate(_) => {}
+            TransactionPoolAction::P2pSendAll(_) => {}
+            TransactionPoolAction::P2pSend(_) => {}
+        }
+    }
+}
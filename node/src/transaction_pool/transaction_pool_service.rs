@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+use mina_p2p_messages::v2::{LedgerHash, MinaBaseUserCommandStableV2};
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+
+#[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
+pub enum TransactionPoolValidateError {
+    #[error("zkapp commands are not supported yet")]
+    ZkappCommandUnsupported,
+    #[error("staged ledger not found")]
+    LedgerNotFound,
+    #[error("fee {fee} is lower than the minimum fee {min_fee}")]
+    FeeTooLow { fee: u64, min_fee: u64 },
+    #[error("invalid public keys")]
+    InvalidKeys,
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("fee payer account not found")]
+    AccountNotFound,
+    #[error("invalid nonce {nonce}, expected nonce between {min_nonce} and {max_nonce}")]
+    InvalidNonce {
+        nonce: u32,
+        min_nonce: u32,
+        max_nonce: u32,
+    },
+    #[error("insufficient balance {balance}, needed {cost}")]
+    InsufficientBalance { balance: u64, cost: u64 },
+}
+
+impl TransactionPoolValidateError {
+    /// Whether the transaction is invalid regardless of our best tip
+    /// and config, so that the peer which sent it can be penalized.
+    pub fn is_invalid(&self) -> bool {
+        matches!(self, Self::InvalidKeys | Self::InvalidSignature)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct TransactionPoolAccount {
+    pub nonce: u32,
+    pub balance: u64,
+}
+
+pub trait TransactionPoolService: redux::Service {
+    /// Validates the transaction against the staged ledger.
+    ///
+    /// `pool_next_nonce` is the nonce that follows the last transaction
+    /// from the same fee payer, which is already in the pool, and
+    /// `pool_pending_cost` is the total cost of the fee payer's
+    /// transactions in the pool, which get applied before this one.
+    fn transaction_pool_validate(
+        &mut self,
+        staged_ledger_hash: &LedgerHash,
+        transaction: &MinaBaseUserCommandStableV2,
+        pool_next_nonce: Option<u32>,
+        pool_pending_cost: u64,
+    ) -> Result<(), TransactionPoolValidateError>;
+
+    /// Nonces and balances of the given accounts in the staged ledger.
+    /// Missing accounts aren't included in the result.
+    fn transaction_pool_accounts(
+        &mut self,
+        staged_ledger_hash: &LedgerHash,
+        accounts: Vec<AccountPublicKey>,
+    ) -> BTreeMap<AccountPublicKey, TransactionPoolAccount>;
+}
@@ -0,0 +1,558 @@
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+use std::{cmp::Reverse, fmt, ops::RangeBounds};
+
+use mina_p2p_messages::v2::{
+    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseUserCommandStableV2, TransactionHash,
+};
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+use crate::p2p::PeerId;
+
+use super::{TransactionPoolAccount, TransactionPoolConfig};
+
+#[derive(Clone)]
+pub struct TransactionPoolState {
+    config: TransactionPoolConfig,
+    counter: u64,
+    list: BTreeMap<u64, TransactionState>,
+    by_hash: BTreeMap<TransactionHash, u64>,
+    /// Transaction indexes by fee payer and nonce.
+    by_fee_payer: BTreeMap<AccountPublicKey, BTreeMap<u32, u64>>,
+    /// Transaction indexes sorted by `(fee, index)`.
+    by_fee: BTreeSet<(u64, u64)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionState {
+    pub time: Timestamp,
+    pub hash: TransactionHash,
+    pub transaction: Box<MinaBaseUserCommandStableV2>,
+    pub fee_payer: AccountPublicKey,
+    pub nonce: u32,
+    pub fee: u64,
+    /// Peer from which we received the transaction. `None` if it was
+    /// submitted to our node directly.
+    pub sender: Option<PeerId>,
+}
+
+impl TransactionPoolState {
+    pub fn new(config: TransactionPoolConfig) -> Self {
+        Self {
+            config,
+            counter: 0,
+            list: Default::default(),
+            by_hash: Default::default(),
+            by_fee_payer: Default::default(),
+            by_fee: Default::default(),
+        }
+    }
+
+    pub fn config(&self) -> &TransactionPoolConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn last_index(&self) -> u64 {
+        self.list.last_key_value().map_or(0, |(k, _)| *k)
+    }
+
+    pub fn contains(&self, hash: &TransactionHash) -> bool {
+        self.by_hash.contains_key(hash)
+    }
+
+    pub fn get(&self, hash: &TransactionHash) -> Option<&TransactionState> {
+        self.by_hash.get(hash).and_then(|i| self.list.get(i))
+    }
+
+    pub fn get_by_fee_payer_and_nonce(
+        &self,
+        fee_payer: &AccountPublicKey,
+        nonce: u32,
+    ) -> Option<&TransactionState> {
+        self.by_fee_payer
+            .get(fee_payer)
+            .and_then(|txs| txs.get(&nonce))
+            .and_then(|i| self.list.get(i))
+    }
+
+    /// Nonce which must be used by the next transaction of the fee payer,
+    /// if the fee payer already has transactions in the pool.
+    pub fn next_nonce(&self, fee_payer: &AccountPublicKey) -> Option<u32> {
+        self.by_fee_payer
+            .get(fee_payer)
+            .and_then(|txs| txs.last_key_value())
+            .map(|(nonce, _)| nonce.saturating_add(1))
+    }
+
+    /// Total cost of the fee payer's transactions in the pool with nonces
+    /// lower than `nonce`, which get applied before the transaction with
+    /// that nonce.
+    pub fn pending_cost(&self, fee_payer: &AccountPublicKey, nonce: u32) -> u64 {
+        self.by_fee_payer
+            .get(fee_payer)
+            .into_iter()
+            .flat_map(|txs| txs.range(..nonce))
+            .filter_map(|(_, index)| self.list.get(index))
+            .fold(0, |sum, tx| sum.saturating_add(tx.cost()))
+    }
+
+    pub fn fee_payers(&self) -> impl '_ + Iterator<Item = &AccountPublicKey> {
+        self.by_fee_payer.keys()
+    }
+
+    pub fn lowest_fee(&self) -> Option<&TransactionState> {
+        self.by_fee
+            .first()
+            .and_then(|(_, index)| self.list.get(index))
+    }
+
+    /// Inserts the transaction into the pool. If the pool already has
+    /// a transaction with the same fee payer and nonce, it gets replaced.
+    pub fn insert(&mut self, tx: TransactionState) {
+        if let Some(hash) = self
+            .get_by_fee_payer_and_nonce(&tx.fee_payer, tx.nonce)
+            .map(|tx| tx.hash.clone())
+        {
+            self.remove(&hash);
+        }
+
+        let index = self.counter;
+        self.by_hash.insert(tx.hash.clone(), index);
+        self.by_fee_payer
+            .entry(tx.fee_payer.clone())
+            .or_default()
+            .insert(tx.nonce, index);
+        self.by_fee.insert((tx.fee, index));
+        self.list.insert(index, tx);
+        self.counter += 1;
+    }
+
+    pub fn remove(&mut self, hash: &TransactionHash) -> Option<TransactionState> {
+        let index = self.by_hash.remove(hash)?;
+        let tx = self.list.remove(&index)?;
+        self.by_fee.remove(&(tx.fee, index));
+        if let Some(txs) = self.by_fee_payer.get_mut(&tx.fee_payer) {
+            txs.remove(&tx.nonce);
+            if txs.is_empty() {
+                self.by_fee_payer.remove(&tx.fee_payer);
+            }
+        }
+        Some(tx)
+    }
+
+    /// Removes the transaction along with the transactions of the same
+    /// fee payer with higher nonces, as those can't be applied anymore.
+    pub fn remove_with_dependents(&mut self, hash: &TransactionHash) -> Vec<TransactionState> {
+        let Some(tx) = self.get(hash) else {
+            return vec![];
+        };
+        let dependents = self
+            .by_fee_payer
+            .get(&tx.fee_payer)
+            .into_iter()
+            .flat_map(|txs| txs.range(tx.nonce..))
+            .filter_map(|(_, index)| self.list.get(index))
+            .map(|tx| tx.hash.clone())
+            .collect::<Vec<_>>();
+        dependents
+            .into_iter()
+            .filter_map(|hash| self.remove(&hash))
+            .collect()
+    }
+
+    /// Evicts transactions with the lowest fee until pool size is
+    /// within the configured limit.
+    pub fn evict_overflow(&mut self) {
+        while self.list.len() > self.config.max_size {
+            let Some(hash) = self.lowest_fee().map(|tx| tx.hash.clone()) else {
+                return;
+            };
+            self.remove_with_dependents(&hash);
+        }
+    }
+
+    pub fn range<'a, R>(
+        &'a self,
+        range: R,
+    ) -> impl 'a + DoubleEndedIterator<Item = (u64, &'a TransactionState)>
+    where
+        R: RangeBounds<u64>,
+    {
+        self.list.range(range).map(|(k, v)| (*k, v))
+    }
+
+    /// Transactions to send to the peer, starting from `index`, along
+    /// with the first and the last index which they cover.
+    pub fn data_to_send(
+        &self,
+        (index, limit): (u64, u8),
+    ) -> (Vec<Box<MinaBaseUserCommandStableV2>>, u64, u64) {
+        if limit == 0 {
+            let index = index.saturating_sub(1);
+            return (vec![], index, index);
+        }
+
+        let list = self.range(index..).take(limit as usize).collect::<Vec<_>>();
+        let first_index = list.first().map_or(index, |(index, _)| *index);
+        let last_index = match list.last() {
+            Some((last, _)) if list.len() >= limit as usize => *last,
+            _ => self.last_index(),
+        };
+        let transactions = list
+            .into_iter()
+            .map(|(_, tx)| tx.transaction.clone())
+            .collect();
+
+        (transactions, first_index, last_index)
+    }
+
+    /// Transactions with the highest fees, while making sure that
+    /// transactions of the same fee payer are ordered by nonce.
+    pub fn best_transactions(&self, limit: usize) -> Vec<&TransactionState> {
+        let mut queues = self
+            .by_fee_payer
+            .values()
+            .map(|txs| {
+                txs.values()
+                    .filter_map(|index| self.list.get(index))
+                    .collect::<VecDeque<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut heap = queues
+            .iter()
+            .enumerate()
+            .filter_map(|(i, txs)| Some((txs.front()?.fee, Reverse(i))))
+            .collect::<BinaryHeap<_>>();
+
+        let mut result = Vec::with_capacity(limit.min(self.list.len()));
+        while result.len() < limit {
+            let Some((_, Reverse(i))) = heap.pop() else {
+                break;
+            };
+            let Some(tx) = queues[i].pop_front() else {
+                continue;
+            };
+            result.push(tx);
+            if let Some(next) = queues[i].front() {
+                heap.push((next.fee, Reverse(i)));
+            }
+        }
+        result
+    }
+
+    /// Hashes of the transactions, which can no longer be applied on
+    /// top of the ledger with the given accounts.
+    pub fn invalid_for_accounts(
+        &self,
+        accounts: &BTreeMap<AccountPublicKey, TransactionPoolAccount>,
+    ) -> Vec<TransactionHash> {
+        self.by_fee_payer
+            .iter()
+            .flat_map(|(fee_payer, txs)| {
+                let account = accounts.get(fee_payer);
+                let mut balance = account.map_or(0, |a| a.balance);
+                let mut expected_nonce = account.map(|a| a.nonce);
+                txs.values()
+                    .filter_map(|index| self.list.get(index))
+                    .filter(move |tx| {
+                        let Some(nonce) = expected_nonce.as_mut() else {
+                            return true;
+                        };
+                        // already applied, or there is a gap in nonces.
+                        if tx.nonce != *nonce {
+                            return tx.nonce < *nonce || balance == 0;
+                        }
+                        match balance.checked_sub(tx.cost()) {
+                            Some(rest) => {
+                                balance = rest;
+                                *nonce += 1;
+                                false
+                            }
+                            None => {
+                                balance = 0;
+                                true
+                            }
+                        }
+                    })
+                    .map(|tx| tx.hash.clone())
+            })
+            .collect()
+    }
+}
+
+impl Default for TransactionPoolState {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl fmt::Debug for TransactionPoolState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransactionPoolState")
+            .field("counter", &self.counter)
+            .field("len", &self.list.len())
+            .finish()
+    }
+}
+
+impl TransactionState {
+    pub fn new(
+        time: Timestamp,
+        hash: TransactionHash,
+        transaction: Box<MinaBaseUserCommandStableV2>,
+        sender: Option<PeerId>,
+    ) -> Option<Self> {
+        let MinaBaseUserCommandStableV2::SignedCommand(cmd) = transaction.as_ref() else {
+            return None;
+        };
+        let common = &cmd.payload.common;
+        Some(Self {
+            time,
+            hash,
+            fee_payer: common.fee_payer_pk.clone().into(),
+            nonce: common.nonce.as_u32(),
+            fee: common.fee.as_u64(),
+            transaction,
+            sender,
+        })
+    }
+
+    pub fn amount(&self) -> u64 {
+        match self.transaction.as_ref() {
+            MinaBaseUserCommandStableV2::SignedCommand(cmd) => match &cmd.payload.body {
+                MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => {
+                    payment.amount.as_u64()
+                }
+                MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(_) => 0,
+            },
+            MinaBaseUserCommandStableV2::ZkappCommand(_) => 0,
+        }
+    }
+
+    /// Total amount that gets deducted from the fee payer's balance.
+    pub fn cost(&self) -> u64 {
+        self.fee.saturating_add(self.amount())
+    }
+}
+
+mod ser {
+    use super::*;
+    use serde::ser::SerializeStruct;
+
+    #[derive(Serialize, Deserialize)]
+    struct TransactionPool {
+        config: TransactionPoolConfig,
+        counter: u64,
+        list: BTreeMap<u64, TransactionState>,
+    }
+
+    impl Serialize for super::TransactionPoolState {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            let mut s = serializer.serialize_struct("TransactionPool", 3)?;
+            s.serialize_field("config", &self.config)?;
+            s.serialize_field("counter", &self.counter)?;
+            s.serialize_field("list", &self.list)?;
+            s.end()
+        }
+    }
+    impl<'de> Deserialize<'de> for super::TransactionPoolState {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let v = TransactionPool::deserialize(deserializer)?;
+            let mut by_hash = BTreeMap::new();
+            let mut by_fee_payer = BTreeMap::<_, BTreeMap<_, _>>::new();
+            let mut by_fee = BTreeSet::new();
+            for (index, tx) in &v.list {
+                by_hash.insert(tx.hash.clone(), *index);
+                by_fee_payer
+                    .entry(tx.fee_payer.clone())
+                    .or_default()
+                    .insert(tx.nonce, *index);
+                by_fee.insert((tx.fee, *index));
+            }
+            Ok(Self {
+                config: v.config,
+                counter: v.counter,
+                list: v.list,
+                by_hash,
+                by_fee_payer,
+                by_fee,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::currency::{Amount, Fee, Nonce};
+    use ledger::scan_state::transaction_logic::signed_command::{
+        Body, PaymentPayload, SignedCommandPayload,
+    };
+    use ledger::scan_state::transaction_logic::Memo;
+    use mina_p2p_messages::v2::MinaBaseSignedCommandStableV2;
+    use mina_signer::NetworkId;
+
+    use redux::EnablingCondition;
+
+    use crate::account::AccountSecretKey;
+    use crate::p2p::channels::transaction::{
+        P2pChannelsTransactionResponseSendAction, P2pChannelsTransactionState,
+        TransactionPropagationState,
+    };
+    use crate::p2p::channels::ChannelId;
+    use crate::p2p::identity::SecretKey;
+    use crate::p2p::{P2pConfig, P2pPeerState, P2pPeerStatus, P2pPeerStatusReady, P2pState};
+
+    use super::*;
+
+    fn tx(sender: &AccountSecretKey, nonce: u32, fee: u64, amount: u64) -> TransactionState {
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(fee),
+            sender.public_key().as_ref().into(),
+            Nonce::from_u32(nonce),
+            None,
+            Memo::empty(),
+            Body::Payment(PaymentPayload {
+                receiver_pk: sender.public_key().as_ref().into(),
+                amount: Amount::from_u64(amount),
+            }),
+        );
+        let cmd = sender.sign_command(NetworkId::TESTNET, payload);
+        let cmd = MinaBaseSignedCommandStableV2::from(&cmd);
+        let hash = cmd.hash().unwrap();
+        let cmd = Box::new(MinaBaseUserCommandStableV2::SignedCommand(cmd));
+        TransactionState::new(Timestamp::ZERO, hash, cmd, None).unwrap()
+    }
+
+    fn ids(txs: Vec<&TransactionState>) -> Vec<(AccountPublicKey, u32)> {
+        txs.into_iter()
+            .map(|tx| (tx.fee_payer.clone(), tx.nonce))
+            .collect()
+    }
+
+    #[test]
+    fn best_transactions_keep_nonce_order() {
+        let (a, b) = (AccountSecretKey::rand(), AccountSecretKey::rand());
+        let mut pool = TransactionPoolState::default();
+        pool.insert(tx(&a, 1, 10, 0));
+        pool.insert(tx(&a, 0, 1, 0));
+        pool.insert(tx(&b, 0, 5, 0));
+
+        assert_eq!(
+            ids(pool.best_transactions(usize::MAX)),
+            vec![
+                (b.public_key(), 0),
+                (a.public_key(), 0),
+                (a.public_key(), 1)
+            ]
+        );
+        assert_eq!(ids(pool.best_transactions(1)), vec![(b.public_key(), 0)]);
+    }
+
+    #[test]
+    fn single_transaction_to_send() {
+        let a = AccountSecretKey::rand();
+        let mut pool = TransactionPoolState::default();
+        pool.insert(tx(&a, 0, 1, 0));
+
+        let (transactions, first_index, last_index) = pool.data_to_send((0, 5));
+        assert_eq!(transactions.len(), 1);
+        assert_eq!((first_index, last_index), (0, 0));
+
+        let mut p2p = P2pState::new(P2pConfig {
+            identity_pub_key: SecretKey::rand().public_key(),
+            initial_peers: vec![],
+            enabled_channels: ChannelId::iter_all().collect(),
+            max_peers: 10,
+            reputation: Default::default(),
+            kademlia: Default::default(),
+            gossipsub: Default::default(),
+        });
+        let peer_id = SecretKey::rand().public_key().peer_id();
+        let mut peer = P2pPeerStatusReady::new(Timestamp::ZERO, &p2p.config.enabled_channels);
+        peer.channels.transaction = P2pChannelsTransactionState::Ready {
+            time: Timestamp::ZERO,
+            local: TransactionPropagationState::WaitingForRequest {
+                time: Timestamp::ZERO,
+            },
+            remote: TransactionPropagationState::Requested {
+                time: Timestamp::ZERO,
+                requested_limit: 5,
+            },
+            next_send_index: 0,
+        };
+        p2p.peers.insert(
+            peer_id,
+            P2pPeerState {
+                dial_opts: None,
+                status: P2pPeerStatus::Ready(peer),
+            },
+        );
+        let action = P2pChannelsTransactionResponseSendAction {
+            peer_id,
+            transactions,
+            first_index,
+            last_index,
+        };
+        assert!(action.is_enabled(&p2p));
+
+        // Nothing left to send after it.
+        let (transactions, _, _) = pool.data_to_send((last_index + 1, 5));
+        assert!(transactions.is_empty());
+    }
+
+    #[test]
+    fn evict_lowest_fee_with_dependents() {
+        let (a, b) = (AccountSecretKey::rand(), AccountSecretKey::rand());
+        let mut pool = TransactionPoolState::new(TransactionPoolConfig {
+            max_size: 2,
+            ..Default::default()
+        });
+        pool.insert(tx(&a, 0, 1, 0));
+        pool.insert(tx(&a, 1, 10, 0));
+        pool.insert(tx(&b, 0, 5, 0));
+        pool.evict_overflow();
+
+        assert_eq!(pool.len(), 1);
+        assert!(pool
+            .get_by_fee_payer_and_nonce(&b.public_key(), 0)
+            .is_some());
+    }
+
+    #[test]
+    fn balance_covers_pending_transactions() {
+        let a = AccountSecretKey::rand();
+        let mut pool = TransactionPoolState::default();
+        let first = tx(&a, 0, 1, 100);
+        let second = tx(&a, 1, 1, 100);
+        let second_hash = second.hash.clone();
+        pool.insert(first);
+        pool.insert(second);
+
+        assert_eq!(pool.pending_cost(&a.public_key(), 0), 0);
+        assert_eq!(pool.pending_cost(&a.public_key(), 1), 101);
+        assert_eq!(pool.pending_cost(&a.public_key(), 2), 202);
+
+        // Balance is enough only for the first transaction.
+        let account = TransactionPoolAccount {
+            nonce: 0,
+            balance: 150,
+        };
+        let accounts = BTreeMap::from([(a.public_key(), account)]);
+        assert_eq!(pool.invalid_for_accounts(&accounts), vec![second_hash]);
+    }
+}
@@ -3,6 +3,9 @@ pub mod sync;
 mod transition_frontier_config;
 pub use transition_frontier_config::*;
 
+mod transition_frontier_genesis;
+pub use transition_frontier_genesis::*;
+
 mod transition_frontier_state;
 pub use transition_frontier_state::*;
 
@@ -37,4 +37,6 @@ pub enum PeerLedgerQueryError {
     Timeout,
     Disconnected,
     DataUnavailable,
+    /// Child hashes don't add up to the parent's hash.
+    InvalidData,
 }
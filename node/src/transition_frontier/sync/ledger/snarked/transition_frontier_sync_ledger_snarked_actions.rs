@@ -35,13 +35,9 @@ pub struct TransitionFrontierSyncLedgerSnarkedPendingAction {}
 
 impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnarkedPendingAction {
     fn is_enabled(&self, state: &crate::State) -> bool {
-        state
-            .transition_frontier
-            .sync
-            .root_ledger()
-            .map_or(false, |s| {
-                matches!(s, TransitionFrontierSyncLedgerState::Init { .. })
-            })
+        state.transition_frontier.sync.ledger().map_or(false, |s| {
+            matches!(s, TransitionFrontierSyncLedgerState::Init { .. })
+        })
     }
 }
 
@@ -60,7 +56,7 @@ impl redux::EnablingCondition<crate::State>
             && state
                 .transition_frontier
                 .sync
-                .root_ledger()
+                .ledger()
                 .and_then(|s| s.snarked())
                 .map_or(false, |s| {
                     s.sync_next().is_some() || s.sync_retry_iter().next().is_some()
@@ -81,7 +77,7 @@ impl redux::EnablingCondition<crate::State>
         let check_next_addr = state
             .transition_frontier
             .sync
-            .root_ledger()
+            .ledger()
             .and_then(|s| s.snarked())
             .map_or(false, |s| match s {
                 TransitionFrontierSyncLedgerSnarkedState::Pending {
@@ -119,7 +115,7 @@ impl redux::EnablingCondition<crate::State>
         let check_next_addr = state
             .transition_frontier
             .sync
-            .root_ledger()
+            .ledger()
             .and_then(|s| s.snarked()?.sync_retry_iter().next())
             .map_or(false, |addr| addr == self.address);
 
@@ -150,7 +146,7 @@ impl redux::EnablingCondition<crate::State>
         state
             .transition_frontier
             .sync
-            .root_ledger()
+            .ledger()
             .and_then(|s| s.snarked()?.fetch_pending())
             .map_or(false, |pending| {
                 pending
@@ -175,7 +171,7 @@ impl redux::EnablingCondition<crate::State>
         state
             .transition_frontier
             .sync
-            .root_ledger()
+            .ledger()
             .and_then(|s| s.snarked())
             .map_or(false, |s| {
                 s.peer_query_get(&self.peer_id, self.rpc_id)
@@ -199,7 +195,7 @@ impl redux::EnablingCondition<crate::State>
         state
             .transition_frontier
             .sync
-            .root_ledger()
+            .ledger()
             .and_then(|s| s.snarked())
             .map_or(false, |s| {
                 // TODO(binier): check if expected response
@@ -226,7 +222,7 @@ impl redux::EnablingCondition<crate::State>
             && state
                 .transition_frontier
                 .sync
-                .root_ledger()
+                .ledger()
                 .and_then(|s| s.snarked()?.fetch_pending()?.get(&self.address))
                 .and_then(|s| s.attempts.get(&self.sender))
                 .map_or(false, |s| s.is_success())
@@ -247,7 +243,7 @@ impl redux::EnablingCondition<crate::State>
         state
             .transition_frontier
             .sync
-            .root_ledger()
+            .ledger()
             .and_then(|s| s.snarked()?.fetch_pending()?.get(&self.address))
             .and_then(|s| s.attempts.get(&self.sender))
             // TODO(binier): check if expected response
@@ -264,7 +260,7 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerSnar
         state
             .transition_frontier
             .sync
-            .root_ledger()
+            .ledger()
             .and_then(|s| s.snarked())
             .map_or(false, |s| match s {
                 TransitionFrontierSyncLedgerSnarkedState::Pending {
@@ -138,11 +138,12 @@ impl TransitionFrontierSyncLedgerSnarkedState {
                 pending.remove(&action.address);
             }
             TransitionFrontierSyncLedgerSnarkedAction::Success(_) => {
-                let Self::Pending { block, .. } = self else {
+                let Self::Pending { kind, block, .. } = self else {
                     return;
                 };
                 *self = Self::Success {
                     time: meta.time(),
+                    kind: *kind,
                     block: block.clone(),
                 };
             }
@@ -3,6 +3,14 @@ use mina_p2p_messages::v2::{LedgerHash, MinaBaseAccountBinableArgStableV2};
 use crate::ledger::LedgerAddress;
 
 pub trait TransitionFrontierSyncLedgerSnarkedService: redux::Service {
+    /// Checks that the child hashes add up to the parent's hash.
+    fn hashes_check(
+        &mut self,
+        snarked_ledger_hash: LedgerHash,
+        parent: &LedgerAddress,
+        hashes: &(LedgerHash, LedgerHash),
+    ) -> Result<(), ()>;
+
     fn hashes_set(
         &mut self,
         snarked_ledger_hash: LedgerHash,
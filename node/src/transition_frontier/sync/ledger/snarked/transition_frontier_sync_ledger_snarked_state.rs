@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 use crate::ledger::LedgerAddress;
 use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
+use crate::transition_frontier::sync::ledger::SyncLedgerTargetKind;
 
 use super::PeerLedgerQueryError;
 
@@ -18,6 +19,7 @@ pub enum TransitionFrontierSyncLedgerSnarkedState {
     /// Doing BFS to sync snarked ledger tree.
     Pending {
         time: Timestamp,
+        kind: SyncLedgerTargetKind,
         block: ArcBlockWithHash,
         pending: BTreeMap<LedgerAddress, LedgerQueryPending>,
         /// `None` means we are done.
@@ -26,6 +28,7 @@ pub enum TransitionFrontierSyncLedgerSnarkedState {
     },
     Success {
         time: Timestamp,
+        kind: SyncLedgerTargetKind,
         block: ArcBlockWithHash,
     },
 }
@@ -78,9 +81,10 @@ impl PeerRpcState {
 }
 
 impl TransitionFrontierSyncLedgerSnarkedState {
-    pub fn pending(time: Timestamp, block: ArcBlockWithHash) -> Self {
+    pub fn pending(time: Timestamp, kind: SyncLedgerTargetKind, block: ArcBlockWithHash) -> Self {
         Self::Pending {
             time,
+            kind,
             block,
             pending: Default::default(),
             next_addr: Some(LedgerAddress::root()),
@@ -88,6 +92,13 @@ impl TransitionFrontierSyncLedgerSnarkedState {
         }
     }
 
+    pub fn kind(&self) -> SyncLedgerTargetKind {
+        match self {
+            Self::Pending { kind, .. } => *kind,
+            Self::Success { kind, .. } => *kind,
+        }
+    }
+
     pub fn block(&self) -> &ArcBlockWithHash {
         match self {
             Self::Pending { block, .. } => block,
@@ -96,7 +107,7 @@ impl TransitionFrontierSyncLedgerSnarkedState {
     }
 
     pub fn ledger_hash(&self) -> &LedgerHash {
-        self.block().snarked_ledger_hash()
+        self.kind().ledger_hash(self.block())
     }
 
     pub fn fetch_pending(&self) -> Option<&BTreeMap<LedgerAddress, LedgerQueryPending>> {
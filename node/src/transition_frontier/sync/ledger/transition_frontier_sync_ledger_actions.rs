@@ -22,13 +22,9 @@ pub struct TransitionFrontierSyncLedgerInitAction {}
 
 impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerInitAction {
     fn is_enabled(&self, state: &crate::State) -> bool {
-        state
-            .transition_frontier
-            .sync
-            .root_ledger()
-            .map_or(false, |s| {
-                matches!(s, TransitionFrontierSyncLedgerState::Init { .. })
-            })
+        state.transition_frontier.sync.ledger().map_or(false, |s| {
+            matches!(s, TransitionFrontierSyncLedgerState::Init { .. })
+        })
     }
 }
 
@@ -1,5 +1,9 @@
 use redux::ActionMeta;
 
+use crate::transition_frontier::sync::{
+    TransitionFrontierSyncLedgerNextEpochSuccessAction,
+    TransitionFrontierSyncLedgerStakingSuccessAction,
+};
 use crate::Store;
 
 use super::snarked::{
@@ -24,6 +28,9 @@ impl TransitionFrontierSyncLedgerSnarkedSuccessAction {
         if !store.dispatch(TransitionFrontierSyncLedgerStagedReconstructEmptyAction {}) {
             store.dispatch(TransitionFrontierSyncLedgerStagedPartsFetchPendingAction {});
         }
+        // epoch ledgers only need the snarked part.
+        store.dispatch(TransitionFrontierSyncLedgerStakingSuccessAction {});
+        store.dispatch(TransitionFrontierSyncLedgerNextEpochSuccessAction {});
     }
 }
 
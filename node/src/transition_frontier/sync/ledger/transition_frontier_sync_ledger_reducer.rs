@@ -16,11 +16,12 @@ impl TransitionFrontierSyncLedgerState {
             TransitionFrontierSyncLedgerAction::Init(_) => {}
             TransitionFrontierSyncLedgerAction::Snarked(action) => {
                 if let TransitionFrontierSyncLedgerSnarkedAction::Pending(_) = action {
-                    let Self::Init { block, .. } = self else {
+                    let Self::Init { kind, block, .. } = self else {
                         return;
                     };
                     let s = TransitionFrontierSyncLedgerSnarkedState::pending(
                         meta.time(),
+                        *kind,
                         block.clone(),
                     );
                     *self = Self::Snarked(s);
@@ -8,10 +8,34 @@ use serde::{Deserialize, Serialize};
 use super::snarked::TransitionFrontierSyncLedgerSnarkedState;
 use super::staged::TransitionFrontierSyncLedgerStagedState;
 
+/// Which snarked ledger of the block is being synced.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum SyncLedgerTargetKind {
+    /// Staking ledger of the block's epoch. Block is the best tip.
+    StakingLedger,
+    /// Ledger which will be the staking ledger in the epoch following
+    /// the block's one. Block is the best tip.
+    NextEpochLedger,
+    /// Snarked ledger of the root block, followed by its staged ledger.
+    RootLedger,
+}
+
+impl SyncLedgerTargetKind {
+    pub fn ledger_hash(self, block: &ArcBlockWithHash) -> &LedgerHash {
+        let consensus_state = &block.header().protocol_state.body.consensus_state;
+        match self {
+            Self::StakingLedger => &consensus_state.staking_epoch_data.ledger.hash,
+            Self::NextEpochLedger => &consensus_state.next_epoch_data.ledger.hash,
+            Self::RootLedger => block.snarked_ledger_hash(),
+        }
+    }
+}
+
 #[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
 pub enum TransitionFrontierSyncLedgerState {
     Init {
         time: Timestamp,
+        kind: SyncLedgerTargetKind,
         block: ArcBlockWithHash,
     },
     #[from]
@@ -48,6 +72,14 @@ impl TransitionFrontierSyncLedgerState {
         }
     }
 
+    pub fn kind(&self) -> SyncLedgerTargetKind {
+        match self {
+            Self::Init { kind, .. } => *kind,
+            Self::Snarked(s) => s.kind(),
+            Self::Staged(_) | Self::Success { .. } => SyncLedgerTargetKind::RootLedger,
+        }
+    }
+
     pub fn block(&self) -> &ArcBlockWithHash {
         match self {
             Self::Init { block, .. } => block,
@@ -58,7 +90,7 @@ impl TransitionFrontierSyncLedgerState {
     }
 
     pub fn snarked_ledger_hash(&self) -> &LedgerHash {
-        self.block().snarked_ledger_hash()
+        self.kind().ledger_hash(self.block())
     }
 
     pub fn staged_ledger_hash(&self) -> &LedgerHash {
@@ -66,13 +98,31 @@ impl TransitionFrontierSyncLedgerState {
     }
 
     pub fn update_block(&mut self, time: Timestamp, new_block: ArcBlockWithHash) {
+        let kind = self.kind();
         match self {
+            Self::Init { block, .. } => {
+                *block = new_block;
+            }
             Self::Snarked(TransitionFrontierSyncLedgerSnarkedState::Pending { block, .. }) => {
-                if block.snarked_ledger_hash() == new_block.snarked_ledger_hash() {
+                if kind.ledger_hash(block) == kind.ledger_hash(&new_block) {
+                    *block = new_block;
+                } else {
+                    *self = Self::Init {
+                        time,
+                        kind,
+                        block: new_block.clone(),
+                    };
+                }
+            }
+            Self::Snarked(TransitionFrontierSyncLedgerSnarkedState::Success { block, .. })
+                if kind != SyncLedgerTargetKind::RootLedger =>
+            {
+                if kind.ledger_hash(block) == kind.ledger_hash(&new_block) {
                     *block = new_block;
                 } else {
                     *self = Self::Init {
                         time,
+                        kind,
                         block: new_block.clone(),
                     };
                 }
@@ -82,12 +132,14 @@ impl TransitionFrontierSyncLedgerState {
                 if block.snarked_ledger_hash() == new_block.snarked_ledger_hash() {
                     *self = TransitionFrontierSyncLedgerSnarkedState::Success {
                         time,
+                        kind,
                         block: new_block.clone(),
                     }
                     .into();
                 } else {
                     *self = Self::Init {
                         time,
+                        kind,
                         block: new_block.clone(),
                     };
                 }
@@ -5,8 +5,12 @@ use serde::{Deserialize, Serialize};
 use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
 
-use super::ledger::{TransitionFrontierSyncLedgerAction, TransitionFrontierSyncLedgerState};
+use super::ledger::snarked::TransitionFrontierSyncLedgerSnarkedState;
+use super::ledger::{
+    SyncLedgerTargetKind, TransitionFrontierSyncLedgerAction, TransitionFrontierSyncLedgerState,
+};
 use super::{PeerBlockFetchError, TransitionFrontierSyncState};
+use crate::transition_frontier::TransitionFrontierState;
 
 pub type TransitionFrontierSyncActionWithMeta = redux::ActionWithMeta<TransitionFrontierSyncAction>;
 pub type TransitionFrontierSyncActionWithMetaRef<'a> =
@@ -16,6 +20,10 @@ pub type TransitionFrontierSyncActionWithMetaRef<'a> =
 pub enum TransitionFrontierSyncAction {
     Init(TransitionFrontierSyncInitAction),
     BestTipUpdate(TransitionFrontierSyncBestTipUpdateAction),
+    LedgerStakingPending(TransitionFrontierSyncLedgerStakingPendingAction),
+    LedgerStakingSuccess(TransitionFrontierSyncLedgerStakingSuccessAction),
+    LedgerNextEpochPending(TransitionFrontierSyncLedgerNextEpochPendingAction),
+    LedgerNextEpochSuccess(TransitionFrontierSyncLedgerNextEpochSuccessAction),
     LedgerRootPending(TransitionFrontierSyncLedgerRootPendingAction),
     LedgerRootSuccess(TransitionFrontierSyncLedgerRootSuccessAction),
     BlocksPending(TransitionFrontierSyncBlocksPendingAction),
@@ -82,6 +90,105 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncBestTipUpd
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransitionFrontierSyncLedgerStakingPendingAction {}
+
+impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerStakingPendingAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        epoch_ledger_missing(
+            &state.transition_frontier,
+            SyncLedgerTargetKind::StakingLedger,
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransitionFrontierSyncLedgerStakingSuccessAction {}
+
+impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerStakingSuccessAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        epoch_ledger_synced(
+            &state.transition_frontier.sync,
+            SyncLedgerTargetKind::StakingLedger,
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransitionFrontierSyncLedgerNextEpochPendingAction {}
+
+impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerNextEpochPendingAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        epoch_ledger_missing(
+            &state.transition_frontier,
+            SyncLedgerTargetKind::NextEpochLedger,
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransitionFrontierSyncLedgerNextEpochSuccessAction {}
+
+impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerNextEpochSuccessAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        epoch_ledger_synced(
+            &state.transition_frontier.sync,
+            SyncLedgerTargetKind::NextEpochLedger,
+        )
+    }
+}
+
+/// Whether epoch ledger of the given kind is missing for the best tip
+/// and can be synced now. Before the root ledger, epoch ledgers are
+/// synced one after another. Once the root ledger is there, they are
+/// synced alongside the blocks instead, when best tip moves to the
+/// next epoch.
+fn epoch_ledger_missing(
+    transition_frontier: &TransitionFrontierState,
+    kind: SyncLedgerTargetKind,
+) -> bool {
+    let best_tip = match &transition_frontier.sync {
+        TransitionFrontierSyncState::Init { best_tip, .. } => Some(best_tip),
+        TransitionFrontierSyncState::StakingLedgerSuccess { best_tip, .. }
+            if kind == SyncLedgerTargetKind::NextEpochLedger =>
+        {
+            Some(best_tip)
+        }
+        TransitionFrontierSyncState::BlocksPending {
+            epoch_ledger: None, ..
+        }
+        | TransitionFrontierSyncState::BlocksSuccess {
+            epoch_ledger: None, ..
+        } => transition_frontier.sync.best_tip(),
+        TransitionFrontierSyncState::Synced {
+            epoch_ledger: None, ..
+        } => transition_frontier.best_tip(),
+        _ => None,
+    };
+    best_tip.map_or(false, |best_tip| {
+        !transition_frontier
+            .epoch_ledgers
+            .contains(kind.ledger_hash(best_tip))
+    })
+}
+
+fn epoch_ledger_synced(sync: &TransitionFrontierSyncState, kind: SyncLedgerTargetKind) -> bool {
+    let ledger = match sync {
+        TransitionFrontierSyncState::StakingLedgerPending { ledger, .. }
+        | TransitionFrontierSyncState::NextEpochLedgerPending { ledger, .. } => ledger,
+        _ => match sync.epoch_ledger() {
+            Some(ledger) => ledger,
+            None => return false,
+        },
+    };
+    matches!(
+        ledger,
+        TransitionFrontierSyncLedgerState::Snarked(
+            TransitionFrontierSyncLedgerSnarkedState::Success { .. }
+        )
+    ) && ledger.kind() == kind
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransitionFrontierSyncLedgerRootPendingAction {}
 
@@ -90,6 +197,8 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncLedgerRoot
         matches!(
             state.transition_frontier.sync,
             TransitionFrontierSyncState::Init { .. }
+                | TransitionFrontierSyncState::StakingLedgerSuccess { .. }
+                | TransitionFrontierSyncState::NextEpochLedgerSuccess { .. }
         )
     }
 }
@@ -328,6 +437,10 @@ macro_rules! impl_into_global_action {
 
 impl_into_global_action!(TransitionFrontierSyncInitAction);
 impl_into_global_action!(TransitionFrontierSyncBestTipUpdateAction);
+impl_into_global_action!(TransitionFrontierSyncLedgerStakingPendingAction);
+impl_into_global_action!(TransitionFrontierSyncLedgerStakingSuccessAction);
+impl_into_global_action!(TransitionFrontierSyncLedgerNextEpochPendingAction);
+impl_into_global_action!(TransitionFrontierSyncLedgerNextEpochSuccessAction);
 impl_into_global_action!(TransitionFrontierSyncLedgerRootPendingAction);
 impl_into_global_action!(TransitionFrontierSyncLedgerRootSuccessAction);
 impl_into_global_action!(TransitionFrontierSyncBlocksPendingAction);
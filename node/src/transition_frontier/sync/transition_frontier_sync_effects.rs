@@ -6,7 +6,10 @@ use crate::transition_frontier::TransitionFrontierService;
 use crate::Store;
 
 use super::ledger::snarked::TransitionFrontierSyncLedgerSnarkedPeersQueryAction;
-use super::ledger::staged::TransitionFrontierSyncLedgerStagedPartsFetchPendingAction;
+use super::ledger::staged::{
+    TransitionFrontierSyncLedgerStagedPartsFetchPendingAction,
+    TransitionFrontierSyncLedgerStagedReconstructEmptyAction,
+};
 use super::ledger::TransitionFrontierSyncLedgerInitAction;
 use super::{
     TransitionFrontierSyncBestTipUpdateAction, TransitionFrontierSyncBlocksFetchSuccessAction,
@@ -20,17 +23,35 @@ use super::{
     TransitionFrontierSyncBlocksPeerQuerySuccessAction,
     TransitionFrontierSyncBlocksPeersQueryAction, TransitionFrontierSyncBlocksPendingAction,
     TransitionFrontierSyncBlocksSuccessAction, TransitionFrontierSyncInitAction,
+    TransitionFrontierSyncLedgerNextEpochPendingAction,
+    TransitionFrontierSyncLedgerNextEpochSuccessAction,
     TransitionFrontierSyncLedgerRootPendingAction, TransitionFrontierSyncLedgerRootSuccessAction,
+    TransitionFrontierSyncLedgerStakingPendingAction,
+    TransitionFrontierSyncLedgerStakingSuccessAction,
 };
 
+/// Starts syncing the next ledger we are missing. Epoch ledgers are
+/// synced first, since root ledger sync is followed by the blocks sync.
+/// If best tip moves to a new epoch during blocks sync, its epoch
+/// ledgers are synced alongside the blocks instead.
+fn ledger_sync_next<S: redux::Service>(store: &mut Store<S>) {
+    if !store.dispatch(TransitionFrontierSyncLedgerStakingPendingAction {})
+        && !store.dispatch(TransitionFrontierSyncLedgerNextEpochPendingAction {})
+    {
+        store.dispatch(TransitionFrontierSyncLedgerRootPendingAction {});
+    }
+}
+
 impl TransitionFrontierSyncInitAction {
     pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>) {
-        store.dispatch(TransitionFrontierSyncLedgerRootPendingAction {});
+        ledger_sync_next(store);
     }
 }
 
 impl TransitionFrontierSyncBestTipUpdateAction {
     pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>) {
+        // if epoch ledgers changed.
+        ledger_sync_next(store);
         // if root snarked ledger changed.
         store.dispatch(TransitionFrontierSyncLedgerInitAction {});
         // if root snarked ledger stayed same but root block changed
@@ -46,9 +67,39 @@ impl TransitionFrontierSyncBestTipUpdateAction {
     }
 }
 
+impl TransitionFrontierSyncLedgerStakingPendingAction {
+    pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>) {
+        store.dispatch(TransitionFrontierSyncLedgerInitAction {});
+    }
+}
+
+impl TransitionFrontierSyncLedgerStakingSuccessAction {
+    pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>) {
+        ledger_sync_next(store);
+    }
+}
+
+impl TransitionFrontierSyncLedgerNextEpochPendingAction {
+    pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>) {
+        store.dispatch(TransitionFrontierSyncLedgerInitAction {});
+    }
+}
+
+impl TransitionFrontierSyncLedgerNextEpochSuccessAction {
+    pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>) {
+        ledger_sync_next(store);
+    }
+}
+
 impl TransitionFrontierSyncLedgerRootPendingAction {
     pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>) {
         store.dispatch(TransitionFrontierSyncLedgerInitAction {});
+        // if root snarked ledger is the same as the current one.
+        if !store.dispatch(TransitionFrontierSyncLedgerStagedReconstructEmptyAction {}) {
+            store.dispatch(TransitionFrontierSyncLedgerStagedPartsFetchPendingAction {});
+        }
+        // if root block is already applied.
+        store.dispatch(TransitionFrontierSyncBlocksPendingAction {});
     }
 }
 impl TransitionFrontierSyncLedgerRootSuccessAction {
@@ -59,6 +110,7 @@ impl TransitionFrontierSyncLedgerRootSuccessAction {
 
 impl TransitionFrontierSyncBlocksPendingAction {
     pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>) {
+        ledger_sync_next(store);
         store.dispatch(TransitionFrontierSyncBlocksPeersQueryAction {});
     }
 }
@@ -1,12 +1,15 @@
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
+use mina_p2p_messages::v2::LedgerHash;
 use openmina_core::block::ArcBlockWithHash;
+use redux::Timestamp;
 
 use crate::TransitionFrontierConfig;
 
 use super::{
     ledger::{
-        snarked::TransitionFrontierSyncLedgerSnarkedState, TransitionFrontierSyncLedgerState,
+        snarked::TransitionFrontierSyncLedgerSnarkedState, SyncLedgerTargetKind,
+        TransitionFrontierSyncLedgerState,
     },
     PeerRpcState, TransitionFrontierSyncAction, TransitionFrontierSyncActionWithMetaRef,
     TransitionFrontierSyncBlockState, TransitionFrontierSyncState,
@@ -18,6 +21,7 @@ impl TransitionFrontierSyncState {
         action: TransitionFrontierSyncActionWithMetaRef<'_>,
         config: &TransitionFrontierConfig,
         best_chain: &[ArcBlockWithHash],
+        epoch_ledgers: &BTreeSet<LedgerHash>,
     ) {
         let (action, meta) = action.split();
         match action {
@@ -30,184 +34,399 @@ impl TransitionFrontierSyncState {
                 };
             }
             // TODO(binier): refactor
-            TransitionFrontierSyncAction::BestTipUpdate(a) => match self {
-                Self::RootLedgerPending {
-                    best_tip,
-                    blocks_inbetween,
-                    root_ledger,
-                    ..
-                } => {
-                    root_ledger.update_block(meta.time(), a.root_block.clone());
+            TransitionFrontierSyncAction::BestTipUpdate(a) => {
+                match self {
+                    Self::StakingLedgerPending {
+                        best_tip,
+                        root_block,
+                        blocks_inbetween,
+                        ledger,
+                        ..
+                    }
+                    | Self::NextEpochLedgerPending {
+                        best_tip,
+                        root_block,
+                        blocks_inbetween,
+                        ledger,
+                        ..
+                    } => {
+                        ledger.update_block(meta.time(), a.best_tip.clone());
 
-                    *best_tip = a.best_tip.clone();
-                    *blocks_inbetween = a.blocks_inbetween.clone();
-                }
-                Self::BlocksPending {
-                    chain,
-                    needed_protocol_states,
-                    ..
-                } => {
-                    let mut applied_blocks: BTreeMap<_, _> =
-                        best_chain.iter().map(|b| (&b.hash, b)).collect();
+                        *best_tip = a.best_tip.clone();
+                        *root_block = a.root_block.clone();
+                        *blocks_inbetween = a.blocks_inbetween.clone();
+                    }
+                    Self::Init {
+                        best_tip,
+                        root_block,
+                        blocks_inbetween,
+                        ..
+                    }
+                    | Self::StakingLedgerSuccess {
+                        best_tip,
+                        root_block,
+                        blocks_inbetween,
+                        ..
+                    }
+                    | Self::NextEpochLedgerSuccess {
+                        best_tip,
+                        root_block,
+                        blocks_inbetween,
+                        ..
+                    } => {
+                        *best_tip = a.best_tip.clone();
+                        *root_block = a.root_block.clone();
+                        *blocks_inbetween = a.blocks_inbetween.clone();
+                    }
+                    Self::RootLedgerPending {
+                        best_tip,
+                        blocks_inbetween,
+                        root_ledger,
+                        ..
+                    } => {
+                        root_ledger.update_block(meta.time(), a.root_block.clone());
+
+                        *best_tip = a.best_tip.clone();
+                        *blocks_inbetween = a.blocks_inbetween.clone();
+                    }
+                    Self::BlocksPending {
+                        chain,
+                        needed_protocol_states,
+                        ..
+                    } => {
+                        let mut applied_blocks: BTreeMap<_, _> =
+                            best_chain.iter().map(|b| (&b.hash, b)).collect();
 
-                    let old_chain = VecDeque::from(std::mem::take(chain));
-                    let old_root = old_chain.front().and_then(|b| b.block()).unwrap().clone();
-                    let new_root = &a.root_block;
+                        let old_chain = VecDeque::from(std::mem::take(chain));
+                        let old_root = old_chain.front().and_then(|b| b.block()).unwrap().clone();
+                        let new_root = &a.root_block;
 
-                    let old_chain_has_new_root_applied = old_chain
-                        .iter()
-                        .find(|b| b.block_hash() == &new_root.hash)
-                        .map_or(false, |b| b.is_apply_pending() || b.is_apply_success());
+                        let old_chain_has_new_root_applied = old_chain
+                            .iter()
+                            .find(|b| b.block_hash() == &new_root.hash)
+                            .map_or(false, |b| b.is_apply_pending() || b.is_apply_success());
 
-                    if applied_blocks.contains_key(&new_root.hash) || old_chain_has_new_root_applied
-                    {
-                        let mut old_block_states: BTreeMap<_, _> = old_chain
-                            .into_iter()
-                            .map(|b| (b.block_hash().clone(), b))
-                            .collect();
+                        if applied_blocks.contains_key(&new_root.hash)
+                            || old_chain_has_new_root_applied
+                        {
+                            let mut old_block_states: BTreeMap<_, _> = old_chain
+                                .into_iter()
+                                .map(|b| (b.block_hash().clone(), b))
+                                .collect();
 
-                        let mut push_block = |hash, maybe_block: Option<&ArcBlockWithHash>| {
-                            chain.push({
-                                if let Some(old_state) =
-                                    old_block_states.remove(hash).filter(|old_state| {
-                                        old_state.block().is_some() || maybe_block.is_none()
-                                    })
-                                {
-                                    old_state
-                                } else if let Some(block) = applied_blocks.remove(hash) {
-                                    TransitionFrontierSyncBlockState::ApplySuccess {
-                                        time: meta.time(),
-                                        block: block.clone(),
+                            let mut push_block = |hash, maybe_block: Option<&ArcBlockWithHash>| {
+                                chain.push({
+                                    if let Some(old_state) =
+                                        old_block_states.remove(hash).filter(|old_state| {
+                                            old_state.block().is_some() || maybe_block.is_none()
+                                        })
+                                    {
+                                        old_state
+                                    } else if let Some(block) = applied_blocks.remove(hash) {
+                                        TransitionFrontierSyncBlockState::ApplySuccess {
+                                            time: meta.time(),
+                                            block: block.clone(),
+                                        }
+                                    } else if let Some(block) = maybe_block {
+                                        TransitionFrontierSyncBlockState::FetchSuccess {
+                                            time: meta.time(),
+                                            block: block.clone(),
+                                        }
+                                    } else {
+                                        TransitionFrontierSyncBlockState::FetchPending {
+                                            time: meta.time(),
+                                            block_hash: hash.clone(),
+                                            attempts: Default::default(),
+                                        }
                                     }
-                                } else if let Some(block) = maybe_block {
-                                    TransitionFrontierSyncBlockState::FetchSuccess {
-                                        time: meta.time(),
-                                        block: block.clone(),
-                                    }
-                                } else {
-                                    TransitionFrontierSyncBlockState::FetchPending {
-                                        time: meta.time(),
-                                        block_hash: hash.clone(),
-                                        attempts: Default::default(),
-                                    }
-                                }
-                            })
-                        };
+                                })
+                            };
 
-                        push_block(&a.root_block.hash, Some(&a.root_block));
-                        for hash in &a.blocks_inbetween {
-                            push_block(hash, None);
-                        }
-                        push_block(&a.best_tip.hash, Some(&a.best_tip));
-                        needed_protocol_states.extend(old_block_states.into_iter().filter_map(
-                            |(hash, s)| {
-                                Some((hash, s.take_block()?.block.header.protocol_state.clone()))
-                            },
-                        ));
-                    } else {
-                        let cur_best_root = best_chain.first();
-                        let root_ledger = if old_root.snarked_ledger_hash()
-                            == new_root.snarked_ledger_hash()
-                            || cur_best_root.map_or(false, |cur| {
-                                cur.snarked_ledger_hash() == new_root.snarked_ledger_hash()
-                            }) {
-                            TransitionFrontierSyncLedgerSnarkedState::Success {
-                                time: meta.time(),
-                                block: new_root.clone(),
+                            push_block(&a.root_block.hash, Some(&a.root_block));
+                            for hash in &a.blocks_inbetween {
+                                push_block(hash, None);
                             }
-                            .into()
+                            push_block(&a.best_tip.hash, Some(&a.best_tip));
+                            needed_protocol_states.extend(old_block_states.into_iter().filter_map(
+                                |(hash, s)| {
+                                    Some((
+                                        hash,
+                                        s.take_block()?.block.header.protocol_state.clone(),
+                                    ))
+                                },
+                            ));
                         } else {
-                            TransitionFrontierSyncLedgerSnarkedState::pending(
-                                meta.time(),
-                                new_root.clone(),
-                            )
-                            .into()
-                        };
-                        *self = Self::RootLedgerPending {
-                            time: meta.time(),
-                            best_tip: a.best_tip.clone(),
-                            blocks_inbetween: a.blocks_inbetween.clone(),
-                            root_ledger,
-                        };
+                            let cur_best_root = best_chain.first();
+                            let root_ledger = if old_root.snarked_ledger_hash()
+                                == new_root.snarked_ledger_hash()
+                                || cur_best_root.map_or(false, |cur| {
+                                    cur.snarked_ledger_hash() == new_root.snarked_ledger_hash()
+                                }) {
+                                TransitionFrontierSyncLedgerSnarkedState::Success {
+                                    time: meta.time(),
+                                    kind: SyncLedgerTargetKind::RootLedger,
+                                    block: new_root.clone(),
+                                }
+                                .into()
+                            } else {
+                                TransitionFrontierSyncLedgerSnarkedState::pending(
+                                    meta.time(),
+                                    SyncLedgerTargetKind::RootLedger,
+                                    new_root.clone(),
+                                )
+                                .into()
+                            };
+                            // Epoch ledgers are synced after the root one,
+                            // if they are still missing then.
+                            *self = Self::RootLedgerPending {
+                                time: meta.time(),
+                                best_tip: a.best_tip.clone(),
+                                blocks_inbetween: a.blocks_inbetween.clone(),
+                                root_ledger,
+                            };
+                        }
                     }
-                }
-                Self::Synced { time, .. } => {
-                    let applied_blocks: BTreeMap<_, _> =
-                        best_chain.iter().map(|b| (&b.hash, b)).collect();
+                    Self::Synced { time, epoch_ledger } => {
+                        let applied_blocks: BTreeMap<_, _> =
+                            best_chain.iter().map(|b| (&b.hash, b)).collect();
 
-                    let old_root = best_chain.first().unwrap();
-                    let new_root = &a.root_block;
+                        let old_root = best_chain.first().unwrap();
+                        let new_root = &a.root_block;
 
-                    if applied_blocks.contains_key(&new_root.hash) {
-                        let chain = std::iter::once(a.root_block.hash())
-                            .chain(&a.blocks_inbetween)
-                            .chain(std::iter::once(a.best_tip.hash()))
-                            .map(|hash| match applied_blocks.get(hash) {
-                                Some(&block) => TransitionFrontierSyncBlockState::ApplySuccess {
-                                    time: *time,
-                                    block: block.clone(),
-                                },
-                                None if hash == a.best_tip.hash() => {
-                                    TransitionFrontierSyncBlockState::FetchSuccess {
-                                        time: meta.time(),
-                                        block: a.best_tip.clone(),
+                        if applied_blocks.contains_key(&new_root.hash) {
+                            let chain = std::iter::once(a.root_block.hash())
+                                .chain(&a.blocks_inbetween)
+                                .chain(std::iter::once(a.best_tip.hash()))
+                                .map(|hash| match applied_blocks.get(hash) {
+                                    Some(&block) => {
+                                        TransitionFrontierSyncBlockState::ApplySuccess {
+                                            time: *time,
+                                            block: block.clone(),
+                                        }
                                     }
-                                }
-                                None => TransitionFrontierSyncBlockState::FetchPending {
-                                    time: meta.time(),
-                                    block_hash: hash.clone(),
-                                    attempts: Default::default(),
-                                },
-                            })
-                            .collect::<Vec<_>>();
-                        *self = Self::BlocksPending {
-                            time: meta.time(),
-                            chain,
-                            needed_protocol_states: Default::default(),
-                        };
-                    } else {
-                        let root_ledger =
-                            if old_root.snarked_ledger_hash() == new_root.snarked_ledger_hash() {
+                                    None if hash == a.best_tip.hash() => {
+                                        TransitionFrontierSyncBlockState::FetchSuccess {
+                                            time: meta.time(),
+                                            block: a.best_tip.clone(),
+                                        }
+                                    }
+                                    None => TransitionFrontierSyncBlockState::FetchPending {
+                                        time: meta.time(),
+                                        block_hash: hash.clone(),
+                                        attempts: Default::default(),
+                                    },
+                                })
+                                .collect::<Vec<_>>();
+                            *self = Self::BlocksPending {
+                                time: meta.time(),
+                                chain,
+                                needed_protocol_states: Default::default(),
+                                epoch_ledger: epoch_ledger.take(),
+                            };
+                        } else {
+                            let root_ledger = if old_root.snarked_ledger_hash()
+                                == new_root.snarked_ledger_hash()
+                            {
                                 TransitionFrontierSyncLedgerSnarkedState::Success {
                                     time: meta.time(),
+                                    kind: SyncLedgerTargetKind::RootLedger,
                                     block: new_root.clone(),
                                 }
                                 .into()
                             } else {
                                 TransitionFrontierSyncLedgerSnarkedState::pending(
                                     meta.time(),
+                                    SyncLedgerTargetKind::RootLedger,
                                     new_root.clone(),
                                 )
                                 .into()
                             };
-                        *self = Self::RootLedgerPending {
-                            time: meta.time(),
-                            best_tip: a.best_tip.clone(),
-                            blocks_inbetween: a.blocks_inbetween.clone(),
-                            root_ledger,
+                            *self = Self::RootLedgerPending {
+                                time: meta.time(),
+                                best_tip: a.best_tip.clone(),
+                                blocks_inbetween: a.blocks_inbetween.clone(),
+                                root_ledger,
+                            }
                         }
                     }
+                    _ => return,
                 }
-                _ => return,
-            },
-            TransitionFrontierSyncAction::LedgerRootPending(_) => {
-                if let Self::Init {
+
+                // Best tip may have moved to the next epoch while its
+                // epoch ledger was being synced.
+                if let Some(epoch_ledger) = self.epoch_ledger_mut() {
+                    if let Some(ledger) = epoch_ledger {
+                        ledger.update_block(meta.time(), a.best_tip.clone());
+                    }
+                    if epoch_ledger
+                        .as_ref()
+                        .map_or(false, |l| epoch_ledgers.contains(l.snarked_ledger_hash()))
+                    {
+                        *epoch_ledger = None;
+                    }
+                }
+            }
+            TransitionFrontierSyncAction::LedgerStakingPending(_) => {
+                let Self::Init {
                     best_tip,
                     root_block,
                     blocks_inbetween,
                     ..
                 } = self
-                {
-                    *self = Self::RootLedgerPending {
+                else {
+                    self.epoch_ledger_init(
+                        meta.time(),
+                        SyncLedgerTargetKind::StakingLedger,
+                        best_chain,
+                    );
+                    return;
+                };
+                *self = Self::StakingLedgerPending {
+                    time: meta.time(),
+                    best_tip: best_tip.clone(),
+                    root_block: root_block.clone(),
+                    blocks_inbetween: std::mem::take(blocks_inbetween),
+                    ledger: TransitionFrontierSyncLedgerState::Init {
                         time: meta.time(),
-                        best_tip: best_tip.clone(),
-                        root_ledger: TransitionFrontierSyncLedgerState::Init {
-                            time: meta.time(),
-                            block: root_block.clone(),
-                        },
-                        blocks_inbetween: std::mem::take(blocks_inbetween),
+                        kind: SyncLedgerTargetKind::StakingLedger,
+                        block: best_tip.clone(),
+                    },
+                };
+            }
+            TransitionFrontierSyncAction::LedgerStakingSuccess(_) => {
+                let Self::StakingLedgerPending {
+                    best_tip,
+                    root_block,
+                    blocks_inbetween,
+                    ..
+                } = self
+                else {
+                    if let Some(epoch_ledger) = self.epoch_ledger_mut() {
+                        *epoch_ledger = None;
+                    }
+                    return;
+                };
+                *self = Self::StakingLedgerSuccess {
+                    time: meta.time(),
+                    best_tip: best_tip.clone(),
+                    root_block: root_block.clone(),
+                    blocks_inbetween: std::mem::take(blocks_inbetween),
+                };
+            }
+            TransitionFrontierSyncAction::LedgerNextEpochPending(_) => {
+                let (Self::Init {
+                    best_tip,
+                    root_block,
+                    blocks_inbetween,
+                    ..
+                }
+                | Self::StakingLedgerSuccess {
+                    best_tip,
+                    root_block,
+                    blocks_inbetween,
+                    ..
+                }) = self
+                else {
+                    self.epoch_ledger_init(
+                        meta.time(),
+                        SyncLedgerTargetKind::NextEpochLedger,
+                        best_chain,
+                    );
+                    return;
+                };
+                *self = Self::NextEpochLedgerPending {
+                    time: meta.time(),
+                    best_tip: best_tip.clone(),
+                    root_block: root_block.clone(),
+                    blocks_inbetween: std::mem::take(blocks_inbetween),
+                    ledger: TransitionFrontierSyncLedgerState::Init {
+                        time: meta.time(),
+                        kind: SyncLedgerTargetKind::NextEpochLedger,
+                        block: best_tip.clone(),
+                    },
+                };
+            }
+            TransitionFrontierSyncAction::LedgerNextEpochSuccess(_) => {
+                let Self::NextEpochLedgerPending {
+                    best_tip,
+                    root_block,
+                    blocks_inbetween,
+                    ..
+                } = self
+                else {
+                    if let Some(epoch_ledger) = self.epoch_ledger_mut() {
+                        *epoch_ledger = None;
+                    }
+                    return;
+                };
+                *self = Self::NextEpochLedgerSuccess {
+                    time: meta.time(),
+                    best_tip: best_tip.clone(),
+                    root_block: root_block.clone(),
+                    blocks_inbetween: std::mem::take(blocks_inbetween),
+                };
+            }
+            TransitionFrontierSyncAction::LedgerRootPending(_) => {
+                let (Self::Init {
+                    best_tip,
+                    root_block,
+                    blocks_inbetween,
+                    ..
+                }
+                | Self::StakingLedgerSuccess {
+                    best_tip,
+                    root_block,
+                    blocks_inbetween,
+                    ..
+                }
+                | Self::NextEpochLedgerSuccess {
+                    best_tip,
+                    root_block,
+                    blocks_inbetween,
+                    ..
+                }) = self
+                else {
+                    return;
+                };
+                let (best_tip, root_block) = (best_tip.clone(), root_block.clone());
+                let blocks_inbetween = std::mem::take(blocks_inbetween);
+
+                if best_chain.iter().any(|b| b.hash == root_block.hash) {
+                    // We only needed to sync epoch ledgers, root block
+                    // is already applied.
+                    *self = Self::RootLedgerSuccess {
+                        time: meta.time(),
+                        best_tip,
+                        root_block,
+                        blocks_inbetween,
+                        needed_protocol_states: Default::default(),
                     };
+                    return;
                 }
+
+                let cur_best_root = best_chain.first();
+                let root_ledger = if cur_best_root.map_or(false, |cur| {
+                    cur.snarked_ledger_hash() == root_block.snarked_ledger_hash()
+                }) {
+                    TransitionFrontierSyncLedgerSnarkedState::Success {
+                        time: meta.time(),
+                        kind: SyncLedgerTargetKind::RootLedger,
+                        block: root_block,
+                    }
+                    .into()
+                } else {
+                    TransitionFrontierSyncLedgerState::Init {
+                        time: meta.time(),
+                        kind: SyncLedgerTargetKind::RootLedger,
+                        block: root_block,
+                    }
+                };
+                *self = Self::RootLedgerPending {
+                    time: meta.time(),
+                    best_tip,
+                    blocks_inbetween,
+                    root_ledger,
+                };
             }
             TransitionFrontierSyncAction::LedgerRootSuccess(_) => {
                 if let Self::RootLedgerPending {
@@ -287,6 +506,7 @@ impl TransitionFrontierSyncState {
                     time: meta.time(),
                     chain,
                     needed_protocol_states: std::mem::take(needed_protocol_states),
+                    epoch_ledger: None,
                 };
             }
             TransitionFrontierSyncAction::BlocksPeersQuery(_) => {}
@@ -391,11 +611,13 @@ impl TransitionFrontierSyncState {
                 let Self::BlocksPending {
                     chain,
                     needed_protocol_states,
+                    epoch_ledger,
                     ..
                 } = self
                 else {
                     return;
                 };
+                let epoch_ledger = epoch_ledger.take();
                 let chain = std::mem::take(chain)
                     .into_iter()
                     .rev()
@@ -408,14 +630,45 @@ impl TransitionFrontierSyncState {
                     time: meta.time(),
                     chain,
                     needed_protocol_states: std::mem::take(needed_protocol_states),
+                    epoch_ledger,
                 };
             }
             TransitionFrontierSyncAction::Ledger(a) => match self {
+                Self::StakingLedgerPending { ledger, .. }
+                | Self::NextEpochLedgerPending { ledger, .. } => {
+                    ledger.reducer(meta.with_action(a));
+                }
                 Self::RootLedgerPending { root_ledger, .. } => {
                     root_ledger.reducer(meta.with_action(a));
                 }
-                _ => {}
+                _ => {
+                    if let Some(Some(ledger)) = self.epoch_ledger_mut() {
+                        ledger.reducer(meta.with_action(a));
+                    }
+                }
             },
         }
     }
+
+    /// Starts syncing epoch ledger of the best tip alongside the blocks.
+    fn epoch_ledger_init(
+        &mut self,
+        time: Timestamp,
+        kind: SyncLedgerTargetKind,
+        best_chain: &[ArcBlockWithHash],
+    ) {
+        let Some(best_tip) = self.best_tip().or(best_chain.last()).cloned() else {
+            return;
+        };
+        let Some(epoch_ledger) = self.epoch_ledger_mut() else {
+            return;
+        };
+        if epoch_ledger.is_none() {
+            *epoch_ledger = Some(TransitionFrontierSyncLedgerState::Init {
+                time,
+                kind,
+                block: best_tip,
+            });
+        }
+    }
 }
@@ -20,6 +20,32 @@ pub enum TransitionFrontierSyncState {
         root_block: ArcBlockWithHash,
         blocks_inbetween: Vec<StateHash>,
     },
+    StakingLedgerPending {
+        time: Timestamp,
+        best_tip: ArcBlockWithHash,
+        root_block: ArcBlockWithHash,
+        blocks_inbetween: Vec<StateHash>,
+        ledger: TransitionFrontierSyncLedgerState,
+    },
+    StakingLedgerSuccess {
+        time: Timestamp,
+        best_tip: ArcBlockWithHash,
+        root_block: ArcBlockWithHash,
+        blocks_inbetween: Vec<StateHash>,
+    },
+    NextEpochLedgerPending {
+        time: Timestamp,
+        best_tip: ArcBlockWithHash,
+        root_block: ArcBlockWithHash,
+        blocks_inbetween: Vec<StateHash>,
+        ledger: TransitionFrontierSyncLedgerState,
+    },
+    NextEpochLedgerSuccess {
+        time: Timestamp,
+        best_tip: ArcBlockWithHash,
+        root_block: ArcBlockWithHash,
+        blocks_inbetween: Vec<StateHash>,
+    },
     RootLedgerPending {
         time: Timestamp,
         best_tip: ArcBlockWithHash,
@@ -37,14 +63,19 @@ pub enum TransitionFrontierSyncState {
         time: Timestamp,
         chain: Vec<TransitionFrontierSyncBlockState>,
         needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+        /// Epoch ledger of the best tip synced alongside the blocks,
+        /// once best tip moves to the next epoch.
+        epoch_ledger: Option<TransitionFrontierSyncLedgerState>,
     },
     BlocksSuccess {
         time: Timestamp,
         chain: Vec<ArcBlockWithHash>,
         needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+        epoch_ledger: Option<TransitionFrontierSyncLedgerState>,
     },
     Synced {
         time: Timestamp,
+        epoch_ledger: Option<TransitionFrontierSyncLedgerState>,
     },
 }
 
@@ -102,6 +133,10 @@ impl TransitionFrontierSyncState {
         match self {
             Self::Idle => None,
             Self::Init { root_block, .. } => Some(root_block),
+            Self::StakingLedgerPending { root_block, .. } => Some(root_block),
+            Self::StakingLedgerSuccess { root_block, .. } => Some(root_block),
+            Self::NextEpochLedgerPending { root_block, .. } => Some(root_block),
+            Self::NextEpochLedgerSuccess { root_block, .. } => Some(root_block),
             Self::RootLedgerPending { root_ledger, .. } => Some(root_ledger.block()),
             Self::RootLedgerSuccess { root_block, .. } => Some(root_block),
             Self::BlocksPending { chain, .. } => chain.first().and_then(|b| b.block()),
@@ -114,6 +149,10 @@ impl TransitionFrontierSyncState {
         match self {
             Self::Idle => None,
             Self::Init { best_tip, .. } => Some(best_tip),
+            Self::StakingLedgerPending { best_tip, .. } => Some(best_tip),
+            Self::StakingLedgerSuccess { best_tip, .. } => Some(best_tip),
+            Self::NextEpochLedgerPending { best_tip, .. } => Some(best_tip),
+            Self::NextEpochLedgerSuccess { best_tip, .. } => Some(best_tip),
             Self::RootLedgerPending { best_tip, .. } => Some(best_tip),
             Self::RootLedgerSuccess { best_tip, .. } => Some(best_tip),
             Self::BlocksPending { chain, .. } => chain.last().and_then(|b| b.block()),
@@ -129,6 +168,39 @@ impl TransitionFrontierSyncState {
         }
     }
 
+    /// Ledger which is currently being synced, be it an epoch ledger
+    /// or the root one.
+    pub fn ledger(&self) -> Option<&TransitionFrontierSyncLedgerState> {
+        match self {
+            Self::StakingLedgerPending { ledger, .. } => Some(ledger),
+            Self::NextEpochLedgerPending { ledger, .. } => Some(ledger),
+            Self::RootLedgerPending { root_ledger, .. } => Some(root_ledger),
+            _ => self.epoch_ledger(),
+        }
+    }
+
+    /// Epoch ledger which is synced alongside the blocks, or after
+    /// they are synced.
+    pub fn epoch_ledger(&self) -> Option<&TransitionFrontierSyncLedgerState> {
+        match self {
+            Self::BlocksPending { epoch_ledger, .. }
+            | Self::BlocksSuccess { epoch_ledger, .. }
+            | Self::Synced { epoch_ledger, .. } => epoch_ledger.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// `None` if epoch ledgers can't be synced alongside the blocks in
+    /// this phase, as they are synced before the root ledger then.
+    pub fn epoch_ledger_mut(&mut self) -> Option<&mut Option<TransitionFrontierSyncLedgerState>> {
+        match self {
+            Self::BlocksPending { epoch_ledger, .. }
+            | Self::BlocksSuccess { epoch_ledger, .. }
+            | Self::Synced { epoch_ledger, .. } => Some(epoch_ledger),
+            _ => None,
+        }
+    }
+
     pub fn blocks_iter(&self) -> impl Iterator<Item = &TransitionFrontierSyncBlockState> {
         static EMPTY: Vec<TransitionFrontierSyncBlockState> = Vec::new();
         match self {
@@ -1,3 +1,4 @@
+use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
 use crate::ProtocolConstants;
@@ -5,27 +6,20 @@ use crate::ProtocolConstants;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransitionFrontierConfig {
     pub protocol_constants: ProtocolConstants,
+    /// Genesis block created locally, in which case transition frontier
+    /// starts with it as the best tip, instead of syncing from peers.
+    pub genesis_block: Option<ArcBlockWithHash>,
 }
 
 impl TransitionFrontierConfig {
-    pub fn k(&self) -> usize {
-        self.protocol_constants.k.0.as_u32() as usize
-    }
-}
-
-impl Default for TransitionFrontierConfig {
-    fn default() -> Self {
-        // TODO(binier): better way.
+    pub fn new(protocol_constants: ProtocolConstants) -> Self {
         Self {
-            protocol_constants: serde_json::from_value(serde_json::json!({
-                "k": "290",
-                "slots_per_epoch": "7140",
-                "slots_per_sub_window": "7",
-                "delta": "0",
-                // TODO(binier): fix wrong timestamp
-                "genesis_state_timestamp": "0",
-            }))
-            .unwrap(),
+            protocol_constants,
+            genesis_block: None,
         }
     }
+
+    pub fn k(&self) -> usize {
+        self.protocol_constants.k.0.as_u32() as usize
+    }
 }
@@ -2,13 +2,15 @@ use redux::Timestamp;
 
 use crate::ledger::LEDGER_DEPTH;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipResponseSendAction;
+use crate::rpc::{RpcEvent, RpcEventNotifyAction, RpcSyncPhase};
 use crate::snark_pool::{SnarkPoolJobsUpdateAction, SnarkWork};
 use crate::stats::sync::SyncingLedger;
+use crate::transaction_pool::TransactionPoolBestTipUpdateAction;
 use crate::Store;
 
 use super::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedAction;
 use super::sync::ledger::staged::TransitionFrontierSyncLedgerStagedAction;
-use super::sync::ledger::TransitionFrontierSyncLedgerAction;
+use super::sync::ledger::{SyncLedgerTargetKind, TransitionFrontierSyncLedgerAction};
 use super::sync::{
     TransitionFrontierSyncAction, TransitionFrontierSyncLedgerRootSuccessAction,
     TransitionFrontierSyncState,
@@ -59,13 +61,57 @@ pub fn transition_frontier_effects<S: crate::Service>(
                 }
                 a.effects(&meta, store);
             }
+            TransitionFrontierSyncAction::LedgerStakingPending(a) => {
+                // Not a separate phase if synced alongside the blocks.
+                if matches!(
+                    store.state().transition_frontier.sync,
+                    TransitionFrontierSyncState::StakingLedgerPending { .. }
+                ) {
+                    store.dispatch(RpcEventNotifyAction {
+                        event: RpcEvent::SyncPhase {
+                            phase: RpcSyncPhase::StakingLedger,
+                        },
+                    });
+                }
+                a.effects(&meta, store);
+            }
+            TransitionFrontierSyncAction::LedgerStakingSuccess(a) => {
+                a.effects(&meta, store);
+            }
+            TransitionFrontierSyncAction::LedgerNextEpochPending(a) => {
+                // Not a separate phase if synced alongside the blocks.
+                if matches!(
+                    store.state().transition_frontier.sync,
+                    TransitionFrontierSyncState::NextEpochLedgerPending { .. }
+                ) {
+                    store.dispatch(RpcEventNotifyAction {
+                        event: RpcEvent::SyncPhase {
+                            phase: RpcSyncPhase::NextEpochLedger,
+                        },
+                    });
+                }
+                a.effects(&meta, store);
+            }
+            TransitionFrontierSyncAction::LedgerNextEpochSuccess(a) => {
+                a.effects(&meta, store);
+            }
             TransitionFrontierSyncAction::LedgerRootPending(a) => {
+                store.dispatch(RpcEventNotifyAction {
+                    event: RpcEvent::SyncPhase {
+                        phase: RpcSyncPhase::RootLedger,
+                    },
+                });
                 a.effects(&meta, store);
             }
             TransitionFrontierSyncAction::LedgerRootSuccess(a) => {
                 a.effects(&meta, store);
             }
             TransitionFrontierSyncAction::BlocksPending(a) => {
+                store.dispatch(RpcEventNotifyAction {
+                    event: RpcEvent::SyncPhase {
+                        phase: RpcSyncPhase::Blocks,
+                    },
+                });
                 if let Some(stats) = store.service.stats() {
                     if let TransitionFrontierSyncState::BlocksPending { chain, .. } =
                         &store.state.get().transition_frontier.sync
@@ -157,9 +203,15 @@ pub fn transition_frontier_effects<S: crate::Service>(
                     return;
                 };
                 let Some(best_tip) = chain.last() else { return };
+                let epoch_ledgers = [
+                    SyncLedgerTargetKind::StakingLedger,
+                    SyncLedgerTargetKind::NextEpochLedger,
+                ]
+                .map(|kind| kind.ledger_hash(best_tip));
                 let ledgers_to_keep = chain
                     .iter()
                     .flat_map(|b| [b.snarked_ledger_hash(), b.staged_ledger_hash()])
+                    .chain(epoch_ledgers)
                     .cloned()
                     .collect();
 
@@ -186,7 +238,11 @@ pub fn transition_frontier_effects<S: crate::Service>(
                     })
                     .collect();
 
-                let res = store.service.commit(ledgers_to_keep, root_block, best_tip);
+                let ledger_syncing = sync.epoch_ledger().map(|l| l.snarked_ledger_hash());
+                let res =
+                    store
+                        .service
+                        .commit(ledgers_to_keep, ledger_syncing, root_block, best_tip);
                 let needed_protocol_states = res.needed_protocol_states;
                 let jobs = res.available_jobs;
                 store.dispatch(TransitionFrontierSyncedAction {
@@ -196,6 +252,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                     jobs,
                     orphaned_snarks,
                 });
+                store.dispatch(TransactionPoolBestTipUpdateAction {});
             }
             TransitionFrontierSyncAction::Ledger(a) => match a {
                 TransitionFrontierSyncLedgerAction::Init(action) => {
@@ -233,7 +290,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                                 .get()
                                 .transition_frontier
                                 .sync
-                                .root_ledger()
+                                .ledger()
                                 .and_then(|s| {
                                     s.snarked()?.peer_query_get(&action.peer_id, action.rpc_id)
                                 })
@@ -328,8 +385,28 @@ pub fn transition_frontier_effects<S: crate::Service>(
                 stats.new_best_tip(meta.time(), best_tip);
             }
 
+            let transition_frontier = &store.state.get().transition_frontier;
+            if let Err(err) = store.service.persist(
+                &transition_frontier.best_chain,
+                &transition_frontier.needed_protocol_states,
+            ) {
+                openmina_core::log::warn!(meta.time(); kind = "TransitionFrontierPersist", summary = format!("failed to persist transition frontier: {err}"));
+            }
+
             // publish new best tip.
             let best_tip = best_tip.clone();
+            store.dispatch(RpcEventNotifyAction {
+                event: RpcEvent::SyncPhase {
+                    phase: RpcSyncPhase::Synced,
+                },
+            });
+            store.dispatch(RpcEventNotifyAction {
+                event: RpcEvent::BestTip {
+                    hash: best_tip.hash().clone(),
+                    height: best_tip.height(),
+                    global_slot: best_tip.global_slot(),
+                },
+            });
             for peer_id in store.state().p2p.ready_peers() {
                 store.dispatch(P2pChannelsBestTipResponseSendAction {
                     peer_id,
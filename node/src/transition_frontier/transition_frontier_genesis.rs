@@ -0,0 +1,402 @@
+use std::sync::Arc;
+
+use ledger::{
+    dummy::dummy_transaction_proof,
+    scan_state::{
+        currency::{Amount, Magnitude, Signed},
+        fee_excess::FeeExcess,
+        pending_coinbase::Stack,
+        scan_state::{
+            transaction_snark::{Registers, Statement},
+            ConstraintConstants,
+        },
+        transaction_logic::local_state::LocalState,
+    },
+    staged_ledger::{staged_ledger::StagedLedger, validate_block::block_body_hash},
+    Account, BaseLedger, Database, Mask,
+};
+use mina_hasher::Fp;
+use mina_p2p_messages::v2::{
+    Blake2MakeStableV1, ConsensusBodyReferenceStableV1, ConsensusGlobalSlotStableV1,
+    ConsensusProofOfStakeDataConsensusStateValueStableV2,
+    ConsensusProofOfStakeDataEpochDataNextValueVersionedValueStableV1,
+    ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStableV1,
+    ConsensusVrfOutputTruncatedStableV1, CurrencyAmountStableV1, DataHashLibStateHashStableV1,
+    EpochSeed, LedgerHash, MinaBaseEpochLedgerValueStableV1, MinaBaseEpochSeedStableV1,
+    MinaBaseLedgerHash0StableV1, MinaBaseProofStableV2, MinaBaseStagedLedgerHashStableV1,
+    MinaBlockBlockStableV2, MinaBlockHeaderStableV2, MinaNumbersGlobalSlotSinceGenesisMStableV1,
+    MinaNumbersGlobalSlotSinceHardForkMStableV1, MinaStateBlockchainStateValueStableV2,
+    MinaStateProtocolStateBodyValueStableV2, MinaStateProtocolStateValueStableV2,
+    NonZeroCurvePoint, ProtocolVersionStableV1, StagedLedgerDiffBodyStableV1,
+    StagedLedgerDiffDiffDiffStableV2, StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2,
+    StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2Coinbase, StagedLedgerDiffDiffStableV2,
+    StateHash, UnsignedExtendedUInt32StableV1,
+};
+use openmina_core::block::{ArcBlockWithHash, BlockWithHash};
+
+use crate::ledger::LEDGER_DEPTH;
+use crate::ProtocolConstants;
+
+/// Public key which is used as a block producer and coinbase receiver
+/// of the genesis block.
+pub const GENESIS_WINNER_PUBLIC_KEY: &str =
+    "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg";
+
+/// Transaction (major) and network (minor) parts of the protocol version.
+pub const PROTOCOL_TRANSACTION_VERSION: u32 = 2;
+pub const PROTOCOL_NETWORK_VERSION: u32 = 0;
+
+/// Creates the genesis block of the network from its genesis ledger.
+///
+/// Follows `Mina_state.Genesis_protocol_state`, but genesis proof is a
+/// dummy one and VRF of the genesis winner isn't evaluated, so the
+/// result will differ from the one of the OCaml node and it can only
+/// be used for private networks consisting of OpenMina nodes.
+pub fn genesis_block(
+    protocol_constants: &ProtocolConstants,
+    constraint_constants: &ConstraintConstants,
+    genesis_ledger: &[Account],
+) -> Result<ArcBlockWithHash, String> {
+    let mut mask = Mask::new_root(Database::create(LEDGER_DEPTH as u8));
+    let mut total_currency = Amount::zero();
+    for account in genesis_ledger {
+        total_currency = total_currency
+            .checked_add(&account.balance.to_amount())
+            .ok_or("genesis ledger total currency overflow")?;
+        mask.get_or_create_account(account.id(), account.clone())
+            .map_err(|err| format!("{err:?}"))?;
+    }
+    let genesis_ledger_hash = mask.merkle_root();
+
+    let mut staged_ledger = StagedLedger::create_exn(constraint_constants.clone(), mask)?;
+    let staged_ledger_hash = MinaBaseStagedLedgerHashStableV1::from(&staged_ledger.hash());
+
+    let body = StagedLedgerDiffBodyStableV1 {
+        staged_ledger_diff: StagedLedgerDiffDiffStableV2 {
+            diff: StagedLedgerDiffDiffDiffStableV2(
+                StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2 {
+                    completed_works: vec![],
+                    commands: vec![],
+                    coinbase:
+                        StagedLedgerDiffDiffPreDiffWithAtMostTwoCoinbaseStableV2Coinbase::Zero,
+                    internal_command_statuses: vec![],
+                },
+                None,
+            ),
+        },
+    };
+    let body_reference =
+        block_body_hash(&body.staged_ledger_diff).map_err(|err| format!("{err:?}"))?;
+
+    let negative_one = negative_one_protocol_state(
+        protocol_constants,
+        constraint_constants,
+        genesis_ledger_hash,
+        (&total_currency).into(),
+        staged_ledger_hash,
+        ConsensusBodyReferenceStableV1(Blake2MakeStableV1(body_reference.as_slice().into())),
+    );
+    let negative_one_hash = negative_one.hash();
+
+    let mut consensus_state = negative_one.body.consensus_state;
+    consensus_state.blockchain_length = 1u32.into();
+    consensus_state.has_ancestor_in_same_checkpoint_window = true;
+    consensus_state.next_epoch_data.lock_checkpoint = negative_one_hash.clone();
+    consensus_state.next_epoch_data.epoch_length = 2u32.into();
+
+    let protocol_state = MinaStateProtocolStateValueStableV2 {
+        previous_state_hash: negative_one_hash.clone(),
+        body: MinaStateProtocolStateBodyValueStableV2 {
+            genesis_state_hash: negative_one_hash,
+            blockchain_state: negative_one.body.blockchain_state,
+            consensus_state,
+            constants: protocol_constants.clone(),
+        },
+    };
+    let genesis_state_hash = protocol_state.hash();
+
+    let block = MinaBlockBlockStableV2 {
+        header: MinaBlockHeaderStableV2 {
+            protocol_state,
+            protocol_state_proof: MinaBaseProofStableV2(dummy_transaction_proof().0.clone()),
+            delta_block_chain_proof: (genesis_state_hash, vec![]),
+            current_protocol_version: ProtocolVersionStableV1 {
+                major: (PROTOCOL_TRANSACTION_VERSION as i64).into(),
+                minor: (PROTOCOL_NETWORK_VERSION as i64).into(),
+                patch: 0i64.into(),
+            },
+            proposed_protocol_version_opt: None,
+        },
+        body,
+    };
+    Ok(BlockWithHash::new(Arc::new(block)))
+}
+
+/// Computes chain id of the private network, the way `Chain_id.make`
+/// of the OCaml node does.
+///
+/// OCaml node also hashes in digests of the constraint systems, which we
+/// don't have, so they are left out and the result is only meaningful
+/// for networks of OpenMina nodes.
+pub fn chain_id(
+    genesis_state_hash: &StateHash,
+    protocol_constants: &ProtocolConstants,
+    txpool_max_size: u32,
+) -> String {
+    let version_digest = |version: u32| format!("{:x}", md5::compute(version.to_string()));
+    blake2b_hex(
+        [
+            genesis_state_hash.to_string(),
+            genesis_constants_hash(protocol_constants, txpool_max_size),
+            version_digest(PROTOCOL_TRANSACTION_VERSION),
+            version_digest(PROTOCOL_NETWORK_VERSION),
+        ]
+        .concat(),
+    )
+}
+
+/// `Genesis_constants.hash` of the OCaml node.
+fn genesis_constants_hash(protocol_constants: &ProtocolConstants, txpool_max_size: u32) -> String {
+    let timestamp = protocol_constants.genesis_state_timestamp.as_u64() as i64;
+    let timestamp = chrono::NaiveDateTime::from_timestamp_millis(timestamp)
+        .unwrap_or_default()
+        .format("%Y-%m-%d %H:%M:%S%.6fZ");
+    blake2b_hex(format!(
+        "{}{}{}{}{}{timestamp}",
+        protocol_constants.k.as_u32(),
+        protocol_constants.slots_per_epoch.as_u32(),
+        protocol_constants.slots_per_sub_window.as_u32(),
+        protocol_constants.delta.as_u32(),
+        txpool_max_size,
+    ))
+}
+
+fn blake2b_hex(data: String) -> String {
+    use blake2::{
+        digest::{Update, VariableOutput},
+        Blake2bVar,
+    };
+
+    let mut hasher = Blake2bVar::new(32).expect("Invalid Blake2bVar output size");
+    hasher.update(data.as_bytes());
+    hex::encode(hasher.finalize_boxed())
+}
+
+/// Protocol state preceding the genesis one, which is the parent of
+/// the genesis block.
+fn negative_one_protocol_state(
+    protocol_constants: &ProtocolConstants,
+    constraint_constants: &ConstraintConstants,
+    genesis_ledger_hash: Fp,
+    total_currency: CurrencyAmountStableV1,
+    staged_ledger_hash: MinaBaseStagedLedgerHashStableV1,
+    body_reference: ConsensusBodyReferenceStableV1,
+) -> MinaStateProtocolStateValueStableV2 {
+    let zero_state_hash: StateHash = DataHashLibStateHashStableV1(Fp::from(0u64).into()).into();
+    let zero_seed: EpochSeed = MinaBaseEpochSeedStableV1(Fp::from(0u64).into()).into();
+    let ledger_hash: LedgerHash = MinaBaseLedgerHash0StableV1(genesis_ledger_hash.into()).into();
+    let epoch_ledger = MinaBaseEpochLedgerValueStableV1 {
+        hash: ledger_hash.clone(),
+        total_currency: total_currency.clone(),
+    };
+    let genesis_winner: NonZeroCurvePoint = GENESIS_WINNER_PUBLIC_KEY.parse().unwrap();
+
+    let registers = Registers {
+        first_pass_ledger: genesis_ledger_hash,
+        second_pass_ledger: genesis_ledger_hash,
+        pending_coinbase_stack: Stack::empty(),
+        local_state: LocalState::empty(),
+    };
+    let ledger_proof_statement = Statement {
+        source: registers.clone(),
+        target: registers,
+        connecting_ledger_left: genesis_ledger_hash,
+        connecting_ledger_right: genesis_ledger_hash,
+        supply_increase: Signed::<Amount>::zero(),
+        fee_excess: FeeExcess::empty(),
+        sok_digest: (),
+    };
+
+    let slots_per_sub_window = protocol_constants.slots_per_sub_window.as_u32();
+    let sub_windows_per_window = constraint_constants.sub_windows_per_window as u32;
+    let sub_window_densities = std::iter::once(0)
+        .chain(std::iter::repeat(slots_per_sub_window).take(sub_windows_per_window as usize - 1))
+        .map(UnsignedExtendedUInt32StableV1::from)
+        .collect();
+
+    MinaStateProtocolStateValueStableV2 {
+        previous_state_hash: zero_state_hash.clone(),
+        body: MinaStateProtocolStateBodyValueStableV2 {
+            genesis_state_hash: zero_state_hash.clone(),
+            blockchain_state: MinaStateBlockchainStateValueStableV2 {
+                staged_ledger_hash,
+                genesis_ledger_hash: ledger_hash,
+                ledger_proof_statement: (&ledger_proof_statement).into(),
+                timestamp: protocol_constants.genesis_state_timestamp.clone(),
+                body_reference,
+            },
+            consensus_state: ConsensusProofOfStakeDataConsensusStateValueStableV2 {
+                blockchain_length: 0u32.into(),
+                epoch_count: 0u32.into(),
+                min_window_density: (slots_per_sub_window * sub_windows_per_window).into(),
+                sub_window_densities,
+                last_vrf_output: ConsensusVrfOutputTruncatedStableV1(vec![0u8; 32].into()),
+                total_currency,
+                curr_global_slot: ConsensusGlobalSlotStableV1 {
+                    slot_number: MinaNumbersGlobalSlotSinceHardForkMStableV1::SinceHardFork(
+                        0u32.into(),
+                    ),
+                    slots_per_epoch: protocol_constants.slots_per_epoch.clone(),
+                },
+                global_slot_since_genesis: MinaNumbersGlobalSlotSinceGenesisMStableV1::SinceGenesis(
+                    0u32.into(),
+                ),
+                staking_epoch_data:
+                    ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStableV1 {
+                        ledger: epoch_ledger.clone(),
+                        seed: zero_seed.clone(),
+                        start_checkpoint: zero_state_hash.clone(),
+                        lock_checkpoint: zero_state_hash.clone(),
+                        epoch_length: 1u32.into(),
+                    },
+                next_epoch_data:
+                    ConsensusProofOfStakeDataEpochDataNextValueVersionedValueStableV1 {
+                        ledger: epoch_ledger,
+                        seed: zero_seed,
+                        start_checkpoint: zero_state_hash.clone(),
+                        lock_checkpoint: zero_state_hash,
+                        epoch_length: 1u32.into(),
+                    },
+                has_ancestor_in_same_checkpoint_window: false,
+                block_stake_winner: genesis_winner.clone(),
+                block_creator: genesis_winner.clone(),
+                coinbase_receiver: genesis_winner,
+                supercharge_coinbase: true,
+            },
+            constants: protocol_constants.clone(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::daemon_json::DaemonJson;
+    use crate::{NetworkConfig, NetworkConfigError};
+
+    use super::*;
+
+    const DAEMON_JSON: &str = r#"{
+        "genesis": {
+            "k": 10,
+            "slots_per_epoch": 480,
+            "genesis_state_timestamp": "2024-01-01T00:00:00Z"
+        },
+        "proof": { "block_window_duration_ms": 20000 },
+        "ledger": {
+            "name": "private",
+            "accounts": [
+                { "pk": "B62qqrHu7qJJrUekPYqNEbsMMzxDebqfApuyT5y6K9xgwm4TUe77kNd", "balance": "1000000" },
+                {
+                    "pk": "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV",
+                    "balance": "500000.5",
+                    "delegate": "B62qqrHu7qJJrUekPYqNEbsMMzxDebqfApuyT5y6K9xgwm4TUe77kNd"
+                }
+            ]
+        }
+    }"#;
+
+    fn private_network(daemon_json: &str) -> NetworkConfig {
+        let daemon_json: DaemonJson = serde_json::from_str(daemon_json).unwrap();
+        NetworkConfig::berkeley()
+            .apply_daemon_json(daemon_json)
+            .unwrap()
+    }
+
+    #[test]
+    fn private_network_genesis_block() {
+        let network = private_network(DAEMON_JSON);
+        let block = network.genesis_block.clone().unwrap();
+        let accounts = network.genesis_ledger.clone().unwrap();
+        let protocol_state = &block.header().protocol_state;
+
+        assert_eq!(block.hash(), &protocol_state.hash());
+        assert_eq!(block.height(), 1);
+        assert_eq!(block.global_slot(), 0);
+        assert_eq!(
+            protocol_state.previous_state_hash,
+            protocol_state.body.genesis_state_hash
+        );
+        assert_eq!(protocol_state.body.constants.k.as_u32(), 10);
+
+        let mut mask = Mask::new_root(Database::create(LEDGER_DEPTH as u8));
+        for account in &accounts {
+            mask.get_or_create_account(account.id(), account.clone())
+                .unwrap();
+        }
+        let ledger_hash: LedgerHash = MinaBaseLedgerHash0StableV1(mask.merkle_root().into()).into();
+        assert_eq!(block.snarked_ledger_hash(), &ledger_hash);
+
+        assert_eq!(&network.genesis_state_hash, block.hash());
+        assert_eq!(
+            network.chain_id,
+            chain_id(
+                block.hash(),
+                &network.protocol_constants,
+                crate::TXPOOL_MAX_SIZE
+            )
+        );
+
+        // Every node of the private network must arrive at the same genesis.
+        let again = genesis_block(
+            &network.protocol_constants,
+            &network.constraint_constants,
+            &accounts,
+        )
+        .unwrap();
+        assert_eq!(again.hash(), block.hash());
+        assert_eq!(private_network(DAEMON_JSON).chain_id, network.chain_id);
+    }
+
+    #[test]
+    fn zero_sub_windows_rejected() {
+        for (from, to) in [
+            (
+                "\"proof\": {",
+                "\"proof\": { \"sub_windows_per_window\": 0,",
+            ),
+            (
+                "\"genesis\": {",
+                "\"genesis\": { \"slots_per_sub_window\": 0,",
+            ),
+        ] {
+            let daemon_json: DaemonJson =
+                serde_json::from_str(&DAEMON_JSON.replace(from, to)).unwrap();
+            assert!(matches!(
+                NetworkConfig::berkeley().apply_daemon_json(daemon_json),
+                Err(NetworkConfigError::ZeroConstant(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn genesis_constants_hash_berkeley() {
+        // blake2b-256 of "290714070" ^ "3000" ^ "2023-02-23 20:00:01.000000Z".
+        assert_eq!(
+            genesis_constants_hash(&NetworkConfig::berkeley().protocol_constants, 3000),
+            "1d37e47cfbc1ad112f266cc59bca0c586b3ec840183615d1f1ea47937ca0d636"
+        );
+    }
+
+    #[test]
+    fn genesis_block_depends_on_ledger_and_constants() {
+        let network = private_network(DAEMON_JSON);
+        let hash = network.genesis_state_hash;
+
+        let other_ledger = private_network(&DAEMON_JSON.replace("500000.5", "500000.25"));
+        assert_ne!(other_ledger.genesis_state_hash, hash);
+
+        let other_constants = private_network(&DAEMON_JSON.replace("\"k\": 10", "\"k\": 20"));
+        assert_ne!(other_constants.genesis_state_hash, hash);
+        assert_ne!(other_constants.chain_id, network.chain_id);
+    }
+}
@@ -1,4 +1,5 @@
-use super::sync::TransitionFrontierSyncState;
+use super::sync::ledger::SyncLedgerTargetKind;
+use super::sync::{TransitionFrontierSyncAction, TransitionFrontierSyncState};
 use super::{
     TransitionFrontierAction, TransitionFrontierActionWithMetaRef, TransitionFrontierState,
 };
@@ -8,13 +9,26 @@ impl TransitionFrontierState {
         let (action, meta) = action.split();
         match action {
             TransitionFrontierAction::Sync(a) => {
-                self.sync
-                    .reducer(meta.with_action(a), &self.config, &self.best_chain);
+                let synced_epoch_ledger = match a {
+                    TransitionFrontierSyncAction::LedgerStakingSuccess(_)
+                    | TransitionFrontierSyncAction::LedgerNextEpochSuccess(_) => {
+                        self.sync.ledger().map(|s| s.snarked_ledger_hash().clone())
+                    }
+                    _ => None,
+                };
+                self.sync.reducer(
+                    meta.with_action(a),
+                    &self.config,
+                    &self.best_chain,
+                    &self.epoch_ledgers,
+                );
+                self.epoch_ledgers.extend(synced_epoch_ledger);
             }
             TransitionFrontierAction::Synced(a) => {
                 let TransitionFrontierSyncState::BlocksSuccess {
                     chain,
                     needed_protocol_states,
+                    epoch_ledger,
                     ..
                 } = &mut self.sync
                 else {
@@ -22,6 +36,7 @@ impl TransitionFrontierState {
                 };
                 let mut needed_protocol_state_hashes = a.needed_protocol_states.clone();
                 let new_chain = std::mem::take(chain);
+                let epoch_ledger = epoch_ledger.take();
 
                 self.needed_protocol_states
                     .extend(std::mem::take(needed_protocol_states));
@@ -41,8 +56,264 @@ impl TransitionFrontierState {
                 }
 
                 self.best_chain = new_chain;
-                self.sync = TransitionFrontierSyncState::Synced { time: meta.time() };
+                if let Some(best_tip) = self.best_chain.last() {
+                    let keep = [
+                        SyncLedgerTargetKind::StakingLedger,
+                        SyncLedgerTargetKind::NextEpochLedger,
+                    ]
+                    .map(|kind| kind.ledger_hash(best_tip));
+                    self.epoch_ledgers.retain(|hash| keep.contains(&hash));
+                }
+                self.sync = TransitionFrontierSyncState::Synced {
+                    time: meta.time(),
+                    epoch_ledger,
+                };
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+
+    use ledger::{scan_state::currency::Balance, AccountId, TokenId};
+    use mina_p2p_messages::v2::LedgerHash;
+    use openmina_core::block::{ArcBlockWithHash, BlockWithHash};
+    use redux::{ActionMeta, Timestamp};
+
+    use crate::account::AccountSecretKey;
+    use crate::transition_frontier::sync::{
+        TransitionFrontierSyncBestTipUpdateAction, TransitionFrontierSyncInitAction,
+        TransitionFrontierSyncLedgerNextEpochPendingAction,
+        TransitionFrontierSyncLedgerNextEpochSuccessAction,
+        TransitionFrontierSyncLedgerStakingPendingAction,
+        TransitionFrontierSyncLedgerStakingSuccessAction,
+    };
+    use crate::transition_frontier::{genesis_block, TransitionFrontierSyncedAction};
+    use crate::{NetworkConfig, TransitionFrontierConfig};
+
+    use super::*;
+
+    const LEDGER_HASHES: [&str; 3] = [
+        "jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr",
+        "jxo5pSyt16XGwA9UeuAdiFDzrwFH3smbNTJF7fxq98w1y9Jem2m",
+        "jwq3nCDr8XejL8HKDxR5qVhFJbKoUTGZgtLBZCp3MrqLTnqmjdP",
+    ];
+
+    fn ledger_hash(i: usize) -> LedgerHash {
+        LEDGER_HASHES[i].parse().unwrap()
+    }
+
+    fn genesis() -> ArcBlockWithHash {
+        let network = NetworkConfig::berkeley();
+        let producer = AccountSecretKey::rand().public_key();
+        let account_id = AccountId::new(producer.as_ref().into(), TokenId::default());
+        let account =
+            ledger::Account::create_with(account_id, Balance::from_u64(1_000_000_000_000));
+        genesis_block(
+            &network.protocol_constants,
+            &network.constraint_constants,
+            &[account],
+        )
+        .unwrap()
+    }
+
+    /// Block with the given staking and next epoch ledgers.
+    fn block(
+        genesis: &ArcBlockWithHash,
+        height: u32,
+        staking: usize,
+        next: usize,
+    ) -> ArcBlockWithHash {
+        let mut block = (*genesis.block).clone();
+        let consensus_state = &mut block.header.protocol_state.body.consensus_state;
+        consensus_state.blockchain_length = height.into();
+        consensus_state.staking_epoch_data.ledger.hash = ledger_hash(staking);
+        consensus_state.next_epoch_data.ledger.hash = ledger_hash(next);
+        BlockWithHash::new(Arc::new(block))
+    }
+
+    fn state() -> TransitionFrontierState {
+        let network = NetworkConfig::berkeley();
+        let config = TransitionFrontierConfig::new(network.protocol_constants.clone());
+        TransitionFrontierState::new(config, Timestamp::ZERO)
+    }
+
+    fn dispatch<A>(state: &mut TransitionFrontierState, action: A)
+    where
+        A: Into<TransitionFrontierSyncAction>,
+    {
+        dispatch_action(state, TransitionFrontierAction::Sync(action.into()));
+    }
+
+    fn dispatch_action(state: &mut TransitionFrontierState, action: TransitionFrontierAction) {
+        let meta = ActionMeta::zero_custom(Timestamp::new(1_000_000_000));
+        state.reducer(meta.with_action(&action));
+    }
+
+    fn synced_ledger_hash(state: &TransitionFrontierState) -> (SyncLedgerTargetKind, LedgerHash) {
+        let ledger = state.sync.ledger().unwrap();
+        (ledger.kind(), ledger.snarked_ledger_hash().clone())
+    }
+
+    #[test]
+    fn epoch_ledgers_synced_before_root_ledger() {
+        let genesis = genesis();
+        let best_tip = block(&genesis, 10, 0, 1);
+        let mut state = state();
+
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncInitAction {
+                best_tip: best_tip.clone(),
+                root_block: best_tip.clone(),
+                blocks_inbetween: vec![],
+            },
+        );
+        assert!(matches!(
+            state.sync,
+            TransitionFrontierSyncState::Init { .. }
+        ));
+
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncLedgerStakingPendingAction {},
+        );
+        assert!(matches!(
+            state.sync,
+            TransitionFrontierSyncState::StakingLedgerPending { .. }
+        ));
+        assert!(matches!(
+            synced_ledger_hash(&state),
+            (SyncLedgerTargetKind::StakingLedger, hash) if hash == ledger_hash(0)
+        ));
+
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncLedgerStakingSuccessAction {},
+        );
+        assert!(matches!(
+            state.sync,
+            TransitionFrontierSyncState::StakingLedgerSuccess { .. }
+        ));
+        assert_eq!(state.epoch_ledgers, BTreeSet::from([ledger_hash(0)]));
+
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncLedgerNextEpochPendingAction {},
+        );
+        assert!(matches!(
+            state.sync,
+            TransitionFrontierSyncState::NextEpochLedgerPending { .. }
+        ));
+        assert!(matches!(
+            synced_ledger_hash(&state),
+            (SyncLedgerTargetKind::NextEpochLedger, hash) if hash == ledger_hash(1)
+        ));
+
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncLedgerNextEpochSuccessAction {},
+        );
+        assert!(matches!(
+            state.sync,
+            TransitionFrontierSyncState::NextEpochLedgerSuccess { .. }
+        ));
+        assert_eq!(
+            state.epoch_ledgers,
+            BTreeSet::from([ledger_hash(0), ledger_hash(1)])
+        );
+    }
+
+    #[test]
+    fn best_tip_in_next_epoch_keeps_syncing_blocks() {
+        let genesis = genesis();
+        let root = block(&genesis, 10, 0, 1);
+        let mut state = state();
+        state.best_chain = vec![root.clone()];
+        state.epoch_ledgers = BTreeSet::from([ledger_hash(0), ledger_hash(1)]);
+        state.sync = TransitionFrontierSyncState::Synced {
+            time: Timestamp::ZERO,
+            epoch_ledger: None,
+        };
+
+        // Next epoch, so its next epoch ledger has to be synced, but
+        // blocks are synced in the meantime.
+        let best_tip = block(&genesis, 12, 1, 2);
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncBestTipUpdateAction {
+                best_tip: best_tip.clone(),
+                root_block: root,
+                blocks_inbetween: vec![],
+            },
+        );
+        assert!(matches!(
+            &state.sync,
+            TransitionFrontierSyncState::BlocksPending {
+                epoch_ledger: None,
+                ..
+            }
+        ));
+
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncLedgerNextEpochPendingAction {},
+        );
+        assert!(matches!(
+            &state.sync,
+            TransitionFrontierSyncState::BlocksPending { chain, epoch_ledger: Some(_), .. }
+                if chain.last().and_then(|b| b.block()).map(|b| &b.hash) == Some(&best_tip.hash)
+        ));
+        assert!(matches!(
+            synced_ledger_hash(&state),
+            (SyncLedgerTargetKind::NextEpochLedger, hash) if hash == ledger_hash(2)
+        ));
+
+        dispatch(
+            &mut state,
+            TransitionFrontierSyncLedgerNextEpochSuccessAction {},
+        );
+        assert!(matches!(
+            &state.sync,
+            TransitionFrontierSyncState::BlocksPending {
+                epoch_ledger: None,
+                ..
+            }
+        ));
+        assert!(state.epoch_ledgers.contains(&ledger_hash(2)));
+    }
+
+    #[test]
+    fn only_best_tip_epoch_ledgers_retained() {
+        let genesis = genesis();
+        let best_tip = block(&genesis, 12, 1, 2);
+        let mut state = state();
+        state.epoch_ledgers = (0..3).map(ledger_hash).collect();
+        state.sync = TransitionFrontierSyncState::BlocksSuccess {
+            time: Timestamp::ZERO,
+            chain: vec![best_tip.clone()],
+            needed_protocol_states: Default::default(),
+            epoch_ledger: None,
+        };
+
+        dispatch_action(
+            &mut state,
+            TransitionFrontierSyncedAction {
+                needed_protocol_states: Default::default(),
+            }
+            .into(),
+        );
+        assert!(matches!(
+            state.sync,
+            TransitionFrontierSyncState::Synced { .. }
+        ));
+        assert_eq!(state.best_chain.len(), 1);
+        assert_eq!(
+            state.epoch_ledgers,
+            BTreeSet::from([ledger_hash(1), ledger_hash(2)])
+        );
+    }
+}
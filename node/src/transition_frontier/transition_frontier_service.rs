@@ -5,8 +5,8 @@ use std::{
 
 use ledger::scan_state::scan_state::{transaction_snark::OneOrTwo, AvailableJobMessage};
 use mina_p2p_messages::v2::{
-    LedgerHash, MinaLedgerSyncLedgerAnswerStableV2, MinaLedgerSyncLedgerQueryStableV1,
-    MinaStateProtocolStateValueStableV2, StateHash,
+    LedgerHash, MinaBaseSparseLedgerBaseStableV2, MinaLedgerSyncLedgerAnswerStableV2,
+    MinaLedgerSyncLedgerQueryStableV1, MinaStateProtocolStateValueStableV2, StateHash,
 };
 use openmina_core::block::ArcBlockWithHash;
 
@@ -24,9 +24,12 @@ pub trait TransitionFrontierService: redux::Service {
         block: ArcBlockWithHash,
         pred_block: ArcBlockWithHash,
     ) -> Result<(), String>;
+    /// Keeps only `ledgers_to_keep` and the epoch ledger which is still
+    /// being synced, if any.
     fn commit(
         &mut self,
         ledgers_to_keep: BTreeSet<LedgerHash>,
+        ledger_syncing: Option<&LedgerHash>,
         new_root: &ArcBlockWithHash,
         new_best_tip: &ArcBlockWithHash,
     ) -> CommitResult;
@@ -40,4 +43,16 @@ pub trait TransitionFrontierService: redux::Service {
         ledger_hash: LedgerHash,
         protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
     ) -> Option<Arc<StagedLedgerAuxAndPendingCoinbases>>;
+    /// Whole snarked ledger with the given hash as a sparse ledger,
+    /// for answering `get_epoch_ledger` rpc. Ledger is built in the
+    /// background on the first request, until then `None` is returned.
+    fn epoch_ledger(&mut self, hash: LedgerHash) -> Option<Arc<MinaBaseSparseLedgerBaseStableV2>>;
+    /// Persist transition frontier, so that it can be restored after
+    /// restart. Data is written in the background. No-op if persistence
+    /// isn't enabled.
+    fn persist(
+        &mut self,
+        best_chain: &[ArcBlockWithHash],
+        needed_protocol_states: &BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    ) -> Result<(), String>;
 }
@@ -1,11 +1,14 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use mina_p2p_messages::v2::{
-    MinaStateProtocolStateBodyValueStableV2, MinaStateProtocolStateValueStableV2, StateHash,
+    LedgerHash, MinaStateProtocolStateBodyValueStableV2, MinaStateProtocolStateValueStableV2,
+    StateHash,
 };
 use openmina_core::block::ArcBlockWithHash;
+use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
+use super::sync::ledger::SyncLedgerTargetKind;
 use super::sync::TransitionFrontierSyncState;
 use super::TransitionFrontierConfig;
 
@@ -16,21 +19,61 @@ pub struct TransitionFrontierState {
     /// Needed protocol states for applying transactions in the root
     /// scan state that we don't have in the `best_chain` list.
     pub needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    /// Staking and next epoch ledgers of the best tip, which are
+    /// present in the ledger service.
+    pub epoch_ledgers: BTreeSet<LedgerHash>,
     pub sync: TransitionFrontierSyncState,
 }
 
 impl TransitionFrontierState {
-    pub fn new(config: TransitionFrontierConfig) -> Self {
+    pub fn new(config: TransitionFrontierConfig, time: Timestamp) -> Self {
         let k = config.protocol_constants.k.0.as_u32() as usize;
+        let mut best_chain = Vec::with_capacity(k);
+        let mut epoch_ledgers = BTreeSet::new();
+        let sync = match config.genesis_block.clone() {
+            Some(genesis_block) => {
+                epoch_ledgers.extend(
+                    [
+                        SyncLedgerTargetKind::StakingLedger,
+                        SyncLedgerTargetKind::NextEpochLedger,
+                    ]
+                    .map(|kind| kind.ledger_hash(&genesis_block).clone()),
+                );
+                best_chain.push(genesis_block);
+                TransitionFrontierSyncState::Synced {
+                    time,
+                    epoch_ledger: None,
+                }
+            }
+            None => TransitionFrontierSyncState::Idle,
+        };
         Self {
             config,
-            // TODO(binier): add genesis_block as initial best_tip.
-            best_chain: Vec::with_capacity(k),
+            best_chain,
             needed_protocol_states: Default::default(),
-            sync: TransitionFrontierSyncState::Idle,
+            epoch_ledgers,
+            sync,
         }
     }
 
+    /// Initializes transition frontier from the restored best chain,
+    /// ledgers for which must already be present in the ledger service.
+    ///
+    /// Restored best tip may be outdated, so sync is left idle and the
+    /// node resyncs from it on the next best tip update. Only epoch
+    /// ledgers missing from `epoch_ledgers` are synced then.
+    pub fn restore(
+        &mut self,
+        best_chain: Vec<ArcBlockWithHash>,
+        needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+        epoch_ledgers: BTreeSet<LedgerHash>,
+    ) {
+        self.best_chain = best_chain;
+        self.needed_protocol_states = needed_protocol_states;
+        self.epoch_ledgers = epoch_ledgers;
+        self.sync = TransitionFrontierSyncState::Idle;
+    }
+
     pub fn best_tip(&self) -> Option<&ArcBlockWithHash> {
         self.best_chain.last()
     }
@@ -101,6 +101,12 @@ impl Cluster {
             P2pSecretKey::from_bytes(bytes)
         };
         let pub_key = secret_key.public_key();
+        let network = testing_config
+            .network()
+            .expect("invalid network config of the testing node");
+        let mut transition_frontier =
+            TransitionFrontierConfig::new(network.protocol_constants.clone());
+        transition_frontier.genesis_block = network.genesis_block.clone();
 
         let config = Config {
             ledger: LedgerConfig {},
@@ -114,14 +120,20 @@ impl Cluster {
             global: GlobalConfig {
                 build: BuildEnv::get().into(),
                 snarker: None,
+                proof_level: network.proof_level,
             },
             p2p: P2pConfig {
                 identity_pub_key: pub_key,
                 initial_peers: vec![],
                 max_peers: 100,
+                reputation: Default::default(),
+                kademlia: Default::default(),
+                gossipsub: testing_config.gossipsub.clone(),
                 enabled_channels: ChannelId::iter_all().collect(),
             },
-            transition_frontier: TransitionFrontierConfig::default(),
+            transition_frontier,
+            block_producer: None,
+            transaction_pool: Default::default(),
         };
 
         let (event_sender, event_receiver) = mpsc::unbounded_channel();
@@ -130,10 +142,16 @@ impl Cluster {
 
         let webrtc_with_libp2p::P2pServiceCtx {
             libp2p,
-            webrtc: P2pServiceCtx { cmd_sender, peers },
+            webrtc:
+                P2pServiceCtx {
+                    cmd_sender,
+                    peers,
+                    secret_key: p2p_secret_key,
+                },
         } = <NodeService as P2pServiceWebrtcWithLibp2p>::init(
             secret_key,
             testing_config.chain_id,
+            config.p2p.gossipsub.clone(),
             p2p_event_sender.clone(),
             P2pTaskSpawner::new(shutdown_tx.clone()),
         );
@@ -174,14 +192,26 @@ impl Cluster {
                 let task = async {
                     tokio::select! {
                         _ = shutdown.closed() => {}
-                        _ = http_server::run(http_port, rpc_sender) => {}
+                        _ = http_server::run(http_server::HttpServerConfig::new(http_port), rpc_sender) => {}
                     }
                 };
                 local_set.block_on(&runtime, task);
             })
             .unwrap();
 
-        let ledger = LedgerCtx::default();
+        let ledger = LedgerCtx::default()
+            .with_constraint_constants(network.constraint_constants)
+            .with_proof_level(network.proof_level);
+        let ledger = match network.genesis_ledger {
+            Some(accounts) => ledger.with_genesis_ledger(accounts),
+            None => ledger,
+        };
+        let ledger = match &network.genesis_block {
+            Some(block) => ledger
+                .with_genesis_block(block)
+                .expect("failed to initialize ledgers of the genesis block"),
+            None => ledger,
+        };
         let real_service = NodeService {
             rng: StdRng::seed_from_u64(0),
             event_sender,
@@ -190,10 +220,13 @@ impl Cluster {
             cmd_sender,
             ledger,
             peers,
+            p2p_secret_key,
             libp2p,
+            peer_store: None,
             rpc: rpc_service,
-            snark_worker_sender: None,
-            stats: node::stats::Stats::new(),
+            snark_workers: Default::default(),
+            block_producer_key: None,
+            stats: node::stats::Stats::new(network.genesis_state_hash),
             recorder: Recorder::None,
             replayer: None,
         };
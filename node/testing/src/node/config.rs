@@ -1,3 +1,6 @@
+use node::daemon_json::DaemonJson;
+use node::p2p::P2pGossipsubConfig;
+use node::{NetworkConfig, NetworkConfigError};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -10,6 +13,12 @@ pub enum NodeTestingConfig {
 pub struct RustNodeTestingConfig {
     pub chain_id: String,
     pub initial_time: redux::Timestamp,
+    /// Runtime config applied on top of the berkeley network. If it
+    /// changes genesis, node creates the genesis block locally.
+    #[serde(default)]
+    pub daemon_json: Option<DaemonJson>,
+    #[serde(default)]
+    pub gossipsub: P2pGossipsubConfig,
 }
 
 impl RustNodeTestingConfig {
@@ -17,6 +26,27 @@ impl RustNodeTestingConfig {
         Self {
             chain_id: "3c41383994b87449625df91769dff7b507825c064287d30fada9286f3f1cb15e".to_owned(),
             initial_time: redux::Timestamp::ZERO,
+            daemon_json: None,
+            gossipsub: Default::default(),
+        }
+    }
+
+    /// Node of the private network, genesis block of which is created
+    /// locally from the given runtime config.
+    pub fn private_network(daemon_json: DaemonJson) -> Result<Self, NetworkConfigError> {
+        let network = NetworkConfig::berkeley().apply_daemon_json(daemon_json.clone())?;
+        Ok(Self {
+            chain_id: network.chain_id,
+            initial_time: redux::Timestamp::ZERO,
+            daemon_json: Some(daemon_json),
+            gossipsub: Default::default(),
+        })
+    }
+
+    pub fn network(&self) -> Result<NetworkConfig, NetworkConfigError> {
+        match self.daemon_json.clone() {
+            Some(daemon_json) => NetworkConfig::berkeley().apply_daemon_json(daemon_json),
+            None => Ok(NetworkConfig::berkeley()),
         }
     }
 }
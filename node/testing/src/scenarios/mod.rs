@@ -1,3 +1,4 @@
+pub mod private_network;
 pub mod solo_node;
 
 mod cluster_runner;
@@ -6,14 +7,19 @@ use cluster_runner::ClusterRunner;
 use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 
 use crate::cluster::Cluster;
+use crate::node::NodeTestingConfig;
 use crate::scenario::{Scenario, ScenarioId, ScenarioStep};
 
+use self::private_network::connect_to_genesis_node::PrivateNetworkConnectToGenesisNode;
+use self::private_network::receive_gossiped_transaction::PrivateNetworkReceiveGossipedTransaction;
 use self::solo_node::sync_root_snarked_ledger::SoloNodeSyncRootSnarkedLedger;
 
 #[derive(EnumIter, EnumString, IntoStaticStr, Clone, Copy)]
 #[strum(serialize_all = "kebab-case")]
 pub enum Scenarios {
     SoloNodeSyncRootSnarkedLedger(SoloNodeSyncRootSnarkedLedger),
+    PrivateNetworkConnectToGenesisNode(PrivateNetworkConnectToGenesisNode),
+    PrivateNetworkReceiveGossipedTransaction(PrivateNetworkReceiveGossipedTransaction),
 }
 
 impl Scenarios {
@@ -32,6 +38,8 @@ impl Scenarios {
     pub fn parent(self) -> Option<Self> {
         match self {
             Self::SoloNodeSyncRootSnarkedLedger(_) => None,
+            Self::PrivateNetworkConnectToGenesisNode(_) => None,
+            Self::PrivateNetworkReceiveGossipedTransaction(_) => None,
         }
     }
 
@@ -43,6 +51,10 @@ impl Scenarios {
         use documented::Documented;
         match self {
             Self::SoloNodeSyncRootSnarkedLedger(_) => SoloNodeSyncRootSnarkedLedger::DOCS,
+            Self::PrivateNetworkConnectToGenesisNode(_) => PrivateNetworkConnectToGenesisNode::DOCS,
+            Self::PrivateNetworkReceiveGossipedTransaction(_) => {
+                PrivateNetworkReceiveGossipedTransaction::DOCS
+            }
         }
     }
 
@@ -60,6 +72,14 @@ impl Scenarios {
                                                                            "#,
             )
             .unwrap()],
+            Self::PrivateNetworkConnectToGenesisNode(_) => {
+                let node =
+                    NodeTestingConfig::Rust(PrivateNetworkConnectToGenesisNode::node_config());
+                vec![node.clone(), node]
+            }
+            Self::PrivateNetworkReceiveGossipedTransaction(_) => vec![NodeTestingConfig::Rust(
+                PrivateNetworkReceiveGossipedTransaction::node_config(),
+            )],
         };
 
         scenario
@@ -72,6 +92,8 @@ impl Scenarios {
         let runner = ClusterRunner::new(cluster, add_step);
         match self {
             Self::SoloNodeSyncRootSnarkedLedger(v) => v.run(runner).await,
+            Self::PrivateNetworkConnectToGenesisNode(v) => v.run(runner).await,
+            Self::PrivateNetworkReceiveGossipedTransaction(v) => v.run(runner).await,
         }
     }
 
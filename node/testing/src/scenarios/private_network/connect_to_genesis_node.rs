@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use node::daemon_json::DaemonJson;
+
+use crate::{
+    node::RustNodeTestingConfig,
+    scenario::{ListenerNode, ScenarioStep},
+    scenarios::cluster_runner::ClusterRunner,
+};
+
+/// Runtime config of the private network used by the scenario.
+pub const DAEMON_JSON: &str = r#"{
+    "genesis": {
+        "k": 10,
+        "slots_per_epoch": 480,
+        "genesis_state_timestamp": "2024-01-01T00:00:00Z"
+    },
+    "proof": { "block_window_duration_ms": 20000 },
+    "ledger": {
+        "name": "private",
+        "accounts": [
+            { "pk": "B62qqrHu7qJJrUekPYqNEbsMMzxDebqfApuyT5y6K9xgwm4TUe77kNd", "balance": "1000000" },
+            { "pk": "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV", "balance": "500000" }
+        ]
+    }
+}"#;
+
+/// Set up 2 Rust nodes of a private network and connect them.
+///
+/// 1. Both nodes create the genesis block locally from the same runtime
+///    config, without reaching any public seeds.
+/// 2. Second node connects to the first one.
+/// 3. Both nodes must end up with a ready peer and the same best tip,
+///    which is the locally created genesis block.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct PrivateNetworkConnectToGenesisNode;
+
+impl PrivateNetworkConnectToGenesisNode {
+    pub fn daemon_json() -> DaemonJson {
+        serde_json::from_str(DAEMON_JSON).expect("invalid private network runtime config")
+    }
+
+    pub fn node_config() -> RustNodeTestingConfig {
+        RustNodeTestingConfig::private_network(Self::daemon_json())
+            .expect("invalid private network config")
+    }
+
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        let config = Self::node_config();
+        let genesis_hash = config
+            .network()
+            .unwrap()
+            .genesis_block
+            .expect("private network must have genesis block")
+            .hash()
+            .clone();
+
+        let genesis_node = runner.add_rust_node(config.clone());
+        let node = runner.add_rust_node(config);
+        for node_id in [genesis_node, node] {
+            let state = runner.node(node_id).unwrap().state();
+            let best_tip = state.transition_frontier.best_tip().unwrap();
+            assert_eq!(best_tip.hash(), &genesis_hash);
+            assert!(state.transition_frontier.sync.is_synced());
+        }
+
+        runner
+            .exec_step(ScenarioStep::ConnectNodes {
+                dialer: node,
+                listener: ListenerNode::Rust(genesis_node),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            if !runner
+                .wait_for_pending_events_with_timeout(Duration::from_secs(10))
+                .await
+            {
+                panic!("waiting for connection event timed out");
+            }
+            let steps = runner
+                .pending_events()
+                .flat_map(|(node_id, _, events)| {
+                    events.map(move |(_, event)| ScenarioStep::Event {
+                        node_id,
+                        event: event.to_string(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            for step in steps {
+                runner.exec_step(step).await.unwrap();
+            }
+
+            let is_connected = |node_id| {
+                runner
+                    .node(node_id)
+                    .unwrap()
+                    .state()
+                    .p2p
+                    .ready_peers_iter()
+                    .any(|(_, peer)| peer.best_tip.is_some())
+            };
+            if is_connected(genesis_node) && is_connected(node) {
+                break;
+            }
+        }
+
+        for node_id in [genesis_node, node] {
+            let state = runner.node(node_id).unwrap().state();
+            let (_, peer) = state.p2p.ready_peers_iter().next().unwrap();
+            assert_eq!(peer.best_tip.as_ref().unwrap().hash(), &genesis_hash);
+            assert_eq!(
+                state.transition_frontier.best_tip().unwrap().hash(),
+                &genesis_hash
+            );
+        }
+    }
+}
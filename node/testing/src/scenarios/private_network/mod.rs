@@ -0,0 +1,2 @@
+pub mod connect_to_genesis_node;
+pub mod receive_gossiped_transaction;
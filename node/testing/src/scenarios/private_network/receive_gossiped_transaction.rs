@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use ledger::scan_state::currency::{Amount, Fee, Nonce};
+use ledger::scan_state::transaction_logic::signed_command::{
+    Body, PaymentPayload, SignedCommandPayload,
+};
+use ledger::scan_state::transaction_logic::Memo;
+use mina_p2p_messages::v2::MinaBaseUserCommandStableV2;
+use node::{
+    account::AccountSecretKey,
+    daemon_json::DaemonJson,
+    event_source::Event,
+    p2p::{
+        connection::outgoing::P2pConnectionOutgoingInitOpts, P2pChannelEvent, P2pConnectionEvent,
+        P2pEvent,
+    },
+    NetworkId,
+};
+
+use crate::{
+    node::RustNodeTestingConfig,
+    scenario::{ListenerNode, ScenarioStep},
+    scenarios::cluster_runner::ClusterRunner,
+};
+
+/// Runtime config of the private network used by the scenario. First
+/// account belongs to [`SENDER_SECRET_KEY`].
+pub const DAEMON_JSON: &str = r#"{
+    "genesis": {
+        "k": 10,
+        "slots_per_epoch": 480,
+        "genesis_state_timestamp": "2024-01-01T00:00:00Z"
+    },
+    "proof": { "block_window_duration_ms": 20000 },
+    "ledger": {
+        "name": "private-tx",
+        "accounts": [
+            { "pk": "B62qjVQLxt9nYMWGn45mkgwYfcz8e8jvjNCBo11VKJb7vxDNwv5QLPS", "balance": "1000000" },
+            { "pk": "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV", "balance": "500000" }
+        ]
+    }
+}"#;
+
+const SENDER_SECRET_KEY: &str = "EKFWgzXsoMYcP1Hnj7dBhsefxNucZ6wyz676Qg5uMFNzytXAi2Ww";
+const RECEIVER_PUBLIC_KEY: &str = "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV";
+
+/// Nobody listens there, the connection to the libp2p peer is
+/// simulated with manual events.
+const LIBP2P_PEER: &str =
+    "/ip4/127.0.0.1/tcp/1/p2p/12D3KooWD8jSyPFXNdAcMBHyHjRBcK1AW9t3xvnpfCFSRKMweVKi";
+
+/// Set up a Rust node of a private network and gossip a transaction to
+/// it from a libp2p peer.
+///
+/// 1. Node dials a libp2p peer and the connection succeeds.
+/// 2. Channels get opened. Libp2p peers don't support the transaction
+///    propagation channel, so it never becomes ready.
+/// 3. Peer gossips a payment from a genesis account on the
+///    `mina/tx/1.0.0` topic.
+/// 4. Transaction must end up in the transaction pool.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct PrivateNetworkReceiveGossipedTransaction;
+
+impl PrivateNetworkReceiveGossipedTransaction {
+    pub fn daemon_json() -> DaemonJson {
+        serde_json::from_str(DAEMON_JSON).expect("invalid private network runtime config")
+    }
+
+    pub fn node_config() -> RustNodeTestingConfig {
+        RustNodeTestingConfig::private_network(Self::daemon_json())
+            .expect("invalid private network config")
+    }
+
+    fn transaction() -> Box<MinaBaseUserCommandStableV2> {
+        let sender: AccountSecretKey = SENDER_SECRET_KEY.parse().unwrap();
+        let receiver: node::account::AccountPublicKey = RECEIVER_PUBLIC_KEY.parse().unwrap();
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(10_000_000),
+            sender.public_key().as_ref().into(),
+            Nonce::from_u32(0),
+            None,
+            Memo::empty(),
+            Body::Payment(PaymentPayload {
+                receiver_pk: receiver.as_ref().into(),
+                amount: Amount::from_u64(1_000_000_000),
+            }),
+        );
+        let cmd = sender.sign_command(NetworkId::Berkeley.signature_network_id(), payload);
+        Box::new(MinaBaseUserCommandStableV2::SignedCommand((&cmd).into()))
+    }
+
+    pub async fn run(self, mut runner: ClusterRunner<'_>) {
+        let node_id = runner.add_rust_node(Self::node_config());
+        let peer: P2pConnectionOutgoingInitOpts = LIBP2P_PEER.parse().unwrap();
+        let peer_id = *peer.peer_id();
+
+        runner
+            .exec_step(ScenarioStep::ConnectNodes {
+                dialer: node_id,
+                listener: ListenerNode::Custom(peer),
+            })
+            .await
+            .unwrap();
+        runner
+            .exec_step(ScenarioStep::ManualEvent {
+                node_id,
+                event: Box::new(Event::P2p(P2pEvent::Connection(
+                    P2pConnectionEvent::Finalized(peer_id, Ok(())),
+                ))),
+            })
+            .await
+            .unwrap();
+        assert!(runner
+            .node(node_id)
+            .unwrap()
+            .state()
+            .p2p
+            .get_ready_peer(&peer_id)
+            .is_some());
+
+        // Process channel open results, but not the failure of the
+        // actual dial.
+        loop {
+            if !runner
+                .wait_for_pending_events_with_timeout(Duration::from_secs(5))
+                .await
+            {
+                break;
+            }
+            let steps = runner
+                .node_pending_events(node_id)
+                .unwrap()
+                .1
+                .map(|(_, event)| event.to_string())
+                .filter(|event| event.contains("Channel, Opened"))
+                .map(|event| ScenarioStep::Event { node_id, event })
+                .collect::<Vec<_>>();
+            if steps.is_empty() {
+                break;
+            }
+            for step in steps {
+                runner.exec_step(step).await.unwrap();
+            }
+        }
+
+        let transaction = Self::transaction();
+        let hash = transaction.hash().unwrap();
+        runner
+            .exec_step(ScenarioStep::ManualEvent {
+                node_id,
+                event: Box::new(Event::P2p(P2pEvent::Channel(
+                    P2pChannelEvent::Libp2pTransactionReceived(peer_id, vec![transaction], 0),
+                ))),
+            })
+            .await
+            .unwrap();
+
+        let state = runner.node(node_id).unwrap().state();
+        let peer = state.p2p.get_ready_peer(&peer_id).unwrap();
+        assert!(!peer.channels.transaction.is_ready());
+        assert!(state.transaction_pool.contains(&hash));
+    }
+}
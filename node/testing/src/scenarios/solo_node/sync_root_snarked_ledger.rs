@@ -118,7 +118,7 @@ impl SoloNodeSyncRootSnarkedLedger {
             let snarked_state = state
                 .transition_frontier
                 .sync
-                .root_ledger()
+                .ledger()
                 .unwrap()
                 .snarked()
                 .unwrap();
@@ -172,7 +172,7 @@ impl SoloNodeSyncRootSnarkedLedger {
             let snarked_state = state
                 .transition_frontier
                 .sync
-                .root_ledger()
+                .ledger()
                 .unwrap()
                 .snarked()
                 .unwrap();
@@ -228,7 +228,7 @@ impl SoloNodeSyncRootSnarkedLedger {
             let snarked_state = state
                 .transition_frontier
                 .sync
-                .root_ledger()
+                .ledger()
                 .unwrap()
                 .snarked()
                 .unwrap();
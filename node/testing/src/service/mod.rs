@@ -3,7 +3,10 @@ mod rpc_service;
 use std::time::Duration;
 use std::{collections::BTreeMap, ffi::OsStr, sync::Arc};
 
-use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
+use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint, StateHash};
+use node::block_producer::{
+    BlockProducerService, BlockProducerVrfEvaluatorInput, BlockProverInput,
+};
 use node::core::channels::mpsc;
 use node::core::requests::{PendingRequests, RequestId};
 use node::core::snark::Snark;
@@ -16,16 +19,17 @@ use node::snark::{VerifierIndex, VerifierSRS};
 use node::stats::Stats;
 use node::{
     event_source::Event,
-    external_snark_worker::{ExternalSnarkWorkerService, SnarkWorkSpec},
+    external_snark_worker::{ExternalSnarkWorkerId, ExternalSnarkWorkerService, SnarkWorkSpec},
     ledger::LedgerCtx,
     p2p::{
         connection::outgoing::P2pConnectionOutgoingInitOpts,
+        identity::SecretKey,
         service_impl::{
             libp2p::Libp2pService,
             webrtc::{Cmd, P2pServiceWebrtc, PeerState},
             webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p,
         },
-        webrtc, P2pEvent, PeerId,
+        webrtc, P2pEvent, P2pPeerStore, P2pPeerStoreService, PeerId,
     },
 };
 use openmina_node_native::NodeService;
@@ -153,6 +157,10 @@ impl P2pServiceWebrtc for NodeTestingService {
         &mut self.real.peers
     }
 
+    fn secret_key(&mut self) -> &SecretKey {
+        &self.real.p2p_secret_key
+    }
+
     fn outgoing_init(&mut self, peer_id: PeerId) {
         self.real.outgoing_init(peer_id);
     }
@@ -168,6 +176,12 @@ impl P2pServiceWebrtcWithLibp2p for NodeTestingService {
     }
 }
 
+impl P2pPeerStoreService for NodeTestingService {
+    fn peer_store(&mut self) -> Option<&mut P2pPeerStore> {
+        self.real.peer_store()
+    }
+}
+
 impl SnarkBlockVerifyService for NodeTestingService {
     fn verify_init(
         &mut self,
@@ -204,28 +218,46 @@ impl SnarkWorkVerifyService for NodeTestingService {
     }
 }
 
+impl BlockProducerService for NodeTestingService {
+    fn vrf_evaluate(&mut self, input: BlockProducerVrfEvaluatorInput) {
+        self.real.vrf_evaluate(input)
+    }
+
+    fn prove(&mut self, block_hash: StateHash, input: Box<BlockProverInput>) {
+        self.real.prove(block_hash, input)
+    }
+}
+
 impl ExternalSnarkWorkerService for NodeTestingService {
     fn start<P: AsRef<OsStr>>(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
         path: P,
         public_key: NonZeroCurvePoint,
         fee: CurrencyFeeStableV1,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
-        self.real.start(path, public_key, fee)
+        self.real.start(worker_id, path, public_key, fee)
     }
 
     fn submit(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
         spec: SnarkWorkSpec,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
-        self.real.submit(spec)
+        self.real.submit(worker_id, spec)
     }
 
-    fn cancel(&mut self) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
-        self.real.cancel()
+    fn cancel(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+    ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
+        self.real.cancel(worker_id)
     }
 
-    fn kill(&mut self) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
-        self.real.kill()
+    fn kill(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+    ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
+        self.real.kill(worker_id)
     }
 }
@@ -1,9 +1,12 @@
-use node::State;
 use node::{p2p::connection::P2pConnectionResponse, rpc::RespondError, service::RpcService};
 use openmina_core::requests::RpcId;
 
 impl RpcService for super::NodeTestingService {
-    fn respond_state_get(&mut self, rpc_id: RpcId, response: &State) -> Result<(), RespondError> {
+    fn respond_state_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcStateGetResponse,
+    ) -> Result<(), RespondError> {
         self.real.respond_state_get(rpc_id, response)
     }
 
@@ -96,6 +99,110 @@ impl RpcService for super::NodeTestingService {
         self.real.respond_snarker_workers(rpc_id, response)
     }
 
+    fn respond_snarker_work_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcSnarkerWorkGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_snarker_work_get(rpc_id, response)
+    }
+
+    fn respond_snarker_work_submit(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcSnarkerWorkSubmitResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_snarker_work_submit(rpc_id, response)
+    }
+
+    fn respond_transaction_nonce_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcTransactionNonceGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_transaction_nonce_get(rpc_id, response)
+    }
+
+    fn respond_transaction_send(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcTransactionSendResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_transaction_send(rpc_id, response)
+    }
+
+    fn respond_stake_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcStakeGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_stake_get(rpc_id, response)
+    }
+
+    fn respond_best_chain_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcBestChainGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_best_chain_get(rpc_id, response)
+    }
+
+    fn respond_block_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcBlockGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_block_get(rpc_id, response)
+    }
+
+    fn respond_account_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcAccountGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_account_get(rpc_id, response)
+    }
+
+    fn respond_transaction_pool_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcTransactionPoolGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_transaction_pool_get(rpc_id, response)
+    }
+
+    fn respond_node_status_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcNodeStatusGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_node_status_get(rpc_id, response)
+    }
+
+    fn respond_metrics_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcMetricsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_metrics_get(rpc_id, response)
+    }
+
+    fn respond_event(
+        &mut self,
+        rpc_id: RpcId,
+        event: node::rpc::RpcEvent,
+    ) -> Result<(), RespondError> {
+        self.real.respond_event(rpc_id, event)
+    }
+
+    fn event_subscriber_is_closed(&self, rpc_id: RpcId) -> bool {
+        self.real.event_subscriber_is_closed(rpc_id)
+    }
+
+    fn event_subscriber_remove(&mut self, rpc_id: RpcId) {
+        self.real.event_subscriber_remove(rpc_id)
+    }
+
     fn respond_snarker_config_get(
         &mut self,
         rpc_id: RpcId,
@@ -104,6 +211,30 @@ impl RpcService for super::NodeTestingService {
         self.real.respond_snarker_config_get(rpc_id, response)
     }
 
+    fn respond_peers_reputation_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcPeersReputationGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_peers_reputation_get(rpc_id, response)
+    }
+
+    fn respond_peer_ban(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcPeerBanResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_peer_ban(rpc_id, response)
+    }
+
+    fn respond_peer_unban(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcPeerUnbanResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_peer_unban(rpc_id, response)
+    }
+
     fn respond_health_check(
         &mut self,
         rpc_id: RpcId,
@@ -4,7 +4,8 @@ use crate::{P2pState, PeerId};
 
 use super::{
     best_tip::P2pChannelsBestTipAction, rpc::P2pChannelsRpcAction, snark::P2pChannelsSnarkAction,
-    snark_job_commitment::P2pChannelsSnarkJobCommitmentAction, ChannelMsg,
+    snark_job_commitment::P2pChannelsSnarkJobCommitmentAction,
+    transaction::P2pChannelsTransactionAction, ChannelMsg,
 };
 
 pub type P2pChannelsActionWithMetaRef<'a> = redux::ActionWithMeta<&'a P2pChannelsAction>;
@@ -14,6 +15,7 @@ pub enum P2pChannelsAction {
     MessageReceived(P2pChannelsMessageReceivedAction),
 
     BestTip(P2pChannelsBestTipAction),
+    Transaction(P2pChannelsTransactionAction),
     Snark(P2pChannelsSnarkAction),
     SnarkJobCommitment(P2pChannelsSnarkJobCommitmentAction),
     Rpc(P2pChannelsRpcAction),
@@ -24,6 +26,7 @@ impl P2pChannelsAction {
         match self {
             Self::MessageReceived(v) => Some(&v.peer_id),
             Self::BestTip(v) => Some(v.peer_id()),
+            Self::Transaction(v) => v.peer_id(),
             Self::Snark(v) => v.peer_id(),
             Self::SnarkJobCommitment(v) => Some(v.peer_id()),
             Self::Rpc(v) => Some(v.peer_id()),
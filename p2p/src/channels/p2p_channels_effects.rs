@@ -2,6 +2,7 @@ use openmina_core::block::BlockWithHash;
 use redux::ActionMeta;
 
 use crate::disconnection::{P2pDisconnectionInitAction, P2pDisconnectionReason};
+use crate::peer::{P2pPeerOffense, P2pPeerPenalizeAction};
 
 use super::{
     best_tip::{
@@ -21,6 +22,10 @@ use super::{
         P2pChannelsSnarkJobCommitmentRequestReceivedAction,
         SnarkJobCommitmentPropagationChannelMsg,
     },
+    transaction::{
+        P2pChannelsTransactionPromiseReceivedAction, P2pChannelsTransactionReceivedAction,
+        P2pChannelsTransactionRequestReceivedAction, TransactionPropagationChannelMsg,
+    },
     ChannelMsg, P2pChannelsMessageReceivedAction,
 };
 
@@ -30,6 +35,9 @@ impl P2pChannelsMessageReceivedAction {
         Store: crate::P2pStore<S>,
         P2pChannelsBestTipRequestReceivedAction: redux::EnablingCondition<S>,
         P2pChannelsBestTipReceivedAction: redux::EnablingCondition<S>,
+        P2pChannelsTransactionRequestReceivedAction: redux::EnablingCondition<S>,
+        P2pChannelsTransactionPromiseReceivedAction: redux::EnablingCondition<S>,
+        P2pChannelsTransactionReceivedAction: redux::EnablingCondition<S>,
         P2pChannelsSnarkRequestReceivedAction: redux::EnablingCondition<S>,
         P2pChannelsSnarkPromiseReceivedAction: redux::EnablingCondition<S>,
         P2pChannelsSnarkReceivedAction: redux::EnablingCondition<S>,
@@ -39,6 +47,7 @@ impl P2pChannelsMessageReceivedAction {
         P2pChannelsRpcRequestReceivedAction: redux::EnablingCondition<S>,
         P2pChannelsRpcResponseReceivedAction: redux::EnablingCondition<S>,
         P2pDisconnectionInitAction: redux::EnablingCondition<S>,
+        P2pPeerPenalizeAction: redux::EnablingCondition<S>,
     {
         let peer_id = self.peer_id;
         let chan_id = self.message.channel_id();
@@ -52,6 +61,26 @@ impl P2pChannelsMessageReceivedAction {
                     store.dispatch(P2pChannelsBestTipReceivedAction { peer_id, best_tip })
                 }
             },
+            ChannelMsg::TransactionPropagation(msg) => match msg {
+                TransactionPropagationChannelMsg::GetNext { limit } => {
+                    store.dispatch(P2pChannelsTransactionRequestReceivedAction {
+                        peer_id,
+                        limit,
+                    })
+                }
+                TransactionPropagationChannelMsg::WillSend { count } => {
+                    store.dispatch(P2pChannelsTransactionPromiseReceivedAction {
+                        peer_id,
+                        promised_count: count,
+                    })
+                }
+                TransactionPropagationChannelMsg::Transaction(transaction) => {
+                    store.dispatch(P2pChannelsTransactionReceivedAction {
+                        peer_id,
+                        transaction,
+                    })
+                }
+            },
             ChannelMsg::SnarkPropagation(msg) => match msg {
                 SnarkPropagationChannelMsg::GetNext { limit } => {
                     store.dispatch(P2pChannelsSnarkRequestReceivedAction { peer_id, limit })
@@ -105,6 +134,10 @@ impl P2pChannelsMessageReceivedAction {
         };
 
         if !was_expected {
+            store.dispatch(P2pPeerPenalizeAction {
+                peer_id,
+                offense: P2pPeerOffense::UnexpectedMessage,
+            });
             let reason = P2pDisconnectionReason::P2pChannelMsgUnexpected(chan_id);
             store.dispatch(P2pDisconnectionInitAction { peer_id, reason });
         }
@@ -1,11 +1,64 @@
-use openmina_core::snark::Snark;
+use mina_p2p_messages::v2::{
+    MinaBaseUserCommandStableV2, NonZeroCurvePoint, StateHash, TransactionHash,
+};
+use openmina_core::snark::{Snark, SnarkJobId};
+use serde::{Deserialize, Serialize};
 
 use crate::PeerId;
 
 use super::{ChannelId, ChannelMsg, MsgId};
 
+/// Identifies the message received through libp2p gossipsub, whose
+/// validation is completed by the state machine.
+#[derive(Serialize, Deserialize, Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub enum P2pGossipValidationKey {
+    Block(StateHash),
+    Snark {
+        job_id: SnarkJobId,
+        snarker: NonZeroCurvePoint,
+    },
+    Transaction(TransactionHash),
+}
+
+/// Outcome of the gossip message validation.
+///
+/// - `Accept` - message is valid and is forwarded to other peers.
+/// - `Reject` - message is invalid, it's not forwarded and the sender
+///   is penalized.
+/// - `Ignore` - message isn't forwarded, but the sender isn't penalized.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum P2pGossipAcceptance {
+    Accept,
+    Reject,
+    Ignore,
+}
+
+impl P2pGossipValidationKey {
+    pub fn snark(snark: &Snark) -> Self {
+        Self::Snark {
+            job_id: snark.job_id(),
+            snarker: snark.snarker.clone(),
+        }
+    }
+}
+
 pub trait P2pChannelsService: redux::Service {
     fn channel_open(&mut self, peer_id: PeerId, id: ChannelId);
     fn channel_send(&mut self, peer_id: PeerId, msg_id: MsgId, msg: ChannelMsg);
     fn libp2p_broadcast_snark(&mut self, snark: Snark, nonce: u32);
+    fn libp2p_broadcast_transaction(
+        &mut self,
+        transaction: Box<MinaBaseUserCommandStableV2>,
+        nonce: u32,
+    );
+    /// Reports the result of the validation of the message received
+    /// through gossipsub, so that gossipsub knows whether to forward it
+    /// and how to score the sender.
+    fn libp2p_gossip_validation_result(
+        &mut self,
+        key: P2pGossipValidationKey,
+        acceptance: P2pGossipAcceptance,
+    );
+    /// Ignores the gossip messages, whose validation took too long.
+    fn libp2p_gossip_validation_check_timeouts(&mut self);
 }
@@ -4,12 +4,14 @@ use serde::{Deserialize, Serialize};
 
 use super::{
     best_tip::P2pChannelsBestTipState, rpc::P2pChannelsRpcState, snark::P2pChannelsSnarkState,
-    snark_job_commitment::P2pChannelsSnarkJobCommitmentState, ChannelId,
+    snark_job_commitment::P2pChannelsSnarkJobCommitmentState,
+    transaction::P2pChannelsTransactionState, ChannelId,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct P2pChannelsState {
     pub best_tip: P2pChannelsBestTipState,
+    pub transaction: P2pChannelsTransactionState,
     pub snark: P2pChannelsSnarkState,
     pub snark_job_commitment: P2pChannelsSnarkJobCommitmentState,
     pub rpc: P2pChannelsRpcState,
@@ -22,6 +24,10 @@ impl P2pChannelsState {
                 false => P2pChannelsBestTipState::Disabled,
                 true => P2pChannelsBestTipState::Enabled,
             },
+            transaction: match enabled_channels.contains(&ChannelId::TransactionPropagation) {
+                false => P2pChannelsTransactionState::Disabled,
+                true => P2pChannelsTransactionState::Enabled,
+            },
             snark_job_commitment: match enabled_channels
                 .contains(&ChannelId::SnarkJobCommitmentPropagation)
             {
@@ -44,6 +50,7 @@ impl P2pChannelsState {
     pub fn is_channel_ready(&self, chan_id: ChannelId) -> bool {
         match chan_id {
             ChannelId::BestTipPropagation => self.best_tip.is_ready(),
+            ChannelId::TransactionPropagation => self.transaction.is_ready(),
             ChannelId::SnarkPropagation => self.snark.is_ready(),
             ChannelId::SnarkJobCommitmentPropagation => self.snark_job_commitment.is_ready(),
             ChannelId::Rpc => self.rpc.is_ready(),
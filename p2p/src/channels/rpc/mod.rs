@@ -18,8 +18,14 @@ use mina_p2p_messages::{
         macros::{BinProtRead, BinProtWrite},
         BinProtRead, BinProtWrite,
     },
-    rpc::GetSomeInitialPeersV1ForV2,
-    v2::NetworkPeerPeerStableV1,
+    rpc::{
+        GetAncestryV2, GetEpochLedgerV2, GetNodeStatusV2, GetSomeInitialPeersV1ForV2,
+        GetTransitionChainProofV1ForV2, WithHashV1,
+    },
+    v2::{
+        ConsensusProofOfStakeDataConsensusStateValueStableV2, MinaBaseSparseLedgerBaseStableV2,
+        NetworkPeerPeerStableV1, NonZeroCurvePoint,
+    },
 };
 use mina_p2p_messages::{
     rpc::{
@@ -30,10 +36,10 @@ use mina_p2p_messages::{
         QueryHeader, QueryID, Response, ResponseHeader, RpcMethod, RpcResult, RpcResultKind,
     },
     v2::{
-        LedgerHash, MinaBaseLedgerHash0StableV1, MinaBasePendingCoinbaseStableV2,
-        MinaBaseStateBodyHashStableV1, MinaLedgerSyncLedgerAnswerStableV2,
-        MinaLedgerSyncLedgerQueryStableV1, MinaStateProtocolStateValueStableV2, StateHash,
-        TransactionSnarkScanStateStableV2,
+        DataHashLibStateHashStableV1, LedgerHash, MinaBaseLedgerHash0StableV1,
+        MinaBasePendingCoinbaseStableV2, MinaBaseStateBodyHashStableV1,
+        MinaLedgerSyncLedgerAnswerStableV2, MinaLedgerSyncLedgerQueryStableV1,
+        MinaStateProtocolStateValueStableV2, StateHash, TransactionSnarkScanStateStableV2,
     },
 };
 use openmina_core::{
@@ -42,6 +48,8 @@ use openmina_core::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::PeerId;
+
 pub type P2pRpcId = u32;
 
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
@@ -67,6 +75,10 @@ pub enum P2pRpcKind {
     Block,
     Snark,
     InitialPeers,
+    TransitionChainProof,
+    NodeStatus,
+    EpochLedger,
+    Ancestry,
 }
 
 impl P2pRpcKind {
@@ -78,6 +90,10 @@ impl P2pRpcKind {
             Self::Block => Some(Duration::from_secs(5)),
             Self::Snark => Some(Duration::from_secs(5)),
             Self::InitialPeers => Some(Duration::from_secs(5)),
+            Self::TransitionChainProof => Some(Duration::from_secs(10)),
+            Self::NodeStatus => Some(Duration::from_secs(5)),
+            Self::EpochLedger => Some(Duration::from_secs(120)),
+            Self::Ancestry => Some(Duration::from_secs(10)),
         }
     }
 
@@ -89,6 +105,10 @@ impl P2pRpcKind {
             Self::Block => true,
             Self::Snark => false,
             Self::InitialPeers => true,
+            Self::TransitionChainProof => true,
+            Self::NodeStatus => true,
+            Self::EpochLedger => true,
+            Self::Ancestry => true,
         }
     }
 }
@@ -101,6 +121,18 @@ pub enum P2pRpcRequest {
     Block(StateHash),
     Snark(SnarkJobId),
     InitialPeers,
+    /// Proof that the block is an ancestor of our best tip, see
+    /// [`P2pRpcResponse::TransitionChainProof`].
+    TransitionChainProof(StateHash),
+    NodeStatus,
+    /// Staking or next epoch ledger with the given hash.
+    EpochLedger(LedgerHash),
+    /// Our best tip with proof, if it would be taken over the block with
+    /// the given hash and consensus state, see [`P2pRpcResponse::BestTipWithProof`].
+    Ancestry(
+        StateHash,
+        Box<ConsensusProofOfStakeDataConsensusStateValueStableV2>,
+    ),
 }
 
 impl P2pRpcRequest {
@@ -114,6 +146,10 @@ impl P2pRpcRequest {
             Self::Block(_) => P2pRpcKind::Block,
             Self::Snark(_) => P2pRpcKind::Snark,
             Self::InitialPeers => P2pRpcKind::InitialPeers,
+            Self::TransitionChainProof(_) => P2pRpcKind::TransitionChainProof,
+            Self::NodeStatus => P2pRpcKind::NodeStatus,
+            Self::EpochLedger(_) => P2pRpcKind::EpochLedger,
+            Self::Ancestry(..) => P2pRpcKind::Ancestry,
         }
     }
 
@@ -159,6 +195,20 @@ impl P2pRpcRequest {
                 "rpc not supported by ocaml node",
             )),
             Self::InitialPeers => Self::write_msg_impl::<GetSomeInitialPeersV1ForV2, _>(w, id, &()),
+            Self::TransitionChainProof(hash) => {
+                Self::write_msg_impl::<GetTransitionChainProofV1ForV2, _>(w, id, &hash.0.clone())
+            }
+            Self::NodeStatus => Self::write_msg_impl::<GetNodeStatusV2, _>(w, id, &()),
+            Self::EpochLedger(hash) => {
+                Self::write_msg_impl::<GetEpochLedgerV2, _>(w, id, &hash.0.clone())
+            }
+            Self::Ancestry(hash, consensus_state) => {
+                let query = WithHashV1 {
+                    data: *consensus_state,
+                    hash: hash.0.clone(),
+                };
+                Self::write_msg_impl::<GetAncestryV2, _>(w, id, &query)
+            }
         }
     }
 }
@@ -192,13 +242,19 @@ impl std::fmt::Display for P2pRpcRequest {
                 write!(f, "ledger: {ledger_hash}")
             }
             Self::StagedLedgerAuxAndPendingCoinbasesAtBlock(block_hash)
-            | Self::Block(block_hash) => {
+            | Self::Block(block_hash)
+            | Self::TransitionChainProof(block_hash)
+            | Self::Ancestry(block_hash, _) => {
                 write!(f, ", {block_hash}")
             }
             Self::Snark(job_id) => {
                 write!(f, ", {job_id}")
             }
             Self::InitialPeers => Ok(()),
+            Self::NodeStatus => Ok(()),
+            Self::EpochLedger(ledger_hash) => {
+                write!(f, ", {ledger_hash}")
+            }
         }
     }
 }
@@ -218,6 +274,34 @@ pub struct StagedLedgerAuxAndPendingCoinbases {
     pub needed_blocks: Vec<MinaStateProtocolStateValueStableV2>,
 }
 
+/// Status of the node, as reported by `get_node_status` rpc. Our ip
+/// address, libp2p peer id and uptime are filled in by the libp2p
+/// service.
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNodeStatus {
+    pub sync_status: P2pNodeSyncStatus,
+    pub peers: Vec<PeerId>,
+    pub block_producers: Vec<NonZeroCurvePoint>,
+    pub best_tip_hash: StateHash,
+    /// Banned peers with the time (in milliseconds) the ban expires at.
+    /// TODO(binier): have to use i64, because binprot doesn't support u64.
+    pub banned_peers: Vec<(PeerId, i64)>,
+    /// Hashes of the blocks in our best chain, with their timestamps.
+    pub k_block_hashes_and_timestamps: Vec<(StateHash, String)>,
+    pub git_commit: String,
+    pub block_height: Option<u32>,
+}
+
+#[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum P2pNodeSyncStatus {
+    Connecting,
+    Listening,
+    Offline,
+    Bootstrap,
+    Synced,
+    Catchup,
+}
+
 #[derive(BinProtWrite, BinProtRead, Serialize, Deserialize, Debug, Clone)]
 pub enum P2pRpcResponse {
     BestTipWithProof(BestTipWithProof),
@@ -226,6 +310,12 @@ pub enum P2pRpcResponse {
     Block(ArcBlock),
     Snark(Snark),
     InitialPeers(Vec<NetworkPeerPeerStableV1>),
+    /// Hash of the oldest block in the chain and body hashes of the
+    /// following blocks, up to the requested one. Requested block's
+    /// hash can be reconstructed from them.
+    TransitionChainProof(StateHash, Vec<MinaBaseStateBodyHashStableV1>),
+    NodeStatus(Box<P2pNodeStatus>),
+    EpochLedger(Arc<MinaBaseSparseLedgerBaseStableV2>),
 }
 
 impl P2pRpcResponse {
@@ -239,6 +329,9 @@ impl P2pRpcResponse {
             Self::Block(_) => P2pRpcKind::Block,
             Self::Snark(_) => P2pRpcKind::Snark,
             Self::InitialPeers(_) => P2pRpcKind::InitialPeers,
+            Self::TransitionChainProof(..) => P2pRpcKind::TransitionChainProof,
+            Self::NodeStatus(_) => P2pRpcKind::NodeStatus,
+            Self::EpochLedger(_) => P2pRpcKind::EpochLedger,
         }
     }
 
@@ -295,6 +388,24 @@ impl P2pRpcResponse {
             Self::InitialPeers(peers) => {
                 Self::write_msg_impl::<GetSomeInitialPeersV1ForV2, _>(w, id, &peers)
             }
+            Self::TransitionChainProof(first_hash, body_hashes) => {
+                let body_hashes = body_hashes.into_iter().map(|hash| hash.0).collect();
+                Self::write_msg_impl::<GetTransitionChainProofV1ForV2, _>(
+                    w,
+                    id,
+                    &Some((first_hash.0.clone(), body_hashes)),
+                )
+            }
+            // Peer ids and such need to be converted into the libp2p
+            // format, which is only done by the libp2p service.
+            Self::NodeStatus(_) => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "node status can only be sent by libp2p service",
+            )),
+            Self::EpochLedger(res) => {
+                let res = Arc::try_unwrap(res).unwrap_or_else(|res| (*res).clone());
+                Self::write_msg_impl::<GetEpochLedgerV2, _>(w, id, &RpcResult(Ok(res)))
+            }
         }
     }
 
@@ -357,6 +468,39 @@ impl P2pRpcResponse {
                     BinProtRead::binprot_read(r)?;
                 Some(P2pRpcResponse::InitialPeers(resp))
             }
+            P2pRpcKind::TransitionChainProof => {
+                let resp: <GetTransitionChainProofV1ForV2 as RpcMethod>::Response =
+                    BinProtRead::binprot_read(r)?;
+                resp.map(|(first_hash, body_hashes)| {
+                    P2pRpcResponse::TransitionChainProof(
+                        DataHashLibStateHashStableV1(first_hash).into(),
+                        body_hashes
+                            .into_iter()
+                            .map(MinaBaseStateBodyHashStableV1)
+                            .collect(),
+                    )
+                })
+            }
+            P2pRpcKind::NodeStatus => None,
+            P2pRpcKind::EpochLedger => {
+                let resp: <GetEpochLedgerV2 as RpcMethod>::Response = BinProtRead::binprot_read(r)?;
+                resp.0
+                    .ok()
+                    .map(|ledger| P2pRpcResponse::EpochLedger(ledger.into()))
+            }
+            P2pRpcKind::Ancestry => {
+                let resp: <GetAncestryV2 as RpcMethod>::Response = BinProtRead::binprot_read(r)?;
+                resp.map(|resp| {
+                    let body_hashes = resp.proof.0.into_iter();
+                    Self::BestTipWithProof(BestTipWithProof {
+                        best_tip: resp.data.into(),
+                        proof: (
+                            body_hashes.map(MinaBaseStateBodyHashStableV1).collect(),
+                            resp.proof.1.into(),
+                        ),
+                    })
+                })
+            }
         })
     }
 }
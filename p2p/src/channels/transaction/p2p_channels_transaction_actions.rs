@@ -0,0 +1,310 @@
+use mina_p2p_messages::v2::MinaBaseUserCommandStableV2;
+use serde::{Deserialize, Serialize};
+
+use crate::{P2pState, PeerId};
+
+use super::{P2pChannelsTransactionState, TransactionPropagationState};
+
+pub type P2pChannelsTransactionActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a P2pChannelsTransactionAction>;
+
+#[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
+pub enum P2pChannelsTransactionAction {
+    Init(P2pChannelsTransactionInitAction),
+    Pending(P2pChannelsTransactionPendingAction),
+    Ready(P2pChannelsTransactionReadyAction),
+
+    RequestSend(P2pChannelsTransactionRequestSendAction),
+    PromiseReceived(P2pChannelsTransactionPromiseReceivedAction),
+    Received(P2pChannelsTransactionReceivedAction),
+
+    RequestReceived(P2pChannelsTransactionRequestReceivedAction),
+    ResponseSend(P2pChannelsTransactionResponseSendAction),
+
+    Libp2pReceived(P2pChannelsTransactionLibp2pReceivedAction),
+    Libp2pBroadcast(P2pChannelsTransactionLibp2pBroadcastAction),
+}
+
+impl P2pChannelsTransactionAction {
+    pub fn peer_id(&self) -> Option<&PeerId> {
+        Some(match self {
+            Self::Init(v) => &v.peer_id,
+            Self::Pending(v) => &v.peer_id,
+            Self::Ready(v) => &v.peer_id,
+            Self::RequestSend(v) => &v.peer_id,
+            Self::PromiseReceived(v) => &v.peer_id,
+            Self::Received(v) => &v.peer_id,
+            Self::RequestReceived(v) => &v.peer_id,
+            Self::ResponseSend(v) => &v.peer_id,
+            Self::Libp2pReceived(_) | Self::Libp2pBroadcast(_) => return None,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsTransactionInitAction {
+    pub peer_id: PeerId,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionInitAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state.get_ready_peer(&self.peer_id).map_or(false, |p| {
+            matches!(
+                &p.channels.transaction,
+                P2pChannelsTransactionState::Enabled
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsTransactionPendingAction {
+    pub peer_id: PeerId,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionPendingAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state.get_ready_peer(&self.peer_id).map_or(false, |p| {
+            matches!(
+                &p.channels.transaction,
+                P2pChannelsTransactionState::Init { .. }
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsTransactionReadyAction {
+    pub peer_id: PeerId,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionReadyAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state.get_ready_peer(&self.peer_id).map_or(false, |p| {
+            matches!(
+                &p.channels.transaction,
+                P2pChannelsTransactionState::Pending { .. }
+            )
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsTransactionRequestSendAction {
+    pub peer_id: PeerId,
+    pub limit: u8,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionRequestSendAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state
+            .get_ready_peer(&self.peer_id)
+            .map_or(false, |p| match &p.channels.transaction {
+                P2pChannelsTransactionState::Ready { local, .. } => match local {
+                    TransactionPropagationState::WaitingForRequest { .. } => true,
+                    TransactionPropagationState::Responded { .. } => true,
+                    _ => false,
+                },
+                _ => false,
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsTransactionPromiseReceivedAction {
+    pub peer_id: PeerId,
+    pub promised_count: u8,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionPromiseReceivedAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state
+            .get_ready_peer(&self.peer_id)
+            .map_or(false, |p| match &p.channels.transaction {
+                P2pChannelsTransactionState::Ready { local, .. } => match local {
+                    TransactionPropagationState::Requested {
+                        requested_limit, ..
+                    } => self.promised_count > 0 && self.promised_count <= *requested_limit,
+                    _ => false,
+                },
+                _ => false,
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsTransactionReceivedAction {
+    pub peer_id: PeerId,
+    pub transaction: Box<MinaBaseUserCommandStableV2>,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionReceivedAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state
+            .get_ready_peer(&self.peer_id)
+            .map_or(false, |p| match &p.channels.transaction {
+                P2pChannelsTransactionState::Ready { local, .. } => match local {
+                    TransactionPropagationState::Responding { .. } => true,
+                    _ => false,
+                },
+                _ => false,
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsTransactionRequestReceivedAction {
+    pub peer_id: PeerId,
+    pub limit: u8,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionRequestReceivedAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        self.limit > 0
+            && state.get_ready_peer(&self.peer_id).map_or(false, |p| {
+                match &p.channels.transaction {
+                    P2pChannelsTransactionState::Ready { remote, .. } => match remote {
+                        TransactionPropagationState::WaitingForRequest { .. } => true,
+                        TransactionPropagationState::Responded { .. } => true,
+                        _ => false,
+                    },
+                    _ => false,
+                }
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsTransactionResponseSendAction {
+    pub peer_id: PeerId,
+    pub transactions: Vec<Box<MinaBaseUserCommandStableV2>>,
+    pub first_index: u64,
+    pub last_index: u64,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionResponseSendAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        !self.transactions.is_empty()
+            && self.first_index <= self.last_index
+            && state.get_ready_peer(&self.peer_id).map_or(false, |p| {
+                match &p.channels.transaction {
+                    P2pChannelsTransactionState::Ready {
+                        remote,
+                        next_send_index,
+                        ..
+                    } => {
+                        if self.first_index < *next_send_index {
+                            return false;
+                        }
+                        match remote {
+                            TransactionPropagationState::Requested {
+                                requested_limit, ..
+                            } => self.transactions.len() <= *requested_limit as usize,
+                            _ => false,
+                        }
+                    }
+                    _ => false,
+                }
+            })
+    }
+}
+
+/// Transaction pool diff received through libp2p gossipsub
+/// (`mina/tx/1.0.0` topic).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsTransactionLibp2pReceivedAction {
+    pub peer_id: PeerId,
+    pub transactions: Vec<Box<MinaBaseUserCommandStableV2>>,
+    pub nonce: u32,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionLibp2pReceivedAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        // Libp2p peers don't support the transaction propagation
+        // channel, so it never becomes ready for them.
+        !self.transactions.is_empty()
+            && state
+                .peers
+                .get(&self.peer_id)
+                .map_or(false, |p| p.is_libp2p() && p.status.as_ready().is_some())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pChannelsTransactionLibp2pBroadcastAction {
+    pub transaction: Box<MinaBaseUserCommandStableV2>,
+    pub nonce: u32,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pChannelsTransactionLibp2pBroadcastAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state
+            .peers
+            .iter()
+            .any(|(_, p)| p.is_libp2p() && p.status.as_ready().is_some())
+    }
+}
+
+// --- From<LeafAction> for Action impls.
+
+use crate::channels::P2pChannelsAction;
+
+impl From<P2pChannelsTransactionInitAction> for crate::P2pAction {
+    fn from(a: P2pChannelsTransactionInitAction) -> Self {
+        Self::Channels(P2pChannelsAction::Transaction(a.into()))
+    }
+}
+
+impl From<P2pChannelsTransactionPendingAction> for crate::P2pAction {
+    fn from(a: P2pChannelsTransactionPendingAction) -> Self {
+        Self::Channels(P2pChannelsAction::Transaction(a.into()))
+    }
+}
+
+impl From<P2pChannelsTransactionReadyAction> for crate::P2pAction {
+    fn from(a: P2pChannelsTransactionReadyAction) -> Self {
+        Self::Channels(P2pChannelsAction::Transaction(a.into()))
+    }
+}
+
+impl From<P2pChannelsTransactionRequestSendAction> for crate::P2pAction {
+    fn from(a: P2pChannelsTransactionRequestSendAction) -> Self {
+        Self::Channels(P2pChannelsAction::Transaction(a.into()))
+    }
+}
+
+impl From<P2pChannelsTransactionPromiseReceivedAction> for crate::P2pAction {
+    fn from(a: P2pChannelsTransactionPromiseReceivedAction) -> Self {
+        Self::Channels(P2pChannelsAction::Transaction(a.into()))
+    }
+}
+
+impl From<P2pChannelsTransactionReceivedAction> for crate::P2pAction {
+    fn from(a: P2pChannelsTransactionReceivedAction) -> Self {
+        Self::Channels(P2pChannelsAction::Transaction(a.into()))
+    }
+}
+
+impl From<P2pChannelsTransactionRequestReceivedAction> for crate::P2pAction {
+    fn from(a: P2pChannelsTransactionRequestReceivedAction) -> Self {
+        Self::Channels(P2pChannelsAction::Transaction(a.into()))
+    }
+}
+
+impl From<P2pChannelsTransactionResponseSendAction> for crate::P2pAction {
+    fn from(a: P2pChannelsTransactionResponseSendAction) -> Self {
+        Self::Channels(P2pChannelsAction::Transaction(a.into()))
+    }
+}
+
+impl From<P2pChannelsTransactionLibp2pReceivedAction> for crate::P2pAction {
+    fn from(a: P2pChannelsTransactionLibp2pReceivedAction) -> Self {
+        Self::Channels(P2pChannelsAction::Transaction(a.into()))
+    }
+}
+
+impl From<P2pChannelsTransactionLibp2pBroadcastAction> for crate::P2pAction {
+    fn from(a: P2pChannelsTransactionLibp2pBroadcastAction) -> Self {
+        Self::Channels(P2pChannelsAction::Transaction(a.into()))
+    }
+}
@@ -0,0 +1,99 @@
+use redux::ActionMeta;
+
+use crate::channels::{ChannelId, MsgId, P2pChannelsService};
+
+use super::{
+    P2pChannelsTransactionInitAction, P2pChannelsTransactionLibp2pBroadcastAction,
+    P2pChannelsTransactionPendingAction, P2pChannelsTransactionReadyAction,
+    P2pChannelsTransactionReceivedAction, P2pChannelsTransactionRequestSendAction,
+    P2pChannelsTransactionResponseSendAction, TransactionPropagationChannelMsg,
+};
+
+impl P2pChannelsTransactionInitAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pChannelsService,
+        P2pChannelsTransactionPendingAction: redux::EnablingCondition<S>,
+    {
+        let peer_id = self.peer_id;
+        store
+            .service()
+            .channel_open(peer_id, ChannelId::TransactionPropagation);
+        store.dispatch(P2pChannelsTransactionPendingAction { peer_id });
+    }
+}
+
+impl P2pChannelsTransactionReadyAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, _store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pChannelsService,
+        P2pChannelsTransactionRequestSendAction: redux::EnablingCondition<S>,
+    {
+    }
+}
+
+impl P2pChannelsTransactionRequestSendAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pChannelsService,
+    {
+        let peer_id = self.peer_id;
+        let limit = self.limit;
+        let msg = TransactionPropagationChannelMsg::GetNext { limit };
+        store
+            .service()
+            .channel_send(peer_id, MsgId::first(), msg.into());
+    }
+}
+
+impl P2pChannelsTransactionReceivedAction {
+    pub fn effects<Store, S>(&self, _: &ActionMeta, _store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pChannelsService,
+        P2pChannelsTransactionRequestSendAction: redux::EnablingCondition<S>,
+    {
+    }
+}
+
+impl P2pChannelsTransactionResponseSendAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pChannelsService,
+    {
+        if self.transactions.is_empty() {
+            return;
+        }
+
+        let peer_id = self.peer_id;
+        let msg = TransactionPropagationChannelMsg::WillSend {
+            count: self.transactions.len() as u8,
+        };
+        store
+            .service()
+            .channel_send(peer_id, MsgId::first(), msg.into());
+
+        for transaction in self.transactions {
+            let msg = TransactionPropagationChannelMsg::Transaction(transaction);
+            store
+                .service()
+                .channel_send(peer_id, MsgId::first(), msg.into());
+        }
+    }
+}
+
+impl P2pChannelsTransactionLibp2pBroadcastAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pChannelsService,
+    {
+        store
+            .service()
+            .libp2p_broadcast_transaction(self.transaction, self.nonce);
+    }
+}
@@ -38,6 +38,10 @@ impl P2pState {
             return Err(RejectionReason::PeerIdAndPublicKeyMismatch);
         }
 
+        if !offer.verify_signature() {
+            return Err(RejectionReason::InvalidSignature);
+        }
+
         let my_peer_id = self.config.identity_pub_key.peer_id();
 
         // TODO(binier): maybe cache own peer_id somewhere.
@@ -49,6 +53,10 @@ impl P2pState {
             return Err(RejectionReason::ConnectingToSelf);
         }
 
+        if self.is_peer_banned(&peer_id) {
+            return Err(RejectionReason::Banned);
+        }
+
         if self.is_peer_connected_or_connecting(&peer_id) {
             return Err(RejectionReason::AlreadyConnected);
         }
@@ -1,7 +1,8 @@
 use redux::ActionMeta;
 
+use crate::connection::P2pConnectionService;
+use crate::disconnection::{P2pDisconnectionInitAction, P2pDisconnectionReason};
 use crate::peer::P2pPeerReadyAction;
-use crate::{connection::P2pConnectionService, webrtc};
 
 use super::{
     P2pConnectionIncomingAnswerReadyAction, P2pConnectionIncomingAnswerSdpCreateErrorAction,
@@ -48,11 +49,7 @@ impl P2pConnectionIncomingAnswerSdpCreateSuccessAction {
         Store::Service: P2pConnectionService,
         P2pConnectionIncomingAnswerReadyAction: redux::EnablingCondition<S>,
     {
-        let answer = webrtc::Answer {
-            sdp: self.sdp,
-            identity_pub_key: store.state().config.identity_pub_key.clone(),
-            target_peer_id: self.peer_id,
-        };
+        let answer = store.service().answer_create(self.sdp, self.peer_id);
         store.dispatch(P2pConnectionIncomingAnswerReadyAction {
             peer_id: self.peer_id,
             answer,
@@ -130,8 +127,17 @@ impl P2pConnectionIncomingSuccessAction {
         Store: crate::P2pStore<S>,
         Store::Service: P2pConnectionService,
         P2pPeerReadyAction: redux::EnablingCondition<S>,
+        P2pDisconnectionInitAction: redux::EnablingCondition<S>,
     {
         let peer_id = self.peer_id;
+        // Peer might have been banned while we were connecting.
+        if store.state().is_peer_banned(&peer_id) {
+            store.dispatch(P2pDisconnectionInitAction {
+                peer_id,
+                reason: P2pDisconnectionReason::Banned,
+            });
+            return;
+        }
         store.dispatch(P2pPeerReadyAction { peer_id });
     }
 }
@@ -20,20 +20,24 @@ use crate::webrtc;
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub enum RejectionReason {
     PeerIdAndPublicKeyMismatch,
+    InvalidSignature,
     TargetPeerIdNotMe,
     PeerCapacityFull,
     AlreadyConnected,
     ConnectingToSelf,
+    Banned,
 }
 
 impl RejectionReason {
     pub fn is_bad(&self) -> bool {
         match self {
             Self::PeerIdAndPublicKeyMismatch => true,
+            Self::InvalidSignature => true,
             Self::TargetPeerIdNotMe => true,
             Self::PeerCapacityFull => false,
             Self::AlreadyConnected => true,
             Self::ConnectingToSelf => false,
+            Self::Banned => true,
         }
     }
 }
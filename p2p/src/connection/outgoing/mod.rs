@@ -15,7 +15,8 @@ use std::{fmt, str::FromStr};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::{webrtc, PeerId};
+use crate::connection::RejectionReason;
+use crate::{webrtc, P2pState, PeerId};
 
 // TODO(binier): maybe move to `crate::webrtc` module
 #[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone)]
@@ -161,3 +162,27 @@ impl<'de> Deserialize<'de> for P2pConnectionOutgoingInitOpts {
         Ok(s.parse().map_err(|err| serde::de::Error::custom(err))?)
     }
 }
+
+impl P2pState {
+    /// Checks that the answer comes from the peer we are connecting to
+    /// and that it's meant for us.
+    pub fn outgoing_answer_check(
+        &self,
+        peer_id: PeerId,
+        answer: &webrtc::Answer,
+    ) -> Result<(), RejectionReason> {
+        if peer_id != answer.identity_pub_key.peer_id() {
+            return Err(RejectionReason::PeerIdAndPublicKeyMismatch);
+        }
+
+        if !answer.verify_signature() {
+            return Err(RejectionReason::InvalidSignature);
+        }
+
+        if answer.target_peer_id != self.config.identity_pub_key.peer_id() {
+            return Err(RejectionReason::TargetPeerIdNotMe);
+        }
+
+        Ok(())
+    }
+}
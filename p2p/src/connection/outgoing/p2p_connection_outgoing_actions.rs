@@ -73,7 +73,9 @@ pub struct P2pConnectionOutgoingInitAction {
 
 impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingInitAction {
     fn is_enabled(&self, state: &P2pState) -> bool {
-        !state.already_has_min_peers() && !state.peers.contains_key(self.opts.peer_id())
+        !state.already_has_min_peers()
+            && !state.peers.contains_key(self.opts.peer_id())
+            && !state.is_peer_banned(self.opts.peer_id())
     }
 }
 
@@ -92,6 +94,7 @@ impl redux::EnablingCondition<P2pState> for P2pConnectionOutgoingReconnectAction
         state
             .peers
             .iter()
+            .filter(|(id, _)| !state.is_peer_banned(id))
             .filter_map(|(id, p)| match &p.status {
                 P2pPeerStatus::Connecting(s) => match s {
                     P2pConnectionState::Outgoing(P2pConnectionOutgoingState::Error {
@@ -1,6 +1,7 @@
 use redux::ActionMeta;
 
 use crate::connection::{P2pConnectionErrorResponse, P2pConnectionState};
+use crate::disconnection::{P2pDisconnectionInitAction, P2pDisconnectionReason};
 use crate::peer::P2pPeerReadyAction;
 use crate::P2pPeerStatus;
 use crate::{connection::P2pConnectionService, webrtc};
@@ -89,11 +90,7 @@ impl P2pConnectionOutgoingOfferSdpCreateSuccessAction {
         Store::Service: P2pConnectionService,
         P2pConnectionOutgoingOfferReadyAction: redux::EnablingCondition<S>,
     {
-        let offer = webrtc::Offer {
-            sdp: self.sdp,
-            identity_pub_key: store.state().config.identity_pub_key.clone(),
-            target_peer_id: self.peer_id,
-        };
+        let offer = store.service().offer_create(self.sdp, self.peer_id);
         store.dispatch(P2pConnectionOutgoingOfferReadyAction {
             peer_id: self.peer_id,
             offer,
@@ -234,8 +231,17 @@ impl P2pConnectionOutgoingSuccessAction {
         Store: crate::P2pStore<S>,
         Store::Service: P2pConnectionService,
         P2pPeerReadyAction: redux::EnablingCondition<S>,
+        P2pDisconnectionInitAction: redux::EnablingCondition<S>,
     {
         let peer_id = self.peer_id;
+        // Peer might have been banned while we were connecting.
+        if store.state().is_peer_banned(&peer_id) {
+            store.dispatch(P2pDisconnectionInitAction {
+                peer_id,
+                reason: P2pDisconnectionReason::Banned,
+            });
+            return;
+        }
         store.dispatch(P2pPeerReadyAction { peer_id });
     }
 }
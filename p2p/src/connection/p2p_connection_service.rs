@@ -16,6 +16,14 @@ pub trait P2pConnectionService: redux::Service {
     /// which will be received in the state machine as an event.
     fn incoming_init(&mut self, peer_id: PeerId, offer: webrtc::Offer);
 
+    /// Creates an offer for the `target_peer_id`, signed with our
+    /// identity secret key.
+    fn offer_create(&mut self, sdp: String, target_peer_id: PeerId) -> webrtc::Offer;
+
+    /// Creates an answer for the `target_peer_id`, signed with our
+    /// identity secret key.
+    fn answer_create(&mut self, sdp: String, target_peer_id: PeerId) -> webrtc::Answer;
+
     fn set_answer(&mut self, peer_id: PeerId, answer: webrtc::Answer);
 
     fn http_signaling_request(&mut self, url: String, offer: webrtc::Offer);
@@ -10,6 +10,8 @@ pub use p2p_disconnection_effects::*;
 mod p2p_disconnection_service;
 pub use p2p_disconnection_service::*;
 
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
 use crate::channels::ChannelId;
@@ -24,4 +26,22 @@ pub enum P2pDisconnectionReason {
     TransitionFrontierRpcTimeout,
 
     SnarkPoolVerifyError,
+
+    Banned,
+}
+
+impl fmt::Display for P2pDisconnectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::P2pChannelMsgUnexpected(chan_id) => {
+                write!(f, "unexpected message on channel `{chan_id}`")
+            }
+            Self::P2pChannelSendFailed(err) => write!(f, "channel send failed: {err}"),
+            Self::P2pChannelReceiveFailed(err) => write!(f, "channel receive failed: {err}"),
+            Self::P2pChannelClosed(chan_id) => write!(f, "channel `{chan_id}` closed"),
+            Self::TransitionFrontierRpcTimeout => write!(f, "transition frontier rpc timeout"),
+            Self::SnarkPoolVerifyError => write!(f, "snark pool verify error"),
+            Self::Banned => write!(f, "banned"),
+        }
+    }
 }
@@ -2,4 +2,9 @@ use crate::PeerId;
 
 pub trait P2pDisconnectionService: redux::Service {
     fn disconnect(&mut self, peer_id: PeerId);
+
+    /// Refuse connections from/to the peer until it's unbanned.
+    fn peer_ban(&mut self, peer_id: PeerId);
+
+    fn peer_unban(&mut self, peer_id: PeerId);
 }
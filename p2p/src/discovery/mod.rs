@@ -1,2 +1,8 @@
 mod p2p_discovery_actions;
 pub use self::p2p_discovery_actions::*;
+
+mod p2p_discovery_state;
+pub use self::p2p_discovery_state::*;
+
+mod p2p_discovery_service;
+pub use self::p2p_discovery_service::*;
@@ -1,7 +1,7 @@
 use mina_p2p_messages::v2;
 use serde::{Deserialize, Serialize};
 
-use crate::{P2pState, PeerId};
+use crate::{connection::outgoing::P2pConnectionOutgoingInitOpts, P2pState, PeerId};
 
 // use super::{incoming::P2pConnectionIncomingAction, outgoing::P2pConnectionOutgoingAction};
 
@@ -12,6 +12,10 @@ pub enum P2pDiscoveryAction {
     Init(P2pDiscoveryInitAction),
     Success(P2pDiscoverySuccessAction),
     Timeout(P2pDiscoveryTimeoutAction),
+    KademliaInit(P2pDiscoveryKademliaInitAction),
+    KademliaAddRoute(P2pDiscoveryKademliaAddRouteAction),
+    KademliaSuccess(P2pDiscoveryKademliaSuccessAction),
+    KademliaFailure(P2pDiscoveryKademliaFailureAction),
 }
 
 impl redux::EnablingCondition<P2pState> for P2pDiscoveryAction {
@@ -20,6 +24,10 @@ impl redux::EnablingCondition<P2pState> for P2pDiscoveryAction {
             Self::Init(action) => action.is_enabled(state),
             Self::Success(action) => action.is_enabled(state),
             Self::Timeout(action) => action.is_enabled(state),
+            Self::KademliaInit(action) => action.is_enabled(state),
+            Self::KademliaAddRoute(action) => action.is_enabled(state),
+            Self::KademliaSuccess(action) => action.is_enabled(state),
+            Self::KademliaFailure(action) => action.is_enabled(state),
         }
     }
 }
@@ -75,3 +83,87 @@ impl From<P2pDiscoveryTimeoutAction> for crate::P2pAction {
         Self::Discovery(a.into())
     }
 }
+
+/// Start kademlia lookup, to discover new peers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pDiscoveryKademliaInitAction {
+    pub now: redux::Timestamp,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pDiscoveryKademliaInitAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        // Kademlia routing table gets populated with the libp2p peers
+        // we are connected to, without them lookup can't be done.
+        let has_libp2p_peers = state
+            .peers
+            .values()
+            .any(|p| p.is_libp2p() && p.status.as_ready().is_some());
+        let out_of_peers =
+            !state.already_has_min_peers() && state.initial_unused_peers().is_empty();
+        has_libp2p_peers
+            && state.kademlia.is_lookup_needed(
+                self.now,
+                state.config.kademlia.refresh_interval,
+                out_of_peers,
+            )
+    }
+}
+
+impl From<P2pDiscoveryKademliaInitAction> for crate::P2pAction {
+    fn from(a: P2pDiscoveryKademliaInitAction) -> Self {
+        Self::Discovery(a.into())
+    }
+}
+
+/// Peer with its addresses, received from kademlia or identify.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pDiscoveryKademliaAddRouteAction {
+    pub peer_id: PeerId,
+    pub addresses: Vec<P2pConnectionOutgoingInitOpts>,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pDiscoveryKademliaAddRouteAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        self.peer_id != state.config.identity_pub_key.peer_id() && !self.addresses.is_empty()
+    }
+}
+
+impl From<P2pDiscoveryKademliaAddRouteAction> for crate::P2pAction {
+    fn from(a: P2pDiscoveryKademliaAddRouteAction) -> Self {
+        Self::Discovery(a.into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pDiscoveryKademliaSuccessAction {
+    pub peers: Vec<PeerId>,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pDiscoveryKademliaSuccessAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state.kademlia.ongoing_lookup.is_some()
+    }
+}
+
+impl From<P2pDiscoveryKademliaSuccessAction> for crate::P2pAction {
+    fn from(a: P2pDiscoveryKademliaSuccessAction) -> Self {
+        Self::Discovery(a.into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pDiscoveryKademliaFailureAction {
+    pub description: String,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pDiscoveryKademliaFailureAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state.kademlia.ongoing_lookup.is_some()
+    }
+}
+
+impl From<P2pDiscoveryKademliaFailureAction> for crate::P2pAction {
+    fn from(a: P2pDiscoveryKademliaFailureAction) -> Self {
+        Self::Discovery(a.into())
+    }
+}
@@ -0,0 +1,5 @@
+pub trait P2pDiscoveryService: redux::Service {
+    /// Starts kademlia lookup for a random key, which refreshes the
+    /// routing table with the peers closest to it.
+    fn kademlia_lookup_random(&mut self);
+}
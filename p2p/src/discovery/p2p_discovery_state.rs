@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct P2pKademliaState {
+    /// Time when the ongoing lookup was started.
+    pub ongoing_lookup: Option<Timestamp>,
+    /// Time when the last lookup was finished.
+    pub last_lookup: Option<Timestamp>,
+    /// Number of peers found by the last successful lookup.
+    pub last_lookup_found: usize,
+    /// Number of routes (peers with dialable addresses) received from
+    /// the kademlia or identify protocols.
+    pub routes_count: usize,
+}
+
+impl P2pKademliaState {
+    /// Minimal interval between lookups, when we are out of peers to
+    /// connect to.
+    const OUT_OF_PEERS_INTERVAL: Duration = Duration::from_secs(10);
+    /// Lookup, whose result didn't arrive within this time, is
+    /// considered failed and a new one can be started.
+    const LOOKUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// Whether a new lookup should be started. Lookups are repeated
+    /// each `refresh_interval`, or more often if we are out of peers
+    /// to connect to.
+    pub fn is_lookup_needed(
+        &self,
+        now: Timestamp,
+        refresh_interval: Duration,
+        out_of_peers: bool,
+    ) -> bool {
+        if let Some(started) = self.ongoing_lookup {
+            return now
+                .checked_sub(started)
+                .map_or(false, |passed| passed >= Self::LOOKUP_TIMEOUT);
+        }
+        let Some(last_lookup) = self.last_lookup else {
+            return true;
+        };
+        let interval = match out_of_peers {
+            true => refresh_interval.min(Self::OUT_OF_PEERS_INTERVAL),
+            false => refresh_interval,
+        };
+        now.checked_sub(last_lookup)
+            .map_or(false, |passed| passed >= interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    #[test]
+    fn lookup_times_out() {
+        let refresh_interval = Duration::from_secs(600);
+        let at = |secs: u64| Timestamp::new(secs * SECOND);
+        let mut state = P2pKademliaState::default();
+        assert!(state.is_lookup_needed(at(0), refresh_interval, false));
+
+        state.ongoing_lookup = Some(at(100));
+        assert!(!state.is_lookup_needed(at(159), refresh_interval, true));
+        assert!(state.is_lookup_needed(at(160), refresh_interval, false));
+
+        state.ongoing_lookup = None;
+        state.last_lookup = Some(at(160));
+        assert!(!state.is_lookup_needed(at(169), refresh_interval, true));
+        assert!(state.is_lookup_needed(at(170), refresh_interval, true));
+        assert!(!state.is_lookup_needed(at(170), refresh_interval, false));
+        assert!(state.is_lookup_needed(at(760), refresh_interval, false));
+    }
+}
@@ -1,5 +1,6 @@
 use std::{fmt, str::FromStr};
 
+use mina_p2p_messages::binprot::{self, BinProtRead, BinProtWrite};
 use serde::{Deserialize, Serialize};
 
 use super::PublicKey;
@@ -79,6 +80,23 @@ impl From<PeerId> for [u8; 32] {
     }
 }
 
+impl BinProtWrite for PeerId {
+    fn binprot_write<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        self.to_string().binprot_write(w)
+    }
+}
+
+impl BinProtRead for PeerId {
+    fn binprot_read<R: std::io::Read + ?Sized>(r: &mut R) -> Result<Self, binprot::Error>
+    where
+        Self: Sized,
+    {
+        let s = String::binprot_read(r)?;
+        s.parse()
+            .map_err(|err| binprot::Error::CustomError(Box::new(err)))
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 impl From<libp2p::PeerId> for PeerId {
     fn from(value: libp2p::PeerId) -> Self {
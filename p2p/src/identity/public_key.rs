@@ -1,10 +1,12 @@
 use std::{fmt, str::FromStr};
 
-use ed25519_dalek::VerifyingKey as Ed25519PublicKey;
+use ed25519_dalek::{Verifier, VerifyingKey as Ed25519PublicKey};
 use serde::{Deserialize, Serialize};
 
 use crate::PeerId;
 
+use super::Signature;
+
 #[derive(Eq, PartialEq, Clone)]
 pub struct PublicKey(pub(super) Ed25519PublicKey);
 
@@ -22,6 +24,10 @@ impl PublicKey {
     pub fn peer_id(&self) -> PeerId {
         PeerId::from_bytes(self.to_bytes())
     }
+
+    pub fn verify(&self, data: &[u8], signature: &Signature) -> bool {
+        self.0.verify(data, &signature.0).is_ok()
+    }
 }
 
 impl fmt::Display for PublicKey {
@@ -1,10 +1,10 @@
 use std::{fmt, str::FromStr};
 
-use ed25519_dalek::SigningKey as Ed25519SecretKey;
+use ed25519_dalek::{Signer, SigningKey as Ed25519SecretKey};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-use crate::identity::PublicKey;
+use crate::identity::{PublicKey, Signature};
 
 #[derive(Clone)]
 pub struct SecretKey(Ed25519SecretKey);
@@ -33,6 +33,10 @@ impl SecretKey {
     pub fn public_key(&self) -> PublicKey {
         PublicKey(self.0.verifying_key())
     }
+
+    pub fn sign(&self, data: &[u8]) -> Signature {
+        Signature(self.0.sign(data))
+    }
 }
 
 impl fmt::Display for SecretKey {
@@ -79,4 +83,13 @@ mod tests {
         let unparsed = sk.to_string();
         assert_eq!(s, &unparsed);
     }
+
+    #[test]
+    fn sign_verify() {
+        let sk = SecretKey::rand();
+        let signature = sk.sign(b"data");
+        assert!(sk.public_key().verify(b"data", &signature));
+        assert!(!sk.public_key().verify(b"other data", &signature));
+        assert!(!SecretKey::rand().public_key().verify(b"data", &signature));
+    }
 }
@@ -0,0 +1,79 @@
+use std::{fmt, str::FromStr};
+
+use ed25519_dalek::Signature as Ed25519Signature;
+use serde::{Deserialize, Serialize};
+
+#[derive(Eq, PartialEq, Clone)]
+pub struct Signature(pub(super) Ed25519Signature);
+
+impl Signature {
+    pub fn from_bytes(bytes: [u8; 64]) -> Self {
+        Self(Ed25519Signature::from_bytes(&bytes))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 64] {
+        self.0.to_bytes()
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", bs58::encode(&self.to_bytes()).into_string())
+    }
+}
+
+impl fmt::Debug for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Signature({})", self)
+    }
+}
+
+#[derive(thiserror::Error, Serialize, Deserialize, Debug, Clone)]
+pub enum SignatureFromStrError {
+    #[error("Base58 decode error: {0}")]
+    Bs58(String),
+}
+
+impl FromStr for Signature {
+    type Err = SignatureFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0u8; 64];
+        let size = bs58::decode(s)
+            .into(&mut bytes)
+            .map_err(|err| SignatureFromStrError::Bs58(err.to_string()))?;
+        if size != 64 {
+            return Err(SignatureFromStrError::Bs58(
+                bs58::decode::Error::BufferTooSmall.to_string(),
+            ));
+        }
+        Ok(Self::from_bytes(bytes))
+    }
+}
+
+impl Serialize for Signature {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Signature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let b58: String = Deserialize::deserialize(deserializer)?;
+            Ok(b58.parse().map_err(serde::de::Error::custom)?)
+        } else {
+            Ok(Self(Deserialize::deserialize(deserializer)?))
+        }
+    }
+}
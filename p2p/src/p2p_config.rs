@@ -1,9 +1,11 @@
 use std::collections::BTreeSet;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
     channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts, identity::PublicKey,
+    peer::P2pPeerOffense,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -14,4 +16,192 @@ pub struct P2pConfig {
     pub enabled_channels: BTreeSet<ChannelId>,
 
     pub max_peers: usize,
+
+    pub reputation: P2pReputationConfig,
+
+    pub kademlia: P2pKademliaConfig,
+
+    pub gossipsub: P2pGossipsubConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pKademliaConfig {
+    /// Interval between kademlia lookups, which refresh the routing
+    /// table and discover new peers.
+    pub refresh_interval: Duration,
+}
+
+impl Default for P2pKademliaConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Configuration of the gossipsub protocol used with libp2p peers.
+///
+/// Missing fields are deserialized with their default values.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct P2pGossipsubConfig {
+    /// Target number of peers in the mesh of each topic.
+    pub mesh_n: usize,
+    /// Minimum number of peers in the mesh, below which more peers are
+    /// grafted.
+    pub mesh_n_low: usize,
+    /// Maximum number of peers in the mesh, above which peers are
+    /// pruned.
+    pub mesh_n_high: usize,
+    /// Publish our own messages to all peers subscribed to the topic
+    /// with good enough score, instead of only to the mesh peers.
+    pub flood_publish: bool,
+    /// Number of heartbeats the received messages are kept for, so that
+    /// they can be forwarded once validated.
+    pub history_length: usize,
+    /// Number of heartbeats of the history that is advertised to peers.
+    pub history_gossip: usize,
+    pub max_message_size: P2pGossipsubMaxMessageSize,
+    /// Peer scoring, `None` to disable it.
+    pub peer_scoring: Option<P2pGossipsubPeerScoringConfig>,
+}
+
+/// Max size (in bytes) of the message received on each topic. Larger
+/// messages are rejected.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct P2pGossipsubMaxMessageSize {
+    pub block: usize,
+    pub snark_work: usize,
+    pub transaction: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct P2pGossipsubPeerScoringConfig {
+    /// Below this score we don't gossip to/from the peer.
+    pub gossip_threshold: f64,
+    /// Below this score we don't publish our messages to the peer.
+    pub publish_threshold: f64,
+    /// Below this score all messages from the peer are ignored.
+    pub graylist_threshold: f64,
+    /// Peer exchange from the peer is accepted only above this score.
+    pub accept_px_threshold: f64,
+    /// Median mesh score below which we graft better scoring peers.
+    pub opportunistic_graft_threshold: f64,
+    /// Weight (negative) of the invalid messages delivered by the peer.
+    /// Applies to each topic.
+    pub invalid_message_weight: f64,
+    /// Factor by which the invalid messages counter decays each decay
+    /// interval.
+    pub invalid_message_decay: f64,
+    /// Weight (negative) of the penalty for too many peers connecting
+    /// from the same IP.
+    pub ip_colocation_factor_weight: f64,
+    /// Number of peers from the same IP, above which they are penalized.
+    pub ip_colocation_factor_threshold: f64,
+    /// Weight (negative) of the protocol misbehaviour, e.g. spamming
+    /// with graft requests.
+    pub behaviour_penalty_weight: f64,
+    pub decay_interval: Duration,
+}
+
+impl Default for P2pGossipsubConfig {
+    fn default() -> Self {
+        Self {
+            mesh_n: 8,
+            mesh_n_low: 6,
+            mesh_n_high: 12,
+            flood_publish: true,
+            // Block verification might take a while, so messages are
+            // kept longer than the default 5 heartbeats.
+            history_length: 12,
+            history_gossip: 3,
+            max_message_size: Default::default(),
+            peer_scoring: Some(Default::default()),
+        }
+    }
+}
+
+impl Default for P2pGossipsubMaxMessageSize {
+    fn default() -> Self {
+        Self {
+            block: 32 * 1024 * 1024,
+            snark_work: 4 * 1024 * 1024,
+            transaction: 2 * 1024 * 1024,
+        }
+    }
+}
+
+impl P2pGossipsubMaxMessageSize {
+    /// Max size of any message, used for topics which carry messages of
+    /// all kinds.
+    pub fn max(&self) -> usize {
+        self.block.max(self.snark_work).max(self.transaction)
+    }
+}
+
+impl Default for P2pGossipsubPeerScoringConfig {
+    fn default() -> Self {
+        Self {
+            gossip_threshold: -4000.0,
+            publish_threshold: -8000.0,
+            graylist_threshold: -16000.0,
+            accept_px_threshold: 100.0,
+            opportunistic_graft_threshold: 5.0,
+            invalid_message_weight: -1000.0,
+            invalid_message_decay: 0.99,
+            ip_colocation_factor_weight: -100.0,
+            ip_colocation_factor_threshold: 10.0,
+            behaviour_penalty_weight: -10.0,
+            decay_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pReputationConfig {
+    /// Peer gets banned once its score drops to or below this value.
+    pub ban_threshold: i32,
+    pub ban_duration: Duration,
+    /// Score of the penalized peer recovers by one point per this
+    /// interval, until it gets back to 0.
+    pub score_recovery_interval: Duration,
+    pub penalties: P2pPeerPenalties,
+}
+
+/// Amount by which the peer's score is decreased for each offense.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pPeerPenalties {
+    pub invalid_ledger_hashes: u32,
+    pub invalid_snark: u32,
+    pub rpc_timeout: u32,
+    pub unexpected_message: u32,
+}
+
+impl P2pPeerPenalties {
+    pub fn get(&self, offense: P2pPeerOffense) -> u32 {
+        match offense {
+            P2pPeerOffense::InvalidLedgerHashes => self.invalid_ledger_hashes,
+            P2pPeerOffense::InvalidSnark => self.invalid_snark,
+            P2pPeerOffense::RpcTimeout => self.rpc_timeout,
+            P2pPeerOffense::UnexpectedMessage => self.unexpected_message,
+        }
+    }
+}
+
+impl Default for P2pReputationConfig {
+    fn default() -> Self {
+        Self {
+            ban_threshold: -100,
+            ban_duration: Duration::from_secs(60 * 60),
+            score_recovery_interval: Duration::from_secs(30),
+            penalties: P2pPeerPenalties {
+                invalid_ledger_hashes: 100,
+                invalid_snark: 100,
+                rpc_timeout: 10,
+                unexpected_message: 25,
+            },
+        }
+    }
 }
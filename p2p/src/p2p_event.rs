@@ -1,10 +1,11 @@
 use derive_more::From;
+use mina_p2p_messages::v2::MinaBaseUserCommandStableV2;
 use openmina_core::snark::Snark;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     channels::{ChannelId, ChannelMsg, MsgId},
-    connection::P2pConnectionResponse,
+    connection::{outgoing::P2pConnectionOutgoingInitOpts, P2pConnectionResponse},
     PeerId,
 };
 
@@ -12,6 +13,7 @@ use crate::{
 pub enum P2pEvent {
     Connection(P2pConnectionEvent),
     Channel(P2pChannelEvent),
+    Discovery(P2pDiscoveryEvent),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -29,9 +31,21 @@ pub enum P2pChannelEvent {
     Sent(PeerId, ChannelId, MsgId, Result<(), String>),
     Received(PeerId, Result<ChannelMsg, String>),
     Libp2pSnarkReceived(PeerId, Snark, u32),
+    Libp2pTransactionReceived(PeerId, Vec<Box<MinaBaseUserCommandStableV2>>, u32),
     Closed(PeerId, ChannelId),
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum P2pDiscoveryEvent {
+    /// Peer and its dialable addresses, learned through kademlia or
+    /// identify protocol.
+    AddRoute(PeerId, Vec<P2pConnectionOutgoingInitOpts>),
+    /// Kademlia lookup is finished, with the peers closest to the
+    /// looked up key.
+    DidFindPeers(Vec<PeerId>),
+    DidFindPeersError(String),
+}
+
 fn res_kind<T, E>(res: &Result<T, E>) -> &'static str {
     match res {
         Err(_) => "Err",
@@ -45,6 +59,7 @@ impl std::fmt::Display for P2pEvent {
         match self {
             Self::Connection(v) => v.fmt(f),
             Self::Channel(v) => v.fmt(f),
+            Self::Discovery(v) => v.fmt(f),
         }
     }
 }
@@ -82,6 +97,7 @@ impl std::fmt::Display for P2pChannelEvent {
         use crate::channels::rpc::RpcChannelMsg;
         use crate::channels::snark::SnarkPropagationChannelMsg;
         use crate::channels::snark_job_commitment::SnarkJobCommitmentPropagationChannelMsg;
+        use crate::channels::transaction::TransactionPropagationChannelMsg;
 
         write!(f, "Channel, ")?;
         match self {
@@ -107,6 +123,13 @@ impl std::fmt::Display for P2pChannelEvent {
                     snark.job_id(),
                 )
             }
+            Self::Libp2pTransactionReceived(peer_id, transactions, nonce) => {
+                write!(
+                    f,
+                    "Libp2pTransactionReceived, {peer_id}, count: {}, nonce: {nonce}",
+                    transactions.len(),
+                )
+            }
             Self::Received(peer_id, res) => {
                 write!(f, "Received, {peer_id}, ")?;
                 let msg = match res {
@@ -127,6 +150,18 @@ impl std::fmt::Display for P2pChannelEvent {
                             }
                         }
                     }
+                    ChannelMsg::TransactionPropagation(v) => match v {
+                        TransactionPropagationChannelMsg::GetNext { limit } => {
+                            write!(f, "GetNext, limit: {limit}")
+                        }
+                        TransactionPropagationChannelMsg::WillSend { count } => {
+                            write!(f, "WillSend, count: {count}")
+                        }
+                        TransactionPropagationChannelMsg::Transaction(tx) => match tx.hash() {
+                            Ok(hash) => write!(f, "Transaction, hash: {hash}"),
+                            Err(_) => write!(f, "Transaction"),
+                        },
+                    },
                     ChannelMsg::SnarkPropagation(v) => match v {
                         SnarkPropagationChannelMsg::GetNext { limit } => {
                             write!(f, "GetNext, limit: {limit}")
@@ -174,3 +209,16 @@ impl std::fmt::Display for P2pChannelEvent {
         }
     }
 }
+
+impl std::fmt::Display for P2pDiscoveryEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Discovery, ")?;
+        match self {
+            Self::AddRoute(peer_id, addrs) => {
+                write!(f, "AddRoute, {peer_id}, addresses: {}", addrs.len())
+            }
+            Self::DidFindPeers(peers) => write!(f, "DidFindPeers, count: {}", peers.len()),
+            Self::DidFindPeersError(err) => write!(f, "DidFindPeersError, {err}"),
+        }
+    }
+}
@@ -2,7 +2,10 @@ use crate::connection::incoming::P2pConnectionIncomingAction;
 use crate::connection::outgoing::{P2pConnectionOutgoingAction, P2pConnectionOutgoingInitOpts};
 use crate::connection::{p2p_connection_reducer, P2pConnectionAction, P2pConnectionState};
 use crate::disconnection::P2pDisconnectionAction;
-use crate::discovery::{P2pDiscoveryAction, P2pDiscoverySuccessAction};
+use crate::discovery::{
+    P2pDiscoveryAction, P2pDiscoveryKademliaAddRouteAction, P2pDiscoveryKademliaSuccessAction,
+    P2pDiscoverySuccessAction,
+};
 use crate::peer::p2p_peer_reducer;
 use crate::{P2pAction, P2pActionWithMetaRef, P2pPeerState, P2pPeerStatus, P2pState};
 
@@ -86,6 +89,29 @@ impl P2pState {
                     }));
                 }
                 P2pDiscoveryAction::Timeout(_) => {}
+                P2pDiscoveryAction::KademliaInit(_) => {
+                    self.kademlia.ongoing_lookup = Some(meta.time());
+                }
+                P2pDiscoveryAction::KademliaAddRoute(P2pDiscoveryKademliaAddRouteAction {
+                    peer_id,
+                    addresses,
+                }) => {
+                    self.kademlia.routes_count += 1;
+                    if let Some(opts) = addresses.first() {
+                        self.known_peer_add(*peer_id, opts.clone());
+                    }
+                }
+                P2pDiscoveryAction::KademliaSuccess(P2pDiscoveryKademliaSuccessAction {
+                    peers,
+                }) => {
+                    self.kademlia.ongoing_lookup = None;
+                    self.kademlia.last_lookup = Some(meta.time());
+                    self.kademlia.last_lookup_found = peers.len();
+                }
+                P2pDiscoveryAction::KademliaFailure(_) => {
+                    self.kademlia.ongoing_lookup = None;
+                    self.kademlia.last_lookup = Some(meta.time());
+                }
             },
         }
     }
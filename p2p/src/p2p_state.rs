@@ -1,12 +1,15 @@
 use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
 
 use openmina_core::requests::RpcId;
 
 use crate::channels::rpc::P2pRpcId;
 use crate::channels::{ChannelId, P2pChannelsState};
 use crate::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use crate::discovery::P2pKademliaState;
+use crate::peer::P2pPeerOffense;
 use crate::PeerId;
 
 use super::connection::P2pConnectionState;
@@ -17,9 +20,18 @@ pub struct P2pState {
     pub config: P2pConfig,
     pub peers: BTreeMap<PeerId, P2pPeerState>,
     pub known_peers: BTreeMap<PeerId, P2pConnectionOutgoingInitOpts>,
+    /// Kept separately from `peers`, so that it outlives the connection
+    /// and so that peers we never connected to can be banned too. Not
+    /// persisted, so scores and bans reset when the node restarts.
+    pub reputation: BTreeMap<PeerId, P2pPeerReputation>,
+    pub kademlia: P2pKademliaState,
 }
 
 impl P2pState {
+    /// Max number of known peers. Once reached, discovered peers
+    /// replace the ones we aren't connected to.
+    pub const MAX_KNOWN_PEERS: usize = 1000;
+
     pub fn new(config: P2pConfig) -> Self {
         let known_peers = config
             .initial_peers
@@ -30,7 +42,34 @@ impl P2pState {
             config,
             peers: Default::default(),
             known_peers,
+            reputation: Default::default(),
+            kademlia: Default::default(),
+        }
+    }
+
+    /// Adds the discovered peer to the known peers. Addresses we
+    /// already know are kept, as we might have connected to the peer
+    /// using them. If there are too many known peers, one we aren't
+    /// connected to and which isn't an initial peer is evicted.
+    pub fn known_peer_add(&mut self, peer_id: PeerId, opts: P2pConnectionOutgoingInitOpts) {
+        if self.known_peers.contains_key(&peer_id) {
+            return;
+        }
+        if self.known_peers.len() >= Self::MAX_KNOWN_PEERS {
+            let evicted = self.known_peers.keys().copied().find(|id| {
+                let is_connected = self
+                    .peers
+                    .get(id)
+                    .map_or(false, |p| p.status.is_connected_or_connecting());
+                let is_initial = self.config.initial_peers.iter().any(|p| p.peer_id() == id);
+                !is_connected && !is_initial
+            });
+            let Some(evicted) = evicted else {
+                return;
+            };
+            self.known_peers.remove(&evicted);
         }
+        self.known_peers.insert(peer_id, opts);
     }
 
     pub fn peer_connection_rpc_id(&self, peer_id: &PeerId) -> Option<RpcId> {
@@ -59,6 +98,7 @@ impl P2pState {
         self.known_peers
             .values()
             .filter(|v| !self.peers.contains_key(v.peer_id()))
+            .filter(|v| !self.is_peer_banned(v.peer_id()))
             .cloned()
             .collect()
     }
@@ -123,6 +163,39 @@ impl P2pState {
             .collect()
     }
 
+    pub fn is_peer_banned(&self, peer_id: &PeerId) -> bool {
+        self.reputation
+            .get(peer_id)
+            .map_or(false, |r| r.ban.is_some())
+    }
+
+    /// Whether the peer's score has dropped to the ban threshold.
+    pub fn peer_reached_ban_threshold(&self, peer_id: &PeerId) -> bool {
+        self.reputation
+            .get(peer_id)
+            .map_or(false, |r| r.score <= self.config.reputation.ban_threshold)
+    }
+
+    /// Peers which aren't banned and whose score has recovered to the
+    /// default, so their reputation no longer needs to be kept.
+    pub fn peer_reputations_recovered(&self, now: redux::Timestamp) -> Vec<PeerId> {
+        let recovery_interval = self.config.reputation.score_recovery_interval;
+        self.reputation
+            .iter()
+            .filter(|(_, r)| r.ban.is_none() && r.score_at(now, recovery_interval) >= 0)
+            .map(|(peer_id, _)| *peer_id)
+            .collect()
+    }
+
+    /// Banned peers, whose ban has expired.
+    pub fn peer_bans_expired(&self, now: redux::Timestamp) -> Vec<PeerId> {
+        self.reputation
+            .iter()
+            .filter(|(_, r)| r.ban.as_ref().map_or(false, |ban| ban.is_expired(now)))
+            .map(|(peer_id, _)| *peer_id)
+            .collect()
+    }
+
     pub fn already_has_min_peers(&self) -> bool {
         self.connected_or_connecting_peers_count() >= (self.config.max_peers / 2).max(3)
     }
@@ -132,6 +205,46 @@ impl P2pState {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct P2pPeerReputation {
+    /// Starts at 0 and gets decreased with each offense. Recovery since
+    /// `score_updated_at` isn't included, see [`Self::score_at`].
+    pub score: i32,
+    pub score_updated_at: Option<redux::Timestamp>,
+    pub last_offense: Option<P2pPeerOffense>,
+    pub ban: Option<P2pPeerBan>,
+}
+
+impl P2pPeerReputation {
+    /// Score at `now`, after it has recovered by one point per
+    /// `recovery_interval` since the last offense, up to 0.
+    pub fn score_at(&self, now: redux::Timestamp, recovery_interval: Duration) -> i32 {
+        let Some(updated_at) = self.score_updated_at else {
+            return self.score;
+        };
+        if self.score >= 0 || recovery_interval.is_zero() {
+            return self.score;
+        }
+        let elapsed = now.checked_sub(updated_at).unwrap_or_default();
+        let recovered = elapsed.as_nanos() / recovery_interval.as_nanos();
+        let recovered = i32::try_from(recovered).unwrap_or(i32::MAX);
+        self.score.saturating_add(recovered).min(0)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pPeerBan {
+    pub since: redux::Timestamp,
+    pub duration: Duration,
+}
+
+impl P2pPeerBan {
+    pub fn is_expired(&self, now: redux::Timestamp) -> bool {
+        now.checked_sub(self.since)
+            .map_or(false, |d| d >= self.duration)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct P2pPeerState {
     pub dial_opts: Option<P2pConnectionOutgoingInitOpts>,
@@ -217,3 +330,58 @@ impl P2pPeerStatusReady {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::identity::SecretKey;
+
+    use super::*;
+
+    fn opts(peer_id: PeerId) -> P2pConnectionOutgoingInitOpts {
+        format!("/{peer_id}/http/127.0.0.1/10000").parse().unwrap()
+    }
+
+    fn peer_id(n: u16) -> PeerId {
+        let mut bytes = [0; 32];
+        bytes[..2].copy_from_slice(&n.to_be_bytes());
+        PeerId::from_bytes(bytes)
+    }
+
+    #[test]
+    fn known_peers_capped() {
+        let initial = peer_id(0);
+        let mut state = P2pState::new(P2pConfig {
+            identity_pub_key: SecretKey::rand().public_key(),
+            initial_peers: vec![opts(initial)],
+            enabled_channels: ChannelId::iter_all().collect(),
+            max_peers: 10,
+            reputation: Default::default(),
+            kademlia: Default::default(),
+            gossipsub: Default::default(),
+        });
+        let connected = peer_id(1);
+        state.peers.insert(
+            connected,
+            P2pPeerState {
+                dial_opts: Some(opts(connected)),
+                status: P2pPeerStatus::Ready(P2pPeerStatusReady::new(
+                    redux::Timestamp::ZERO,
+                    &state.config.enabled_channels,
+                )),
+            },
+        );
+        for n in 1..P2pState::MAX_KNOWN_PEERS as u16 {
+            state.known_peer_add(peer_id(n), opts(peer_id(n)));
+        }
+        assert_eq!(state.known_peers.len(), P2pState::MAX_KNOWN_PEERS);
+
+        // Neither the initial nor the connected peer is evicted.
+        let new = peer_id(P2pState::MAX_KNOWN_PEERS as u16);
+        state.known_peer_add(new, opts(new));
+        assert_eq!(state.known_peers.len(), P2pState::MAX_KNOWN_PEERS);
+        assert!(state.known_peers.contains_key(&new));
+        assert!(state.known_peers.contains_key(&initial));
+        assert!(state.known_peers.contains_key(&connected));
+        assert!(!state.known_peers.contains_key(&peer_id(2)));
+    }
+}
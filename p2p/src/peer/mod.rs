@@ -6,3 +6,14 @@ pub use p2p_peer_reducer::*;
 
 mod p2p_peer_effects;
 pub use p2p_peer_effects::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Misbehaviour of the peer, for which its score gets decreased.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
+pub enum P2pPeerOffense {
+    InvalidLedgerHashes,
+    InvalidSnark,
+    RpcTimeout,
+    UnexpectedMessage,
+}
@@ -1,8 +1,12 @@
+use std::time::Duration;
+
 use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
 use crate::{P2pAction, P2pState, PeerId};
 
+use super::P2pPeerOffense;
+
 pub type P2pPeerActionWithMeta = redux::ActionWithMeta<P2pPeerAction>;
 pub type P2pPeerActionWithMetaRef<'a> = redux::ActionWithMeta<&'a P2pPeerAction>;
 
@@ -10,6 +14,10 @@ pub type P2pPeerActionWithMetaRef<'a> = redux::ActionWithMeta<&'a P2pPeerAction>
 pub enum P2pPeerAction {
     Ready(P2pPeerReadyAction),
     BestTipUpdate(P2pPeerBestTipUpdateAction),
+    Penalize(P2pPeerPenalizeAction),
+    Ban(P2pPeerBanAction),
+    Unban(P2pPeerUnbanAction),
+    Forgive(P2pPeerForgiveAction),
 }
 
 impl P2pPeerAction {
@@ -17,6 +25,10 @@ impl P2pPeerAction {
         match self {
             Self::Ready(v) => &v.peer_id,
             Self::BestTipUpdate(v) => &v.peer_id,
+            Self::Penalize(v) => &v.peer_id,
+            Self::Ban(v) => &v.peer_id,
+            Self::Unban(v) => &v.peer_id,
+            Self::Forgive(v) => &v.peer_id,
         }
     }
 }
@@ -32,6 +44,7 @@ impl redux::EnablingCondition<P2pState> for P2pPeerReadyAction {
             .peers
             .get(&self.peer_id)
             .map_or(false, |p| p.status.is_connecting_success())
+            && !state.is_peer_banned(&self.peer_id)
     }
 }
 
@@ -49,6 +62,58 @@ impl redux::EnablingCondition<P2pState> for P2pPeerBestTipUpdateAction {
     }
 }
 
+/// Decreases the peer's score for the offense and bans the peer, if the
+/// score drops to the ban threshold.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pPeerPenalizeAction {
+    pub peer_id: PeerId,
+    pub offense: P2pPeerOffense,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pPeerPenalizeAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        !state.is_peer_banned(&self.peer_id)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pPeerBanAction {
+    pub peer_id: PeerId,
+    /// If `None`, ban duration from the config is used.
+    pub duration: Option<Duration>,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pPeerBanAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        self.peer_id != state.config.identity_pub_key.peer_id()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pPeerUnbanAction {
+    pub peer_id: PeerId,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pPeerUnbanAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state.is_peer_banned(&self.peer_id)
+    }
+}
+
+/// Forgets the reputation of the peer, whose score has recovered to
+/// the default, so that reputations of the peers we no longer see
+/// don't pile up.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pPeerForgiveAction {
+    pub peer_id: PeerId,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pPeerForgiveAction {
+    fn is_enabled(&self, state: &P2pState) -> bool {
+        state.reputation.contains_key(&self.peer_id) && !state.is_peer_banned(&self.peer_id)
+    }
+}
+
 impl From<P2pPeerReadyAction> for P2pAction {
     fn from(value: P2pPeerReadyAction) -> Self {
         Self::Peer(value.into())
@@ -60,3 +125,27 @@ impl From<P2pPeerBestTipUpdateAction> for P2pAction {
         Self::Peer(value.into())
     }
 }
+
+impl From<P2pPeerPenalizeAction> for P2pAction {
+    fn from(value: P2pPeerPenalizeAction) -> Self {
+        Self::Peer(value.into())
+    }
+}
+
+impl From<P2pPeerBanAction> for P2pAction {
+    fn from(value: P2pPeerBanAction) -> Self {
+        Self::Peer(value.into())
+    }
+}
+
+impl From<P2pPeerUnbanAction> for P2pAction {
+    fn from(value: P2pPeerUnbanAction) -> Self {
+        Self::Peer(value.into())
+    }
+}
+
+impl From<P2pPeerForgiveAction> for P2pAction {
+    fn from(value: P2pPeerForgiveAction) -> Self {
+        Self::Peer(value.into())
+    }
+}
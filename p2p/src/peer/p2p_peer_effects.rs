@@ -3,16 +3,22 @@ use redux::ActionMeta;
 use crate::channels::{
     best_tip::P2pChannelsBestTipInitAction, rpc::P2pChannelsRpcInitAction,
     snark::P2pChannelsSnarkInitAction,
-    snark_job_commitment::P2pChannelsSnarkJobCommitmentInitAction, ChannelId,
+    snark_job_commitment::P2pChannelsSnarkJobCommitmentInitAction,
+    transaction::P2pChannelsTransactionInitAction, ChannelId,
 };
 
-use super::P2pPeerReadyAction;
+use crate::disconnection::{
+    P2pDisconnectionInitAction, P2pDisconnectionReason, P2pDisconnectionService,
+};
+
+use super::{P2pPeerBanAction, P2pPeerPenalizeAction, P2pPeerReadyAction, P2pPeerUnbanAction};
 
 impl P2pPeerReadyAction {
     pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
     where
         Store: crate::P2pStore<S>,
         P2pChannelsBestTipInitAction: redux::EnablingCondition<S>,
+        P2pChannelsTransactionInitAction: redux::EnablingCondition<S>,
         P2pChannelsSnarkInitAction: redux::EnablingCondition<S>,
         P2pChannelsSnarkJobCommitmentInitAction: redux::EnablingCondition<S>,
         P2pChannelsRpcInitAction: redux::EnablingCondition<S>,
@@ -25,6 +31,9 @@ impl P2pPeerReadyAction {
                 ChannelId::BestTipPropagation => {
                     store.dispatch(P2pChannelsBestTipInitAction { peer_id });
                 }
+                ChannelId::TransactionPropagation => {
+                    store.dispatch(P2pChannelsTransactionInitAction { peer_id });
+                }
                 ChannelId::SnarkPropagation => {
                     store.dispatch(P2pChannelsSnarkInitAction { peer_id });
                 }
@@ -38,3 +47,46 @@ impl P2pPeerReadyAction {
         }
     }
 }
+
+impl P2pPeerPenalizeAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        P2pPeerBanAction: redux::EnablingCondition<S>,
+    {
+        if store.state().peer_reached_ban_threshold(&self.peer_id) {
+            store.dispatch(P2pPeerBanAction {
+                peer_id: self.peer_id,
+                duration: None,
+            });
+        }
+    }
+}
+
+impl P2pPeerBanAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pDisconnectionService,
+        P2pDisconnectionInitAction: redux::EnablingCondition<S>,
+    {
+        // Service refuses the peer's connections while it's banned,
+        // including the incoming libp2p ones, which we don't track in
+        // the state.
+        store.service().peer_ban(self.peer_id);
+        store.dispatch(P2pDisconnectionInitAction {
+            peer_id: self.peer_id,
+            reason: P2pDisconnectionReason::Banned,
+        });
+    }
+}
+
+impl P2pPeerUnbanAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pDisconnectionService,
+    {
+        store.service().peer_unban(self.peer_id);
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{P2pPeerStatus, P2pPeerStatusReady, P2pState};
+use crate::connection::incoming::P2pConnectionIncomingState;
+use crate::connection::outgoing::P2pConnectionOutgoingState;
+use crate::connection::P2pConnectionState;
+use crate::{P2pPeerBan, P2pPeerStatus, P2pPeerStatusReady, P2pState};
 
 use super::{P2pPeerAction, P2pPeerActionWithMetaRef};
 
@@ -10,10 +13,31 @@ pub fn p2p_peer_reducer(state: &mut P2pState, action: P2pPeerActionWithMetaRef<'
             let Some(peer) = state.peers.get_mut(&action.peer_id) else {
                 return;
             };
-            peer.status = P2pPeerStatus::Ready(P2pPeerStatusReady::new(
-                meta.time(),
-                &state.config.enabled_channels,
-            ));
+            // Only open channels which the peer supports too, as
+            // advertised in its webrtc offer/answer.
+            let remote_channels = match &peer.status {
+                P2pPeerStatus::Connecting(P2pConnectionState::Outgoing(
+                    P2pConnectionOutgoingState::Success {
+                        answer: Some(answer),
+                        ..
+                    },
+                )) => Some(answer.supported_channels()),
+                P2pPeerStatus::Connecting(P2pConnectionState::Incoming(
+                    P2pConnectionIncomingState::Success { offer, .. },
+                )) => Some(offer.supported_channels()),
+                _ => None,
+            };
+            let enabled_channels = match remote_channels {
+                Some(remote_channels) => state
+                    .config
+                    .enabled_channels
+                    .intersection(&remote_channels)
+                    .copied()
+                    .collect(),
+                None => state.config.enabled_channels.clone(),
+            };
+            peer.status =
+                P2pPeerStatus::Ready(P2pPeerStatusReady::new(meta.time(), &enabled_channels));
         }
         P2pPeerAction::BestTipUpdate(action) => {
             let Some(peer) = state.get_ready_peer_mut(&action.peer_id) else {
@@ -21,5 +45,233 @@ pub fn p2p_peer_reducer(state: &mut P2pState, action: P2pPeerActionWithMetaRef<'
             };
             peer.best_tip = Some(action.best_tip.clone());
         }
+        P2pPeerAction::Penalize(action) => {
+            let config = &state.config.reputation;
+            let penalty = config.penalties.get(action.offense);
+            let reputation = state.reputation.entry(action.peer_id).or_default();
+            reputation.score = reputation
+                .score_at(meta.time(), config.score_recovery_interval)
+                .saturating_sub(penalty.try_into().unwrap_or(i32::MAX));
+            reputation.score_updated_at = Some(meta.time());
+            reputation.last_offense = Some(action.offense);
+        }
+        P2pPeerAction::Ban(action) => {
+            let duration = action
+                .duration
+                .unwrap_or(state.config.reputation.ban_duration);
+            state.reputation.entry(action.peer_id).or_default().ban = Some(P2pPeerBan {
+                since: meta.time(),
+                duration,
+            });
+        }
+        P2pPeerAction::Unban(action) => {
+            // Peer gets a fresh start after the ban.
+            state.reputation.remove(&action.peer_id);
+        }
+        P2pPeerAction::Forgive(action) => {
+            state.reputation.remove(&action.peer_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use redux::{ActionMeta, EnablingCondition, Timestamp};
+
+    use crate::channels::rpc::P2pChannelsRpcState;
+    use crate::channels::transaction::P2pChannelsTransactionState;
+    use crate::channels::ChannelId;
+    use crate::connection::incoming::IncomingSignalingMethod;
+    use crate::identity::SecretKey;
+    use crate::peer::{
+        P2pPeerBanAction, P2pPeerForgiveAction, P2pPeerOffense, P2pPeerPenalizeAction,
+        P2pPeerReadyAction, P2pPeerUnbanAction,
+    };
+    use crate::webrtc::{Answer, Offer};
+    use crate::{P2pConfig, P2pPeerState, PeerId};
+
+    use super::*;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    fn state() -> P2pState {
+        P2pState::new(P2pConfig {
+            identity_pub_key: SecretKey::rand().public_key(),
+            initial_peers: vec![],
+            enabled_channels: ChannelId::iter_all().collect(),
+            max_peers: 10,
+            reputation: Default::default(),
+            kademlia: Default::default(),
+            gossipsub: Default::default(),
+        })
+    }
+
+    fn dispatch(state: &mut P2pState, action: impl Into<P2pPeerAction>, secs: u64) {
+        let action = action.into();
+        let meta = ActionMeta::zero_custom(Timestamp::new(secs * SECOND));
+        p2p_peer_reducer(state, meta.with_action(&action));
+    }
+
+    fn penalize(state: &mut P2pState, peer_id: PeerId, offense: P2pPeerOffense, secs: u64) {
+        dispatch(state, P2pPeerPenalizeAction { peer_id, offense }, secs);
+    }
+
+    #[test]
+    fn score_recovers_over_time() {
+        let mut state = state();
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let interval = state.config.reputation.score_recovery_interval;
+        let interval_secs = interval.as_secs();
+
+        penalize(&mut state, peer_id, P2pPeerOffense::UnexpectedMessage, 0);
+        penalize(&mut state, peer_id, P2pPeerOffense::UnexpectedMessage, 0);
+        let reputation = &state.reputation[&peer_id];
+        assert_eq!(reputation.score, -50);
+
+        let at = |secs: u64| Timestamp::new(secs * SECOND);
+        assert_eq!(reputation.score_at(at(interval_secs - 1), interval), -50);
+        assert_eq!(reputation.score_at(at(interval_secs * 10), interval), -40);
+        // Never recovers above 0.
+        assert_eq!(reputation.score_at(at(interval_secs * 1000), interval), 0);
+
+        // Recovered points count towards the next penalty.
+        penalize(
+            &mut state,
+            peer_id,
+            P2pPeerOffense::RpcTimeout,
+            interval_secs * 20,
+        );
+        assert_eq!(state.reputation[&peer_id].score, -40);
+    }
+
+    #[test]
+    fn ban_threshold_reached() {
+        let mut state = state();
+        let peer_id = PeerId::from_bytes([1; 32]);
+
+        // -10 per offense, threshold is -100.
+        for _ in 0..9 {
+            penalize(&mut state, peer_id, P2pPeerOffense::RpcTimeout, 0);
+            assert!(!state.peer_reached_ban_threshold(&peer_id));
+        }
+        penalize(&mut state, peer_id, P2pPeerOffense::RpcTimeout, 0);
+        assert!(state.peer_reached_ban_threshold(&peer_id));
+
+        // Offenses spread out in time don't reach it.
+        let other_peer_id = PeerId::from_bytes([2; 32]);
+        let interval_secs = state.config.reputation.score_recovery_interval.as_secs();
+        for i in 0..20 {
+            let secs = i * interval_secs * 10;
+            penalize(&mut state, other_peer_id, P2pPeerOffense::RpcTimeout, secs);
+        }
+        assert!(!state.peer_reached_ban_threshold(&other_peer_id));
+    }
+
+    #[test]
+    fn ban_expires() {
+        let mut state = state();
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let ban = |duration| P2pPeerBanAction {
+            peer_id,
+            duration: Some(Duration::from_secs(duration)),
+        };
+
+        dispatch(&mut state, ban(60), 100);
+        assert!(state.is_peer_banned(&peer_id));
+        let at = |secs: u64| Timestamp::new(secs * SECOND);
+        assert!(state.peer_bans_expired(at(159)).is_empty());
+        assert_eq!(state.peer_bans_expired(at(160)), vec![peer_id]);
+
+        // Peer gets a fresh start after being unbanned.
+        penalize(&mut state, peer_id, P2pPeerOffense::InvalidSnark, 160);
+        dispatch(&mut state, P2pPeerUnbanAction { peer_id }, 160);
+        assert!(!state.is_peer_banned(&peer_id));
+        assert!(!state.reputation.contains_key(&peer_id));
+
+        // Ban without duration uses the configured one.
+        state.config.reputation.ban_duration = Duration::from_secs(10);
+        dispatch(
+            &mut state,
+            P2pPeerBanAction {
+                peer_id,
+                duration: None,
+            },
+            200,
+        );
+        assert!(state.peer_bans_expired(at(209)).is_empty());
+        assert_eq!(state.peer_bans_expired(at(210)), vec![peer_id]);
+    }
+
+    #[test]
+    fn recovered_reputation_forgotten() {
+        let mut state = state();
+        let (peer_id, banned_peer_id) = (PeerId::from_bytes([1; 32]), PeerId::from_bytes([2; 32]));
+        let interval_secs = state.config.reputation.score_recovery_interval.as_secs();
+        let at = |secs: u64| Timestamp::new(secs * SECOND);
+
+        penalize(&mut state, peer_id, P2pPeerOffense::RpcTimeout, 0);
+        let ban = P2pPeerBanAction {
+            peer_id: banned_peer_id,
+            duration: Some(Duration::from_secs(interval_secs * 100)),
+        };
+        dispatch(&mut state, ban, 0);
+
+        // -10, recovering by a point per interval.
+        assert!(state
+            .peer_reputations_recovered(at(interval_secs * 9))
+            .is_empty());
+        assert_eq!(
+            state.peer_reputations_recovered(at(interval_secs * 10)),
+            vec![peer_id]
+        );
+        dispatch(
+            &mut state,
+            P2pPeerForgiveAction { peer_id },
+            interval_secs * 10,
+        );
+        assert!(!state.reputation.contains_key(&peer_id));
+        // Banned peer is kept until the ban expires.
+        assert!(!P2pPeerForgiveAction {
+            peer_id: banned_peer_id
+        }
+        .is_enabled(&state));
+        assert!(state.reputation.contains_key(&banned_peer_id));
+    }
+
+    #[test]
+    fn channels_negotiated_with_older_peer() {
+        let mut state = state();
+        let offerer = SecretKey::rand();
+        let peer_id = offerer.public_key().peer_id();
+        let own_id = state.config.identity_pub_key.peer_id();
+        let mut offer = Offer::new("offer sdp".to_owned(), own_id, &offerer);
+        // Older peers don't advertise their channels.
+        offer.channels.clear();
+        let answer = Answer::new("answer sdp".to_owned(), peer_id, &SecretKey::rand());
+        state.peers.insert(
+            peer_id,
+            P2pPeerState {
+                dial_opts: None,
+                status: P2pPeerStatus::Connecting(P2pConnectionState::Incoming(
+                    P2pConnectionIncomingState::Success {
+                        time: Timestamp::ZERO,
+                        signaling: IncomingSignalingMethod::Http,
+                        offer,
+                        answer,
+                        rpc_id: None,
+                    },
+                )),
+            },
+        );
+
+        dispatch(&mut state, P2pPeerReadyAction { peer_id }, 0);
+        let channels = &state.get_ready_peer(&peer_id).unwrap().channels;
+        assert!(matches!(
+            channels.transaction,
+            P2pChannelsTransactionState::Disabled
+        ));
+        assert!(matches!(channels.rpc, P2pChannelsRpcState::Enabled));
     }
 }
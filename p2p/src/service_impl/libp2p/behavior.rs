@@ -1,10 +1,15 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Instant;
 
-use libp2p::{gossipsub, swarm::NetworkBehaviour, PeerId};
+use libp2p::gossipsub::TopicHash;
+use libp2p::kad::{store::MemoryStore, Kademlia, KademliaEvent};
+use libp2p::{gossipsub, identify, swarm::NetworkBehaviour, PeerId};
 use openmina_core::channels::mpsc;
 
 use crate::P2pEvent;
 
+use super::gossip::GossipValidationPending;
+
 use libp2p_rpc_behaviour::{Behaviour as RpcBehaviour, Event as RpcEvent, StreamId};
 
 #[derive(NetworkBehaviour)]
@@ -12,6 +17,8 @@ use libp2p_rpc_behaviour::{Behaviour as RpcBehaviour, Event as RpcEvent, StreamI
 pub struct Behaviour<E: 'static + From<P2pEvent>> {
     pub gossipsub: gossipsub::Behaviour,
     pub rpc: RpcBehaviour,
+    pub identify: identify::Behaviour,
+    pub kademlia: Kademlia<MemoryStore>,
     #[behaviour(ignore)]
     pub event_source_sender: mpsc::UnboundedSender<E>,
     // TODO(vlad9486): move maps inside `RpcBehaviour`
@@ -22,12 +29,25 @@ pub struct Behaviour<E: 'static + From<P2pEvent>> {
     //
     #[behaviour(ignore)]
     pub ongoing_incoming: BTreeMap<(PeerId, u32), (StreamId, String, i32)>,
+    /// Gossip messages waiting for the state machine to validate them.
+    #[behaviour(ignore)]
+    pub gossip_pending: GossipValidationPending,
+    #[behaviour(ignore)]
+    pub gossip_max_message_size: HashMap<TopicHash, usize>,
+    /// Reported as uptime in `get_node_status` responses.
+    #[behaviour(ignore)]
+    pub started_at: Instant,
+    /// Peers banned by the state machine. Their connections get closed
+    /// as soon as they are established.
+    #[behaviour(ignore)]
+    pub banned_peers: BTreeSet<PeerId>,
 }
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, derive_more::From)]
 pub enum Event {
-    // Identify(IdentifyEvent),
     Gossipsub(gossipsub::Event),
     Rpc((PeerId, RpcEvent)),
+    Identify(identify::Event),
+    Kademlia(KademliaEvent),
 }
@@ -1 +1,215 @@
+//! Peer discovery on the libp2p transport, using kademlia DHT and
+//! identify protocols, same as the Mina daemon does.
 
+use std::borrow::Cow;
+
+use libp2p::identify;
+use libp2p::identity::Keypair;
+use libp2p::kad::store::MemoryStore;
+use libp2p::kad::{
+    GetClosestPeersError, GetClosestPeersOk, Kademlia, KademliaConfig, KademliaEvent, QueryResult,
+};
+use libp2p::multiaddr::Protocol;
+use libp2p::{Multiaddr, PeerId, Swarm};
+
+use crate::connection::outgoing::P2pConnectionOutgoingInitOpts;
+use crate::{P2pDiscoveryEvent, P2pEvent};
+
+use super::{Behaviour, Libp2pService};
+
+/// Kademlia protocol id used by the Mina daemon.
+const KADEMLIA_PROTOCOL: &[u8] = b"/coda/kad/1.0.0";
+const IDENTIFY_PROTOCOL_VERSION: &str = "ipfs/0.1.0";
+
+impl Libp2pService {
+    pub(super) fn kademlia(peer_id: PeerId) -> Kademlia<MemoryStore> {
+        let mut config = KademliaConfig::default();
+        config.set_protocol_names(vec![Cow::Borrowed(KADEMLIA_PROTOCOL)]);
+        Kademlia::with_config(peer_id, MemoryStore::new(peer_id), config)
+    }
+
+    pub(super) fn identify(identity_keys: &Keypair) -> identify::Behaviour {
+        let config =
+            identify::Config::new(IDENTIFY_PROTOCOL_VERSION.to_owned(), identity_keys.public())
+                .with_agent_version(format!("openmina/{}", env!("CARGO_PKG_VERSION")));
+        identify::Behaviour::new(config)
+    }
+
+    /// Lookup of a random key, which refreshes the routing table with
+    /// the peers closest to it. The result is sent to the state
+    /// machine once the query is finished.
+    pub(super) fn kademlia_lookup_random<E>(swarm: &mut Swarm<Behaviour<E>>)
+    where
+        E: From<P2pEvent>,
+    {
+        swarm
+            .behaviour_mut()
+            .kademlia
+            .get_closest_peers(PeerId::random());
+    }
+
+    pub(super) fn handle_event_identify<E>(swarm: &mut Swarm<Behaviour<E>>, event: identify::Event)
+    where
+        E: From<P2pEvent>,
+    {
+        let identify::Event::Received { peer_id, info } = event else {
+            return;
+        };
+        let b = swarm.behaviour_mut();
+        // Peer will be used in lookups only if it speaks kademlia.
+        if info
+            .protocols
+            .iter()
+            .any(|p| p.as_bytes() == KADEMLIA_PROTOCOL)
+        {
+            for addr in &info.listen_addrs {
+                b.kademlia.add_address(&peer_id, addr.clone());
+            }
+        }
+        Self::send_route(b, peer_id, info.listen_addrs.iter());
+    }
+
+    pub(super) fn handle_event_kademlia<E>(swarm: &mut Swarm<Behaviour<E>>, event: KademliaEvent)
+    where
+        E: From<P2pEvent>,
+    {
+        let b = swarm.behaviour_mut();
+        match event {
+            KademliaEvent::RoutingUpdated {
+                peer, addresses, ..
+            } => {
+                Self::send_route(b, peer, addresses.iter());
+            }
+            KademliaEvent::OutboundQueryProgressed {
+                result: QueryResult::GetClosestPeers(result),
+                ..
+            } => {
+                let event = match result {
+                    Ok(GetClosestPeersOk { peers, .. }) => {
+                        P2pDiscoveryEvent::DidFindPeers(supported_peer_ids(peers))
+                    }
+                    // Lookup still might have found some peers.
+                    Err(GetClosestPeersError::Timeout { peers, .. }) if !peers.is_empty() => {
+                        P2pDiscoveryEvent::DidFindPeers(supported_peer_ids(peers))
+                    }
+                    Err(err) => P2pDiscoveryEvent::DidFindPeersError(err.to_string()),
+                };
+                let _ = b
+                    .event_source_sender
+                    .send(P2pEvent::Discovery(event).into());
+            }
+            event => {
+                openmina_core::log::trace!(
+                    openmina_core::log::system_time();
+                    kind = "IgnoredKademliaEvent",
+                    event = format!("{:?}", event)
+                );
+            }
+        }
+    }
+
+    /// Sends the peer with its dialable addresses to the state machine.
+    fn send_route<'a, E>(
+        b: &mut Behaviour<E>,
+        peer_id: PeerId,
+        addrs: impl Iterator<Item = &'a Multiaddr>,
+    ) where
+        E: From<P2pEvent>,
+    {
+        let Some(our_peer_id) = supported_peer_id(peer_id) else {
+            return;
+        };
+        let addrs = addrs
+            .filter_map(|addr| dialable_addr(addr, peer_id))
+            .map(|maddr| P2pConnectionOutgoingInitOpts::LibP2P {
+                peer_id: our_peer_id,
+                maddr,
+            })
+            .collect::<Vec<_>>();
+        if addrs.is_empty() {
+            return;
+        }
+        let event = P2pDiscoveryEvent::AddRoute(our_peer_id, addrs);
+        let _ = b
+            .event_source_sender
+            .send(P2pEvent::Discovery(event).into());
+    }
+}
+
+/// Our `PeerId` can only represent peers with ed25519 identity keys.
+pub(super) fn supported_peer_id(peer_id: PeerId) -> Option<crate::PeerId> {
+    const IDENTITY_MULTIHASH_CODE: u64 = 0x00;
+
+    let multihash = peer_id.as_ref();
+    if multihash.code() != IDENTITY_MULTIHASH_CODE {
+        return None;
+    }
+    libp2p::identity::PublicKey::from_protobuf_encoding(multihash.digest())
+        .ok()?
+        .into_ed25519()?;
+    Some(peer_id.into())
+}
+
+fn supported_peer_ids(peers: Vec<PeerId>) -> Vec<crate::PeerId> {
+    peers.into_iter().filter_map(supported_peer_id).collect()
+}
+
+/// Returns the address with `/p2p/<peer_id>` suffix, if it's an
+/// `ip/tcp` or `dns/tcp` address, which we can dial.
+fn dialable_addr(addr: &Multiaddr, peer_id: PeerId) -> Option<Multiaddr> {
+    let mut iter = addr.iter();
+    match iter.next()? {
+        Protocol::Ip4(ip) if !ip.is_loopback() && !ip.is_unspecified() => {}
+        Protocol::Ip6(ip) if !ip.is_loopback() && !ip.is_unspecified() => {}
+        Protocol::Dns(_) | Protocol::Dns4(_) | Protocol::Dns6(_) => {}
+        _ => return None,
+    }
+    let Protocol::Tcp(_) = iter.next()? else {
+        return None;
+    };
+    match iter.next() {
+        None => Some(addr.clone().with(Protocol::P2p(peer_id.into()))),
+        Some(Protocol::P2p(hash))
+            if PeerId::from_multihash(hash).ok() == Some(peer_id) && iter.next().is_none() =>
+        {
+            Some(addr.clone())
+        }
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use libp2p::identity::Keypair;
+    use libp2p::Multiaddr;
+
+    use super::dialable_addr;
+
+    #[test]
+    fn dialable_addresses() {
+        let peer_id = Keypair::generate_ed25519().public().to_peer_id();
+        let other_peer_id = Keypair::generate_ed25519().public().to_peer_id();
+        let dialable = |addr: &str| dialable_addr(&addr.parse::<Multiaddr>().unwrap(), peer_id);
+        let with_peer_id = |addr: &str| format!("{addr}/p2p/{peer_id}").parse().ok();
+
+        assert_eq!(
+            dialable("/ip4/1.2.3.4/tcp/8302"),
+            with_peer_id("/ip4/1.2.3.4/tcp/8302")
+        );
+        assert_eq!(
+            dialable("/dns4/seed.example.com/tcp/10001"),
+            with_peer_id("/dns4/seed.example.com/tcp/10001")
+        );
+        assert_eq!(
+            dialable(&format!("/ip4/1.2.3.4/tcp/8302/p2p/{peer_id}")),
+            with_peer_id("/ip4/1.2.3.4/tcp/8302")
+        );
+        assert_eq!(
+            dialable(&format!("/ip4/1.2.3.4/tcp/8302/p2p/{other_peer_id}")),
+            None
+        );
+        assert_eq!(dialable("/ip4/127.0.0.1/tcp/8302"), None);
+        assert_eq!(dialable("/ip4/0.0.0.0/tcp/8302"), None);
+        assert_eq!(dialable("/ip4/1.2.3.4/udp/8302/quic"), None);
+    }
+}
@@ -0,0 +1,589 @@
+//! Validation of the messages received through gossipsub.
+//!
+//! Messages are kept pending until the state machine validates them
+//! and reports the result with [`Cmd::GossipValidationResult`](super::Cmd).
+//! Transaction diffs are only checked for invalid keys and signatures
+//! right here, the rest is done by the transaction pool.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+use ledger::scan_state::transaction_logic::signed_command::SignedCommand;
+use ledger::scan_state::transaction_logic::{verifiable, TransactionStatus, WithStatus};
+use ledger::verifier::common::{check, CheckResult};
+use libp2p::gossipsub::{
+    Behaviour as Gossipsub, ConfigBuilder as GossipsubConfigBuilder, IdentTopic, MessageAcceptance,
+    MessageAuthenticity, MessageId, PeerScoreParams, PeerScoreThresholds, TopicHash,
+    TopicScoreParams,
+};
+use libp2p::identity::Keypair;
+use libp2p::{PeerId, Swarm};
+use mina_p2p_messages::number::Int32;
+use mina_p2p_messages::v2::{
+    MinaBaseUserCommandStableV2, NetworkPoolTransactionPoolDiffVersionedStableV2,
+};
+
+use crate::channels::{P2pGossipAcceptance, P2pGossipValidationKey};
+use crate::{P2pChannelEvent, P2pEvent, P2pGossipsubConfig, P2pGossipsubPeerScoringConfig};
+
+use super::{Behaviour, Libp2pService};
+
+/// Messages not validated by the state machine within this time are
+/// ignored. Block or snark might not get verified at all, e.g. if it's
+/// not better than what we already have.
+const GOSSIP_VALIDATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Gossip messages waiting for the state machine to validate them.
+#[derive(Default)]
+pub struct GossipValidationPending {
+    messages: BTreeMap<P2pGossipValidationKey, Vec<(MessageId, PeerId, Instant)>>,
+    /// Transaction diffs, whose transactions are validated separately.
+    /// Number of transactions yet to be validated and the acceptance
+    /// so far.
+    transaction_diffs: HashMap<MessageId, (usize, P2pGossipAcceptance)>,
+}
+
+impl GossipValidationPending {
+    fn insert(
+        &mut self,
+        key: P2pGossipValidationKey,
+        message_id: MessageId,
+        source: PeerId,
+        now: Instant,
+    ) {
+        self.messages
+            .entry(key)
+            .or_default()
+            .push((message_id, source, now));
+    }
+
+    /// Keeps the transaction diff until all of its transactions are
+    /// validated. `acceptance` is the result of the validation done so
+    /// far.
+    fn insert_transaction_diff(
+        &mut self,
+        keys: BTreeSet<P2pGossipValidationKey>,
+        acceptance: P2pGossipAcceptance,
+        message_id: MessageId,
+        source: PeerId,
+        now: Instant,
+    ) {
+        self.transaction_diffs
+            .insert(message_id.clone(), (keys.len(), acceptance));
+        for key in keys {
+            self.insert(key, message_id.clone(), source, now);
+        }
+    }
+
+    /// Removes messages with the given key, once their validation
+    /// result is known. Transaction diff is returned only once all of
+    /// its transactions are validated.
+    fn remove(
+        &mut self,
+        key: &P2pGossipValidationKey,
+        acceptance: P2pGossipAcceptance,
+    ) -> Vec<(MessageId, PeerId, P2pGossipAcceptance)> {
+        let messages = self.messages.remove(key).into_iter().flatten();
+        messages
+            .filter_map(|(message_id, source, _)| {
+                let Some((left, diff_acceptance)) = self.transaction_diffs.get_mut(&message_id)
+                else {
+                    return Some((message_id, source, acceptance));
+                };
+                *left -= 1;
+                *diff_acceptance = acceptance_combine(*diff_acceptance, acceptance);
+                if *left > 0 {
+                    return None;
+                }
+                let acceptance = *diff_acceptance;
+                self.transaction_diffs.remove(&message_id);
+                Some((message_id, source, acceptance))
+            })
+            .collect()
+    }
+
+    /// Removes messages, which weren't validated within
+    /// [`GOSSIP_VALIDATION_TIMEOUT`].
+    fn remove_expired(&mut self, now: Instant) -> Vec<(MessageId, PeerId)> {
+        let mut expired = vec![];
+        self.messages.retain(|_, messages| {
+            messages.retain(|(message_id, source, time)| {
+                let is_expired = now.saturating_duration_since(*time) > GOSSIP_VALIDATION_TIMEOUT;
+                if is_expired {
+                    expired.push((message_id.clone(), *source));
+                }
+                !is_expired
+            });
+            !messages.is_empty()
+        });
+        // Transaction diff is pending under several keys.
+        expired.sort();
+        expired.dedup();
+        for (message_id, _) in &expired {
+            self.transaction_diffs.remove(message_id);
+        }
+        expired
+    }
+}
+
+impl Libp2pService {
+    fn gossip_topics(config: &P2pGossipsubConfig) -> [(&'static str, usize); 4] {
+        let max_size = &config.max_message_size;
+        [
+            // Legacy topic, which carries messages of all kinds.
+            (Self::GOSSIPSUB_TOPIC, max_size.max()),
+            (Self::BLOCK_TOPIC, max_size.block),
+            (Self::TRANSACTION_TOPIC, max_size.transaction),
+            (Self::SNARK_WORK_TOPIC, max_size.snark_work),
+        ]
+    }
+
+    /// Gossipsub subscribed to all topics. Messages are validated by us
+    /// before they are forwarded.
+    pub(super) fn gossipsub(identity_keys: &Keypair, config: &P2pGossipsubConfig) -> Gossipsub {
+        Self::try_gossipsub(identity_keys, config).expect("invalid gossipsub config")
+    }
+
+    /// Checks that libp2p accepts the config, so that the invalid one
+    /// is reported before the node starts.
+    pub fn gossipsub_config_validate(config: &P2pGossipsubConfig) -> Result<(), String> {
+        Self::try_gossipsub(&Keypair::generate_ed25519(), config).map(|_| ())
+    }
+
+    fn try_gossipsub(
+        identity_keys: &Keypair,
+        config: &P2pGossipsubConfig,
+    ) -> Result<Gossipsub, String> {
+        let topics = Self::gossip_topics(config);
+        let gossipsub_config = GossipsubConfigBuilder::default()
+            .mesh_n(config.mesh_n)
+            .mesh_n_low(config.mesh_n_low)
+            .mesh_n_high(config.mesh_n_high)
+            .flood_publish(config.flood_publish)
+            .history_length(config.history_length)
+            .history_gossip(config.history_gossip)
+            // Per topic limits are checked once the message is received.
+            .max_transmit_size(config.max_message_size.max())
+            .validate_messages()
+            .build()
+            .map_err(|err| err.to_string())?;
+        let message_authenticity = MessageAuthenticity::Signed(identity_keys.clone());
+        let mut gossipsub = Gossipsub::new(message_authenticity, gossipsub_config)
+            .map_err(|err| err.to_string())?;
+        if let Some(scoring) = &config.peer_scoring {
+            let topics = topics
+                .iter()
+                .map(|(topic, _)| IdentTopic::new(*topic).hash());
+            let (params, thresholds) = peer_score_params(scoring, topics);
+            gossipsub
+                .with_peer_score(params, thresholds)
+                .map_err(|err| format!("invalid peer scoring config: {err}"))?;
+        }
+        for (topic, _) in topics {
+            gossipsub
+                .subscribe(&IdentTopic::new(topic))
+                .map_err(|err| format!("{err:?}"))?;
+        }
+        Ok(gossipsub)
+    }
+
+    pub(super) fn gossip_max_message_size(
+        config: &P2pGossipsubConfig,
+    ) -> HashMap<TopicHash, usize> {
+        Self::gossip_topics(config)
+            .into_iter()
+            .map(|(topic, max_size)| (IdentTopic::new(topic).hash(), max_size))
+            .collect()
+    }
+
+    /// Keeps the message until the state machine reports the result of
+    /// its validation. Expired messages are ignored.
+    pub(super) fn gossip_validation_pending<E>(
+        swarm: &mut Swarm<Behaviour<E>>,
+        key: P2pGossipValidationKey,
+        message_id: MessageId,
+        source: PeerId,
+    ) where
+        E: From<P2pEvent>,
+    {
+        Self::gossip_validation_check_timeouts(swarm);
+        swarm
+            .behaviour_mut()
+            .gossip_pending
+            .insert(key, message_id, source, Instant::now());
+    }
+
+    /// Ignores pending messages, which weren't validated in time, so
+    /// that gossipsub can drop them from its cache.
+    pub(super) fn gossip_validation_check_timeouts<E>(swarm: &mut Swarm<Behaviour<E>>)
+    where
+        E: From<P2pEvent>,
+    {
+        let b = swarm.behaviour_mut();
+        for (message_id, source) in b.gossip_pending.remove_expired(Instant::now()) {
+            let _ = b.gossipsub.report_message_validation_result(
+                &message_id,
+                &source,
+                MessageAcceptance::Ignore,
+            );
+        }
+    }
+
+    /// Reports the validation result for all pending messages with the
+    /// given key.
+    pub(super) fn gossip_validation_result<E>(
+        swarm: &mut Swarm<Behaviour<E>>,
+        key: &P2pGossipValidationKey,
+        acceptance: P2pGossipAcceptance,
+    ) where
+        E: From<P2pEvent>,
+    {
+        let b = swarm.behaviour_mut();
+        for (message_id, source, acceptance) in b.gossip_pending.remove(key, acceptance) {
+            let _ = b.gossipsub.report_message_validation_result(
+                &message_id,
+                &source,
+                acceptance.into(),
+            );
+        }
+    }
+
+    /// Sends transactions from the received diff to the state machine,
+    /// unless the diff is invalid. Diff is forwarded only if the
+    /// transaction pool accepts all of its transactions.
+    pub(super) fn handle_gossip_transaction_pool_diff<E>(
+        swarm: &mut Swarm<Behaviour<E>>,
+        message_id: MessageId,
+        peer_id: PeerId,
+        diff: NetworkPoolTransactionPoolDiffVersionedStableV2,
+        nonce: Int32,
+    ) where
+        E: From<P2pEvent>,
+    {
+        let acceptance = match validate_transaction_pool_diff(&diff) {
+            MessageAcceptance::Accept => P2pGossipAcceptance::Accept,
+            MessageAcceptance::Ignore => P2pGossipAcceptance::Ignore,
+            MessageAcceptance::Reject => P2pGossipAcceptance::Reject,
+        };
+        // TODO: zkapp command hashing isn't supported yet, those are
+        // ignored by the state machine.
+        let keys = diff
+            .0
+            .iter()
+            .filter_map(|transaction| transaction.hash().ok())
+            .map(P2pGossipValidationKey::Transaction)
+            .collect::<BTreeSet<_>>();
+        let acceptance_now = match acceptance {
+            P2pGossipAcceptance::Reject => Some(P2pGossipAcceptance::Reject),
+            _ if keys.is_empty() => Some(P2pGossipAcceptance::Ignore),
+            _ => None,
+        };
+        if let Some(acceptance) = acceptance_now {
+            let _ = swarm
+                .behaviour_mut()
+                .gossipsub
+                .report_message_validation_result(&message_id, &peer_id, acceptance.into());
+            return;
+        }
+
+        Self::gossip_validation_check_timeouts(swarm);
+        swarm
+            .behaviour_mut()
+            .gossip_pending
+            .insert_transaction_diff(keys, acceptance, message_id, peer_id, Instant::now());
+        let transactions = diff.0.into_iter().map(Box::new).collect();
+        let event = P2pEvent::Channel(P2pChannelEvent::Libp2pTransactionReceived(
+            peer_id.into(),
+            transactions,
+            nonce.as_u32(),
+        ));
+        let _ = swarm.behaviour_mut().event_source_sender.send(event.into());
+    }
+}
+
+fn peer_score_params(
+    config: &P2pGossipsubPeerScoringConfig,
+    topics: impl Iterator<Item = TopicHash>,
+) -> (PeerScoreParams, PeerScoreThresholds) {
+    let topic_params = TopicScoreParams {
+        invalid_message_deliveries_weight: config.invalid_message_weight,
+        invalid_message_deliveries_decay: config.invalid_message_decay,
+        // Some topics are quiet (e.g. snark work when nobody is
+        // snarking), so mesh peers aren't penalized for not delivering
+        // messages.
+        mesh_message_deliveries_weight: 0.0,
+        mesh_failure_penalty_weight: 0.0,
+        ..Default::default()
+    };
+    let params = PeerScoreParams {
+        topics: topics.map(|topic| (topic, topic_params.clone())).collect(),
+        ip_colocation_factor_weight: config.ip_colocation_factor_weight,
+        ip_colocation_factor_threshold: config.ip_colocation_factor_threshold,
+        behaviour_penalty_weight: config.behaviour_penalty_weight,
+        decay_interval: config.decay_interval,
+        ..Default::default()
+    };
+    let thresholds = PeerScoreThresholds {
+        gossip_threshold: config.gossip_threshold,
+        publish_threshold: config.publish_threshold,
+        graylist_threshold: config.graylist_threshold,
+        accept_px_threshold: config.accept_px_threshold,
+        opportunistic_graft_threshold: config.opportunistic_graft_threshold,
+    };
+    (params, thresholds)
+}
+
+/// Acceptance of the message made of several parts, e.g. transaction
+/// diff. Message is forwarded only if all the parts are accepted.
+fn acceptance_combine(a: P2pGossipAcceptance, b: P2pGossipAcceptance) -> P2pGossipAcceptance {
+    match (a, b) {
+        (P2pGossipAcceptance::Reject, _) | (_, P2pGossipAcceptance::Reject) => {
+            P2pGossipAcceptance::Reject
+        }
+        (P2pGossipAcceptance::Ignore, _) | (_, P2pGossipAcceptance::Ignore) => {
+            P2pGossipAcceptance::Ignore
+        }
+        _ => P2pGossipAcceptance::Accept,
+    }
+}
+
+impl From<P2pGossipAcceptance> for MessageAcceptance {
+    fn from(value: P2pGossipAcceptance) -> Self {
+        match value {
+            P2pGossipAcceptance::Accept => Self::Accept,
+            P2pGossipAcceptance::Reject => Self::Reject,
+            P2pGossipAcceptance::Ignore => Self::Ignore,
+        }
+    }
+}
+
+/// Stateless validation of the transaction pool diff. Diffs which are
+/// empty or contain commands with invalid keys or signature are
+/// rejected, so that gossipsub doesn't forward them and penalizes the
+/// sender.
+///
+/// Checks which depend on our best tip (nonce, balance, fee) are done
+/// by the transaction pool and don't affect the acceptance, as peers
+/// might be at a different best tip.
+pub fn validate_transaction_pool_diff(
+    diff: &NetworkPoolTransactionPoolDiffVersionedStableV2,
+) -> MessageAcceptance {
+    if diff.0.is_empty() {
+        return MessageAcceptance::Reject;
+    }
+    let mut acceptance = MessageAcceptance::Accept;
+    for cmd in &diff.0 {
+        match cmd {
+            MinaBaseUserCommandStableV2::SignedCommand(cmd) => {
+                let cmd = WithStatus {
+                    data: verifiable::UserCommand::SignedCommand(Box::new(SignedCommand::from(
+                        cmd,
+                    ))),
+                    status: TransactionStatus::Applied,
+                };
+                if !matches!(check(cmd), CheckResult::Valid(_)) {
+                    return MessageAcceptance::Reject;
+                }
+            }
+            // We can't verify zkapp commands yet, so we don't forward
+            // them, but don't penalize the sender either.
+            MinaBaseUserCommandStableV2::ZkappCommand(_) => {
+                acceptance = MessageAcceptance::Ignore;
+            }
+        }
+    }
+    acceptance
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::time::{Duration, Instant};
+
+    use libp2p::gossipsub::{IdentTopic, MessageAcceptance, MessageId};
+    use libp2p::PeerId;
+    use mina_p2p_messages::v2::{
+        NetworkPoolTransactionPoolDiffVersionedStableV2, StateHash, TransactionHash,
+    };
+
+    use crate::channels::{P2pGossipAcceptance, P2pGossipValidationKey};
+    use crate::P2pGossipsubConfig;
+
+    use super::{
+        peer_score_params, validate_transaction_pool_diff, GossipValidationPending, Libp2pService,
+        GOSSIP_VALIDATION_TIMEOUT,
+    };
+
+    const ACCEPT: P2pGossipAcceptance = P2pGossipAcceptance::Accept;
+    const REJECT: P2pGossipAcceptance = P2pGossipAcceptance::Reject;
+    const IGNORE: P2pGossipAcceptance = P2pGossipAcceptance::Ignore;
+
+    fn transaction_key(n: u8) -> P2pGossipValidationKey {
+        let hash = match n {
+            1 => "KwPN2VaGWejbfwL28b82bYvHVWSeXVEWuJYB3ktTpPuvaKTZPzUE7",
+            2 => "KwPN2wDcMRn4qR8T7MdoY42sTNRwqbYBGsib5NAfgN52BaL6ZAYbN",
+            _ => "KwPN3NrxCCpXztvt689aUZ9TRERF9hqqeSu16ySsYLE7nqCoHRQQy",
+        };
+        P2pGossipValidationKey::Transaction(hash.parse::<TransactionHash>().unwrap())
+    }
+
+    fn block_key(n: u8) -> P2pGossipValidationKey {
+        let hash = match n {
+            1 => "3NLESd9gzU52bDWSXL5uUAYbCojHXSVdeBX4sCMF3V8Ns9D1Sriy",
+            _ => "3NLQfKJ4kBagLgmiwyiVw9zbi53tiNy8TNu2ua1jmCyEecgbBJoN",
+        };
+        P2pGossipValidationKey::Block(hash.parse::<StateHash>().unwrap())
+    }
+
+    #[test]
+    fn gossip_validation_result_removes_pending_messages() {
+        let now = Instant::now();
+        let (a, b) = (PeerId::random(), PeerId::random());
+        let mut pending = GossipValidationPending::default();
+        pending.insert(block_key(1), MessageId::from("m1"), a, now);
+        pending.insert(block_key(1), MessageId::from("m2"), b, now);
+        pending.insert(block_key(2), MessageId::from("m3"), a, now);
+
+        // Same block received from 2 peers gets the result reported to
+        // both of them, be it accept or reject.
+        assert_eq!(
+            pending.remove(&block_key(1), ACCEPT),
+            vec![
+                (MessageId::from("m1"), a, ACCEPT),
+                (MessageId::from("m2"), b, ACCEPT)
+            ]
+        );
+        assert_eq!(pending.remove(&block_key(1), ACCEPT), vec![]);
+        assert_eq!(
+            pending.remove(&block_key(2), REJECT),
+            vec![(MessageId::from("m3"), a, REJECT)]
+        );
+        assert!(pending.messages.is_empty());
+
+        assert!(matches!(
+            MessageAcceptance::from(P2pGossipAcceptance::Accept),
+            MessageAcceptance::Accept
+        ));
+        assert!(matches!(
+            MessageAcceptance::from(P2pGossipAcceptance::Reject),
+            MessageAcceptance::Reject
+        ));
+        assert!(matches!(
+            MessageAcceptance::from(P2pGossipAcceptance::Ignore),
+            MessageAcceptance::Ignore
+        ));
+    }
+
+    #[test]
+    fn gossip_validation_pending_expires() {
+        let now = Instant::now();
+        let peer_id = PeerId::random();
+        let mut pending = GossipValidationPending::default();
+        pending.insert(block_key(1), MessageId::from("old"), peer_id, now);
+        let later = now + GOSSIP_VALIDATION_TIMEOUT / 2;
+        pending.insert(block_key(2), MessageId::from("new"), peer_id, later);
+
+        assert_eq!(
+            pending.remove_expired(now + GOSSIP_VALIDATION_TIMEOUT),
+            vec![]
+        );
+        let expired_at = now + GOSSIP_VALIDATION_TIMEOUT + Duration::from_secs(1);
+        assert_eq!(
+            pending.remove_expired(expired_at),
+            vec![(MessageId::from("old"), peer_id)]
+        );
+        // Result reported after the expiry is a no-op.
+        assert_eq!(pending.remove(&block_key(1), ACCEPT), vec![]);
+        assert_eq!(
+            pending.remove(&block_key(2), ACCEPT),
+            vec![(MessageId::from("new"), peer_id, ACCEPT)]
+        );
+    }
+
+    #[test]
+    fn transaction_diff_validated_once_all_transactions_are() {
+        let now = Instant::now();
+        let (a, b) = (PeerId::random(), PeerId::random());
+        let mut pending = GossipValidationPending::default();
+        let keys = |keys: &[u8]| {
+            keys.iter()
+                .map(|n| transaction_key(*n))
+                .collect::<BTreeSet<_>>()
+        };
+        pending.insert_transaction_diff(keys(&[1, 2]), ACCEPT, MessageId::from("d1"), a, now);
+        pending.insert_transaction_diff(keys(&[2]), ACCEPT, MessageId::from("d2"), b, now);
+        pending.insert_transaction_diff(keys(&[1, 3]), IGNORE, MessageId::from("d3"), b, now);
+
+        assert_eq!(pending.remove(&transaction_key(1), ACCEPT), vec![]);
+        assert_eq!(
+            pending.remove(&transaction_key(2), ACCEPT),
+            vec![
+                (MessageId::from("d1"), a, ACCEPT),
+                (MessageId::from("d2"), b, ACCEPT)
+            ]
+        );
+        // Diff with a transaction that couldn't be validated isn't
+        // forwarded, one with an invalid transaction gets rejected.
+        assert_eq!(
+            pending.remove(&transaction_key(3), ACCEPT),
+            vec![(MessageId::from("d3"), b, IGNORE)]
+        );
+        pending.insert_transaction_diff(keys(&[1, 2]), ACCEPT, MessageId::from("d4"), a, now);
+        assert_eq!(pending.remove(&transaction_key(1), REJECT), vec![]);
+        assert_eq!(
+            pending.remove(&transaction_key(2), ACCEPT),
+            vec![(MessageId::from("d4"), a, REJECT)]
+        );
+        assert!(pending.messages.is_empty());
+        assert!(pending.transaction_diffs.is_empty());
+
+        // Expired diff is ignored once.
+        pending.insert_transaction_diff(keys(&[1, 2]), ACCEPT, MessageId::from("d5"), a, now);
+        let expired_at = now + GOSSIP_VALIDATION_TIMEOUT + Duration::from_secs(1);
+        assert_eq!(
+            pending.remove_expired(expired_at),
+            vec![(MessageId::from("d5"), a)]
+        );
+        assert!(pending.transaction_diffs.is_empty());
+    }
+
+    #[test]
+    fn default_gossipsub_config_valid() {
+        let config = P2pGossipsubConfig::default();
+        let scoring = config.peer_scoring.as_ref().unwrap();
+        let topics = Libp2pService::gossip_topics(&config)
+            .map(|(topic, _)| IdentTopic::new(topic).hash())
+            .into_iter();
+        let (params, thresholds) = peer_score_params(scoring, topics);
+        params.validate().unwrap();
+        thresholds.validate().unwrap();
+
+        Libp2pService::gossipsub_config_validate(&config).unwrap();
+        let no_scoring = P2pGossipsubConfig {
+            peer_scoring: None,
+            ..config
+        };
+        Libp2pService::gossipsub_config_validate(&no_scoring).unwrap();
+    }
+
+    #[test]
+    fn invalid_gossipsub_config_reported() {
+        let mut config = P2pGossipsubConfig::default();
+        config.mesh_n_high = config.mesh_n_low - 1;
+        assert!(Libp2pService::gossipsub_config_validate(&config).is_err());
+
+        let mut config = P2pGossipsubConfig::default();
+        // Thresholds must be ordered: publish <= gossip.
+        config.peer_scoring.as_mut().unwrap().publish_threshold = 0.0;
+        assert!(Libp2pService::gossipsub_config_validate(&config).is_err());
+    }
+
+    #[test]
+    fn empty_transaction_pool_diff_rejected() {
+        let diff = NetworkPoolTransactionPoolDiffVersionedStableV2(vec![]);
+        assert!(matches!(
+            validate_transaction_pool_diff(&diff),
+            MessageAcceptance::Reject
+        ));
+    }
+}
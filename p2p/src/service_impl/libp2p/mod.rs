@@ -4,13 +4,19 @@ pub use behavior::*;
 use mina_p2p_messages::rpc::GetSomeInitialPeersV1ForV2;
 
 mod discovery;
+mod gossip;
+mod node_status;
+pub use gossip::validate_transaction_pool_diff;
 
 use std::collections::BTreeMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use mina_p2p_messages::binprot::{self, BinProtRead, BinProtWrite};
-use mina_p2p_messages::v2::NetworkPoolSnarkPoolDiffVersionedStableV2;
+use mina_p2p_messages::v2::{
+    MinaBaseUserCommandStableV2, NetworkPoolSnarkPoolDiffVersionedStableV2,
+    NetworkPoolTransactionPoolDiffVersionedStableV2,
+};
 use multihash::{Blake2b256, Hasher};
 use openmina_core::channels::mpsc;
 use openmina_core::snark::Snark;
@@ -19,10 +25,7 @@ use libp2p::core::muxing::StreamMuxerBox;
 use libp2p::core::transport;
 use libp2p::core::transport::upgrade;
 use libp2p::futures::{select, FutureExt, StreamExt};
-use libp2p::gossipsub::{
-    Behaviour as Gossipsub, ConfigBuilder as GossipsubConfigBuilder, Event as GossipsubEvent,
-    IdentTopic, MessageAcceptance, MessageAuthenticity,
-};
+use libp2p::gossipsub::{Event as GossipsubEvent, IdentTopic, MessageAcceptance};
 use libp2p::identity::Keypair;
 use libp2p::noise;
 use libp2p::pnet::{PnetConfig, PreSharedKey};
@@ -40,9 +43,9 @@ use crate::channels::rpc::{
     BestTipWithProof, P2pRpcRequest, P2pRpcResponse, RpcChannelMsg,
     StagedLedgerAuxAndPendingCoinbases,
 };
-use crate::channels::{ChannelId, ChannelMsg};
+use crate::channels::{ChannelId, ChannelMsg, P2pGossipAcceptance, P2pGossipValidationKey};
 use crate::identity::SecretKey;
-use crate::{P2pChannelEvent, P2pConnectionEvent, P2pEvent};
+use crate::{P2pChannelEvent, P2pConnectionEvent, P2pEvent, P2pGossipsubConfig};
 
 use super::TaskSpawner;
 
@@ -55,8 +58,14 @@ pub type BoxedP2PTransport = transport::Boxed<P2PTransport>;
 pub enum Cmd {
     Dial(DialOpts),
     Disconnect(PeerId),
+    Ban(PeerId),
+    Unban(PeerId),
     SendMessage(PeerId, ChannelMsg),
     SnarkBroadcast(Snark, u32),
+    TransactionBroadcast(Box<MinaBaseUserCommandStableV2>, u32),
+    GossipValidationResult(P2pGossipValidationKey, P2pGossipAcceptance),
+    GossipValidationCheckTimeouts,
+    KademliaLookupRandom,
 }
 
 pub struct Libp2pService {
@@ -65,6 +74,9 @@ pub struct Libp2pService {
 
 impl Libp2pService {
     const GOSSIPSUB_TOPIC: &'static str = "coda/consensus-messages/0.0.1";
+    const BLOCK_TOPIC: &'static str = "mina/block/1.0.0";
+    const TRANSACTION_TOPIC: &'static str = "mina/tx/1.0.0";
+    const SNARK_WORK_TOPIC: &'static str = "mina/snark-work/1.0.0";
 
     async fn build_transport(
         chain_id: String,
@@ -121,6 +133,7 @@ impl Libp2pService {
     pub fn run<E, S>(
         secret_key: SecretKey,
         chain_id: String,
+        gossipsub_config: P2pGossipsubConfig,
         event_source_sender: mpsc::UnboundedSender<E>,
         spawner: S,
     ) -> Self
@@ -128,35 +141,19 @@ impl Libp2pService {
         E: 'static + Send + From<P2pEvent>,
         S: TaskSpawner,
     {
-        let topics_iter = IntoIterator::into_iter([
-            Self::GOSSIPSUB_TOPIC,
-            "mina/block/1.0.0",
-            "mina/tx/1.0.0",
-            "mina/snark-work/1.0.0",
-        ]);
-
         let identity_keys = Keypair::ed25519_from_bytes(secret_key.to_bytes())
             .expect("secret key bytes must be valid");
 
-        let message_authenticity = MessageAuthenticity::Signed(identity_keys.clone());
-        let gossipsub_config = GossipsubConfigBuilder::default()
-            .max_transmit_size(1024 * 1024 * 32)
-            .validate_messages()
-            .build()
-            .unwrap();
-        let mut gossipsub: Gossipsub =
-            Gossipsub::new(message_authenticity, gossipsub_config).unwrap();
-        topics_iter
-            .map(|v| IdentTopic::new(v))
-            .for_each(|topic| assert!(gossipsub.subscribe(&topic).unwrap()));
-
+        let local_peer_id = identity_keys.public().to_peer_id();
         let behaviour = Behaviour {
-            gossipsub,
+            gossipsub: Self::gossipsub(&identity_keys, &gossipsub_config),
+            identify: Self::identify(&identity_keys),
+            kademlia: Self::kademlia(local_peer_id),
             rpc: {
                 use mina_p2p_messages::rpc::{
-                    AnswerSyncLedgerQueryV2, GetAncestryV2, GetBestTipV2,
-                    GetStagedLedgerAuxAndPendingCoinbasesAtHashV2, GetTransitionChainProofV1ForV2,
-                    GetTransitionChainV2,
+                    AnswerSyncLedgerQueryV2, GetAncestryV2, GetBestTipV2, GetEpochLedgerV2,
+                    GetNodeStatusV2, GetStagedLedgerAuxAndPendingCoinbasesAtHashV2,
+                    GetTransitionChainProofV1ForV2, GetTransitionChainV2,
                 };
 
                 BehaviourBuilder::default()
@@ -166,11 +163,17 @@ impl Libp2pService {
                     .register_method::<AnswerSyncLedgerQueryV2>()
                     .register_method::<GetTransitionChainV2>()
                     .register_method::<GetTransitionChainProofV1ForV2>()
+                    .register_method::<GetNodeStatusV2>()
+                    .register_method::<GetEpochLedgerV2>()
                     .build()
             },
             event_source_sender,
             ongoing: BTreeMap::default(),
             ongoing_incoming: BTreeMap::default(),
+            gossip_pending: Default::default(),
+            gossip_max_message_size: Self::gossip_max_message_size(&gossipsub_config),
+            started_at: Instant::now(),
+            banned_peers: Default::default(),
         };
 
         let (cmd_sender, mut cmd_receiver) = mpsc::unbounded_channel();
@@ -201,7 +204,7 @@ impl Libp2pService {
         Self { cmd_sender }
     }
 
-    fn gossipsub_send<E>(swarm: &mut Swarm<Behaviour<E>>, msg: &GossipNetMessage)
+    fn gossipsub_send<E>(swarm: &mut Swarm<Behaviour<E>>, topic: &str, msg: &GossipNetMessage)
     where
         E: From<P2pEvent>,
     {
@@ -218,7 +221,7 @@ impl Libp2pService {
         let msg_len = (encoded.len() as u64 - 8).to_le_bytes();
         encoded[..8].clone_from_slice(&msg_len);
 
-        let topic = IdentTopic::new(Self::GOSSIPSUB_TOPIC);
+        let topic = IdentTopic::new(topic);
         let _ = swarm.behaviour_mut().gossipsub.publish(topic, encoded);
     }
 
@@ -236,7 +239,17 @@ impl Libp2pService {
             Cmd::Disconnect(peer_id) => {
                 let _ = swarm.disconnect_peer_id(peer_id);
             }
+            Cmd::Ban(peer_id) => {
+                swarm.behaviour_mut().banned_peers.insert(peer_id);
+                let _ = swarm.disconnect_peer_id(peer_id);
+            }
+            Cmd::Unban(peer_id) => {
+                swarm.behaviour_mut().banned_peers.remove(&peer_id);
+            }
             Cmd::SendMessage(peer_id, msg) => match msg {
+                ChannelMsg::TransactionPropagation(_) => {
+                    // unsupported. Instead `Cmd::TransactionBroadcast` will be used.
+                }
                 ChannelMsg::SnarkPropagation(_) => {
                     // unsupported. Instead `Cmd::SnarkBroadcast` will be used.
                 }
@@ -258,6 +271,7 @@ impl Libp2pService {
                         // from being published, but it's still wasteful.
                         Self::gossipsub_send(
                             swarm,
+                            Self::GOSSIPSUB_TOPIC,
                             &GossipNetMessage::NewState(block.as_ref().clone()),
                         );
                         // TODO(binier): send event: `P2pChannelEvent::Sent`
@@ -272,8 +286,26 @@ impl Libp2pService {
                 let message = Box::new((snark.statement(), (&snark).into()));
                 let message = NetworkPoolSnarkPoolDiffVersionedStableV2::AddSolvedWork(message);
                 let nonce = nonce.into();
-                Self::gossipsub_send(swarm, &GossipNetMessage::SnarkPoolDiff { message, nonce });
+                Self::gossipsub_send(
+                    swarm,
+                    Self::GOSSIPSUB_TOPIC,
+                    &GossipNetMessage::SnarkPoolDiff { message, nonce },
+                );
+            }
+            Cmd::TransactionBroadcast(transaction, nonce) => {
+                let message = NetworkPoolTransactionPoolDiffVersionedStableV2(vec![*transaction]);
+                let nonce = nonce.into();
+                Self::gossipsub_send(
+                    swarm,
+                    Self::TRANSACTION_TOPIC,
+                    &GossipNetMessage::TransactionPoolDiff { message, nonce },
+                );
             }
+            Cmd::GossipValidationResult(key, acceptance) => {
+                Self::gossip_validation_result(swarm, &key, acceptance);
+            }
+            Cmd::GossipValidationCheckTimeouts => Self::gossip_validation_check_timeouts(swarm),
+            Cmd::KademliaLookupRandom => Self::kademlia_lookup_random(swarm),
         }
     }
 
@@ -285,13 +317,15 @@ impl Libp2pService {
         use mina_p2p_messages::{
             core::Info,
             rpc::{
-                AnswerSyncLedgerQueryV2, GetAncestryV2, GetBestTipV2,
-                GetStagedLedgerAuxAndPendingCoinbasesAtHashV2, GetTransitionChainV2,
-                ProofCarryingDataStableV1, ProofCarryingDataWithHashV1,
+                AnswerSyncLedgerQueryV2, GetAncestryV2, GetBestTipV2, GetEpochLedgerV2,
+                GetNodeStatusV2, GetStagedLedgerAuxAndPendingCoinbasesAtHashV2,
+                GetTransitionChainProofV1ForV2, GetTransitionChainV2, ProofCarryingDataStableV1,
+                ProofCarryingDataWithHashV1, WithHashV1,
             },
             rpc_kernel::{RpcMethod, RpcResult},
         };
 
+        let local_peer_id = *swarm.local_peer_id();
         let b = swarm.behaviour_mut();
         match msg {
             RpcChannelMsg::Request(id, req) => {
@@ -329,6 +363,30 @@ impl Libp2pService {
                         b.ongoing.insert(key, (T::NAME.to_string(), T::VERSION));
                         b.rpc.query::<T>(peer_id, stream_id, id, ())?;
                     }
+                    P2pRpcRequest::TransitionChainProof(hash) => {
+                        type T = GetTransitionChainProofV1ForV2;
+                        b.ongoing.insert(key, (T::NAME.to_string(), T::VERSION));
+                        b.rpc.query::<T>(peer_id, stream_id, id, hash.0.clone())?;
+                    }
+                    P2pRpcRequest::NodeStatus => {
+                        type T = GetNodeStatusV2;
+                        b.ongoing.insert(key, (T::NAME.to_string(), T::VERSION));
+                        b.rpc.query::<T>(peer_id, stream_id, id, ())?;
+                    }
+                    P2pRpcRequest::EpochLedger(hash) => {
+                        type T = GetEpochLedgerV2;
+                        b.ongoing.insert(key, (T::NAME.to_string(), T::VERSION));
+                        b.rpc.query::<T>(peer_id, stream_id, id, hash.0.clone())?;
+                    }
+                    P2pRpcRequest::Ancestry(hash, consensus_state) => {
+                        type T = GetAncestryV2;
+                        b.ongoing.insert(key, (T::NAME.to_string(), T::VERSION));
+                        let query = WithHashV1 {
+                            data: *consensus_state,
+                            hash: hash.0.clone(),
+                        };
+                        b.rpc.query::<T>(peer_id, stream_id, id, query)?;
+                    }
                 };
             }
             RpcChannelMsg::Response(id, resp) => {
@@ -371,6 +429,33 @@ impl Libp2pService {
                                 type T = GetSomeInitialPeersV1ForV2;
                                 b.rpc.respond::<T>(peer_id, stream_id, id, Ok(vec![]))?
                             }
+                            (
+                                GetTransitionChainProofV1ForV2::NAME,
+                                GetTransitionChainProofV1ForV2::VERSION,
+                            ) => {
+                                type T = GetTransitionChainProofV1ForV2;
+                                b.rpc.respond::<T>(peer_id, stream_id, id, Ok(None))?
+                            }
+                            (GetNodeStatusV2::NAME, GetNodeStatusV2::VERSION) => {
+                                type T = GetNodeStatusV2;
+                                let err = Info::String("node status not available".into());
+                                b.rpc.respond::<T>(
+                                    peer_id,
+                                    stream_id,
+                                    id,
+                                    Ok(RpcResult(Err(err))),
+                                )?
+                            }
+                            (GetEpochLedgerV2::NAME, GetEpochLedgerV2::VERSION) => {
+                                type T = GetEpochLedgerV2;
+                                let err = "epoch ledger not available".into();
+                                b.rpc.respond::<T>(
+                                    peer_id,
+                                    stream_id,
+                                    id,
+                                    Ok(RpcResult(Err(err))),
+                                )?
+                            }
                             _ => {}
                         },
                         Some(P2pRpcResponse::BestTipWithProof(msg)) => {
@@ -418,6 +503,25 @@ impl Libp2pService {
                             let r = Ok(peers);
                             b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
                         }
+                        Some(P2pRpcResponse::TransitionChainProof(first_hash, body_hashes)) => {
+                            type T = GetTransitionChainProofV1ForV2;
+                            let body_hashes = body_hashes.into_iter().map(|hash| hash.0).collect();
+                            let r = Ok(Some((first_hash.0.clone(), body_hashes)));
+                            b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
+                        }
+                        Some(P2pRpcResponse::NodeStatus(status)) => {
+                            type T = GetNodeStatusV2;
+                            let uptime = b.started_at.elapsed();
+                            let status =
+                                node_status::node_status_v2(*status, local_peer_id, uptime);
+                            let r = Ok(RpcResult(Ok(status)));
+                            b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
+                        }
+                        Some(P2pRpcResponse::EpochLedger(ledger)) => {
+                            type T = GetEpochLedgerV2;
+                            let r = Ok(RpcResult(Ok((*ledger).clone())));
+                            b.rpc.respond::<T>(peer_id, stream_id, id, r)?;
+                        }
                     }
                 }
             }
@@ -434,12 +538,28 @@ impl Libp2pService {
             SwarmEvent::ConnectionEstablished {
                 peer_id, endpoint, ..
             } => {
+                if swarm.behaviour().banned_peers.contains(&peer_id) {
+                    openmina_core::log::info!(
+                        openmina_core::log::system_time();
+                        kind = "PeerBannedRefused",
+                        summary = format!("peer_id: {}", peer_id),
+                        peer_id = peer_id.to_string()
+                    );
+                    let _ = swarm.disconnect_peer_id(peer_id);
+                    return;
+                }
                 openmina_core::log::info!(
                     openmina_core::log::system_time();
                     kind = "PeerConnected",
                     summary = format!("peer_id: {}", peer_id),
                     peer_id = peer_id.to_string()
                 );
+                if endpoint.is_dialer() {
+                    // We know the address is reachable, so it can be
+                    // used in kademlia lookups.
+                    let addr = endpoint.get_remote_address().clone();
+                    swarm.behaviour_mut().kademlia.add_address(&peer_id, addr);
+                }
                 let event = if endpoint.is_dialer() {
                     P2pEvent::Connection(P2pConnectionEvent::Finalized(peer_id.into(), Ok(())))
                 } else {
@@ -478,42 +598,70 @@ impl Libp2pService {
                     message_id,
                     message,
                 }) => {
-                    // We will manually publish applied blocks.
-                    // TODO(binier): better approach
-                    let _ = swarm
-                        .behaviour_mut()
-                        .gossipsub
-                        .report_message_validation_result(
-                            &message_id,
-                            &propagation_source,
-                            MessageAcceptance::Ignore,
-                        );
-
                     let bytes = &message.data;
-                    let res = if bytes.len() < 8 {
+                    let max_size = swarm
+                        .behaviour()
+                        .gossip_max_message_size
+                        .get(&message.topic)
+                        .copied()
+                        .unwrap_or(usize::MAX);
+                    let res = if bytes.len() > max_size {
+                        Err(format!("message too large: {} > {max_size}", bytes.len()))
+                    } else if bytes.len() < 8 {
                         Err("message too short".to_owned())
                     } else {
                         let len = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
                         let data = &bytes[8..];
-                        assert_eq!(len, data.len() as u64);
-                        GossipNetMessage::binprot_read(&mut &*data)
-                            .map_err(|err| format!("{err:?}"))
+                        if len != data.len() as u64 {
+                            Err(format!("length mismatch: {len} != {}", data.len()))
+                        } else {
+                            GossipNetMessage::binprot_read(&mut &*data)
+                                .map_err(|err| format!("{err:?}"))
+                        }
+                    };
+
+                    let report = |swarm: &mut Swarm<Behaviour<E>>, acceptance| {
+                        let _ = swarm
+                            .behaviour_mut()
+                            .gossipsub
+                            .report_message_validation_result(
+                                &message_id,
+                                &propagation_source,
+                                acceptance,
+                            );
                     };
+
                     let res = match res {
-                        Err(err) => Err(err),
                         Ok(GossipNetMessage::NewState(block)) => {
+                            // Block is forwarded once the state machine
+                            // verifies it.
+                            let key = P2pGossipValidationKey::Block(block.hash());
+                            Self::gossip_validation_pending(
+                                swarm,
+                                key,
+                                message_id.clone(),
+                                propagation_source,
+                            );
                             Ok(ChannelMsg::BestTipPropagation(
                                 BestTipPropagationChannelMsg::BestTip(block.into()),
                             ))
                         }
                         Ok(GossipNetMessage::SnarkPoolDiff { message, nonce }) => match message {
-                            // TODO(binier): Why empty? Should we error?
-                            NetworkPoolSnarkPoolDiffVersionedStableV2::Empty => return,
+                            NetworkPoolSnarkPoolDiffVersionedStableV2::Empty => {
+                                return report(swarm, MessageAcceptance::Ignore);
+                            }
                             NetworkPoolSnarkPoolDiffVersionedStableV2::AddSolvedWork(work) => {
+                                let snark = Snark::from(work.1);
+                                Self::gossip_validation_pending(
+                                    swarm,
+                                    P2pGossipValidationKey::snark(&snark),
+                                    message_id.clone(),
+                                    propagation_source,
+                                );
                                 let event =
                                     P2pEvent::Channel(P2pChannelEvent::Libp2pSnarkReceived(
                                         propagation_source.into(),
-                                        work.1.into(),
+                                        snark,
                                         nonce.as_u32(),
                                     ));
                                 let _ =
@@ -521,7 +669,23 @@ impl Libp2pService {
                                 return;
                             }
                         },
-                        _ => return,
+                        Ok(GossipNetMessage::TransactionPoolDiff { message, nonce }) => {
+                            // Diff is forwarded once the transaction
+                            // pool validates its transactions.
+                            return Self::handle_gossip_transaction_pool_diff(
+                                swarm,
+                                message_id.clone(),
+                                propagation_source,
+                                message,
+                                nonce,
+                            );
+                        }
+                        Err(err) => {
+                            // Peer sent garbage, don't forward it and
+                            // penalize the peer.
+                            report(swarm, MessageAcceptance::Reject);
+                            Err(err)
+                        }
                     };
 
                     let event = P2pEvent::Channel(P2pChannelEvent::Received(
@@ -533,6 +697,12 @@ impl Libp2pService {
                 BehaviourEvent::Rpc((peer_id, event)) => {
                     Self::handle_event_rpc(swarm, peer_id, event);
                 }
+                BehaviourEvent::Identify(event) => {
+                    Self::handle_event_identify(swarm, event);
+                }
+                BehaviourEvent::Kademlia(event) => {
+                    Self::handle_event_kademlia(swarm, event);
+                }
                 _ => {
                     openmina_core::log::trace!(
                         openmina_core::log::system_time();
@@ -578,8 +748,8 @@ impl Libp2pService {
                 use libp2p_rpc_behaviour::Received;
                 use mina_p2p_messages::{
                     rpc::{
-                        AnswerSyncLedgerQueryV2, GetAncestryV2, GetBestTipV2,
-                        GetStagedLedgerAuxAndPendingCoinbasesAtHashV2,
+                        AnswerSyncLedgerQueryV2, GetAncestryV2, GetBestTipV2, GetEpochLedgerV2,
+                        GetNodeStatusV2, GetStagedLedgerAuxAndPendingCoinbasesAtHashV2,
                         GetTransitionChainProofV1ForV2, GetTransitionChainV2,
                     },
                     rpc_kernel::{
@@ -640,12 +810,10 @@ impl Libp2pService {
                             }
                             (GetAncestryV2::NAME, GetAncestryV2::VERSION) => {
                                 match parse_q::<GetAncestryV2>(bytes) {
-                                    Ok(query) => {
-                                        // TODO (vlad9486): Check query
-                                        let _ = query.data; // must be equal current best tip consensus state
-                                        let _ = query.hash; // must be equal best_tip.data.header.protocol_state.hash()
-                                        send(P2pRpcRequest::BestTipWithProof)
-                                    }
+                                    Ok(query) => send(P2pRpcRequest::Ancestry(
+                                        v2::DataHashLibStateHashStableV1(query.hash).into(),
+                                        Box::new(query.data),
+                                    )),
                                     Err(err) => send_error(err),
                                 };
                             }
@@ -687,16 +855,25 @@ impl Libp2pService {
                             (
                                 GetTransitionChainProofV1ForV2::NAME,
                                 GetTransitionChainProofV1ForV2::VERSION,
-                            ) => swarm
-                                .behaviour_mut()
-                                .rpc
-                                .respond::<GetTransitionChainProofV1ForV2>(
-                                    peer_id,
-                                    stream_id,
-                                    id,
-                                    Ok(None),
-                                )
-                                .unwrap(),
+                            ) => {
+                                match parse_q::<GetTransitionChainProofV1ForV2>(bytes) {
+                                    Ok(hash) => send(P2pRpcRequest::TransitionChainProof(
+                                        v2::DataHashLibStateHashStableV1(hash).into(),
+                                    )),
+                                    Err(err) => send_error(err),
+                                };
+                            }
+                            (GetNodeStatusV2::NAME, GetNodeStatusV2::VERSION) => {
+                                send(P2pRpcRequest::NodeStatus)
+                            }
+                            (GetEpochLedgerV2::NAME, GetEpochLedgerV2::VERSION) => {
+                                match parse_q::<GetEpochLedgerV2>(bytes) {
+                                    Ok(hash) => send(P2pRpcRequest::EpochLedger(
+                                        v2::MinaBaseLedgerHash0StableV1(hash).into(),
+                                    )),
+                                    Err(err) => send_error(err),
+                                };
+                            }
                             _ => (),
                         };
                     }
@@ -799,6 +976,75 @@ impl Libp2pService {
                                 }
                                 Err(err) => send_error(err),
                             },
+                            (
+                                GetTransitionChainProofV1ForV2::NAME,
+                                GetTransitionChainProofV1ForV2::VERSION,
+                            ) => match parse_r::<GetTransitionChainProofV1ForV2>(bytes) {
+                                Ok(response) => {
+                                    let response =
+                                        response.ok().flatten().map(|(first_hash, body_hashes)| {
+                                            P2pRpcResponse::TransitionChainProof(
+                                                v2::DataHashLibStateHashStableV1(first_hash).into(),
+                                                body_hashes
+                                                    .into_iter()
+                                                    .map(v2::MinaBaseStateBodyHashStableV1)
+                                                    .collect(),
+                                            )
+                                        });
+                                    send(response)
+                                }
+                                Err(err) => send_error(err),
+                            },
+                            (GetNodeStatusV2::NAME, GetNodeStatusV2::VERSION) => {
+                                match parse_r::<GetNodeStatusV2>(bytes) {
+                                    Ok(response) => {
+                                        let response = response
+                                            .ok()
+                                            .and_then(|x| x.0.ok())
+                                            .map(node_status::node_status_from_v2)
+                                            .map(|status| {
+                                                P2pRpcResponse::NodeStatus(Box::new(status))
+                                            });
+                                        send(response)
+                                    }
+                                    Err(err) => send_error(err),
+                                }
+                            }
+                            (GetEpochLedgerV2::NAME, GetEpochLedgerV2::VERSION) => {
+                                match parse_r::<GetEpochLedgerV2>(bytes) {
+                                    Ok(response) => {
+                                        let response =
+                                            response.ok().and_then(|x| x.0.ok()).map(|ledger| {
+                                                P2pRpcResponse::EpochLedger(Arc::new(ledger))
+                                            });
+                                        send(response)
+                                    }
+                                    Err(err) => send_error(err),
+                                }
+                            }
+                            (GetAncestryV2::NAME, GetAncestryV2::VERSION) => {
+                                match parse_r::<GetAncestryV2>(bytes) {
+                                    Ok(response) => {
+                                        let response = response
+                                            .ok()
+                                            .flatten()
+                                            .map(|resp| BestTipWithProof {
+                                                best_tip: resp.data.into(),
+                                                proof: (
+                                                    resp.proof
+                                                        .0
+                                                        .into_iter()
+                                                        .map(v2::MinaBaseStateBodyHashStableV1)
+                                                        .collect(),
+                                                    resp.proof.1.into(),
+                                                ),
+                                            })
+                                            .map(P2pRpcResponse::BestTipWithProof);
+                                        send(response)
+                                    }
+                                    Err(err) => send_error(err),
+                                }
+                            }
                             _ => send(None),
                         }
                     }
@@ -0,0 +1,190 @@
+//! Conversion between our [`P2pNodeStatus`] and the `get_node_status`
+//! rpc response, which identifies peers by libp2p peer ids.
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+use libp2p::PeerId;
+use mina_p2p_messages::core::InetAddrV1;
+use mina_p2p_messages::number::Float64;
+use mina_p2p_messages::rpc::NodeStatusV2;
+use mina_p2p_messages::v1;
+use mina_p2p_messages::v2::{DataHashLibStateHashStableV1, NonZeroCurvePointUncompressedStableV1};
+
+use crate::channels::rpc::{P2pNodeStatus, P2pNodeSyncStatus};
+
+use super::discovery::supported_peer_id;
+
+fn peer_id_v1(peer_id: PeerId) -> v1::NetworkPeerPeerIdStableV1Versioned {
+    v1::NetworkPeerPeerIdStableV1VersionedV1(peer_id.to_string().as_str().into()).into()
+}
+
+fn peer_id_from_v1(peer_id: &v1::NetworkPeerPeerIdStableV1Versioned) -> Option<crate::PeerId> {
+    let peer_id = String::try_from(&peer_id.inner().0).ok()?;
+    supported_peer_id(peer_id.parse().ok()?)
+}
+
+/// Fills in the fields known only to the libp2p service.
+pub(super) fn node_status_v2(
+    status: P2pNodeStatus,
+    local_peer_id: PeerId,
+    uptime: Duration,
+) -> NodeStatusV2 {
+    use v1::SyncStatusTStableV1VersionedV1 as SyncStatus;
+
+    let sync_status = match status.sync_status {
+        P2pNodeSyncStatus::Connecting => SyncStatus::Connecting,
+        P2pNodeSyncStatus::Listening => SyncStatus::Listening,
+        P2pNodeSyncStatus::Offline => SyncStatus::Offline,
+        P2pNodeSyncStatus::Bootstrap => SyncStatus::Bootstrap,
+        P2pNodeSyncStatus::Synced => SyncStatus::Synced,
+        P2pNodeSyncStatus::Catchup => SyncStatus::Catchup,
+    };
+    let ban_statuses = status
+        .banned_peers
+        .into_iter()
+        .map(|(peer_id, banned_until)| {
+            let banned_until = Float64::from(banned_until as f64 / 1000.0);
+            let status = v1::TrustSystemPeerStatusStableV1VersionedV1 {
+                trust: Float64::from(-1.0),
+                banned: v1::TrustSystemBannedStatusStableV1VersionedV1::BannedUntil(banned_until)
+                    .into(),
+            };
+            (peer_id_v1(peer_id.into()), status.into())
+        })
+        .collect();
+
+    NodeStatusV2 {
+        // We don't know our external address.
+        node_ip_addr: InetAddrV1::from(IpAddr::V4(Ipv4Addr::UNSPECIFIED)).into(),
+        node_peer_id: peer_id_v1(local_peer_id),
+        sync_status: sync_status.into(),
+        peers: status
+            .peers
+            .into_iter()
+            .map(|peer_id| peer_id_v1(peer_id.into()))
+            .collect(),
+        block_producers: status
+            .block_producers
+            .iter()
+            .map(|key| {
+                let key = v1::NonzeroCurvePointV1 {
+                    x: key.x.clone(),
+                    is_odd: key.is_odd,
+                };
+                v1::NonzeroCurvePointV1Versioned::from(key.into())
+            })
+            .collect(),
+        protocol_state_hash: status.best_tip_hash.0.clone().into(),
+        ban_statuses,
+        k_block_hashes_and_timestamps: status
+            .k_block_hashes_and_timestamps
+            .into_iter()
+            .map(|(hash, time)| (hash.0.clone().into(), time.as_str().into()))
+            .collect(),
+        git_commit: status.git_commit.as_str().into(),
+        uptime_minutes: (uptime.as_secs() / 60) as i32,
+        block_height_opt: status.block_height.map(|height| height as i32),
+    }
+}
+
+/// Peers with unsupported (not ed25519) peer ids are left out.
+pub(super) fn node_status_from_v2(status: NodeStatusV2) -> P2pNodeStatus {
+    use v1::SyncStatusTStableV1VersionedV1 as SyncStatus;
+
+    let sync_status = match status.sync_status.into_inner() {
+        SyncStatus::Connecting => P2pNodeSyncStatus::Connecting,
+        SyncStatus::Listening => P2pNodeSyncStatus::Listening,
+        SyncStatus::Offline => P2pNodeSyncStatus::Offline,
+        SyncStatus::Bootstrap => P2pNodeSyncStatus::Bootstrap,
+        SyncStatus::Synced => P2pNodeSyncStatus::Synced,
+        SyncStatus::Catchup => P2pNodeSyncStatus::Catchup,
+    };
+    let state_hash =
+        |hash: v1::StateHashV1Versioned| DataHashLibStateHashStableV1(hash.into_inner()).into();
+
+    P2pNodeStatus {
+        sync_status,
+        peers: status.peers.iter().filter_map(peer_id_from_v1).collect(),
+        block_producers: status
+            .block_producers
+            .into_iter()
+            .map(|key| {
+                let key = key.into_inner().into_inner();
+                NonZeroCurvePointUncompressedStableV1 {
+                    x: key.x,
+                    is_odd: key.is_odd,
+                }
+                .into()
+            })
+            .collect(),
+        best_tip_hash: state_hash(status.protocol_state_hash),
+        banned_peers: status
+            .ban_statuses
+            .iter()
+            .filter_map(|(peer_id, status)| {
+                let v1::TrustSystemBannedStatusStableV1VersionedV1::BannedUntil(until) =
+                    status.inner().banned.inner()
+                else {
+                    return None;
+                };
+                Some((peer_id_from_v1(peer_id)?, (until.0 * 1000.0) as i64))
+            })
+            .collect(),
+        k_block_hashes_and_timestamps: status
+            .k_block_hashes_and_timestamps
+            .into_iter()
+            .map(|(hash, time)| (state_hash(hash), time.to_string()))
+            .collect(),
+        git_commit: status.git_commit.to_string(),
+        block_height: status.block_height_opt.map(|height| height as u32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use libp2p::identity::Keypair;
+    use mina_p2p_messages::binprot::{BinProtRead, BinProtWrite};
+    use mina_p2p_messages::v2::{NonZeroCurvePoint, StateHash};
+
+    use super::*;
+
+    #[test]
+    fn node_status_v2_roundtrip() {
+        let local_peer_id = Keypair::generate_ed25519().public().to_peer_id();
+        let peer_id = Keypair::generate_ed25519().public().to_peer_id();
+        let banned_peer_id = Keypair::generate_ed25519().public().to_peer_id();
+        let hash = "3NLESd9gzU52bDWSXL5uUAYbCojHXSVdeBX4sCMF3V8Ns9D1Sriy"
+            .parse::<StateHash>()
+            .unwrap();
+        let producer = "B62qjVQLxt9nYMWGn45mkgwYfcz8e8jvjNCBo11VKJb7vxDNwv5QLPS"
+            .parse::<NonZeroCurvePoint>()
+            .unwrap();
+        let status = P2pNodeStatus {
+            sync_status: P2pNodeSyncStatus::Catchup,
+            peers: vec![peer_id.into()],
+            block_producers: vec![producer],
+            best_tip_hash: hash.clone(),
+            banned_peers: vec![(banned_peer_id.into(), 1_700_000_000_500)],
+            k_block_hashes_and_timestamps: vec![(hash, "1700000000000".to_owned())],
+            git_commit: "0123abcd".to_owned(),
+            block_height: Some(12),
+        };
+
+        let status_v2 = node_status_v2(status.clone(), local_peer_id, Duration::from_secs(150));
+        assert_eq!(status_v2.uptime_minutes, 2);
+        assert_eq!(
+            peer_id_from_v1(&status_v2.node_peer_id),
+            Some(local_peer_id.into())
+        );
+
+        let mut bytes = Vec::new();
+        status_v2.binprot_write(&mut bytes).unwrap();
+        let status_v2 = NodeStatusV2::binprot_read(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(node_status_from_v2(status_v2)).unwrap(),
+            serde_json::to_value(status).unwrap()
+        );
+    }
+}
@@ -61,6 +61,8 @@ enum PeerCmdAll {
 pub struct P2pServiceCtx {
     pub cmd_sender: mpsc::UnboundedSender<Cmd>,
     pub peers: BTreeMap<PeerId, PeerState>,
+    /// Identity key, used to sign offers and answers.
+    pub secret_key: SecretKey,
 }
 
 pub struct PeerAddArgs {
@@ -612,12 +614,11 @@ pub trait P2pServiceWebrtc: redux::Service {
 
     fn peers(&mut self) -> &mut BTreeMap<PeerId, PeerState>;
 
+    fn secret_key(&mut self) -> &SecretKey;
+
     fn init<S: TaskSpawner>(secret_key: SecretKey, spawner: S) -> P2pServiceCtx {
         let (cmd_sender, mut cmd_receiver) = mpsc::unbounded_channel();
 
-        // TODO: sing/verify SDP
-        let _ = secret_key;
-
         spawner.spawn_main("webrtc", async move {
             while let Some(cmd) = cmd_receiver.recv().await {
                 match cmd {
@@ -631,6 +632,7 @@ pub trait P2pServiceWebrtc: redux::Service {
         P2pServiceCtx {
             cmd_sender,
             peers: Default::default(),
+            secret_key,
         }
     }
 
@@ -670,6 +672,14 @@ pub trait P2pServiceWebrtc: redux::Service {
         }));
     }
 
+    fn offer_create(&mut self, sdp: String, target_peer_id: PeerId) -> webrtc::Offer {
+        webrtc::Offer::new(sdp, target_peer_id, self.secret_key())
+    }
+
+    fn answer_create(&mut self, sdp: String, target_peer_id: PeerId) -> webrtc::Answer {
+        webrtc::Answer::new(sdp, target_peer_id, self.secret_key())
+    }
+
     fn set_answer(&mut self, peer_id: PeerId, answer: webrtc::Answer) {
         if let Some(peer) = self.peers().get(&peer_id) {
             let _ = peer.cmd_sender.send(PeerCmd::AnswerSet(answer));
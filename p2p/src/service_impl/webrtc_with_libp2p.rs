@@ -1,13 +1,18 @@
 use libp2p::swarm::dial_opts::DialOpts;
+use mina_p2p_messages::v2::MinaBaseUserCommandStableV2;
 use openmina_core::channels::mpsc;
 use openmina_core::snark::Snark;
 
 use crate::{
-    channels::{ChannelId, ChannelMsg, MsgId, P2pChannelsService},
+    channels::{
+        ChannelId, ChannelMsg, MsgId, P2pChannelsService, P2pGossipAcceptance,
+        P2pGossipValidationKey,
+    },
     connection::{outgoing::P2pConnectionOutgoingInitOpts, P2pConnectionService},
     disconnection::P2pDisconnectionService,
+    discovery::P2pDiscoveryService,
     identity::SecretKey,
-    P2pChannelEvent, P2pEvent, PeerId,
+    P2pChannelEvent, P2pEvent, P2pGossipsubConfig, PeerId,
 };
 
 use super::{libp2p::Libp2pService, webrtc::P2pServiceWebrtc, TaskSpawner};
@@ -23,12 +28,19 @@ pub trait P2pServiceWebrtcWithLibp2p: P2pServiceWebrtc {
     fn init<S: TaskSpawner>(
         secret_key: SecretKey,
         chain_id: String,
+        gossipsub_config: P2pGossipsubConfig,
         event_source_sender: mpsc::UnboundedSender<P2pEvent>,
         spawner: S,
     ) -> P2pServiceCtx {
         P2pServiceCtx {
             webrtc: <Self as P2pServiceWebrtc>::init(secret_key.clone(), spawner.clone()),
-            libp2p: Libp2pService::run(secret_key, chain_id, event_source_sender, spawner),
+            libp2p: Libp2pService::run(
+                secret_key,
+                chain_id,
+                gossipsub_config,
+                event_source_sender,
+                spawner,
+            ),
         }
     }
 }
@@ -60,6 +72,14 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pConnectionService for T {
         P2pServiceWebrtc::incoming_init(self, peer_id, offer)
     }
 
+    fn offer_create(&mut self, sdp: String, target_peer_id: PeerId) -> crate::webrtc::Offer {
+        P2pServiceWebrtc::offer_create(self, sdp, target_peer_id)
+    }
+
+    fn answer_create(&mut self, sdp: String, target_peer_id: PeerId) -> crate::webrtc::Answer {
+        P2pServiceWebrtc::answer_create(self, sdp, target_peer_id)
+    }
+
     fn set_answer(&mut self, peer_id: PeerId, answer: crate::webrtc::Answer) {
         P2pServiceWebrtc::set_answer(self, peer_id, answer)
     }
@@ -82,6 +102,16 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pDisconnectionService for T {
                 .send(Cmd::Disconnect(peer_id.into()));
         }
     }
+
+    fn peer_ban(&mut self, peer_id: PeerId) {
+        use super::libp2p::Cmd;
+        let _ = self.libp2p().cmd_sender().send(Cmd::Ban(peer_id.into()));
+    }
+
+    fn peer_unban(&mut self, peer_id: PeerId) {
+        use super::libp2p::Cmd;
+        let _ = self.libp2p().cmd_sender().send(Cmd::Unban(peer_id.into()));
+    }
 }
 
 impl<T: P2pServiceWebrtcWithLibp2p> P2pChannelsService for T {
@@ -120,4 +150,43 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pChannelsService for T {
             .cmd_sender()
             .send(Cmd::SnarkBroadcast(snark, nonce));
     }
+
+    fn libp2p_broadcast_transaction(
+        &mut self,
+        transaction: Box<MinaBaseUserCommandStableV2>,
+        nonce: u32,
+    ) {
+        use super::libp2p::Cmd;
+        let _ = self
+            .libp2p()
+            .cmd_sender()
+            .send(Cmd::TransactionBroadcast(transaction, nonce));
+    }
+
+    fn libp2p_gossip_validation_result(
+        &mut self,
+        key: P2pGossipValidationKey,
+        acceptance: P2pGossipAcceptance,
+    ) {
+        use super::libp2p::Cmd;
+        let _ = self
+            .libp2p()
+            .cmd_sender()
+            .send(Cmd::GossipValidationResult(key, acceptance));
+    }
+
+    fn libp2p_gossip_validation_check_timeouts(&mut self) {
+        use super::libp2p::Cmd;
+        let _ = self
+            .libp2p()
+            .cmd_sender()
+            .send(Cmd::GossipValidationCheckTimeouts);
+    }
+}
+
+impl<T: P2pServiceWebrtcWithLibp2p> P2pDiscoveryService for T {
+    fn kademlia_lookup_random(&mut self) {
+        use super::libp2p::Cmd;
+        let _ = self.libp2p().cmd_sender().send(Cmd::KademliaLookupRandom);
+    }
 }
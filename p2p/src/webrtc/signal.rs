@@ -1,7 +1,10 @@
+use std::collections::BTreeSet;
+
 use derive_more::From;
 use serde::{Deserialize, Serialize};
 
-use crate::identity::{PeerId, PublicKey};
+use crate::channels::ChannelId;
+use crate::identity::{PeerId, PublicKey, SecretKey, Signature};
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub struct Offer {
@@ -10,15 +13,33 @@ pub struct Offer {
     pub identity_pub_key: PublicKey,
     /// Peer id that the offerer wants to connect to.
     pub target_peer_id: PeerId,
+    /// Ids of the channels that the offerer supports. Not signed, so
+    /// that peers which don't know about it can still verify the offer.
+    #[serde(default)]
+    pub channels: Vec<u8>,
+    /// Signature of the `sdp` and `target_peer_id`, made with the
+    /// offerer's identity secret key. Missing for peers which predate
+    /// signing, see [`Offer::verify_signature`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub struct Answer {
     pub sdp: String,
-    /// Offerer's identity public key.
+    /// Answerer's identity public key.
     pub identity_pub_key: PublicKey,
-    /// Peer id that the offerer wants to connect to.
+    /// Peer id of the offerer.
     pub target_peer_id: PeerId,
+    /// Ids of the channels that the answerer supports. Not signed,
+    /// same as for the offer.
+    #[serde(default)]
+    pub channels: Vec<u8>,
+    /// Signature of the `sdp` and `target_peer_id`, made with the
+    /// answerer's identity secret key. Missing for peers which predate
+    /// signing, same as for the offer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
 }
 
 #[derive(Serialize, Deserialize, From, Eq, PartialEq, Debug, Clone)]
@@ -26,3 +47,150 @@ pub enum Signal {
     Offer(Offer),
     Answer(Answer),
 }
+
+/// Data which is signed for the offer/answer. Prefix makes sure that
+/// the offer's signature can't be reused for an answer and vice versa.
+fn signed_data(prefix: &[u8], sdp: &str, target_peer_id: &PeerId) -> Vec<u8> {
+    [prefix, &target_peer_id.to_bytes()[..], sdp.as_bytes()].concat()
+}
+
+fn verify_signature(pub_key: &PublicKey, data: &[u8], signature: Option<&Signature>) -> bool {
+    signature.map_or(true, |signature| pub_key.verify(data, signature))
+}
+
+/// Ids of the channels supported by us.
+fn own_channels() -> Vec<u8> {
+    ChannelId::iter_all().map(ChannelId::to_u8).collect()
+}
+
+/// Channels supported by the peer, given the channel ids it sent.
+///
+/// Peers which predate channel negotiation don't send any. They
+/// support every channel except for the transaction propagation one.
+fn supported_channels(channels: &[u8]) -> BTreeSet<ChannelId> {
+    ChannelId::iter_all()
+        .filter(|id| match channels.is_empty() {
+            true => *id != ChannelId::TransactionPropagation,
+            false => channels.contains(&id.to_u8()),
+        })
+        .collect()
+}
+
+impl Offer {
+    const SIGNATURE_PREFIX: &'static [u8] = b"openmina_webrtc_offer";
+
+    pub fn new(sdp: String, target_peer_id: PeerId, secret_key: &SecretKey) -> Self {
+        let data = signed_data(Self::SIGNATURE_PREFIX, &sdp, &target_peer_id);
+        Self {
+            signature: Some(secret_key.sign(&data)),
+            sdp,
+            identity_pub_key: secret_key.public_key(),
+            target_peer_id,
+            channels: own_channels(),
+        }
+    }
+
+    /// Unsigned offers are accepted during the transition period, until
+    /// peers which predate signing are upgraded.
+    pub fn verify_signature(&self) -> bool {
+        let data = signed_data(Self::SIGNATURE_PREFIX, &self.sdp, &self.target_peer_id);
+        verify_signature(&self.identity_pub_key, &data, self.signature.as_ref())
+    }
+
+    /// Channels supported by the offerer.
+    pub fn supported_channels(&self) -> BTreeSet<ChannelId> {
+        supported_channels(&self.channels)
+    }
+}
+
+impl Answer {
+    const SIGNATURE_PREFIX: &'static [u8] = b"openmina_webrtc_answer";
+
+    pub fn new(sdp: String, target_peer_id: PeerId, secret_key: &SecretKey) -> Self {
+        let data = signed_data(Self::SIGNATURE_PREFIX, &sdp, &target_peer_id);
+        Self {
+            signature: Some(secret_key.sign(&data)),
+            sdp,
+            identity_pub_key: secret_key.public_key(),
+            target_peer_id,
+            channels: own_channels(),
+        }
+    }
+
+    /// Unsigned answers are accepted, same as unsigned offers.
+    pub fn verify_signature(&self) -> bool {
+        let data = signed_data(Self::SIGNATURE_PREFIX, &self.sdp, &self.target_peer_id);
+        verify_signature(&self.identity_pub_key, &data, self.signature.as_ref())
+    }
+
+    /// Channels supported by the answerer.
+    pub fn supported_channels(&self) -> BTreeSet<ChannelId> {
+        supported_channels(&self.channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channels::ChannelId;
+    use crate::identity::SecretKey;
+
+    use super::{Answer, Offer};
+
+    #[test]
+    fn offer_answer_signature() {
+        let offerer = SecretKey::rand();
+        let answerer = SecretKey::rand();
+        let offerer_id = offerer.public_key().peer_id();
+        let answerer_id = answerer.public_key().peer_id();
+
+        let offer = Offer::new("offer sdp".to_owned(), answerer_id, &offerer);
+        assert!(offer.verify_signature());
+        let answer = Answer::new("answer sdp".to_owned(), offerer_id, &answerer);
+        assert!(answer.verify_signature());
+
+        let mut tampered = offer.clone();
+        tampered.sdp.push(' ');
+        assert!(!tampered.verify_signature());
+
+        let mut tampered = offer.clone();
+        tampered.target_peer_id = offerer_id;
+        assert!(!tampered.verify_signature());
+
+        let mut impersonated = offer;
+        impersonated.identity_pub_key = answerer.public_key();
+        assert!(!impersonated.verify_signature());
+    }
+
+    #[test]
+    fn unsigned_offer_of_older_peers() {
+        let offerer = SecretKey::rand();
+        let answerer_id = SecretKey::rand().public_key().peer_id();
+        let offer = Offer::new("offer sdp".to_owned(), answerer_id, &offerer);
+
+        let mut json = serde_json::to_value(&offer).unwrap();
+        json.as_object_mut().unwrap().remove("signature");
+        let unsigned: Offer = serde_json::from_value(json).unwrap();
+        assert_eq!(unsigned.signature, None);
+        assert!(unsigned.verify_signature());
+
+        // Signed offers are still checked.
+        let mut tampered = offer;
+        tampered.sdp.push(' ');
+        assert!(!tampered.verify_signature());
+    }
+
+    #[test]
+    fn channels_of_older_peers() {
+        let offerer = SecretKey::rand();
+        let answerer_id = SecretKey::rand().public_key().peer_id();
+        let mut offer = Offer::new("offer sdp".to_owned(), answerer_id, &offerer);
+        assert_eq!(offer.supported_channels(), ChannelId::iter_all().collect());
+
+        // Offer of a peer which doesn't send its channels.
+        offer.channels.clear();
+        assert!(offer.verify_signature());
+        let channels = offer.supported_channels();
+        assert!(!channels.contains(&ChannelId::TransactionPropagation));
+        assert!(channels.contains(&ChannelId::Rpc));
+    }
+}